@@ -0,0 +1,34 @@
+//! Embeds build provenance for `version_info`: the git commit and the locked version of the
+//! base64 dependency.
+
+use std::process::Command;
+
+// Version of `package` recorded in Cargo.lock
+fn locked_version(lock: &str, package: &str) -> Option<String> {
+    let name_line = format!("name = \"{}\"", package);
+    let mut lines = lock.lines();
+    lines.by_ref().find(|line| *line == name_line)?;
+    let version = lines.next()?.strip_prefix("version = \"")?.strip_suffix('"')?;
+    Some(version.to_string())
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs/heads");
+
+    // Builds from a source archive have no git metadata
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=Z85_WASM_GIT_HASH={}", git_hash);
+
+    let lock = std::fs::read_to_string("Cargo.lock").unwrap_or_default();
+    let base64_version = locked_version(&lock, "base64").unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=Z85_WASM_BASE64_VERSION={}", base64_version);
+}
@@ -86,13 +86,11 @@ async function main() {
   
   for (const size of dataSizes) {
     const stats = get_encoding_efficiency(size);
-    // The result is a Map in Node.js
-    const statsMap = stats as Map<string, number>;
     console.log(`Data size: ${size.toLocaleString()} bytes`);
-    console.log(`  Base64 size: ${statsMap.get('base64_size')?.toLocaleString()} bytes`);
-    console.log(`  Z85 size: ${statsMap.get('z85_size')?.toLocaleString()} bytes`);
-    console.log(`  Efficiency ratio: ${statsMap.get('efficiency_ratio')?.toFixed(4)}`);
-    console.log(`  Bandwidth saving: ${statsMap.get('bandwidth_saving')?.toFixed(2)}%`);
+    console.log(`  Base64 size: ${stats.base64_size.toLocaleString()} bytes`);
+    console.log(`  Z85 size: ${stats.z85_size.toLocaleString()} bytes`);
+    console.log(`  Efficiency ratio: ${stats.efficiency_ratio.toFixed(4)}`);
+    console.log(`  Bandwidth saving: ${stats.bandwidth_saving.toFixed(2)}%`);
   }
   console.log('\n');
 
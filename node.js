@@ -16,9 +16,147 @@ const {
   z85_to_base64_with_options,
   base64_to_z85_with_options,
   get_encoding_efficiency,
+  pack_cbor,
+  unpack_cbor,
+  cbor_to_z85,
+  z85_to_cbor,
+  cid_for,
+  pack_archive,
+  unpack_archive,
+  set_telemetry_hook,
+  create_z85_encode_stream,
+  create_z85_decode_stream,
+  encode_z85_async,
+  decode_z85_into,
+  decoded_size_hint,
+  encode_z85_strict,
+  decode_z85_strict,
+  uuid_to_z85,
+  z85_to_uuid,
+  encode_curve_key,
+  decode_curve_key,
+  generate_curve_keypair,
+  encode_z85_v2,
+  decode_z85_v2,
+  sha256_z85,
+  verify_sha256_z85,
+  encode_z85_authenticated,
+  decode_z85_authenticated,
+  encrypt_to_z85,
+  decrypt_from_z85,
+  encrypt_to_z85_with_password,
+  decrypt_from_z85_with_password,
+  sign_z85,
+  verify_z85,
+  generate_signing_keypair,
+  decode_z85_ct,
+  encode_z85_secure,
+  decode_z85_secure,
+  set_compression_dictionary,
+  create_data_url_transcode_stream,
+  encode_data_url,
+  decode_data_url,
+  transcode,
+  hex_to_z85,
+  z85_to_hex,
+  encode_ascii85,
+  decode_ascii85,
+  encode_base45,
+  decode_base45,
+  compare_qr_encodings,
+  encode_base32,
+  decode_base32,
+  encode_base32_crockford,
+  decode_base32_crockford,
+  encode_bech32,
+  decode_bech32,
+  detect_encoding,
+  transcode_auto,
+  validate_z85,
+  try_encode_z85,
+  try_decode_z85,
+  try_z85_to_base64,
+  try_base64_to_z85,
+  try_transcode,
+  set_panic_handler,
+  set_strict_padding,
+  set_limits,
+  decode_z85_lenient,
+  encode_z85_formatted,
+  decode_z85_formatted,
+  armor_z85,
+  dearmor_z85,
+  split_z85,
+  join_z85_parts,
+  encode_file_container,
+  decode_file_container,
+  encode_z85_seekable,
+  decode_range,
+  encode_z85_batch,
+  decode_z85_batch,
+  transcode_batch,
+  encode_blob_z85,
+  decode_z85_to_blob,
+  fetch_z85,
+  fetch_z85_blob,
+  decode_z85_to_object_url,
+  revoke_object_url,
+  encode_image_data_z85,
+  decode_z85_to_image_data,
+  z85_decode_readable,
+  z85_encode_writable,
+  decode_z85_response,
+  decode_z85_buffer,
+  decode_z85_batch_buffers,
+  encode_z85_bytes,
+  decode_z85_from_bytes,
+  encode_text_z85,
+  decode_z85_to_text,
+  encode_json_z85,
+  decode_z85_to_json,
+  encode_z85_view,
+  release_encoded_views,
+  wasm_memory,
+  memory_stats,
+  reset_peak,
+  run_benchmark,
+  get_encoding_efficiency_for,
+  compare_encodings,
+  estimate_compressed_size,
+  set_log_level,
+  set_logger,
+  version_info,
   init_wasm,
   ConversionOptions,
-  DataType
+  ConversionOptionsBuilder,
+  DataType,
+  ConversionTask,
+  Z85Encoder,
+  Z85Decoder,
+  PaddingFormat,
+  Algorithm,
+  PasswordParams,
+  Compression,
+  Pipeline,
+  DataUrl,
+  DataUrlEncoding,
+  DataUrlTranscoder,
+  Encoding,
+  Base64Variant,
+  Alphabet,
+  Bech32Variant,
+  DetectedFormat,
+  DetectionResult,
+  FountainEncoder,
+  FountainDecoder,
+  ArchiveBuilder,
+  Archive,
+  EncodedView,
+  Z85Session,
+  EfficiencyContext,
+  EfficiencyReport,
+  ConversionResult,
+  LogLevel
 } = wasmModule;
 
 // CommonJS exports
@@ -31,9 +169,147 @@ module.exports = {
   z85_to_base64_with_options,
   base64_to_z85_with_options,
   get_encoding_efficiency,
+  pack_cbor,
+  unpack_cbor,
+  cbor_to_z85,
+  z85_to_cbor,
+  cid_for,
+  pack_archive,
+  unpack_archive,
+  set_telemetry_hook,
+  create_z85_encode_stream,
+  create_z85_decode_stream,
+  encode_z85_async,
+  decode_z85_into,
+  decoded_size_hint,
+  encode_z85_strict,
+  decode_z85_strict,
+  uuid_to_z85,
+  z85_to_uuid,
+  encode_curve_key,
+  decode_curve_key,
+  generate_curve_keypair,
+  encode_z85_v2,
+  decode_z85_v2,
+  sha256_z85,
+  verify_sha256_z85,
+  encode_z85_authenticated,
+  decode_z85_authenticated,
+  encrypt_to_z85,
+  decrypt_from_z85,
+  encrypt_to_z85_with_password,
+  decrypt_from_z85_with_password,
+  sign_z85,
+  verify_z85,
+  generate_signing_keypair,
+  decode_z85_ct,
+  encode_z85_secure,
+  decode_z85_secure,
+  set_compression_dictionary,
+  create_data_url_transcode_stream,
+  encode_data_url,
+  decode_data_url,
+  transcode,
+  hex_to_z85,
+  z85_to_hex,
+  encode_ascii85,
+  decode_ascii85,
+  encode_base45,
+  decode_base45,
+  compare_qr_encodings,
+  encode_base32,
+  decode_base32,
+  encode_base32_crockford,
+  decode_base32_crockford,
+  encode_bech32,
+  decode_bech32,
+  detect_encoding,
+  transcode_auto,
+  validate_z85,
+  try_encode_z85,
+  try_decode_z85,
+  try_z85_to_base64,
+  try_base64_to_z85,
+  try_transcode,
+  set_panic_handler,
+  set_strict_padding,
+  set_limits,
+  decode_z85_lenient,
+  encode_z85_formatted,
+  decode_z85_formatted,
+  armor_z85,
+  dearmor_z85,
+  split_z85,
+  join_z85_parts,
+  encode_file_container,
+  decode_file_container,
+  encode_z85_seekable,
+  decode_range,
+  encode_z85_batch,
+  decode_z85_batch,
+  transcode_batch,
+  encode_blob_z85,
+  decode_z85_to_blob,
+  fetch_z85,
+  fetch_z85_blob,
+  decode_z85_to_object_url,
+  revoke_object_url,
+  encode_image_data_z85,
+  decode_z85_to_image_data,
+  z85_decode_readable,
+  z85_encode_writable,
+  decode_z85_response,
+  decode_z85_buffer,
+  decode_z85_batch_buffers,
+  encode_z85_bytes,
+  decode_z85_from_bytes,
+  encode_text_z85,
+  decode_z85_to_text,
+  encode_json_z85,
+  decode_z85_to_json,
+  encode_z85_view,
+  release_encoded_views,
+  wasm_memory,
+  memory_stats,
+  reset_peak,
+  run_benchmark,
+  get_encoding_efficiency_for,
+  compare_encodings,
+  estimate_compressed_size,
+  set_log_level,
+  set_logger,
+  version_info,
   init_wasm,
   ConversionOptions,
-  DataType
+  ConversionOptionsBuilder,
+  DataType,
+  ConversionTask,
+  Z85Encoder,
+  Z85Decoder,
+  PaddingFormat,
+  Algorithm,
+  PasswordParams,
+  Compression,
+  Pipeline,
+  DataUrl,
+  DataUrlEncoding,
+  DataUrlTranscoder,
+  Encoding,
+  Base64Variant,
+  Alphabet,
+  Bech32Variant,
+  DetectedFormat,
+  DetectionResult,
+  FountainEncoder,
+  FountainDecoder,
+  ArchiveBuilder,
+  Archive,
+  EncodedView,
+  Z85Session,
+  EfficiencyContext,
+  EfficiencyReport,
+  ConversionResult,
+  LogLevel
 };
 
 // ESM compatibility
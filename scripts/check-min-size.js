@@ -0,0 +1,52 @@
+#!/usr/bin/env node
+
+const { spawn } = require('child_process');
+const fs = require('fs');
+const path = require('path');
+const zlib = require('zlib');
+
+// Largest gzipped min-size module allowed (the budget documented for the feature in Cargo.toml)
+const MAX_GZIPPED_BYTES = 30 * 1024;
+
+const outDir = path.join(__dirname, '..', 'pkg-min');
+
+// Build the min-size module without the default `full` dependencies
+function buildMinSize() {
+  console.log('Building min-size WASM module...');
+  return new Promise((resolve, reject) => {
+    const build = spawn('wasm-pack', [
+      'build', '--release', '--target', 'web', '--out-dir', outDir,
+      '--', '--no-default-features', '--features', 'min-size',
+    ], {
+      shell: true,
+      stdio: 'inherit'
+    });
+    build.on('close', (code) => {
+      if (code === 0) {
+        resolve();
+      } else {
+        reject(new Error('Failed to build min-size WASM module'));
+      }
+    });
+  });
+}
+
+async function main() {
+  try {
+    await buildMinSize();
+
+    const wasm = fs.readFileSync(path.join(outDir, 'z85_wasm_bg.wasm'));
+    const gzipped = zlib.gzipSync(wasm, { level: 9 }).length;
+    console.log(`min-size module: ${wasm.length} bytes, ${gzipped} bytes gzipped (max ${MAX_GZIPPED_BYTES})`);
+
+    if (gzipped > MAX_GZIPPED_BYTES) {
+      console.error('The min-size module is over its size budget');
+      process.exit(1);
+    }
+  } catch (error) {
+    console.error('Size check failed:', error.message);
+    process.exit(1);
+  }
+}
+
+main();
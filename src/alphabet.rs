@@ -0,0 +1,152 @@
+use wasm_bindgen::prelude::*;
+
+use crate::codec::{self, Base85Alphabet};
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::{push_padding_suffix, split_padding_suffix, PaddingFormat};
+
+// Encode bytes in a base85 alphabet with Z85's grouping: big-endian 4-byte words, the final
+// partial word zero-padded and recorded in the padding suffix
+pub(crate) fn encode_base85(alphabet: &Base85Alphabet, data: &[u8], padding_format: PaddingFormat) -> String {
+    let aligned = data.len() / 4 * 4;
+    let mut encoded = String::with_capacity(data.len().div_ceil(4) * 5 + 7);
+    codec::encode_append_with(alphabet, &data[..aligned], &mut encoded);
+    let padding = codec::encode_padded_tail_with(alphabet, &data[aligned..], &mut encoded);
+    push_padding_suffix(&mut encoded, padding, padding_format);
+    encoded
+}
+
+// Decode base85 text with a `:padding` or `#length` suffix in the given alphabet
+pub(crate) fn decode_base85(alphabet: &Base85Alphabet, encoded: &str) -> Result<Vec<u8>, Error> {
+    let (data, padding) = split_padding_suffix(encoded)?;
+    let mut decoded = vec![0u8; (data.len() / 5 * 4).saturating_sub(padding)];
+    let written = codec::decode_into_with(alphabet, data.as_bytes(), padding, &mut decoded)?;
+    decoded.truncate(written);
+    Ok(decoded)
+}
+
+/// An 85-character alphabet used with Z85's chunking and `:padding` suffix
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Alphabet {
+    tables: Base85Alphabet,
+}
+
+impl Alphabet {
+    // Validate a custom alphabet: exactly 85 distinct printable ASCII characters (no space)
+    fn custom_internal(characters: &str) -> Result<Alphabet, Error> {
+        let symbols: [u8; 85] = characters.as_bytes().try_into()
+            .map_err(|_| Error::new(ErrorCode::InvalidOptions, format!("Alphabet must have 85 characters, got {}", characters.chars().count())))?;
+        if let Some(position) = symbols.iter().position(|c| !c.is_ascii_graphic()) {
+            return Err(Error::new(ErrorCode::InvalidOptions, format!("Alphabet character at index {} is not printable ASCII", position)));
+        }
+        if let Some(position) = (1..symbols.len()).find(|&i| symbols[..i].contains(&symbols[i])) {
+            return Err(Error::new(ErrorCode::InvalidOptions, format!("Alphabet character '{}' at index {} is repeated", symbols[position] as char, position)));
+        }
+        Ok(Alphabet { tables: Base85Alphabet::new("Base85", &symbols) })
+    }
+}
+
+#[wasm_bindgen]
+impl Alphabet {
+    /// Create a custom alphabet from 85 distinct printable ASCII characters, in digit order.
+    /// It is used with the same grouping and `:padding` suffix as Z85.
+    #[wasm_bindgen(constructor)]
+    pub fn new(characters: &str) -> Result<Alphabet, JsValue> {
+        Alphabet::custom_internal(characters)
+            .map_err(js_error)
+    }
+
+    /// The Z85 alphabet (ZeroMQ RFC 32)
+    pub fn z85() -> Alphabet {
+        Alphabet { tables: codec::Z85 }
+    }
+
+    /// The RFC 1924 alphabet (`0-9A-Za-z!#$%&()*+-;<=>?@^_\`{|}~`)
+    pub fn rfc1924() -> Alphabet {
+        Alphabet { tables: codec::RFC1924 }
+    }
+
+    /// The 85 characters, in digit order
+    #[wasm_bindgen(getter)]
+    pub fn characters(&self) -> String {
+        self.tables.symbols.iter().map(|&c| c as char).collect()
+    }
+
+    /// Encode raw bytes to `data:padding` in this alphabet
+    pub fn encode(&self, data: &[u8]) -> Result<String, JsValue> {
+        limits::enforce(data.len(), String::len, || Ok(encode_base85(&self.tables, data, PaddingFormat::Count)))
+            .map_err(js_error)
+    }
+
+    /// Decode `data:padding` (or `data#length`) in this alphabet to raw bytes
+    pub fn decode(&self, encoded: &str) -> Result<Vec<u8>, JsValue> {
+        limits::enforce(encoded.len(), Vec::len, || decode_base85(&self.tables, encoded))
+            .map_err(js_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_z85_internal;
+
+    #[test]
+    fn test_rfc1924_matches_reference() {
+        // Reference output from Python's `base64.b85encode(..., pad=True)`
+        let encoded = encode_base85(&codec::RFC1924, b"Hello, World\xfb\xff", PaddingFormat::Count);
+        assert_eq!(encoded, "NM&qnZ!92JZ*pv8`~Ls{:2");
+        assert_eq!(decode_base85(&codec::RFC1924, &encoded).unwrap(), b"Hello, World\xfb\xff");
+        assert_eq!(decode_base85(&codec::RFC1924, "NM&qnZ!92JZ*pv8`~Ls{#14").unwrap(), b"Hello, World\xfb\xff");
+    }
+
+    #[test]
+    fn test_alphabet_class() {
+        let data: Vec<u8> = (0..=255u8).collect();
+        assert_eq!(Alphabet::z85().encode(&data).unwrap(), encode_z85_internal(&data));
+        assert_eq!(Alphabet::rfc1924().characters().len(), 85);
+        for alphabet in [Alphabet::z85(), Alphabet::rfc1924()] {
+            for size in 0..8 {
+                let encoded = encode_base85(&alphabet.tables, &data[..size], PaddingFormat::Count);
+                assert_eq!(decode_base85(&alphabet.tables, &encoded).unwrap(), &data[..size]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        // Z85 with the markup-hostile characters swapped out
+        let characters = codec::Z85_ALPHABET.iter()
+            .map(|&c| match c { b'<' => '~', b'>' => '|', c => c as char })
+            .collect::<String>();
+        let custom = Alphabet::custom_internal(&characters).unwrap();
+        assert_eq!(custom.characters(), characters);
+        let data = b"\x00\x01custom alphabet\xff";
+        let encoded = encode_base85(&custom.tables, data, PaddingFormat::Count);
+        assert_eq!(decode_base85(&custom.tables, &encoded).unwrap(), data);
+        assert_eq!(
+            decode_base85(&custom.tables, "<<<<<:0").err().unwrap().message,
+            "Base85 decode error: invalid character at offset 0"
+        );
+
+        let reversed: String = characters.chars().rev().collect();
+        let custom = Alphabet::custom_internal(&reversed).unwrap();
+        assert_ne!(encode_base85(&custom.tables, data, PaddingFormat::Count), encoded);
+    }
+
+    #[test]
+    fn test_custom_alphabet_validation() {
+        let z85: String = codec::Z85_ALPHABET.iter().map(|&c| c as char).collect();
+        assert_eq!(Alphabet::custom_internal(&z85[..84]).err().unwrap().message, "Alphabet must have 85 characters, got 84");
+        assert_eq!(Alphabet::custom_internal(&format!("{}é", &z85[..84])).err().unwrap().message, "Alphabet must have 85 characters, got 85");
+        assert_eq!(Alphabet::custom_internal(&format!("{} ", &z85[..84])).err().unwrap().message, "Alphabet character at index 84 is not printable ASCII");
+        assert_eq!(Alphabet::custom_internal(&format!("{}0", &z85[..84])).err().unwrap().message, "Alphabet character '0' at index 84 is repeated");
+    }
+
+    #[test]
+    fn test_rfc1924_errors() {
+        assert_eq!(decode_base85(&codec::RFC1924, "NM&q.:0").err().unwrap().message, "RFC 1924 decode error: invalid character at offset 4");
+        assert_eq!(decode_base85(&codec::RFC1924, "NM&q:0").err().unwrap().message, "RFC 1924 decode error: data length (4) is not a multiple of 5");
+        assert_eq!(decode_base85(&codec::RFC1924, "~~~~~:0").err().unwrap().message, "RFC 1924 decode error: invalid group at offset 0");
+    }
+}
@@ -0,0 +1,354 @@
+use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use sha2::{Digest, Sha256};
+use tsify::Tsify;
+
+use crate::compression::{deflate_raw, inflate_raw};
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::{decode_z85_internal, encode_z85_internal};
+
+/// Archive format version written by `pack_archive`
+const ARCHIVE_VERSION: u8 = 1;
+
+/// Compression applied to a single archive entry
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum EntryCompression {
+    None,
+    Deflate,
+}
+
+/// Entry as supplied by the caller
+#[derive(Deserialize, Tsify)]
+struct ArchiveEntryInput {
+    name: String,
+    #[serde(with = "serde_bytes")]
+    #[tsify(type = "Uint8Array")]
+    bytes: Vec<u8>,
+    #[serde(default)]
+    #[tsify(optional)]
+    compress: bool,
+}
+
+/// Entry as returned to the caller
+#[derive(Serialize, Tsify)]
+struct ArchiveEntryOutput {
+    name: String,
+    #[tsify(type = "Uint8Array")]
+    bytes: ByteBuf,
+}
+
+/// Index record describing where an entry lives in the archive body
+#[derive(Serialize, Deserialize)]
+struct ArchiveIndexEntry {
+    name: String,
+    offset: u64,
+    length: u64,
+    size: u64,
+    compression: EntryCompression,
+    sha256: ByteBuf,
+}
+
+/// Archive layout: index followed by the concatenated entry bodies
+#[derive(Serialize, Deserialize)]
+struct ArchiveFile {
+    version: u8,
+    index: Vec<ArchiveIndexEntry>,
+    body: ByteBuf,
+}
+
+// Internal pure Rust function for packing named buffers into one Z85 payload
+fn pack_archive_internal(entries: &[(String, Vec<u8>, bool)]) -> Result<String, Error> {
+    let mut index = Vec::with_capacity(entries.len());
+    let mut body = Vec::new();
+
+    for (name, bytes, compress) in entries {
+        if index.iter().any(|e: &ArchiveIndexEntry| &e.name == name) {
+            return Err(Error::new(ErrorCode::InvalidInput, format!("Duplicate archive entry name: {}", name)));
+        }
+
+        // Only keep the compressed form when it actually saves space
+        let (stored, compression) = if *compress {
+            let compressed = deflate_raw(bytes);
+            if compressed.len() < bytes.len() {
+                (compressed, EntryCompression::Deflate)
+            } else {
+                (bytes.clone(), EntryCompression::None)
+            }
+        } else {
+            (bytes.clone(), EntryCompression::None)
+        };
+
+        index.push(ArchiveIndexEntry {
+            name: name.clone(),
+            offset: body.len() as u64,
+            length: stored.len() as u64,
+            size: bytes.len() as u64,
+            compression,
+            sha256: ByteBuf::from(Sha256::digest(bytes).to_vec()),
+        });
+        body.extend_from_slice(&stored);
+    }
+
+    let archive = ArchiveFile { version: ARCHIVE_VERSION, index, body: ByteBuf::from(body) };
+
+    // Serialize the archive to CBOR
+    let mut cbor_data = Vec::new();
+    ciborium::into_writer(&archive, &mut cbor_data)
+        .map_err(|e| Error::new(ErrorCode::InvalidInput, format!("CBOR encode error: {}", e)))?;
+
+    // Encode to Z85
+    Ok(encode_z85_internal(&cbor_data))
+}
+
+impl ArchiveFile {
+    // Decode a Z85 archive and check its version; entry bodies are extracted separately
+    fn parse(payload: &str) -> Result<ArchiveFile, Error> {
+        // Decode Z85 data
+        let cbor_data = decode_z85_internal(payload)?;
+
+        // Deserialize the archive from CBOR
+        let archive: ArchiveFile = ciborium::from_reader(cbor_data.as_slice())
+            .map_err(|e| Error::new(ErrorCode::BadContainer, format!("CBOR decode error: {}", e)))?;
+
+        if archive.version != ARCHIVE_VERSION {
+            return Err(Error::new(ErrorCode::Unsupported, format!("Unsupported archive version: {}", archive.version)));
+        }
+        Ok(archive)
+    }
+
+    // Decompress and verify the body of one entry
+    fn extract(&self, entry: &ArchiveIndexEntry) -> Result<Vec<u8>, Error> {
+        // Locate the entry body
+        let start = entry.offset as usize;
+        let end = start.checked_add(entry.length as usize)
+            .filter(|&end| end <= self.body.len())
+            .ok_or_else(|| Error::new(ErrorCode::BadContainer, format!("Archive entry out of bounds: {}", entry.name)))?;
+        let stored = &self.body[start..end];
+
+        let bytes = match entry.compression {
+            EntryCompression::None => stored.to_vec(),
+            EntryCompression::Deflate => inflate_raw(stored)?,
+        };
+
+        // Verify integrity
+        if bytes.len() as u64 != entry.size || Sha256::digest(&bytes).as_slice() != entry.sha256.as_slice() {
+            return Err(Error::new(ErrorCode::ChecksumMismatch, format!("Integrity check failed for archive entry: {}", entry.name)));
+        }
+        Ok(bytes)
+    }
+}
+
+// Internal pure Rust function for unpacking a Z85 archive into named buffers
+fn unpack_archive_internal(payload: &str) -> Result<Vec<(String, Vec<u8>)>, Error> {
+    let archive = ArchiveFile::parse(payload)?;
+    archive.index.iter()
+        .map(|entry| Ok((entry.name.clone(), archive.extract(entry)?)))
+        .collect()
+}
+
+/// Pack many named buffers (`Array<{ name, bytes, compress? }>`) into a single Z85 payload
+#[wasm_bindgen]
+pub fn pack_archive(#[wasm_bindgen(unchecked_param_type = "ArchiveEntryInput[]")] entries: JsValue) -> Result<String, JsValue> {
+    let entries: Vec<ArchiveEntryInput> = serde_wasm_bindgen::from_value(entries)
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, format!("Invalid archive entries: {}", e))))?;
+    let entries: Vec<(String, Vec<u8>, bool)> = entries.into_iter()
+        .map(|e| (e.name, e.bytes, e.compress))
+        .collect();
+    let bytes_in = entries.iter().map(|(_, bytes, _)| bytes.len()).sum();
+    limits::enforce(bytes_in, String::len, || pack_archive_internal(&entries))
+        .map_err(js_error)
+}
+
+/// Unpack a payload produced by `pack_archive` into `Array<{ name, bytes }>`
+#[wasm_bindgen(unchecked_return_type = "ArchiveEntryOutput[]")]
+pub fn unpack_archive(payload: &str) -> Result<JsValue, JsValue> {
+    let entries: Vec<ArchiveEntryOutput> = limits::enforce(payload.len(), |entries: &Vec<(String, Vec<u8>)>| entries.iter().map(|(_, bytes)| bytes.len()).sum(), || unpack_archive_internal(payload))
+        .map_err(js_error)?
+        .into_iter()
+        .map(|(name, bytes)| ArchiveEntryOutput { name, bytes: ByteBuf::from(bytes) })
+        .collect();
+    serde_wasm_bindgen::to_value(&entries)
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
+}
+
+/// Incremental archive writer: `add()` files one at a time, then `build()` the Z85 payload
+/// (the same format as `pack_archive`)
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct ArchiveBuilder {
+    entries: Vec<(String, Vec<u8>, bool)>,
+}
+
+impl ArchiveBuilder {
+    fn add_internal(&mut self, name: String, bytes: Vec<u8>, compress: bool) -> Result<(), Error> {
+        if self.entries.iter().any(|(existing, _, _)| *existing == name) {
+            return Err(Error::new(ErrorCode::InvalidInput, format!("Duplicate archive entry name: {}", name)));
+        }
+        self.entries.push((name, bytes, compress));
+        Ok(())
+    }
+}
+
+#[wasm_bindgen]
+impl ArchiveBuilder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ArchiveBuilder {
+        ArchiveBuilder::default()
+    }
+
+    /// Add a file; `compress` deflates it when that makes it smaller. Names must be unique.
+    pub fn add(&mut self, name: String, bytes: Vec<u8>, compress: Option<bool>) -> Result<(), JsValue> {
+        self.add_internal(name, bytes, compress.unwrap_or(false))
+            .map_err(js_error)
+    }
+
+    /// Number of files added so far
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Encode every file added so far into one Z85 payload. The builder can keep adding files.
+    pub fn build(&self) -> Result<String, JsValue> {
+        let bytes_in = self.entries.iter().map(|(_, bytes, _)| bytes.len()).sum();
+        limits::enforce(bytes_in, String::len, || pack_archive_internal(&self.entries))
+            .map_err(js_error)
+    }
+}
+
+/// Read-only view of an archive from `ArchiveBuilder` or `pack_archive`. Opening decodes the
+/// payload and its index; each file is decompressed and verified only when requested.
+#[wasm_bindgen]
+pub struct Archive {
+    file: ArchiveFile,
+}
+
+impl Archive {
+    fn get_internal(&self, name: &str) -> Result<Option<Vec<u8>>, Error> {
+        match self.file.index.iter().find(|entry| entry.name == name) {
+            Some(entry) => {
+                limits::check_output(entry.size as usize)?;
+                self.file.extract(entry).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Archive {
+    /// Decode an archive payload
+    pub fn open(payload: &str) -> Result<Archive, JsValue> {
+        limits::check_input(payload.len())
+            .and_then(|_| ArchiveFile::parse(payload))
+            .map(|file| Archive { file })
+            .map_err(js_error)
+    }
+
+    /// File names in the order they were added
+    pub fn names(&self) -> Vec<String> {
+        self.file.index.iter().map(|entry| entry.name.clone()).collect()
+    }
+
+    /// Whether the archive holds a file called `name`
+    pub fn has(&self, name: &str) -> bool {
+        self.file.index.iter().any(|entry| entry.name == name)
+    }
+
+    /// The contents of `name` (verified against its SHA-256), or `undefined` if there is no such file
+    pub fn get(&self, name: &str) -> Result<Option<Vec<u8>>, JsValue> {
+        self.get_internal(name)
+            .map_err(js_error)
+    }
+
+    /// Number of files
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.file.index.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_entries() -> Vec<(String, Vec<u8>, bool)> {
+        vec![
+            ("module.wasm".to_string(), vec![0x00, 0x61, 0x73, 0x6d, 0x01], false),
+            ("readme.txt".to_string(), b"hello hello hello hello hello".to_vec(), true),
+            ("empty.bin".to_string(), Vec::new(), true),
+        ]
+    }
+
+    #[test]
+    fn test_pack_unpack_archive_roundtrip() {
+        let entries = sample_entries();
+        let payload = pack_archive_internal(&entries).unwrap();
+        let unpacked = unpack_archive_internal(&payload).unwrap();
+
+        assert_eq!(unpacked.len(), entries.len());
+        for ((name, bytes, _), (out_name, out_bytes)) in entries.iter().zip(unpacked.iter()) {
+            assert_eq!(name, out_name);
+            assert_eq!(bytes, out_bytes);
+        }
+    }
+
+    #[test]
+    fn test_archive_compression_only_when_smaller() {
+        let payload = pack_archive_internal(&sample_entries()).unwrap();
+        let cbor_data = decode_z85_internal(&payload).unwrap();
+        let archive: ArchiveFile = ciborium::from_reader(cbor_data.as_slice()).unwrap();
+
+        assert_eq!(archive.index[0].compression, EntryCompression::None);
+        assert_eq!(archive.index[1].compression, EntryCompression::Deflate);
+        assert_eq!(archive.index[2].compression, EntryCompression::None);
+    }
+
+    #[test]
+    fn test_archive_duplicate_names() {
+        let entries = vec![
+            ("a".to_string(), vec![1], false),
+            ("a".to_string(), vec![2], false),
+        ];
+        let result = pack_archive_internal(&entries);
+        assert_eq!(result.err().unwrap().message, "Duplicate archive entry name: a");
+    }
+
+    #[test]
+    fn test_archive_integrity_failure() {
+        let payload = pack_archive_internal(&sample_entries()).unwrap();
+        let cbor_data = decode_z85_internal(&payload).unwrap();
+        let mut archive: ArchiveFile = ciborium::from_reader(cbor_data.as_slice()).unwrap();
+
+        // Tamper with the first entry body
+        archive.body[0] ^= 0xff;
+        let mut tampered = Vec::new();
+        ciborium::into_writer(&archive, &mut tampered).unwrap();
+
+        let result = unpack_archive_internal(&encode_z85_internal(&tampered));
+        assert_eq!(result.err().unwrap().message, "Integrity check failed for archive entry: module.wasm");
+    }
+
+    #[test]
+    fn test_archive_builder_and_reader() {
+        let mut builder = ArchiveBuilder::new();
+        for (name, bytes, compress) in sample_entries() {
+            builder.add_internal(name, bytes, compress).unwrap();
+        }
+        assert_eq!(builder.add_internal("readme.txt".to_string(), vec![], false).err().unwrap().message, "Duplicate archive entry name: readme.txt");
+        assert_eq!(builder.length(), 3);
+
+        let payload = pack_archive_internal(&builder.entries).unwrap();
+        assert_eq!(unpack_archive_internal(&payload).unwrap().len(), 3);
+
+        let archive = Archive { file: ArchiveFile::parse(&payload).unwrap() };
+        assert_eq!(archive.names(), ["module.wasm", "readme.txt", "empty.bin"]);
+        assert!(archive.has("empty.bin") && !archive.has("missing"));
+        assert_eq!(archive.get_internal("readme.txt").unwrap().unwrap(), b"hello hello hello hello hello");
+        assert_eq!(archive.get_internal("empty.bin").unwrap().unwrap(), b"");
+        assert_eq!(archive.get_internal("missing").unwrap(), None);
+    }
+}
@@ -0,0 +1,155 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::codec;
+use crate::container::crc32;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::formatting::format_z85_internal;
+use crate::limits;
+use crate::{decode_z85_internal, encode_z85_internal};
+
+/// Length of the Z85 lines between the BEGIN and END lines, as in PEM
+const LINE_WIDTH: usize = 64;
+
+/// Result of `dearmor_z85` (returned to JS as a plain object)
+#[derive(Serialize, Tsify)]
+struct Dearmored {
+    label: String,
+    #[tsify(type = "Uint8Array")]
+    data: serde_bytes::ByteBuf,
+}
+
+// Labels follow RFC 7468: printable ASCII without '-', no surrounding spaces
+fn check_label(label: &str) -> Result<(), Error> {
+    let printable = label.bytes().all(|c| (b' '..=b'~').contains(&c) && c != b'-');
+    if label.is_empty() || !printable || label.trim() != label {
+        return Err(Error::new(ErrorCode::InvalidOptions, format!("Armor error: invalid label '{}'", label)));
+    }
+    Ok(())
+}
+
+// CRC-32 of the data as a 6-character `=` line
+fn checksum_line(data: &[u8]) -> String {
+    let mut line = "=".to_string();
+    codec::encode_append(&crc32(data).to_be_bytes(), &mut line);
+    line
+}
+
+// Internal pure Rust function for wrapping bytes in a PEM-style block: a BEGIN line, Z85 with
+// padding info in lines of 64 characters, a `=` line holding the CRC-32 of the data in Z85,
+// and an END line
+fn armor_z85_internal(data: &[u8], label: &str) -> Result<String, Error> {
+    check_label(label)?;
+    let body = format_z85_internal(&encode_z85_internal(data), Some(LINE_WIDTH), "")?;
+    Ok(format!(
+        "-----BEGIN {label}-----\n{}\n{}\n-----END {label}-----\n",
+        body,
+        checksum_line(data),
+    ))
+}
+
+// Internal pure Rust function for reading the block written by `armor_z85_internal`, returning
+// its label and data. Text around the block (e.g. an email body) and indentation are ignored,
+// as is whatever precedes the BEGIN line on every line of the block (e.g. `> ` quoting).
+fn dearmor_z85_internal(text: &str) -> Result<(String, Vec<u8>), Error> {
+    let mut lines = text.lines();
+    let (quote, label) = lines.by_ref()
+        .find_map(|line| {
+            let start = line.find("-----BEGIN ")?;
+            let label = line[start + "-----BEGIN ".len()..].trim_end().strip_suffix("-----")?;
+            Some((line[..start].trim_end(), label))
+        })
+        .ok_or_else(|| Error::new(ErrorCode::BadContainer, "Armor error: missing BEGIN line"))?;
+    let mut lines = lines.map(|line| line.strip_prefix(quote).unwrap_or(line).trim());
+
+    let mut body: Vec<&str> = Vec::new();
+    loop {
+        match lines.next() {
+            None => return Err(Error::new(ErrorCode::BadContainer, format!("Armor error: missing END line for '{}'", label))),
+            Some(line) => match line.strip_prefix("-----END ").and_then(|rest| rest.strip_suffix("-----")) {
+                Some(end_label) if end_label == label => break,
+                Some(end_label) => return Err(Error::new(ErrorCode::BadContainer, format!("Armor error: END label '{}' does not match BEGIN label '{}'", end_label, label))),
+                None if line.is_empty() => {}
+                None => body.push(line),
+            },
+        }
+    }
+
+    // The checksum is the last line; Z85 lines may start with '=' too
+    let checksum = body.pop()
+        .filter(|line| line.len() == 6 && line.starts_with('='))
+        .ok_or_else(|| Error::new(ErrorCode::BadContainer, "Armor error: missing checksum line"))?;
+    let data = decode_z85_internal(&body.concat())?;
+    if checksum_line(&data) != checksum {
+        return Err(Error::new(ErrorCode::ChecksumMismatch, "Armor error: checksum mismatch"));
+    }
+    Ok((label.to_string(), data))
+}
+
+/// Wrap raw bytes in a copy-paste-safe text block for email or chat:
+/// `-----BEGIN <label>-----`, Z85 with padding info in 64-character lines, a `=` line with the
+/// CRC-32 of the data, and `-----END <label>-----`. `label` is printable ASCII without `-`.
+#[wasm_bindgen]
+pub fn armor_z85(data: &[u8], label: &str) -> Result<String, JsValue> {
+    limits::enforce(data.len(), String::len, || armor_z85_internal(data, label))
+        .map_err(js_error)
+}
+
+/// Read a block written by `armor_z85` into `{ label, data: Uint8Array }`, verifying its
+/// checksum. Text before and after the block and indentation are ignored.
+#[wasm_bindgen(unchecked_return_type = "Dearmored")]
+pub fn dearmor_z85(text: &str) -> Result<JsValue, JsValue> {
+    let (label, data) = limits::enforce(text.len(), |(_, data): &(String, Vec<u8>)| data.len(), || dearmor_z85_internal(text))
+        .map_err(js_error)?;
+    serde_wasm_bindgen::to_value(&Dearmored { label, data: serde_bytes::ByteBuf::from(data) })
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_armor_roundtrip() {
+        let data: Vec<u8> = (0..200u16).map(|i| (i * 7) as u8).collect();
+        let armored = armor_z85_internal(&data, "APP CONFIG").unwrap();
+        let lines: Vec<&str> = armored.lines().collect();
+        assert_eq!(lines[0], "-----BEGIN APP CONFIG-----");
+        assert_eq!(lines[lines.len() - 1], "-----END APP CONFIG-----");
+        assert!(lines[1..lines.len() - 2].iter().all(|line| line.len() <= LINE_WIDTH));
+        assert_eq!(lines[lines.len() - 2], checksum_line(&data));
+        assert_eq!(dearmor_z85_internal(&armored).unwrap(), ("APP CONFIG".to_string(), data));
+
+        let empty = armor_z85_internal(b"", "EMPTY").unwrap();
+        assert_eq!(dearmor_z85_internal(&empty).unwrap().1, b"");
+    }
+
+    #[test]
+    fn test_dearmor_quoted_email() {
+        let armored = armor_z85_internal(b"Hello, World", "CONFIG").unwrap();
+        let email = format!("Hi,\r\n\r\nhere it is:\r\n\r\n    {}\r\n\r\nThanks", armored.replace('\n', "\r\n    "));
+        assert_eq!(dearmor_z85_internal(&email).unwrap().1, b"Hello, World");
+
+        // A reply quoting the block; Z85 lines may themselves start with '>'
+        let data = [0xe6, 0x3e, 0x5b, 0x2a];
+        let armored = armor_z85_internal(&data, "CONFIG").unwrap();
+        assert!(armored.contains("\n>"));
+        let reply = format!("On Monday you wrote:\n> {}", armored.replace('\n', "\n> "));
+        assert_eq!(dearmor_z85_internal(&reply).unwrap().1, data);
+    }
+
+    #[test]
+    fn test_dearmor_errors() {
+        let armored = armor_z85_internal(b"Hello, World", "CONFIG").unwrap();
+        assert_eq!(dearmor_z85_internal("no block").err().unwrap().message, "Armor error: missing BEGIN line");
+        assert_eq!(
+            dearmor_z85_internal(&armored.replace("END CONFIG", "END OTHER")).err().unwrap().message,
+            "Armor error: END label 'OTHER' does not match BEGIN label 'CONFIG'"
+        );
+        assert_eq!(dearmor_z85_internal(&armored.replace("nm=QN", "nm=QM")).err().unwrap().message, "Armor error: checksum mismatch");
+        let checksum = checksum_line(b"Hello, World");
+        assert_eq!(dearmor_z85_internal(&armored.replace(&checksum, "")).err().unwrap().message, "Armor error: missing checksum line");
+        assert_eq!(armor_z85_internal(b"", "BAD-LABEL").err().unwrap().message, "Armor error: invalid label 'BAD-LABEL'");
+    }
+}
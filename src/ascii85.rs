@@ -0,0 +1,164 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+
+/// Opening delimiter of an Adobe Ascii85 stream
+const PREFIX: &str = "<~";
+
+/// Closing (end-of-data) delimiter of an Adobe Ascii85 stream
+const SUFFIX: &str = "~>";
+
+/// First character of the Ascii85 alphabet (`!` = digit 0, `u` = digit 84)
+const FIRST_DIGIT: u8 = b'!';
+
+// Encode bytes as Adobe Ascii85. Full all-zero words become `z` and, with `space_runs`,
+// words of four spaces become `y` (the btoa extension). A final partial word of n bytes
+// is written as n + 1 characters.
+pub(crate) fn encode_ascii85_internal(data: &[u8], delimiters: bool, space_runs: bool) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(4) * 5 + PREFIX.len() + SUFFIX.len());
+    if delimiters {
+        out.push_str(PREFIX);
+    }
+    for word in data.chunks(4) {
+        let mut padded = [0u8; 4];
+        padded[..word.len()].copy_from_slice(word);
+        let value = u32::from_be_bytes(padded);
+        if word.len() == 4 && value == 0 {
+            out.push('z');
+            continue;
+        }
+        if word.len() == 4 && space_runs && value == 0x2020_2020 {
+            out.push('y');
+            continue;
+        }
+
+        let mut group = [0u8; 5];
+        let mut value = value;
+        for slot in group.iter_mut().rev() {
+            *slot = FIRST_DIGIT + (value % 85) as u8;
+            value /= 85;
+        }
+        out.extend(group[..word.len() + 1].iter().map(|&c| c as char));
+    }
+    if delimiters {
+        out.push_str(SUFFIX);
+    }
+    out
+}
+
+// Decode a group of up to 5 digits, padding short groups with `u` (digit 84)
+fn decode_group(digits: &[u64], group_offset: usize) -> Result<[u8; 4], Error> {
+    let value = (0..5).fold(0u64, |value, index| value * 85 + digits.get(index).copied().unwrap_or(84));
+    let value = u32::try_from(value)
+        .map_err(|_| Error::new(ErrorCode::InvalidGroup, format!("Ascii85 decode error: invalid group at offset {}", group_offset)).at(group_offset))?;
+    Ok(value.to_be_bytes())
+}
+
+// Decode Adobe Ascii85, with or without the `<~` `~>` delimiters. Whitespace is ignored
+// and both the `z` and `y` shortcuts are accepted. Error offsets refer to the input.
+pub(crate) fn decode_ascii85_internal(text: &str) -> Result<Vec<u8>, Error> {
+    let start = text.len() - text.trim_start().len();
+    let body = text.trim_end();
+    let (start, body) = match body[start..].strip_prefix(PREFIX) {
+        Some(rest) => {
+            let rest = rest.strip_suffix(SUFFIX)
+                .ok_or_else(|| Error::new(ErrorCode::InvalidFormat, "Ascii85 decode error: missing '~>' end marker"))?;
+            (start + PREFIX.len(), rest)
+        }
+        None => (start, body[start..].strip_suffix(SUFFIX).unwrap_or(&body[start..])),
+    };
+
+    let mut out = Vec::with_capacity(body.len() / 5 * 4 + 4);
+    let mut digits = [0u64; 5];
+    let mut count = 0;
+    let mut group_offset = start;
+    for (index, c) in body.bytes().enumerate() {
+        let offset = start + index;
+        match c {
+            c if c.is_ascii_whitespace() => continue,
+            b'z' | b'y' if count == 0 => {
+                out.extend_from_slice(if c == b'z' { &[0; 4] } else { b"    " });
+                continue;
+            }
+            b'!'..=b'u' => {}
+            _ => return Err(Error::new(ErrorCode::InvalidChar, format!("Ascii85 decode error: invalid character at offset {}", offset)).at(offset)),
+        }
+        if count == 0 {
+            group_offset = offset;
+        }
+        digits[count] = (c - FIRST_DIGIT) as u64;
+        count += 1;
+        if count == 5 {
+            out.extend_from_slice(&decode_group(&digits, group_offset)?);
+            count = 0;
+        }
+    }
+
+    // A final partial group of n + 1 characters carries n bytes
+    match count {
+        0 => {}
+        1 => return Err(Error::new(ErrorCode::InvalidLength, format!("Ascii85 decode error: truncated group at offset {}", group_offset)).at(group_offset)),
+        _ => out.extend_from_slice(&decode_group(&digits[..count], group_offset)?[..count - 1]),
+    }
+    Ok(out)
+}
+
+/// Encode raw bytes as Adobe Ascii85 (as embedded in PostScript and PDF streams).
+/// `delimiters` (default `true`) wraps the output in `<~` `~>`; `space_runs` (default `false`)
+/// writes `y` for groups of four spaces. All-zero groups are always written as `z`.
+#[wasm_bindgen]
+pub fn encode_ascii85(data: &[u8], delimiters: Option<bool>, space_runs: Option<bool>) -> Result<String, JsValue> {
+    limits::enforce(data.len(), String::len, || Ok(encode_ascii85_internal(data, delimiters.unwrap_or(true), space_runs.unwrap_or(false))))
+        .map_err(js_error)
+}
+
+/// Decode Adobe Ascii85 to raw bytes. The `<~` `~>` delimiters are optional, whitespace is
+/// ignored and the `z` and `y` shortcuts are expanded.
+#[wasm_bindgen]
+pub fn decode_ascii85(text: &str) -> Result<Vec<u8>, JsValue> {
+    limits::enforce(text.len(), Vec::len, || decode_ascii85_internal(text))
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii85_known_vectors() {
+        assert_eq!(encode_ascii85_internal(b"Man is distinguished", true, false), "<~9jqo^BlbD-BleB1DJ+*+F(f,q~>");
+        assert_eq!(encode_ascii85_internal(b"\0\0\0\0\0", false, false), "z!!");
+        assert_eq!(encode_ascii85_internal(b"    !", false, false), "+<VdL+T");
+        assert_eq!(encode_ascii85_internal(b"    !", false, true), "y+T");
+        assert_eq!(encode_ascii85_internal(b"", true, false), "<~~>");
+        assert_eq!(decode_ascii85_internal("<~9jqo^BlbD-BleB1DJ+*+F(f,q~>").unwrap(), b"Man is distinguished");
+    }
+
+    #[test]
+    fn test_ascii85_roundtrip() {
+        for size in 0..=13 {
+            let data: Vec<u8> = (0..size).map(|i| if i % 5 == 0 { 0 } else { (i * 47) as u8 }).chain([0; 4]).collect();
+            for (delimiters, space_runs) in [(true, false), (false, true)] {
+                let encoded = encode_ascii85_internal(&data, delimiters, space_runs);
+                assert_eq!(decode_ascii85_internal(&encoded).unwrap(), data, "size {}", size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ascii85_lenient_input() {
+        assert_eq!(decode_ascii85_internal("  <~9jqo^\r\nBlbD-\n BleB1DJ+*+F(f,q~>\n").unwrap(), b"Man is distinguished");
+        assert_eq!(decode_ascii85_internal("9jqo^BlbD-BleB1DJ+*+F(f,q~>").unwrap(), b"Man is distinguished");
+        assert_eq!(decode_ascii85_internal("zy").unwrap(), b"\0\0\0\0    ");
+    }
+
+    #[test]
+    fn test_ascii85_errors() {
+        assert_eq!(decode_ascii85_internal("<~9jqo^").err().unwrap().message, "Ascii85 decode error: missing '~>' end marker");
+        assert_eq!(decode_ascii85_internal("9jqo^Bl{").err().unwrap().message, "Ascii85 decode error: invalid character at offset 7");
+        assert_eq!(decode_ascii85_internal("9jz").err().unwrap().message, "Ascii85 decode error: invalid character at offset 2");
+        assert_eq!(decode_ascii85_internal("<~9jqo^B~>").err().unwrap().message, "Ascii85 decode error: truncated group at offset 7");
+        assert_eq!(decode_ascii85_internal("uuuuu").err().unwrap().message, "Ascii85 decode error: invalid group at offset 0");
+    }
+}
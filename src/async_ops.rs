@@ -0,0 +1,104 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::AbortSignal;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::stream::Z85Encoder;
+
+/// Default slice size for the async APIs (1 MiB)
+const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024;
+
+// Yield to the host event loop with a zero-delay `setTimeout`, so rendering and input can run
+pub(crate) async fn yield_to_event_loop() -> Result<(), JsValue> {
+    let set_timeout: js_sys::Function = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("setTimeout"))?
+        .dyn_into()
+        .map_err(|_| js_error(Error::new(ErrorCode::Unsupported, "setTimeout is not available in this host")))?;
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let _ = set_timeout.call2(&JsValue::NULL, &resolve, &JsValue::from_f64(0.0));
+    });
+    JsFuture::from(promise).await?;
+    Ok(())
+}
+
+// What an aborted operation rejects with: the signal's `reason` (an `AbortError` unless the
+// caller passed one), or a `Z85Error` in hosts that predate `AbortSignal.reason`
+pub(crate) fn abort_reason(signal: &AbortSignal) -> JsValue {
+    let reason = signal.reason();
+    if reason.is_undefined() {
+        js_error(Error::new(ErrorCode::Aborted, "Operation aborted"))
+    } else {
+        reason
+    }
+}
+
+// Fail with the abort reason if `signal` has aborted
+pub(crate) fn check_aborted(signal: Option<&AbortSignal>) -> Result<(), JsValue> {
+    match signal {
+        Some(signal) if signal.aborted() => Err(abort_reason(signal)),
+        _ => Ok(()),
+    }
+}
+
+// Check an encode of `len` bytes against the limits, including its projected output, before
+// any work starts
+fn check_encode_limits(len: usize) -> Result<(), Error> {
+    limits::check_input(len)?;
+    limits::check_output(len.div_ceil(4) * 5)
+}
+
+// Resolve the slice size used by the async APIs
+fn resolve_chunk_size(chunk_size: Option<usize>) -> Result<usize, Error> {
+    match chunk_size {
+        None => Ok(DEFAULT_CHUNK_SIZE),
+        Some(0) => Err(Error::new(ErrorCode::InvalidOptions, "Chunk size must be greater than zero")),
+        Some(size) => Ok(size),
+    }
+}
+
+/// Encode raw bytes to Z85 with padding info, processing `chunk_size` bytes (default 1 MiB)
+/// at a time and yielding to the event loop between slices. Resolves to the same string as `encode_z85`.
+/// If `signal` aborts, the work stops at the next slice, its buffers are freed and the promise
+/// rejects with `signal.reason`.
+#[wasm_bindgen]
+pub async fn encode_z85_async(data: Vec<u8>, chunk_size: Option<usize>, signal: Option<AbortSignal>) -> Result<String, JsValue> {
+    let chunk_size = check_encode_limits(data.len())
+        .and_then(|()| resolve_chunk_size(chunk_size))
+        .map_err(js_error)?;
+    check_aborted(signal.as_ref())?;
+
+    let mut encoder = Z85Encoder::new();
+    let mut output = String::with_capacity(data.len().div_ceil(4) * 5 + 2);
+    for (index, chunk) in data.chunks(chunk_size).enumerate() {
+        if index > 0 {
+            yield_to_event_loop().await?;
+            check_aborted(signal.as_ref())?;
+        }
+        output.push_str(&encoder.update(chunk));
+    }
+    output.push_str(&encoder.finalize());
+    limits::check_output(output.len()).map_err(js_error)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_chunk_size() {
+        assert_eq!(resolve_chunk_size(None).unwrap(), DEFAULT_CHUNK_SIZE);
+        assert_eq!(resolve_chunk_size(Some(4096)).unwrap(), 4096);
+        assert_eq!(resolve_chunk_size(Some(0)).err().unwrap().message, "Chunk size must be greater than zero");
+    }
+
+    #[test]
+    fn test_check_encode_limits() {
+        limits::with_limits(None, Some(10), || {
+            assert!(check_encode_limits(8).is_ok());
+            let error = check_encode_limits(9).err().unwrap();
+            assert_eq!(error.code, ErrorCode::LimitExceeded);
+            assert_eq!(error.message, "Output size limit exceeded: 15 bytes (max_output_bytes is 10)");
+        });
+    }
+}
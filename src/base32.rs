@@ -0,0 +1,231 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+
+/// RFC 4648 base32 alphabet
+pub(crate) const RFC4648_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Crockford base32 alphabet: digits and uppercase letters without `I`, `L`, `O` and `U`
+const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Crockford check symbols for values 32-36 (the check symbol is the value modulo 37)
+const CROCKFORD_CHECK_SYMBOLS: &[u8; 5] = b"*~$=U";
+
+/// Remainders of a 5-bit group count modulo 8 that no whole number of bytes produces
+const INVALID_TAIL_LENGTHS: [usize; 3] = [1, 3, 6];
+
+// Encode bytes 5 bits at a time, most significant first, with the final group zero-filled.
+// With `pad`, the output is `=`-padded to a multiple of 8 characters.
+pub(crate) fn encode_base32_with(alphabet: &[u8; 32], data: &[u8], pad: bool) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(alphabet[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(alphabet[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    if pad {
+        while !out.len().is_multiple_of(8) {
+            out.push('=');
+        }
+    }
+    out
+}
+
+// Pack 5-bit digits back into bytes, dropping the zero fill of the final group
+fn pack_digits(digits: &[u8], name: &str) -> Result<Vec<u8>, Error> {
+    if INVALID_TAIL_LENGTHS.contains(&(digits.len() % 8)) {
+        return Err(Error::new(ErrorCode::InvalidLength, format!("{} decode error: invalid length ({} digits)", name, digits.len())));
+    }
+    let mut out = Vec::with_capacity(digits.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &digit in digits {
+        buffer = (buffer << 5) | digit as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Ok(out)
+}
+
+// Decode RFC 4648 base32 in either case. Trailing `=` padding is optional, but padded input
+// must be a multiple of 8 characters.
+pub(crate) fn decode_base32_internal(text: &str) -> Result<Vec<u8>, Error> {
+    let data = text.trim_end_matches('=');
+    if data.len() != text.len() && !text.len().is_multiple_of(8) {
+        return Err(Error::new(ErrorCode::InvalidPadding, format!("Base32 decode error: invalid padding (length {})", text.len())));
+    }
+    let digits = data.bytes().enumerate()
+        .map(|(offset, c)| {
+            RFC4648_ALPHABET.iter().position(|&a| a == c.to_ascii_uppercase())
+                .map(|digit| digit as u8)
+                .ok_or_else(|| Error::new(ErrorCode::InvalidChar, format!("Base32 decode error: invalid character at offset {}", offset)).at(offset))
+        })
+        .collect::<Result<Vec<u8>, Error>>()?;
+    pack_digits(&digits, "Base32")
+}
+
+// Map a Crockford symbol to its value: case-insensitive, with `I`/`L` read as 1 and `O` as 0
+fn crockford_value(c: u8) -> Option<u8> {
+    match c.to_ascii_uppercase() {
+        b'I' | b'L' => Some(1),
+        b'O' => Some(0),
+        c => CROCKFORD_ALPHABET.iter().position(|&a| a == c).map(|digit| digit as u8),
+    }
+}
+
+// Check value of a digit string: the number it spells, modulo 37
+fn crockford_check(digits: &[u8]) -> u8 {
+    digits.iter().fold(0u32, |check, &digit| (check * 32 + digit as u32) % 37) as u8
+}
+
+// Encode bytes as Crockford base32 (uppercase, unpadded), optionally followed by a check symbol
+pub(crate) fn encode_base32_crockford_internal(data: &[u8], check_symbol: bool) -> String {
+    let mut out = encode_base32_with(CROCKFORD_ALPHABET, data, false);
+    if check_symbol {
+        let digits: Vec<u8> = out.bytes().filter_map(crockford_value).collect();
+        let check = crockford_check(&digits) as usize;
+        out.push(*CROCKFORD_ALPHABET.iter().chain(CROCKFORD_CHECK_SYMBOLS).nth(check).unwrap() as char);
+    }
+    out
+}
+
+// Decode Crockford base32. Case is ignored, as are `-` separators; `I`/`L` and `O` are read as
+// 1 and 0. With `check_symbol`, the last symbol is verified and removed. Error offsets refer
+// to the input.
+pub(crate) fn decode_base32_crockford_internal(text: &str, check_symbol: bool) -> Result<Vec<u8>, Error> {
+    let mut body = text.trim_end_matches('-');
+    let mut expected_check = None;
+    if check_symbol {
+        let index = body.len().checked_sub(1)
+            .ok_or_else(|| Error::new(ErrorCode::InvalidFormat, "Crockford Base32 decode error: missing check symbol"))?;
+        let c = body.as_bytes()[index];
+        expected_check = Some(
+            CROCKFORD_CHECK_SYMBOLS.iter().position(|&s| s == c.to_ascii_uppercase())
+                .map(|position| 32 + position as u8)
+                .or_else(|| crockford_value(c))
+                .ok_or_else(|| Error::new(ErrorCode::InvalidChar, format!("Crockford Base32 decode error: invalid check symbol at offset {}", index)).at(index))?,
+        );
+        body = body.get(..index)
+            .ok_or_else(|| Error::new(ErrorCode::InvalidChar, format!("Crockford Base32 decode error: invalid check symbol at offset {}", index)).at(index))?;
+    }
+
+    let digits = body.bytes().enumerate()
+        .filter(|&(_, c)| c != b'-')
+        .map(|(offset, c)| {
+            crockford_value(c)
+                .ok_or_else(|| Error::new(ErrorCode::InvalidChar, format!("Crockford Base32 decode error: invalid character at offset {}", offset)).at(offset))
+        })
+        .collect::<Result<Vec<u8>, Error>>()?;
+    if expected_check.is_some_and(|check| check != crockford_check(&digits)) {
+        return Err(Error::new(ErrorCode::ChecksumMismatch, "Crockford Base32 decode error: check symbol mismatch"));
+    }
+    pack_digits(&digits, "Crockford Base32")
+}
+
+/// Encode raw bytes as RFC 4648 base32 (uppercase, `=` padded)
+#[wasm_bindgen]
+pub fn encode_base32(data: &[u8]) -> Result<String, JsValue> {
+    limits::enforce(data.len(), String::len, || Ok(encode_base32_with(RFC4648_ALPHABET, data, true)))
+        .map_err(js_error)
+}
+
+/// Decode RFC 4648 base32 to raw bytes. Either case is accepted and `=` padding is optional,
+/// as in TOTP secrets.
+#[wasm_bindgen]
+pub fn decode_base32(text: &str) -> Result<Vec<u8>, JsValue> {
+    limits::enforce(text.len(), Vec::len, || decode_base32_internal(text))
+        .map_err(js_error)
+}
+
+/// Encode raw bytes as Crockford base32. With `check_symbol` (default `false`), a mod-37 check
+/// symbol is appended.
+#[wasm_bindgen]
+pub fn encode_base32_crockford(data: &[u8], check_symbol: Option<bool>) -> Result<String, JsValue> {
+    limits::enforce(data.len(), String::len, || Ok(encode_base32_crockford_internal(data, check_symbol.unwrap_or(false))))
+        .map_err(js_error)
+}
+
+/// Decode Crockford base32 to raw bytes, ignoring case and `-` separators. With `check_symbol`
+/// (default `false`), the trailing check symbol is verified.
+#[wasm_bindgen]
+pub fn decode_base32_crockford(text: &str, check_symbol: Option<bool>) -> Result<Vec<u8>, JsValue> {
+    limits::enforce(text.len(), Vec::len, || decode_base32_crockford_internal(text, check_symbol.unwrap_or(false)))
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base32_rfc_vectors() {
+        // RFC 4648 section 10
+        let vectors = [
+            ("", ""), ("f", "MY======"), ("fo", "MZXQ===="), ("foo", "MZXW6==="),
+            ("foob", "MZXW6YQ="), ("fooba", "MZXW6YTB"), ("foobar", "MZXW6YTBOI======"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(encode_base32_with(RFC4648_ALPHABET, plain.as_bytes(), true), encoded);
+            assert_eq!(decode_base32_internal(encoded).unwrap(), plain.as_bytes());
+        }
+        // TOTP secrets are commonly lowercase and unpadded
+        assert_eq!(decode_base32_internal("mzxw6ytboi").unwrap(), b"foobar");
+    }
+
+    #[test]
+    fn test_base32_errors() {
+        assert_eq!(decode_base32_internal("MZXW6YT1").err().unwrap().message, "Base32 decode error: invalid character at offset 7");
+        assert_eq!(decode_base32_internal("MZX").err().unwrap().message, "Base32 decode error: invalid length (3 digits)");
+        assert_eq!(decode_base32_internal("MZXQ==").err().unwrap().message, "Base32 decode error: invalid padding (length 6)");
+    }
+
+    #[test]
+    fn test_crockford_roundtrip() {
+        for size in 0..24 {
+            let data: Vec<u8> = (0..size).map(|i| (i * 71 + size) as u8).collect();
+            for check_symbol in [false, true] {
+                let encoded = encode_base32_crockford_internal(&data, check_symbol);
+                assert_eq!(decode_base32_crockford_internal(&encoded, check_symbol).unwrap(), data, "size {}", size);
+                assert_eq!(decode_base32_crockford_internal(&encoded.to_lowercase(), check_symbol).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_crockford_symbols() {
+        assert_eq!(encode_base32_crockford_internal(b"foobar", false), "CSQPYRK1E8");
+        // Ambiguous letters, lowercase and hyphens all read as the canonical digits
+        assert_eq!(decode_base32_crockford_internal("csqp-yrki-e8", false).unwrap(), b"foobar");
+        assert_eq!(decode_base32_crockford_internal("CSQPYRKLE8", false).unwrap(), b"foobar");
+        assert_eq!(decode_base32_crockford_internal("0O", false).unwrap(), b"\0");
+
+        // The check symbol is the encoded number modulo 37, using `*~$=U` for 32-36
+        assert_eq!(encode_base32_crockford_internal(b"\0", true), "000");
+        assert_eq!(encode_base32_crockford_internal(b"\x08", true), "10*");
+        assert_eq!(crockford_check(&[1, 0, 0]) as u32, 1024 % 37);
+        assert_eq!(decode_base32_crockford_internal("10*", true).unwrap(), b"\x08");
+        assert_eq!(decode_base32_crockford_internal("10u", true).err().unwrap().message, "Crockford Base32 decode error: check symbol mismatch");
+    }
+
+    #[test]
+    fn test_crockford_errors() {
+        assert_eq!(decode_base32_crockford_internal("CSQPU", false).err().unwrap().message, "Crockford Base32 decode error: invalid character at offset 4");
+        assert_eq!(decode_base32_crockford_internal("", true).err().unwrap().message, "Crockford Base32 decode error: missing check symbol");
+        assert_eq!(decode_base32_crockford_internal("10!", true).err().unwrap().message, "Crockford Base32 decode error: invalid check symbol at offset 2");
+        let encoded = encode_base32_crockford_internal(b"foobar", true);
+        let tampered = format!("D{}", &encoded[1..]);
+        assert_eq!(decode_base32_crockford_internal(&tampered, true).err().unwrap().message, "Crockford Base32 decode error: check symbol mismatch");
+    }
+}
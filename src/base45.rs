@@ -0,0 +1,164 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::encode_z85_internal;
+
+/// Base45 alphabet (RFC 9285), a subset of the QR alphanumeric character set
+const BASE45_ALPHABET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// QR mode indicator length in bits
+const QR_MODE_BITS: usize = 4;
+
+/// QR character count indicator widths for versions 1-9, 10-26 and 27-40
+const QR_BYTE_COUNT_BITS: [usize; 3] = [8, 16, 16];
+const QR_ALPHANUMERIC_COUNT_BITS: [usize; 3] = [9, 11, 13];
+
+// Encode bytes as Base45: each pair of bytes becomes three characters, a final odd byte two
+pub(crate) fn encode_base45_internal(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(2) * 3);
+    for pair in data.chunks(2) {
+        let mut value = pair.iter().fold(0u32, |value, &byte| value << 8 | byte as u32);
+        let digits = if pair.len() == 2 { 3 } else { 2 };
+        for _ in 0..digits {
+            out.push(BASE45_ALPHABET[(value % 45) as usize] as char);
+            value /= 45;
+        }
+    }
+    out
+}
+
+// Decode Base45, reporting the offset of the first invalid character or group
+pub(crate) fn decode_base45_internal(text: &str) -> Result<Vec<u8>, Error> {
+    let chars = text.as_bytes();
+    if chars.len() % 3 == 1 {
+        return Err(Error::new(ErrorCode::InvalidLength, format!("Base45 decode error: invalid length ({})", chars.len())));
+    }
+    let mut out = Vec::with_capacity(chars.len() / 3 * 2 + 1);
+    for (index, group) in chars.chunks(3).enumerate() {
+        let offset = index * 3;
+        let mut value = 0u32;
+        for (position, &c) in group.iter().enumerate().rev() {
+            let digit = BASE45_ALPHABET.iter().position(|&a| a == c)
+                .ok_or_else(|| Error::new(ErrorCode::InvalidChar, format!("Base45 decode error: invalid character at offset {}", offset + position)).at(offset + position))?;
+            value = value * 45 + digit as u32;
+        }
+        match group.len() {
+            3 if value <= 0xffff => out.extend_from_slice(&(value as u16).to_be_bytes()),
+            2 if value <= 0xff => out.push(value as u8),
+            _ => return Err(Error::new(ErrorCode::InvalidGroup, format!("Base45 decode error: invalid group at offset {}", offset)).at(offset)),
+        }
+    }
+    Ok(out)
+}
+
+// Bits of a single QR segment: mode indicator, character count indicator and data.
+// The count indicator is the narrowest one (by version range) that can hold `chars`.
+fn qr_segment_bits(chars: usize, count_bits: [usize; 3], data_bits: usize) -> usize {
+    let count_bits = count_bits.into_iter()
+        .find(|&bits| chars < 1 << bits)
+        .unwrap_or(count_bits[2]);
+    QR_MODE_BITS + count_bits + data_bits
+}
+
+/// Encoded sizes of a payload as Z85 in QR byte mode and as Base45 in QR alphanumeric mode
+#[derive(Serialize, Tsify)]
+struct QrSizeComparison {
+    z85_chars: usize,
+    z85_bits: usize,
+    base45_chars: usize,
+    base45_bits: usize,
+    #[tsify(type = "\"z85\" | \"base45\"")]
+    recommended: &'static str,
+}
+
+// Internal pure Rust function for comparing the QR segment sizes of both encodings
+fn compare_qr_encodings_internal(data: &[u8]) -> QrSizeComparison {
+    let z85_chars = encode_z85_internal(data).len();
+    let z85_bits = qr_segment_bits(z85_chars, QR_BYTE_COUNT_BITS, z85_chars * 8);
+
+    // Alphanumeric mode packs two characters into 11 bits and a final odd one into 6
+    let base45_chars = data.len() / 2 * 3 + data.len() % 2 * 2;
+    let base45_bits = qr_segment_bits(base45_chars, QR_ALPHANUMERIC_COUNT_BITS, base45_chars / 2 * 11 + base45_chars % 2 * 6);
+
+    QrSizeComparison {
+        z85_chars,
+        z85_bits,
+        base45_chars,
+        base45_bits,
+        recommended: if base45_bits < z85_bits { "base45" } else { "z85" },
+    }
+}
+
+/// Encode raw bytes as Base45 (RFC 9285) for QR codes in alphanumeric mode
+#[wasm_bindgen]
+pub fn encode_base45(data: &[u8]) -> Result<String, JsValue> {
+    limits::enforce(data.len(), String::len, || Ok(encode_base45_internal(data)))
+        .map_err(js_error)
+}
+
+/// Decode Base45 (RFC 9285) to raw bytes
+#[wasm_bindgen]
+pub fn decode_base45(text: &str) -> Result<Vec<u8>, JsValue> {
+    limits::enforce(text.len(), Vec::len, || decode_base45_internal(text))
+        .map_err(js_error)
+}
+
+/// Compare the QR segment size of `data` as Z85 in byte mode with Base45 in alphanumeric mode.
+/// Returns `{ z85_chars, z85_bits, base45_chars, base45_bits, recommended: "z85" | "base45" }`;
+/// the segment with fewer bits needs the same or a smaller QR version.
+#[wasm_bindgen(unchecked_return_type = "QrSizeComparison")]
+pub fn compare_qr_encodings(data: &[u8]) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&compare_qr_encodings_internal(data))
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base45_rfc_vectors() {
+        // Examples from RFC 9285 section 4.3
+        assert_eq!(encode_base45_internal(b"AB"), "BB8");
+        assert_eq!(encode_base45_internal(b"Hello!!"), "%69 VD92EX0");
+        assert_eq!(encode_base45_internal(b"base-45"), "UJCLQE7W581");
+        assert_eq!(decode_base45_internal("QED8WEX0").unwrap(), b"ietf!");
+        assert_eq!(encode_base45_internal(b""), "");
+    }
+
+    #[test]
+    fn test_base45_roundtrip() {
+        for size in 0..16 {
+            let data: Vec<u8> = (0..size).map(|i| (i * 97 + 255) as u8).collect();
+            assert_eq!(decode_base45_internal(&encode_base45_internal(&data)).unwrap(), data, "size {}", size);
+        }
+        assert_eq!(decode_base45_internal(&encode_base45_internal(&[0xff; 4])).unwrap(), [0xff; 4]);
+    }
+
+    #[test]
+    fn test_base45_errors() {
+        assert_eq!(decode_base45_internal("GGW").err().unwrap().message, "Base45 decode error: invalid group at offset 0");
+        assert_eq!(decode_base45_internal("BB8a").err().unwrap().message, "Base45 decode error: invalid length (4)");
+        assert_eq!(decode_base45_internal("BB8ab").err().unwrap().message, "Base45 decode error: invalid character at offset 4");
+        assert_eq!(decode_base45_internal("ZZ").err().unwrap().message, "Base45 decode error: invalid group at offset 0");
+    }
+
+    #[test]
+    fn test_compare_qr_encodings() {
+        // 4 bytes: 7 Z85 chars (with ":0") in byte mode vs 6 Base45 chars in alphanumeric mode
+        let small = compare_qr_encodings_internal(b"1234");
+        assert_eq!((small.z85_chars, small.z85_bits), (7, 4 + 8 + 56));
+        assert_eq!((small.base45_chars, small.base45_bits), (6, 4 + 9 + 33));
+        assert_eq!(small.recommended, "base45");
+
+        // Count indicators widen with the payload
+        let large = compare_qr_encodings_internal(&[0x5a; 400]);
+        assert_eq!(large.z85_chars, 502);
+        assert_eq!(large.z85_bits, 4 + 16 + 502 * 8);
+        assert_eq!(large.base45_chars, 600);
+        assert_eq!(large.base45_bits, 4 + 11 + 300 * 11);
+    }
+}
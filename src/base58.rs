@@ -0,0 +1,126 @@
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, ErrorCode};
+
+/// Bitcoin Base58 alphabet: alphanumerics without `0`, `O`, `I` and `l`
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Length of the Base58Check checksum (leading bytes of a double SHA-256)
+const CHECKSUM_LEN: usize = 4;
+
+// Encode bytes as Base58. Each leading zero byte becomes a leading `1`; the rest is
+// converted as one big-endian number.
+pub(crate) fn encode_base58(data: &[u8]) -> String {
+    let zeros = data.iter().take_while(|&&b| b == 0).count();
+    // Little-endian base-58 digits of the value after the leading zeros
+    let mut digits: Vec<u8> = Vec::with_capacity((data.len() - zeros) * 138 / 100 + 1);
+    for &byte in &data[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    std::iter::repeat_n('1', zeros)
+        .chain(digits.iter().rev().map(|&d| BASE58_ALPHABET[d as usize] as char))
+        .collect()
+}
+
+// Decode Base58, reporting the offset of the first character outside the alphabet
+pub(crate) fn decode_base58(text: &str) -> Result<Vec<u8>, Error> {
+    let chars = text.as_bytes();
+    let zeros = chars.iter().take_while(|&&c| c == b'1').count();
+    // Little-endian bytes of the value after the leading `1`s
+    let mut bytes: Vec<u8> = Vec::with_capacity((chars.len() - zeros) * 733 / 1000 + 1);
+    for (offset, &c) in chars.iter().enumerate().skip(zeros) {
+        let mut carry = BASE58_ALPHABET.iter().position(|&a| a == c)
+            .ok_or_else(|| Error::new(ErrorCode::InvalidChar, format!("Base58 decode error: invalid character at offset {}", offset)).at(offset))? as u32;
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    Ok(std::iter::repeat_n(0, zeros).chain(bytes.into_iter().rev()).collect())
+}
+
+// First four bytes of SHA-256(SHA-256(payload))
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let digest = Sha256::digest(Sha256::digest(payload));
+    let mut checksum = [0u8; CHECKSUM_LEN];
+    checksum.copy_from_slice(&digest[..CHECKSUM_LEN]);
+    checksum
+}
+
+// Encode bytes as Base58Check: the payload followed by its 4-byte checksum. Version bytes,
+// if any, are part of the payload.
+pub(crate) fn encode_base58check(payload: &[u8]) -> String {
+    let mut data = Vec::with_capacity(payload.len() + CHECKSUM_LEN);
+    data.extend_from_slice(payload);
+    data.extend_from_slice(&checksum(payload));
+    encode_base58(&data)
+}
+
+// Decode Base58Check and verify its checksum, returning the payload (version bytes included)
+pub(crate) fn decode_base58check(text: &str) -> Result<Vec<u8>, Error> {
+    let mut data = decode_base58(text)?;
+    if data.len() < CHECKSUM_LEN {
+        return Err(Error::new(ErrorCode::InvalidLength, format!("Base58Check decode error: too short for a checksum ({} bytes)", data.len())));
+    }
+    let payload_len = data.len() - CHECKSUM_LEN;
+    if data[payload_len..] != checksum(&data[..payload_len]) {
+        return Err(Error::new(ErrorCode::ChecksumMismatch, "Base58Check decode error: checksum mismatch"));
+    }
+    data.truncate(payload_len);
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base58_known_vectors() {
+        assert_eq!(encode_base58(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(encode_base58(b"\0\0\x01"), "112");
+        assert_eq!(encode_base58(b""), "");
+        assert_eq!(decode_base58("2NEpo7TZRRrLZSi2U").unwrap(), b"Hello World!");
+        assert_eq!(decode_base58("111").unwrap(), [0; 3]);
+    }
+
+    #[test]
+    fn test_base58_roundtrip() {
+        for size in 0..40 {
+            let data: Vec<u8> = (0..size).map(|i| if i < size / 4 { 0 } else { (i * 53 + size) as u8 }).collect();
+            assert_eq!(decode_base58(&encode_base58(&data)).unwrap(), data, "size {}", size);
+        }
+    }
+
+    #[test]
+    fn test_base58check_addresses() {
+        // Version byte 0x00 and a zero hash160: the well-known "burn" address
+        let burn: Vec<u8> = [0u8; 21].to_vec();
+        assert_eq!(encode_base58check(&burn), "1111111111111111111114oLvT2");
+        assert_eq!(decode_base58check("1111111111111111111114oLvT2").unwrap(), burn);
+
+        let address = decode_base58check("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2").unwrap();
+        assert_eq!((address.len(), address[0]), (21, 0x00));
+        assert_eq!(encode_base58check(&address), "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2");
+    }
+
+    #[test]
+    fn test_base58_errors() {
+        assert_eq!(decode_base58("2NEpo0TZ").err().unwrap().message, "Base58 decode error: invalid character at offset 5");
+        assert_eq!(decode_base58check("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3").err().unwrap().message, "Base58Check decode error: checksum mismatch");
+        assert_eq!(decode_base58check("2NE").err().unwrap().message, "Base58Check decode error: too short for a checksum (2 bytes)");
+    }
+}
@@ -0,0 +1,111 @@
+use crate::error::{Error, ErrorCode};
+
+/// basE91 alphabet (Joachim Henke's reference implementation)
+const BASE91_ALPHABET: &[u8; 91] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789!#$%&()*+,./:;<=>?@[]^_`{|}~\"";
+
+/// Reverse lookup table: byte -> basE91 digit, or `INVALID_DIGIT`
+const DECODE_TABLE: [u8; 256] = {
+    let mut table = [INVALID_DIGIT; 256];
+    let mut i = 0;
+    while i < BASE91_ALPHABET.len() {
+        table[BASE91_ALPHABET[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+};
+
+/// Marker for bytes outside the basE91 alphabet in `DECODE_TABLE`
+const INVALID_DIGIT: u8 = 0xff;
+
+// Encode bytes as basE91: 13 or 14 bits at a time become two characters.
+// The output length is implied by the data, so no padding suffix is needed.
+pub(crate) fn encode_base91(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len() * 16 / 13 + 2);
+    let mut push = |value: u32| out.push(BASE91_ALPHABET[value as usize] as char);
+    let mut bits = 0u32;
+    let mut count = 0;
+    for &byte in data {
+        bits |= (byte as u32) << count;
+        count += 8;
+        if count > 13 {
+            let mut value = bits & 8191;
+            if value > 88 {
+                bits >>= 13;
+                count -= 13;
+            } else {
+                value = bits & 16383;
+                bits >>= 14;
+                count -= 14;
+            }
+            push(value % 91);
+            push(value / 91);
+        }
+    }
+    if count > 0 {
+        push(bits % 91);
+        if count > 7 || bits > 90 {
+            push(bits / 91);
+        }
+    }
+    out
+}
+
+// Decode basE91, reporting the offset of the first character outside the alphabet
+pub(crate) fn decode_base91(text: &str) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::with_capacity(text.len() * 13 / 16 + 1);
+    let mut pending: Option<u32> = None;
+    let mut bits = 0u32;
+    let mut count = 0;
+    for (offset, c) in text.bytes().enumerate() {
+        let digit = match DECODE_TABLE[c as usize] {
+            INVALID_DIGIT => return Err(Error::new(ErrorCode::InvalidChar, format!("basE91 decode error: invalid character at offset {}", offset)).at(offset)),
+            digit => digit as u32,
+        };
+        let Some(low) = pending.take() else {
+            pending = Some(digit);
+            continue;
+        };
+        let value = low + digit * 91;
+        bits |= value << count;
+        count += if value & 8191 > 88 { 13 } else { 14 };
+        while count > 7 {
+            out.push(bits as u8);
+            bits >>= 8;
+            count -= 8;
+        }
+    }
+    if let Some(low) = pending {
+        out.push((bits | low << count) as u8);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base91_known_vectors() {
+        assert_eq!(encode_base91(b"test"), "fPNKd");
+        assert_eq!(encode_base91(b"Hello, World!"), ">OwJh>}AQ;r@@Y?F");
+        assert_eq!(encode_base91(b""), "");
+        assert_eq!(decode_base91(">OwJh>}AQ;r@@Y?F").unwrap(), b"Hello, World!");
+    }
+
+    #[test]
+    fn test_base91_roundtrip() {
+        for size in 0..64 {
+            let data: Vec<u8> = (0..size).map(|i| (i * 89 + size) as u8).collect();
+            assert_eq!(decode_base91(&encode_base91(&data)).unwrap(), data, "size {}", size);
+        }
+        let zeros = vec![0u8; 1000];
+        assert_eq!(decode_base91(&encode_base91(&zeros)).unwrap(), zeros);
+    }
+
+    #[test]
+    fn test_base91_errors() {
+        assert_eq!(decode_base91("fPN Kd").err().unwrap().message, "basE91 decode error: invalid character at offset 3");
+        assert_eq!(decode_base91("fP-").err().unwrap().message, "basE91 decode error: invalid character at offset 2");
+    }
+}
@@ -0,0 +1,277 @@
+use wasm_bindgen::prelude::*;
+
+use crate::compression::Compression;
+use crate::encoding::{transcode_internal, Encoding};
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{decode_z85_internal, encode_z85_with_options_internal, telemetry, ConversionOptions, DataType, PaddingFormat};
+
+// Marshalling helpers that move a whole batch across the JS↔WASM boundary as one buffer or
+// string plus item lengths, instead of one crossing per item
+#[wasm_bindgen(inline_js = "
+export function concat_bytes(items) {
+    const lengths = Uint32Array.from(items, (item) => item.length);
+    const bytes = new Uint8Array(lengths.reduce((total, length) => total + length, 0));
+    let offset = 0;
+    for (const item of items) {
+        bytes.set(item, offset);
+        offset += item.length;
+    }
+    return [bytes, lengths];
+}
+export function split_bytes(view, lengths) {
+    // `view` is WASM memory that is freed on return: copy it once, then hand out subarrays
+    const bytes = view.slice();
+    const items = new Array(lengths.length);
+    let offset = 0;
+    lengths.forEach((length, index) => {
+        items[index] = bytes.subarray(offset, offset + length);
+        offset += length;
+    });
+    return items;
+}
+export function concat_strings(items) {
+    if (items.some((item) => typeof item !== 'string')) {
+        return [undefined, undefined];
+    }
+    return [items.join(''), Uint32Array.from(items, (item) => item.length)];
+}
+export function split_strings(joined, lengths) {
+    const items = new Array(lengths.length);
+    let offset = 0;
+    lengths.forEach((length, index) => {
+        items[index] = joined.slice(offset, offset + length);
+        offset += length;
+    });
+    return items;
+}
+")]
+extern "C" {
+    fn concat_bytes(items: &js_sys::Array) -> js_sys::Array;
+    fn split_bytes(bytes: &[u8], lengths: &[u32]) -> js_sys::Array;
+    fn concat_strings(items: &js_sys::Array) -> js_sys::Array;
+    fn split_strings(joined: &str, lengths: &[u32]) -> js_sys::Array;
+}
+
+// Split a concatenated batch back into its items
+pub(crate) fn split_items<'a>(bytes: &'a [u8], lengths: &[u32]) -> Result<Vec<&'a [u8]>, Error> {
+    let mut rest = bytes;
+    lengths.iter()
+        .map(|&length| {
+            let item = rest.get(..length as usize)
+                .ok_or_else(|| Error::new(ErrorCode::InvalidInput, "Invalid batch: item lengths exceed the data"))?;
+            rest = &rest[item.len()..];
+            Ok(item)
+        })
+        .collect()
+}
+
+// UTF-8 byte lengths of the items of a joined string, given their lengths in UTF-16 code units
+// as JS counts them
+fn utf8_lengths(joined: &str, utf16_lengths: &[u32]) -> Vec<u32> {
+    if joined.is_ascii() {
+        return utf16_lengths.to_vec();
+    }
+    let mut chars = joined.chars();
+    utf16_lengths.iter()
+        .map(|&length| {
+            let (mut units, mut bytes) = (0, 0);
+            while units < length as usize {
+                let Some(c) = chars.next() else { break };
+                units += c.len_utf16();
+                bytes += c.len_utf8();
+            }
+            bytes as u32
+        })
+        .collect()
+}
+
+// Copy an array of `Uint8Array`s into WASM memory as one buffer and the item lengths
+fn bytes_from_js(items: &js_sys::Array) -> (Vec<u8>, Vec<u32>) {
+    let parts = concat_bytes(items);
+    (js_sys::Uint8Array::new(&parts.get(0)).to_vec(), js_sys::Uint32Array::new(&parts.get(1)).to_vec())
+}
+
+// Copy an array of strings into WASM memory as one string and the item byte lengths
+pub(crate) fn strings_from_js(items: &js_sys::Array) -> Result<(String, Vec<u32>), Error> {
+    let parts = concat_strings(items);
+    let joined = parts.get(0).as_string()
+        .ok_or_else(|| Error::new(ErrorCode::InvalidInput, "Invalid batch: items must be strings"))?;
+    let lengths = utf8_lengths(&joined, &js_sys::Uint32Array::new(&parts.get(1)).to_vec());
+    Ok((joined, lengths))
+}
+
+/// Results of a batch, concatenated, with the length of each
+pub(crate) type Joined<T> = (T, Vec<u32>);
+
+/// Output of a batch conversion: results are appended to one string or buffer
+trait Output: Default + Send {
+    fn byte_len(&self) -> usize;
+    fn append(&mut self, other: Self);
+}
+
+impl Output for String {
+    fn byte_len(&self) -> usize {
+        self.len()
+    }
+
+    fn append(&mut self, other: String) {
+        self.push_str(&other);
+    }
+}
+
+impl Output for Vec<u8> {
+    fn byte_len(&self) -> usize {
+        self.len()
+    }
+
+    fn append(&mut self, mut other: Vec<u8>) {
+        Vec::append(self, &mut other);
+    }
+}
+
+// Append the result of `convert` for every item to one output, naming the failing item in the
+// error. Item indexes start at `first`.
+fn convert_run<T: Output>(first: usize, items: &[&[u8]], convert: &impl Fn(&[u8], &mut T) -> Result<(), Error>) -> Result<Joined<T>, Error> {
+    let mut output = T::default();
+    let mut lengths = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let start = output.byte_len();
+        convert(item, &mut output).map_err(|e| Error { message: format!("Batch item {}: {}", first + index, e), ..e })?;
+        lengths.push((output.byte_len() - start) as u32);
+    }
+    Ok((output, lengths))
+}
+
+// Convert every item, splitting large batches across the thread pool in `threads` builds
+fn convert_all<T: Output>(items: &[&[u8]], convert: impl Fn(&[u8], &mut T) -> Result<(), Error> + Sync) -> Result<Joined<T>, Error> {
+    #[cfg(feature = "threads")]
+    let runs = if items.iter().map(|item| item.len()).sum::<usize>() >= crate::parallel::PARALLEL_THRESHOLD {
+        crate::parallel::map_runs(items, |first, run| convert_run(first, run, &convert))?
+    } else {
+        vec![convert_run(0, items, &convert)?]
+    };
+    #[cfg(not(feature = "threads"))]
+    let runs = vec![convert_run(0, items, &convert)?];
+
+    let mut runs = runs.into_iter();
+    let mut joined = runs.next().unwrap_or_default();
+    for (output, lengths) in runs {
+        joined.0.append(output);
+        joined.1.extend(lengths);
+    }
+    Ok(joined)
+}
+
+// Items of a string batch. They were JS strings, so they are valid UTF-8 unless a length split one.
+fn as_text(item: &[u8]) -> Result<&str, Error> {
+    std::str::from_utf8(item).map_err(|_| Error::new(ErrorCode::InvalidInput, "Invalid batch: item lengths split a character"))
+}
+
+// Internal pure Rust function for encoding many buffers at once
+fn encode_z85_batch_internal(items: &[&[u8]], compression: Compression) -> Result<Joined<String>, Error> {
+    convert_all(items, |item, output: &mut String| {
+        output.push_str(&encode_z85_with_options_internal(item, PaddingFormat::Count, compression));
+        Ok(())
+    })
+}
+
+// Internal pure Rust function for decoding many Z85 strings at once
+pub(crate) fn decode_z85_batch_internal(items: &[&[u8]]) -> Result<Joined<Vec<u8>>, Error> {
+    convert_all(items, |item, output: &mut Vec<u8>| {
+        output.extend_from_slice(&decode_z85_internal(as_text(item)?)?);
+        Ok(())
+    })
+}
+
+// Internal pure Rust function for transcoding many strings at once
+fn transcode_batch_internal(items: &[&[u8]], from: Encoding, to: Encoding, options: &ConversionOptions) -> Result<Joined<String>, Error> {
+    convert_all(items, |item, output: &mut String| {
+        output.push_str(&transcode_internal(as_text(item)?, from, to, options)?);
+        Ok(())
+    })
+}
+
+/// Encode many buffers in one call, as `encode_z85` would encode each (without progress
+/// reporting). The batch crosses the JS↔WASM boundary as one buffer and one string rather
+/// than once per item, which matters for thousands of small inputs.
+#[wasm_bindgen(unchecked_return_type = "string[]")]
+pub fn encode_z85_batch(
+    #[wasm_bindgen(unchecked_param_type = "Uint8Array[]")] items: js_sys::Array,
+    compression: Option<Compression>,
+) -> Result<js_sys::Array, JsValue> {
+    let (bytes, lengths) = bytes_from_js(&items);
+    let compression = compression.unwrap_or(Compression::None);
+    // Z85 and containers are ASCII, so byte lengths are also JS string lengths
+    telemetry::instrument("encode_z85_batch", bytes.len(), |(encoded, _): &Joined<String>| encoded.len(), || {
+        encode_z85_batch_internal(&split_items(&bytes, &lengths)?, compression)
+    })
+        .map(|(encoded, lengths)| split_strings(&encoded, &lengths))
+        .map_err(js_error)
+}
+
+/// Decode many Z85 strings (with padding info, or `z85v2:` containers) in one call. Fails on
+/// the first invalid item with `Batch item <index>: ...`.
+#[wasm_bindgen(unchecked_return_type = "Uint8Array[]")]
+pub fn decode_z85_batch(#[wasm_bindgen(unchecked_param_type = "string[]")] items: js_sys::Array) -> Result<js_sys::Array, JsValue> {
+    let (joined, lengths) = strings_from_js(&items).map_err(js_error)?;
+    telemetry::instrument("decode_z85_batch", joined.len(), |(decoded, _): &Joined<Vec<u8>>| decoded.len(), || {
+        decode_z85_batch_internal(&split_items(joined.as_bytes(), &lengths)?)
+    })
+        .map(|(decoded, lengths)| split_bytes(&decoded, &lengths))
+        .map_err(js_error)
+}
+
+/// Convert many strings from one encoding to another in one call, as `transcode` would
+/// convert each. Fails on the first invalid item with `Batch item <index>: ...`.
+#[wasm_bindgen(unchecked_return_type = "string[]")]
+pub fn transcode_batch(
+    #[wasm_bindgen(unchecked_param_type = "string[]")] items: js_sys::Array,
+    from: Encoding,
+    to: Encoding,
+    options: Option<ConversionOptions>,
+) -> Result<js_sys::Array, JsValue> {
+    let (joined, lengths) = strings_from_js(&items).map_err(js_error)?;
+    let opts = options.unwrap_or(ConversionOptions::new(DataType::Raw, DataType::Raw));
+    telemetry::instrument("transcode_batch", joined.len(), |(converted, _): &Joined<String>| converted.len(), || {
+        transcode_batch_internal(&split_items(joined.as_bytes(), &lengths)?, from, to, &opts)
+    })
+        .map(|(converted, lengths)| split_strings(&converted, &lengths))
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_z85_internal;
+
+    #[test]
+    fn test_batch_matches_single_calls() {
+        let items: Vec<Vec<u8>> = (0..20u8).map(|len| (0..len).collect()).collect();
+        let slices: Vec<&[u8]> = items.iter().map(Vec::as_slice).collect();
+        let (encoded, lengths) = encode_z85_batch_internal(&slices, Compression::None).unwrap();
+        let expected: Vec<String> = items.iter().map(|item| encode_z85_internal(item)).collect();
+        assert_eq!(split_items(encoded.as_bytes(), &lengths).unwrap(), expected.iter().map(String::as_bytes).collect::<Vec<_>>());
+
+        let (decoded, lengths) = decode_z85_batch_internal(&split_items(encoded.as_bytes(), &lengths).unwrap()).unwrap();
+        assert_eq!(split_items(&decoded, &lengths).unwrap(), slices);
+
+        let long = b"ab".repeat(100);
+        let (compressed, lengths) = encode_z85_batch_internal(&[&long], Compression::Deflate).unwrap();
+        assert!(compressed.starts_with("z85v2:"));
+        assert_eq!(decode_z85_batch_internal(&split_items(compressed.as_bytes(), &lengths).unwrap()).unwrap().0, long);
+
+        let opts = ConversionOptions::new(DataType::Raw, DataType::Raw);
+        let hex = transcode_batch_internal(&[b"HelloWorld:0", b"nm=QNzVx+q:3"], Encoding::Z85, Encoding::Hex, &opts).unwrap();
+        assert_eq!(hex, ("864fd26fb559f75b48656c6c6f".to_string(), vec![16, 10]));
+    }
+
+    #[test]
+    fn test_batch_errors_and_lengths() {
+        let result = decode_z85_batch_internal(&[b"HelloWorld:0", b"Hello~orld:0"]);
+        assert_eq!(result.err().unwrap().message, "Batch item 1: Z85 decode error: invalid character at offset 5");
+        assert_eq!(split_items(b"abc", &[2, 2]).err().unwrap().message, "Invalid batch: item lengths exceed the data");
+
+        // JS lengths count UTF-16 code units
+        assert_eq!(utf8_lengths("ab€😀c", &[1, 2, 2, 1]), [1, 4, 4, 1]);
+    }
+}
@@ -0,0 +1,276 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+
+/// Bech32 data alphabet (BIP 173)
+const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Generator coefficients of the BCH checksum
+const GENERATORS: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+/// Separator between the human-readable part and the data part
+const SEPARATOR: char = '1';
+
+/// Length of the checksum in data characters
+const CHECKSUM_LEN: usize = 6;
+
+/// Maximum length of the human-readable part
+const MAX_HRP_LEN: usize = 83;
+
+/// Checksum variant: original Bech32 (BIP 173) or Bech32m (BIP 350)
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bech32Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl Bech32Variant {
+    // Constant the checksum residue must equal
+    fn constant(self) -> u32 {
+        match self {
+            Bech32Variant::Bech32 => 1,
+            Bech32Variant::Bech32m => 0x2bc830a3,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Bech32Variant::Bech32 => "Bech32",
+            Bech32Variant::Bech32m => "Bech32m",
+        }
+    }
+}
+
+// Advance the checksum state by one 5-bit value
+fn polymod_step(chk: u32, value: u8) -> u32 {
+    let top = chk >> 25;
+    let mut chk = (chk & 0x1ffffff) << 5 ^ value as u32;
+    for (i, generator) in GENERATORS.iter().enumerate() {
+        if (top >> i) & 1 == 1 {
+            chk ^= generator;
+        }
+    }
+    chk
+}
+
+// Checksum state over the expanded HRP (high bits, a zero, low bits) followed by `values`
+fn polymod(hrp: &str, values: &[u8]) -> u32 {
+    let expanded = hrp.bytes().map(|c| c >> 5)
+        .chain([0])
+        .chain(hrp.bytes().map(|c| c & 31));
+    expanded.chain(values.iter().copied()).fold(1, polymod_step)
+}
+
+// Check that an HRP is 1-83 printable ASCII characters, returning it in lowercase
+pub(crate) fn validate_hrp(hrp: &str, error_prefix: &str) -> Result<String, Error> {
+    if hrp.is_empty() || hrp.len() > MAX_HRP_LEN {
+        return Err(Error::new(ErrorCode::InvalidLength, format!("{}: HRP must be 1 to {} characters, got {}", error_prefix, MAX_HRP_LEN, hrp.len())));
+    }
+    if let Some(position) = hrp.bytes().position(|c| !(33..=126).contains(&c)) {
+        return Err(Error::new(ErrorCode::InvalidChar, format!("{}: invalid HRP character at offset {}", error_prefix, position)).at(position));
+    }
+    if hrp.bytes().any(|c| c.is_ascii_lowercase()) && hrp.bytes().any(|c| c.is_ascii_uppercase()) {
+        return Err(Error::new(ErrorCode::InvalidInput, format!("{}: mixed-case HRP", error_prefix)));
+    }
+    Ok(hrp.to_ascii_lowercase())
+}
+
+// Offset within the data part of the single substitution that explains a checksum residue
+// `residue` (the polymod XOR the expected constant), if there is one. The checksum is linear,
+// so an error `e` at `k` characters from the end changes the residue by `e` advanced by `k`
+// zero steps.
+fn locate_single_error(residue: u32, data_len: usize) -> Option<usize> {
+    let mut shifted: Vec<u32> = (1..32).collect();
+    for k in 0..data_len {
+        if shifted.contains(&residue) {
+            return Some(data_len - 1 - k);
+        }
+        shifted.iter_mut().for_each(|chk| *chk = polymod_step(*chk, 0));
+    }
+    None
+}
+
+// Regroup bits between widths, most significant first. Encoding (`pad`) zero-fills the final
+// group; decoding rejects more than 4 leftover bits or leftover bits that are not zero.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity((data.len() * from as usize).div_ceil(to as usize));
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    let mask = (1 << to) - 1;
+    for &value in data {
+        buffer = (buffer << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((buffer >> bits) & mask) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            out.push(((buffer << (to - bits)) & mask) as u8);
+        }
+    } else if bits >= from || (buffer << (to - bits)) & mask != 0 {
+        return None;
+    }
+    Some(out)
+}
+
+// Encode bytes under a human-readable part, e.g. `bc1...`. Output is lowercase.
+// BIP 173's 90-character limit is for addresses and is not enforced.
+pub(crate) fn encode_bech32_internal(hrp: &str, data: &[u8], variant: Bech32Variant) -> Result<String, Error> {
+    let hrp = validate_hrp(hrp, &format!("{} encode error", variant.name()))?;
+    let mut values = convert_bits(data, 8, 5, true).unwrap_or_default();
+    let residue = polymod(&hrp, &values.iter().copied().chain([0; CHECKSUM_LEN]).collect::<Vec<u8>>()) ^ variant.constant();
+    values.extend((0..CHECKSUM_LEN).map(|i| ((residue >> (5 * (CHECKSUM_LEN - 1 - i))) & 31) as u8));
+
+    let mut out = String::with_capacity(hrp.len() + 1 + values.len());
+    out.push_str(&hrp);
+    out.push(SEPARATOR);
+    out.extend(values.iter().map(|&v| CHARSET[v as usize] as char));
+    Ok(out)
+}
+
+// Decode a Bech32 or Bech32m string into its lowercase HRP, bytes and checksum variant.
+// A checksum that fails by a single character reports that character's offset.
+pub(crate) fn decode_bech32_internal(text: &str) -> Result<(String, Vec<u8>, Bech32Variant), Error> {
+    let prefix = "Bech32 decode error";
+    if text.bytes().any(|c| c.is_ascii_lowercase()) && text.bytes().any(|c| c.is_ascii_uppercase()) {
+        return Err(Error::new(ErrorCode::InvalidInput, format!("{}: mixed case", prefix)));
+    }
+    let separator = text.rfind(SEPARATOR)
+        .ok_or_else(|| Error::new(ErrorCode::InvalidFormat, format!("{}: missing '{}' separator", prefix, SEPARATOR)))?;
+    let hrp = validate_hrp(&text[..separator], prefix)?;
+    let data_offset = separator + 1;
+    let data_part = &text[data_offset..];
+    if data_part.len() < CHECKSUM_LEN {
+        return Err(Error::new(ErrorCode::InvalidLength, format!("{}: data part too short for a checksum ({} characters)", prefix, data_part.len())));
+    }
+    let values = data_part.bytes().enumerate()
+        .map(|(index, c)| {
+            CHARSET.iter().position(|&a| a == c.to_ascii_lowercase())
+                .map(|value| value as u8)
+                .ok_or_else(|| Error::new(ErrorCode::InvalidChar, format!("{}: invalid character at offset {}", prefix, data_offset + index)).at(data_offset + index))
+        })
+        .collect::<Result<Vec<u8>, Error>>()?;
+
+    let chk = polymod(&hrp, &values);
+    let variant = [Bech32Variant::Bech32, Bech32Variant::Bech32m].into_iter()
+        .find(|variant| chk == variant.constant())
+        .ok_or_else(|| match locate_single_error(chk ^ Bech32Variant::Bech32.constant(), values.len())
+            .or_else(|| locate_single_error(chk ^ Bech32Variant::Bech32m.constant(), values.len()))
+        {
+            Some(index) => Error::new(ErrorCode::ChecksumMismatch, format!("{}: invalid checksum (likely error at offset {})", prefix, data_offset + index)).at(data_offset + index),
+            None => Error::new(ErrorCode::ChecksumMismatch, format!("{}: invalid checksum", prefix)),
+        })?;
+
+    let data = convert_bits(&values[..values.len() - CHECKSUM_LEN], 5, 8, false)
+        .ok_or_else(|| Error::new(ErrorCode::InvalidPadding, format!("{}: invalid padding bits", prefix)))?;
+    Ok((hrp, data, variant))
+}
+
+// Decode, requiring the given checksum variant (used by `transcode`, which drops the HRP)
+pub(crate) fn decode_bech32_variant(text: &str, expected: Bech32Variant) -> Result<Vec<u8>, Error> {
+    let (_, data, variant) = decode_bech32_internal(text)?;
+    if variant != expected {
+        return Err(Error::new(ErrorCode::ChecksumMismatch, format!("{} decode error: checksum is {}", expected.name(), variant.name())));
+    }
+    Ok(data)
+}
+
+/// Result of `decode_bech32` (returned to JS as a plain object)
+#[derive(Serialize, Tsify)]
+struct DecodedBech32 {
+    hrp: String,
+    #[tsify(type = "Uint8Array")]
+    data: serde_bytes::ByteBuf,
+    #[tsify(type = "\"bech32\" | \"bech32m\"")]
+    variant: &'static str,
+}
+
+/// Encode raw bytes as Bech32 (`variant` = `Bech32Variant.Bech32`, the default) or Bech32m
+/// under the human-readable part `hrp`. Output is lowercase.
+#[wasm_bindgen]
+pub fn encode_bech32(hrp: &str, data: &[u8], variant: Option<Bech32Variant>) -> Result<String, JsValue> {
+    limits::enforce(data.len(), String::len, || encode_bech32_internal(hrp, data, variant.unwrap_or(Bech32Variant::Bech32)))
+        .map_err(js_error)
+}
+
+/// Decode a Bech32 or Bech32m string. Returns `{ hrp, data, variant: "bech32" | "bech32m" }`.
+/// A checksum error that a single character substitution explains names that character's offset.
+#[wasm_bindgen(unchecked_return_type = "DecodedBech32")]
+pub fn decode_bech32(text: &str) -> Result<JsValue, JsValue> {
+    let (hrp, data, variant) = limits::enforce(text.len(), |(_, data, _): &(String, Vec<u8>, Bech32Variant)| data.len(), || decode_bech32_internal(text))
+        .map_err(js_error)?;
+    let decoded = DecodedBech32 {
+        hrp,
+        data: serde_bytes::ByteBuf::from(data),
+        variant: match variant {
+            Bech32Variant::Bech32 => "bech32",
+            Bech32Variant::Bech32m => "bech32m",
+        },
+    };
+    serde_wasm_bindgen::to_value(&decoded)
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bech32_bip_vectors() {
+        // Valid checksums from BIP 173 and BIP 350
+        let (hrp, data, variant) = decode_bech32_internal("A12UEL5L").unwrap();
+        assert_eq!((hrp.as_str(), data.len(), variant), ("a", 0, Bech32Variant::Bech32));
+        let (hrp, _, variant) = decode_bech32_internal("abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw").unwrap();
+        assert_eq!((hrp.as_str(), variant), ("abcdef", Bech32Variant::Bech32));
+        let (hrp, _, variant) = decode_bech32_internal("a1lqfn3a").unwrap();
+        assert_eq!((hrp.as_str(), variant), ("a", Bech32Variant::Bech32m));
+        assert_eq!(encode_bech32_internal("A", b"", Bech32Variant::Bech32).unwrap(), "a12uel5l");
+        assert_eq!(encode_bech32_internal("a", b"", Bech32Variant::Bech32m).unwrap(), "a1lqfn3a");
+    }
+
+    #[test]
+    fn test_bech32_roundtrip() {
+        for size in 0..40 {
+            let data: Vec<u8> = (0..size).map(|i| (i * 67 + size) as u8).collect();
+            for variant in [Bech32Variant::Bech32, Bech32Variant::Bech32m] {
+                let encoded = encode_bech32_internal("desc", &data, variant).unwrap();
+                assert_eq!(decode_bech32_internal(&encoded).unwrap(), ("desc".to_string(), data.clone(), variant));
+                assert_eq!(decode_bech32_internal(&encoded.to_uppercase()).unwrap().1, data);
+                assert_eq!(decode_bech32_variant(&encoded, variant).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bech32_error_positions() {
+        let encoded = encode_bech32_internal("desc", b"wallet descriptor", Bech32Variant::Bech32m).unwrap();
+        for offset in [5, 12, encoded.len() - 1] {
+            let mut typo = encoded.clone().into_bytes();
+            typo[offset] = if typo[offset] == b'q' { b'p' } else { b'q' };
+            assert_eq!(
+                decode_bech32_internal(std::str::from_utf8(&typo).unwrap()).err().unwrap().message,
+                format!("Bech32 decode error: invalid checksum (likely error at offset {})", offset)
+            );
+        }
+        assert_eq!(
+            decode_bech32_variant(&encoded, Bech32Variant::Bech32).err().unwrap().message,
+            "Bech32 decode error: checksum is Bech32m"
+        );
+    }
+
+    #[test]
+    fn test_bech32_errors() {
+        assert_eq!(decode_bech32_internal("A12uEL5L").err().unwrap().message, "Bech32 decode error: mixed case");
+        assert_eq!(decode_bech32_internal("pzry9x0s0muk").err().unwrap().message, "Bech32 decode error: missing '1' separator");
+        assert_eq!(decode_bech32_internal("1pzry9x0s0muk").err().unwrap().message, "Bech32 decode error: HRP must be 1 to 83 characters, got 0");
+        assert_eq!(decode_bech32_internal("x1b4n0q5v").err().unwrap().message, "Bech32 decode error: invalid character at offset 2");
+        assert_eq!(decode_bech32_internal("li1dgmt3").err().unwrap().message, "Bech32 decode error: data part too short for a checksum (5 characters)");
+        assert_eq!(encode_bech32_internal("d\u{7f}", b"", Bech32Variant::Bech32).err().unwrap().message, "Bech32 encode error: invalid HRP character at offset 1");
+    }
+}
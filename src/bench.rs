@@ -0,0 +1,106 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use std::hint::black_box;
+use tsify::Tsify;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{decode_z85_internal, encode_z85_internal, z85_to_base64_internal};
+
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
+#[wasm_bindgen]
+extern "C" {
+    // High-resolution timer of browsers, workers and Node.js
+    #[wasm_bindgen(js_namespace = performance, js_name = now)]
+    fn performance_now() -> f64;
+}
+
+// Milliseconds from an arbitrary origin, with sub-millisecond resolution where available
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
+fn precise_now_ms() -> f64 {
+    performance_now()
+}
+
+// Milliseconds from an arbitrary origin, with sub-millisecond resolution
+#[cfg(any(not(target_arch = "wasm32"), target_os = "wasi"))]
+fn precise_now_ms() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    ORIGIN.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
+/// Throughput of the core conversions, in MB (10⁶ bytes) of raw payload per second
+#[derive(Serialize, Tsify, Debug)]
+struct BenchReport {
+    payload_bytes: usize,
+    iterations: u32,
+    /// `encode_z85`
+    encode_mb_per_s: f64,
+    /// `decode_z85`
+    decode_mb_per_s: f64,
+    /// `transcode` from Z85 to base64
+    transcode_mb_per_s: f64,
+}
+
+// Run `f` `iterations` times after one warm-up run and return the throughput for `bytes` per run
+fn measure<T>(bytes: usize, iterations: u32, mut f: impl FnMut() -> T) -> f64 {
+    black_box(f());
+    let start = precise_now_ms();
+    for _ in 0..iterations {
+        black_box(f());
+    }
+    let elapsed_ms = precise_now_ms() - start;
+    (bytes as f64 * iterations as f64 / 1e6) / (elapsed_ms / 1000.0)
+}
+
+// Internal pure Rust function for benchmarking the conversions on a pseudo-random payload
+fn run_benchmark_internal(payload_size: usize, iterations: u32) -> Result<BenchReport, Error> {
+    if payload_size == 0 || iterations == 0 {
+        return Err(Error::new(ErrorCode::InvalidOptions, "Benchmark payload size and iterations must be non-zero"));
+    }
+    // xorshift, so the payload is not trivially compressible or cacheable
+    let mut state = 0x2545_f491_u32;
+    let payload: Vec<u8> = (0..payload_size)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect();
+    let encoded = encode_z85_internal(&payload);
+
+    Ok(BenchReport {
+        payload_bytes: payload_size,
+        iterations,
+        encode_mb_per_s: measure(payload_size, iterations, || encode_z85_internal(black_box(&payload))),
+        decode_mb_per_s: measure(payload_size, iterations, || decode_z85_internal(black_box(&encoded))),
+        transcode_mb_per_s: measure(payload_size, iterations, || z85_to_base64_internal(black_box(&encoded))),
+    })
+}
+
+/// Measure encode, decode and transcode (Z85 to base64) throughput inside the module, without
+/// JS↔WASM copies, on `payload_size` bytes over `iterations` runs each. Returns
+/// `{ payload_bytes, iterations, encode_mb_per_s, decode_mb_per_s, transcode_mb_per_s }`.
+#[wasm_bindgen(unchecked_return_type = "BenchReport")]
+pub fn run_benchmark(payload_size: usize, iterations: u32) -> Result<JsValue, JsValue> {
+    let report = run_benchmark_internal(payload_size, iterations)
+        .map_err(js_error)?;
+    serde_wasm_bindgen::to_value(&report)
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_benchmark() {
+        let report = run_benchmark_internal(64 * 1024, 3).unwrap();
+        assert_eq!((report.payload_bytes, report.iterations), (64 * 1024, 3));
+        for mb_per_s in [report.encode_mb_per_s, report.decode_mb_per_s, report.transcode_mb_per_s] {
+            assert!(mb_per_s > 0.0, "{:?}", report);
+        }
+        assert!(run_benchmark_internal(0, 3).is_err());
+    }
+}
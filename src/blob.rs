@@ -0,0 +1,116 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, BlobPropertyBag, File, FilePropertyBag, Url};
+
+use crate::compression::Compression;
+use crate::container::{self, FileMetadata};
+use crate::error::{js_error, Error};
+use crate::limits;
+use crate::{decode_z85_internal, encode_z85_with_options_internal, mime, PaddingFormat};
+
+// Internal pure Rust function for encoding the contents of a blob: a file container when the
+// blob is a `File`, otherwise what `encode_z85` (or a `z85v2:` container with `compression`) gives
+fn encode_blob_internal(data: &[u8], file: Option<FileMetadata>, compression: Compression) -> Result<String, Error> {
+    match file {
+        Some(metadata) => container::encode_file_container_internal(data, metadata, compression),
+        None => Ok(encode_z85_with_options_internal(data, PaddingFormat::Count, compression)),
+    }
+}
+
+// Internal pure Rust function for decoding Z85 text from `encode_blob_z85`, with the file
+// metadata of file containers
+fn decode_blob_internal(z85_text: &str) -> Result<(Vec<u8>, FileMetadata), Error> {
+    if z85_text.trim().starts_with(container::V2_PREFIX) {
+        container::decode_file_container_internal(z85_text)
+    } else {
+        Ok((decode_z85_internal(z85_text)?, FileMetadata::default()))
+    }
+}
+
+// Metadata of a `File`. Types the container cannot record are left out, so the type is sniffed.
+fn file_metadata(file: &File) -> FileMetadata {
+    let mime = Some(file.type_()).filter(|mime_type| mime::validate_mime_type(mime_type).is_ok());
+    FileMetadata { name: Some(file.name()), mime, modified: Some(file.last_modified() as i64), size: None }
+}
+
+/// Read a `Blob` and encode its contents to Z85. A `File` is stored in a checksummed `z85v2:`
+/// file container together with its name, MIME type and modification time (see
+/// `encode_file_container`); other blobs encode as `encode_z85` would, or into a `z85v2:`
+/// container when `compression` is given.
+#[wasm_bindgen]
+pub async fn encode_blob_z85(blob: Blob, compression: Option<Compression>) -> Result<String, JsValue> {
+    // Check the size before reading the blob into memory
+    limits::check_input(blob.size() as usize).map_err(js_error)?;
+    let file = blob.dyn_ref::<File>().map(file_metadata);
+    let buffer = JsFuture::from(blob.array_buffer()).await?;
+    let data = js_sys::Uint8Array::new(&buffer).to_vec();
+    let compression = compression.unwrap_or(Compression::None);
+    limits::enforce(data.len(), String::len, || encode_blob_internal(&data, file, compression))
+        .map_err(js_error)
+}
+
+/// Decode Z85 text (with padding info, or a `z85v2:` container) into a `Blob` of type `mime`.
+/// Text from `encode_blob_z85` of a `File` becomes a `File` again, with its name and
+/// modification time; its recorded MIME type applies unless `mime` is given.
+#[wasm_bindgen]
+pub fn decode_z85_to_blob(z85_text: &str, mime: Option<String>) -> Result<Blob, JsValue> {
+    if let Some(mime_type) = &mime {
+        mime::validate_mime_type(mime_type).map_err(js_error)?;
+    }
+    let (data, metadata) = limits::enforce(z85_text.len(), |(data, _): &(Vec<u8>, FileMetadata)| data.len(), || decode_blob_internal(z85_text))
+        .map_err(js_error)?;
+    let mime_type = mime.or(metadata.mime).unwrap_or_default();
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(data.as_slice()));
+    match metadata.name {
+        Some(name) => {
+            let options = FilePropertyBag::new();
+            options.set_type(&mime_type);
+            if let Some(modified) = metadata.modified {
+                options.set_last_modified(modified as f64);
+            }
+            Ok(File::new_with_u8_array_sequence_and_options(&parts, &name, &options)?.into())
+        }
+        None => {
+            let options = BlobPropertyBag::new();
+            options.set_type(&mime_type);
+            Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        }
+    }
+}
+
+/// Decode Z85 text as `decode_z85_to_blob` does and return a `blob:` URL for it, e.g. for an
+/// `<img src>`. The URL keeps the bytes alive until `revoke_object_url` is called with it.
+#[wasm_bindgen]
+pub fn decode_z85_to_object_url(z85_text: &str, mime: Option<String>) -> Result<String, JsValue> {
+    Url::create_object_url_with_blob(&decode_z85_to_blob(z85_text, mime)?)
+}
+
+/// Release a URL from `decode_z85_to_object_url` so its bytes can be freed
+#[wasm_bindgen]
+pub fn revoke_object_url(url: &str) -> Result<(), JsValue> {
+    Url::revoke_object_url(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_roundtrip() {
+        let plain = encode_blob_internal(b"Hello", None, Compression::None).unwrap();
+        assert_eq!(plain, "nm=QNzVx+q:3");
+        assert_eq!(decode_blob_internal(&plain).unwrap(), (b"Hello".to_vec(), FileMetadata::default()));
+
+        let file = FileMetadata { name: Some("notes.txt".to_string()), mime: None, modified: Some(1_700_000_000_000), size: None };
+        let encoded = encode_blob_internal(b"Hello", Some(file), Compression::Deflate).unwrap();
+        let (data, metadata) = decode_blob_internal(&encoded).unwrap();
+        assert_eq!(data, b"Hello");
+        assert_eq!(metadata.name.as_deref(), Some("notes.txt"));
+        assert_eq!(metadata.mime.as_deref(), Some("application/octet-stream"));
+        assert_eq!((metadata.modified, metadata.size), (Some(1_700_000_000_000), Some(5)));
+
+        // Containers without file metadata decode with only the size set
+        let container = encode_blob_internal(&[7; 300], None, Compression::Deflate).unwrap();
+        assert_eq!(decode_blob_internal(&container).unwrap().1.name, None);
+    }
+}
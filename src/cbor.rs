@@ -0,0 +1,248 @@
+use wasm_bindgen::prelude::*;
+use ciborium::Value;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{decode_z85_internal, encode_z85_internal, limits, telemetry};
+
+/// CBOR envelope holding the payload bytes next to free-form metadata
+#[derive(Serialize, Deserialize)]
+struct CborEnvelope {
+    #[serde(with = "serde_bytes")]
+    data: Vec<u8>,
+    metadata: Value,
+}
+
+/// Result of unpacking a CBOR envelope (returned to JS as a plain object)
+#[derive(Serialize, Tsify)]
+struct UnpackedCbor {
+    #[tsify(type = "Uint8Array")]
+    data: serde_bytes::ByteBuf,
+    #[tsify(type = "any")]
+    metadata: Value,
+}
+
+// Internal pure Rust function for wrapping bytes and metadata in a Z85 encoded CBOR envelope
+fn pack_cbor_internal(data: &[u8], metadata: Value) -> Result<String, Error> {
+    let envelope = CborEnvelope { data: data.to_vec(), metadata };
+
+    // Serialize the envelope to CBOR
+    let mut cbor_data = Vec::new();
+    ciborium::into_writer(&envelope, &mut cbor_data)
+        .map_err(|e| Error::new(ErrorCode::InvalidInput, format!("CBOR encode error: {}", e)))?;
+
+    // Encode to Z85
+    Ok(encode_z85_internal(&cbor_data))
+}
+
+// Internal pure Rust function for unwrapping a Z85 encoded CBOR envelope
+fn unpack_cbor_internal(payload: &str) -> Result<(Vec<u8>, Value), Error> {
+    // Decode Z85 data
+    let cbor_data = decode_z85_internal(payload)?;
+
+    // Deserialize the envelope from CBOR
+    let envelope: CborEnvelope = ciborium::from_reader(cbor_data.as_slice())
+        .map_err(|e| Error::new(ErrorCode::InvalidInput, format!("CBOR decode error: {}", e)))?;
+
+    Ok((envelope.data, envelope.metadata))
+}
+
+// Sort map keys by their encoded bytes, recursively, for RFC 8949 core deterministic encoding.
+// ciborium already writes definite lengths and the shortest integer, float and length forms.
+fn into_deterministic(value: Value) -> Result<Value, Error> {
+    Ok(match value {
+        Value::Map(entries) => {
+            let mut entries = entries.into_iter()
+                .map(|(key, value)| Ok((to_cbor_bytes(&key)?, key, into_deterministic(value)?)))
+                .collect::<Result<Vec<_>, Error>>()?;
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Map(entries.into_iter().map(|(_, key, value)| (key, value)).collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(into_deterministic).collect::<Result<_, _>>()?),
+        Value::Tag(tag, inner) => Value::Tag(tag, Box::new(into_deterministic(*inner)?)),
+        value => value,
+    })
+}
+
+fn to_cbor_bytes(value: &Value) -> Result<Vec<u8>, Error> {
+    let mut cbor_data = Vec::new();
+    ciborium::into_writer(value, &mut cbor_data)
+        .map_err(|e| Error::new(ErrorCode::InvalidInput, format!("CBOR encode error: {}", e)))?;
+    Ok(cbor_data)
+}
+
+// Internal pure Rust function for encoding a value as deterministic CBOR
+fn deterministic_cbor_internal(value: Value) -> Result<Vec<u8>, Error> {
+    to_cbor_bytes(&into_deterministic(value)?)
+}
+
+// Internal pure Rust function for reading exactly one CBOR data item
+fn read_cbor_internal(mut cbor_data: &[u8]) -> Result<Value, Error> {
+    let value = ciborium::from_reader(&mut cbor_data)
+        .map_err(|e| Error::new(ErrorCode::InvalidInput, format!("CBOR decode error: {}", e)))?;
+    if !cbor_data.is_empty() {
+        return Err(Error::new(ErrorCode::InvalidInput, format!("CBOR decode error: {} bytes after the data item", cbor_data.len())));
+    }
+    Ok(value)
+}
+
+// Convert a CBOR data item to a JS value: maps with only text keys become plain objects and other
+// maps `Map`s, integers outside the safe range `BigInt`s and tagged items `{ tag, value }`
+fn cbor_to_js(value: &Value) -> Result<JsValue, Error> {
+    const MAX_SAFE_INTEGER: i128 = (1 << 53) - 1;
+    Ok(match value {
+        Value::Integer(integer) => match i128::from(*integer) {
+            integer if integer.abs() <= MAX_SAFE_INTEGER => JsValue::from_f64(integer as f64),
+            integer => js_sys::BigInt::from(integer).into(),
+        },
+        Value::Bytes(bytes) => js_sys::Uint8Array::from(bytes.as_slice()).into(),
+        Value::Float(float) => JsValue::from_f64(*float),
+        Value::Text(text) => JsValue::from_str(text),
+        Value::Bool(bool) => JsValue::from_bool(*bool),
+        Value::Null => JsValue::NULL,
+        Value::Tag(tag, inner) => {
+            // Setting a property of a fresh plain object cannot fail
+            let tagged = js_sys::Object::new();
+            let _ = js_sys::Reflect::set(&tagged, &JsValue::from_str("tag"), &JsValue::from_f64(*tag as f64));
+            let _ = js_sys::Reflect::set(&tagged, &JsValue::from_str("value"), &cbor_to_js(inner)?);
+            tagged.into()
+        }
+        Value::Array(items) => items.iter().map(cbor_to_js).collect::<Result<js_sys::Array, _>>()?.into(),
+        Value::Map(entries) if entries.iter().all(|(key, _)| key.is_text()) => {
+            // `Object.fromEntries` defines own properties, so a `__proto__` key stays data
+            let pairs = entries.iter()
+                .map(|(key, value)| Ok(js_sys::Array::of2(&cbor_to_js(key)?, &cbor_to_js(value)?)))
+                .collect::<Result<js_sys::Array, Error>>()?;
+            js_sys::Object::from_entries(&pairs)
+                .map_err(|_| Error::new(ErrorCode::InvalidInput, "CBOR decode error: invalid map"))?
+                .into()
+        }
+        Value::Map(entries) => {
+            let map = js_sys::Map::new();
+            for (key, value) in entries {
+                map.set(&cbor_to_js(key)?, &cbor_to_js(value)?);
+            }
+            map.into()
+        }
+        _ => return Err(Error::new(ErrorCode::InvalidInput, "CBOR decode error: unsupported data item")),
+    })
+}
+
+/// Encode a JS value as deterministic CBOR (RFC 8949 core deterministic encoding: shortest
+/// forms, definite lengths, map keys sorted by their encoded bytes) and the CBOR to Z85 with
+/// padding info, so equal values always give the same text. Integers within the safe range and
+/// `BigInt`s become CBOR integers, other numbers floats, `Uint8Array`s byte strings, `undefined`
+/// null and objects and `Map`s maps.
+#[wasm_bindgen]
+pub fn cbor_to_z85(value: JsValue) -> Result<String, JsValue> {
+    let value: Value = serde_wasm_bindgen::from_value(value)
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, format!("Invalid CBOR value: {}", e))))?;
+    let cbor_data = deterministic_cbor_internal(value).map_err(js_error)?;
+    telemetry::instrument("cbor_to_z85", cbor_data.len(), String::len, || Ok(encode_z85_internal(&cbor_data)))
+        .map_err(js_error)
+}
+
+/// Decode Z85 data with padding info (or a `z85v2:` container) holding one CBOR data item into
+/// a JS value. Maps with only text keys become plain objects and other maps `Map`s, byte strings
+/// `Uint8Array`s, integers outside the safe range `BigInt`s and tagged items `{ tag, value }`.
+#[wasm_bindgen]
+pub fn z85_to_cbor(z85_data_with_padding: &str) -> Result<JsValue, JsValue> {
+    let cbor_data = telemetry::instrument("z85_to_cbor", z85_data_with_padding.len(), Vec::len, || decode_z85_internal(z85_data_with_padding))
+        .map_err(js_error)?;
+    read_cbor_internal(&cbor_data)
+        .and_then(|value| cbor_to_js(&value))
+        .map_err(js_error)
+}
+
+/// Wrap raw bytes and arbitrary metadata (mime, filename, timestamps, ...) in a CBOR envelope and encode it to Z85
+#[wasm_bindgen]
+pub fn pack_cbor(data: &[u8], metadata: JsValue) -> Result<String, JsValue> {
+    let metadata: Value = if metadata.is_undefined() || metadata.is_null() {
+        Value::Null
+    } else {
+        serde_wasm_bindgen::from_value(metadata)
+            .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, format!("Invalid metadata: {}", e))))?
+    };
+    limits::enforce(data.len(), String::len, || pack_cbor_internal(data, metadata))
+        .map_err(js_error)
+}
+
+/// Unpack a Z85 encoded CBOR envelope into `{ data: Uint8Array, metadata: any }`
+#[wasm_bindgen(unchecked_return_type = "UnpackedCbor")]
+pub fn unpack_cbor(payload: &str) -> Result<JsValue, JsValue> {
+    let (data, metadata) = limits::enforce(payload.len(), |(data, _): &(Vec<u8>, Value)| data.len(), || unpack_cbor_internal(payload))
+        .map_err(js_error)?;
+    let unpacked = UnpackedCbor { data: serde_bytes::ByteBuf::from(data), metadata };
+    let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+    unpacked.serialize(&serializer)
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, format!("Metadata conversion error: {}", e))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_metadata() -> Value {
+        Value::Map(vec![
+            (Value::Text("mime".into()), Value::Text("image/png".into())),
+            (Value::Text("filename".into()), Value::Text("logo.png".into())),
+            (Value::Text("modified".into()), Value::Integer(1_700_000_000.into())),
+        ])
+    }
+
+    #[test]
+    fn test_pack_unpack_cbor_roundtrip() {
+        let data = b"\x89PNG\r\n\x1a\nHello";
+        let payload = pack_cbor_internal(data, sample_metadata()).unwrap();
+        assert!(payload.contains(':'));
+
+        let (unpacked_data, metadata) = unpack_cbor_internal(&payload).unwrap();
+        assert_eq!(unpacked_data.as_slice(), data);
+        assert_eq!(metadata, sample_metadata());
+    }
+
+    #[test]
+    fn test_pack_cbor_null_metadata() {
+        let payload = pack_cbor_internal(b"", Value::Null).unwrap();
+        let (data, metadata) = unpack_cbor_internal(&payload).unwrap();
+        assert!(data.is_empty());
+        assert_eq!(metadata, Value::Null);
+    }
+
+    #[test]
+    fn test_deterministic_cbor() {
+        // Keys sort by their encoded bytes: shorter text first, integers before text
+        let value = Value::Map(vec![
+            (Value::Text("bb".into()), Value::Float(1.5)),
+            (Value::Text("a".into()), Value::Array(vec![Value::Map(vec![
+                (Value::Text("z".into()), Value::Null),
+                (Value::Integer(1.into()), Value::Bool(true)),
+            ])])),
+        ]);
+        let cbor_data = deterministic_cbor_internal(value).unwrap();
+        assert_eq!(cbor_data, [
+            0xa2, 0x61, b'a', 0x81, 0xa2, 0x01, 0xf5, 0x61, b'z', 0xf6,
+            0x62, b'b', b'b', 0xf9, 0x3e, 0x00,
+        ]);
+        assert_eq!(deterministic_cbor_internal(read_cbor_internal(&cbor_data).unwrap()).unwrap(), cbor_data);
+    }
+
+    #[test]
+    fn test_read_cbor_errors() {
+        assert!(read_cbor_internal(&[0xa1]).unwrap_err().message.starts_with("CBOR decode error"));
+        assert_eq!(read_cbor_internal(&[0x01, 0x02]).unwrap_err().message, "CBOR decode error: 1 bytes after the data item");
+    }
+
+    #[test]
+    fn test_unpack_cbor_errors() {
+        // Not a Z85 payload
+        let result = unpack_cbor_internal("no_colon_here");
+        assert_eq!(result.err().unwrap().message, "Invalid format: expected 'z85_data:padding'");
+
+        // Valid Z85 but not a CBOR envelope
+        let payload = encode_z85_internal(b"plain bytes");
+        let result = unpack_cbor_internal(&payload);
+        assert!(result.err().unwrap().message.contains("CBOR decode error"));
+    }
+}
@@ -0,0 +1,110 @@
+use wasm_bindgen::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::base32::encode_base32_with;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::decode_z85_internal;
+
+/// CID version emitted by `cid_for`
+const CID_VERSION: u64 = 1;
+/// Multihash code for sha2-256
+const MULTIHASH_SHA2_256: u64 = 0x12;
+/// Multibase prefix for lowercase, unpadded RFC 4648 base32
+const MULTIBASE_BASE32: char = 'b';
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+// Map a multicodec name to its code
+fn multicodec_code(codec: &str) -> Result<u64, Error> {
+    match codec {
+        "raw" => Ok(0x55),
+        "dag-pb" => Ok(0x70),
+        "dag-cbor" => Ok(0x71),
+        "dag-json" => Ok(0x0129),
+        "json" => Ok(0x0200),
+        "cbor" => Ok(0x51),
+        _ => Err(Error::new(ErrorCode::Unsupported, format!("Unsupported codec: {}", codec))),
+    }
+}
+
+// Append an unsigned LEB128 varint as used by multiformats
+fn push_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+// Internal pure Rust function for computing a CIDv1 (sha2-256, base32) of raw bytes
+fn cid_for_internal(data: &[u8], codec: &str) -> Result<String, Error> {
+    let codec_code = multicodec_code(codec)?;
+    let digest = Sha256::digest(data);
+
+    // <version><codec><multihash-code><digest-length><digest>
+    let mut cid = Vec::with_capacity(digest.len() + 8);
+    push_varint(&mut cid, CID_VERSION);
+    push_varint(&mut cid, codec_code);
+    push_varint(&mut cid, MULTIHASH_SHA2_256);
+    push_varint(&mut cid, digest.len() as u64);
+    cid.extend_from_slice(&digest);
+
+    Ok(format!("{}{}", MULTIBASE_BASE32, encode_base32_with(BASE32_ALPHABET, &cid, false)))
+}
+
+/// Compute an IPFS-compatible CIDv1 (sha2-256 multihash, base32 multibase) for the decoded content.
+/// Accepts either a Z85 payload string (`z85_data:padding`) or raw bytes; `codec` defaults to `raw`.
+#[wasm_bindgen]
+pub fn cid_for(#[wasm_bindgen(unchecked_param_type = "string | Uint8Array")] payload_or_bytes: JsValue, codec: Option<String>) -> Result<String, JsValue> {
+    let codec = codec.as_deref().unwrap_or("raw");
+    if let Some(payload) = payload_or_bytes.as_string() {
+        limits::enforce(payload.len(), String::len, || cid_for_internal(&decode_z85_internal(&payload)?, codec))
+    } else if payload_or_bytes.is_instance_of::<js_sys::Uint8Array>() {
+        // Check the size before copying the bytes into WASM memory
+        let bytes = js_sys::Uint8Array::new(&payload_or_bytes);
+        limits::enforce(bytes.length() as usize, String::len, || cid_for_internal(&bytes.to_vec(), codec))
+    } else {
+        Err(Error::new(ErrorCode::InvalidInput, "Expected a Z85 string or Uint8Array"))
+    }
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_z85_internal;
+
+    #[test]
+    fn test_cid_for_known_vector() {
+        // `echo -n "hello world" | ipfs add --cid-version 1 --raw-leaves`
+        let cid = cid_for_internal(b"hello world", "raw").unwrap();
+        assert_eq!(cid, "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e");
+    }
+
+    #[test]
+    fn test_cid_for_codecs() {
+        let raw = cid_for_internal(b"{}", "raw").unwrap();
+        let json = cid_for_internal(b"{}", "json").unwrap();
+        assert!(raw.starts_with("bafkrei"));
+        assert!(json.starts_with('b'));
+        assert_ne!(raw, json);
+
+        let result = cid_for_internal(b"{}", "unknown");
+        assert_eq!(result.err().unwrap().message, "Unsupported codec: unknown");
+    }
+
+    #[test]
+    fn test_cid_for_decoded_payload_matches_bytes() {
+        let data = b"Hello, World!";
+        let payload = encode_z85_internal(data);
+        let decoded = decode_z85_internal(&payload).unwrap();
+        assert_eq!(cid_for_internal(&decoded, "raw").unwrap(), cid_for_internal(data, "raw").unwrap());
+    }
+
+    #[test]
+    fn test_push_varint() {
+        let mut out = Vec::new();
+        push_varint(&mut out, 0x0129);
+        assert_eq!(out, vec![0xa9, 0x02]);
+    }
+}
@@ -0,0 +1,104 @@
+//! Z85 group codec: the scalar code of `z85_core::codec` behind the SIMD and multi-threaded kernels
+
+pub(crate) use z85_core::codec::{encode_append_with, encode_padded_tail_with, Base85Alphabet, INVALID_DIGIT, RFC1924, Z85, Z85_ALPHABET};
+use z85_core::codec::decode_groups_with;
+
+use crate::error::Error;
+
+// Encode 4-byte aligned data as Z85, appending to `out`
+pub(crate) fn encode_append(data: &[u8], out: &mut String) {
+    debug_assert!(data.len().is_multiple_of(4));
+    #[cfg(feature = "threads")]
+    if data.len() >= crate::parallel::PARALLEL_THRESHOLD {
+        return crate::parallel::encode_append(data, out);
+    }
+    out.reserve(data.len() / 4 * 5);
+
+    // SIMD path: 16 bytes at a time, scalar code handles the remaining words
+    #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+    let data = {
+        let blocks = data.chunks_exact(16);
+        let rest = blocks.remainder();
+        let mut encoded = [0u8; 20];
+        for block in blocks {
+            crate::simd::encode_block(block.try_into().unwrap(), &mut encoded);
+            out.extend(encoded.iter().map(|&c| c as char));
+        }
+        rest
+    };
+
+    encode_append_with(&Z85, data, out);
+}
+
+// Encode a final partial word (< 4 bytes) zero-padded to a full group, appending to `out`.
+// Returns the number of padding bytes added.
+pub(crate) fn encode_padded_tail(tail: &[u8], out: &mut String) -> usize {
+    encode_padded_tail_with(&Z85, tail, out)
+}
+
+// Decode whole 5-char groups into `out` (exactly `z85_data.len() / 5 * 4` bytes).
+// `base_offset` is the position of `z85_data` in the full input, used for error offsets.
+pub(crate) fn decode_groups(z85_data: &[u8], out: &mut [u8], base_offset: usize) -> Result<(), z85_core::Error> {
+    debug_assert_eq!(out.len(), z85_data.len() / 5 * 4);
+    #[cfg(feature = "threads")]
+    if out.len() >= crate::parallel::PARALLEL_THRESHOLD {
+        return crate::parallel::decode_groups(z85_data, out, base_offset);
+    }
+
+    // SIMD path: 20 chars at a time; blocks with invalid input fall through to the
+    // scalar code, which reports the exact offset
+    #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+    for (index, (block, words)) in z85_data.chunks_exact(20).zip(out.chunks_exact_mut(16)).enumerate() {
+        if !crate::simd::decode_block(block.try_into().unwrap(), words.try_into().unwrap()) {
+            crate::logging::log(crate::logging::LogLevel::Debug, format_args!("SIMD decode: invalid block at offset {}, rechecking with scalar code", base_offset + index * 20));
+            return decode_groups_with(&Z85, block, words, base_offset + index * 20);
+        }
+    }
+    #[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+    let (z85_data, out, base_offset) = {
+        let blocks = z85_data.len() / 20;
+        (&z85_data[blocks * 20..], &mut out[blocks * 16..], base_offset + blocks * 20)
+    };
+
+    decode_groups_with(&Z85, z85_data, out, base_offset)
+}
+
+// Decode Z85 text (length a multiple of 5) into `out`, which must hold at least
+// `len / 5 * 4 - padding` bytes; the last `padding` bytes of the final group are dropped.
+// Returns the number of bytes written.
+pub(crate) fn decode_into(z85_data: &[u8], padding: usize, out: &mut [u8]) -> Result<usize, Error> {
+    decode_into_with(&Z85, z85_data, padding, out)
+}
+
+// `decode_into` in any base85 alphabet
+pub(crate) fn decode_into_with(alphabet: &Base85Alphabet, z85_data: &[u8], padding: usize, out: &mut [u8]) -> Result<usize, Error> {
+    let written = if alphabet.symbols == *Z85_ALPHABET {
+        z85_core::codec::decode_into_using(alphabet, z85_data, padding, out, |groups, words| decode_groups(groups, words, 0))?
+    } else {
+        z85_core::codec::decode_into_with(alphabet, z85_data, padding, out)?
+    };
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    // The scalar codec is tested in `z85_core`; these check the kernels dispatched to here
+    use super::*;
+
+    #[test]
+    fn test_decode_into_matches_z85_crate() {
+        let data: Vec<u8> = (0..64u8).collect();
+        let encoded = z85::encode(&data);
+        let mut out = vec![0u8; data.len()];
+        assert_eq!(decode_into(encoded.as_bytes(), 0, &mut out).unwrap(), data.len());
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn test_encode_append_matches_z85_crate() {
+        let data: Vec<u8> = (0..64u8).map(|i| i.wrapping_mul(37)).collect();
+        let mut out = String::from("prefix:");
+        encode_append(&data, &mut out);
+        assert_eq!(out, format!("prefix:{}", z85::encode(&data)));
+    }
+}
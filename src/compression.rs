@@ -0,0 +1,294 @@
+use wasm_bindgen::prelude::*;
+use ruzstd::encoding::{CompressionLevel, FrameCompressor, Matcher, Sequence};
+use std::cell::RefCell;
+use std::rc::Rc;
+use z85_core::compression::ZSTD_MAX_BLOCK;
+use z85_core::DecodeOptions;
+
+use crate::container::crc32;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+
+/// Compression applied to the payload before Z85 encoding
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+    /// Store the payload as is
+    None,
+    /// Raw deflate (RFC 1951)
+    Deflate,
+    /// Zstandard (RFC 8878), using the dictionary from `set_compression_dictionary` if one is set
+    Zstd,
+}
+
+impl Compression {
+    // Name used in `z85v2:` container headers and by `Pipeline`
+    pub(crate) fn name(self) -> &'static str {
+        z85_core::Compression::from(self).name()
+    }
+
+    pub(crate) fn from_name(name: &str) -> Result<Compression, Error> {
+        Ok(z85_core::Compression::from_name(name)?.into())
+    }
+}
+
+impl From<Compression> for z85_core::Compression {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::None => z85_core::Compression::None,
+            Compression::Deflate => z85_core::Compression::Deflate,
+            Compression::Zstd => z85_core::Compression::Zstd,
+        }
+    }
+}
+
+impl From<z85_core::Compression> for Compression {
+    fn from(compression: z85_core::Compression) -> Self {
+        match compression {
+            z85_core::Compression::None => Compression::None,
+            z85_core::Compression::Deflate => Compression::Deflate,
+            z85_core::Compression::Zstd => Compression::Zstd,
+        }
+    }
+}
+
+/// Largest accepted compression dictionary
+const MAX_DICTIONARY_BYTES: usize = 1024 * 1024;
+
+/// Zstandard dictionary content shared by the encoder and decoder
+struct CompressionDictionary {
+    id: u32,
+    content: Rc<[u8]>,
+}
+
+thread_local! {
+    static COMPRESSION_DICTIONARY: RefCell<Option<CompressionDictionary>> = const { RefCell::new(None) };
+}
+
+pub(crate) use z85_core::compression::deflate_raw;
+
+// Inflate raw-deflate (RFC 1951) data, stopping at `max_output_bytes`
+pub(crate) fn inflate_raw(data: &[u8]) -> Result<Vec<u8>, Error> {
+    Ok(z85_core::compression::inflate_raw(data, limits::max_output_bytes())?)
+}
+
+// Internal pure Rust function for installing (or clearing) the Zstandard dictionary
+fn set_compression_dictionary_internal(dictionary: Option<&[u8]>) -> Result<(), Error> {
+    let dictionary = match dictionary {
+        None => None,
+        Some(content) => {
+            if content.is_empty() || content.len() > MAX_DICTIONARY_BYTES {
+                return Err(Error::new(ErrorCode::InvalidOptions, format!("Compression dictionary must be 1 to {} bytes, got {}", MAX_DICTIONARY_BYTES, content.len())));
+            }
+            Some(CompressionDictionary { id: crc32(content), content: content.into() })
+        }
+    };
+    COMPRESSION_DICTIONARY.with(|slot| *slot.borrow_mut() = dictionary);
+    Ok(())
+}
+
+/// Set the dictionary used for `Compression.Zstd`, shared by compression and decompression
+/// (pass `undefined` to clear it). The bytes are used as a raw-content dictionary: typical samples of the data.
+/// Payloads compressed with a dictionary record its id and can only be decompressed with the same dictionary.
+#[wasm_bindgen]
+pub fn set_compression_dictionary(dictionary: Option<Vec<u8>>) -> Result<(), JsValue> {
+    set_compression_dictionary_internal(dictionary.as_deref())
+        .map_err(js_error)
+}
+
+/// Match finder that treats the dictionary as data preceding every frame, like a zstd
+/// "raw content" dictionary: sequences may reference it with offsets reaching back before the frame
+struct DictionaryMatcher {
+    dictionary: Rc<[u8]>,
+    /// Dictionary followed by every block committed in the current frame
+    history: Vec<u8>,
+    /// Start of the last committed block in `history`
+    block_start: usize,
+    /// Last position + 1 of each hashed 4-byte prefix
+    table: Vec<u32>,
+}
+
+impl DictionaryMatcher {
+    const MIN_MATCH: usize = 4;
+    /// ruzstd 0.8 mis-encodes match lengths above 65538 (the largest match-length code)
+    const MAX_MATCH: usize = 65538;
+    const HASH_BITS: u32 = 16;
+
+    fn new(dictionary: Rc<[u8]>) -> Self {
+        DictionaryMatcher { dictionary, history: Vec::new(), block_start: 0, table: vec![0; 1 << Self::HASH_BITS] }
+    }
+
+    fn hash(bytes: &[u8]) -> usize {
+        let key = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        (key.wrapping_mul(0x9E37_79B1) >> (32 - Self::HASH_BITS)) as usize
+    }
+
+    // Record positions `start..end` of the history for future matches
+    fn index(&mut self, start: usize, end: usize) {
+        let last = end.min(self.history.len().saturating_sub(Self::MIN_MATCH - 1));
+        for position in start..last {
+            self.table[Self::hash(&self.history[position..])] = position as u32 + 1;
+        }
+    }
+}
+
+impl Matcher for DictionaryMatcher {
+    fn get_next_space(&mut self) -> Vec<u8> {
+        vec![0; ZSTD_MAX_BLOCK]
+    }
+
+    fn get_last_space(&mut self) -> &[u8] {
+        &self.history[self.block_start..]
+    }
+
+    fn commit_space(&mut self, space: Vec<u8>) {
+        self.block_start = self.history.len();
+        self.history.extend_from_slice(&space);
+    }
+
+    fn skip_matching(&mut self) {
+        self.index(self.block_start, self.history.len());
+    }
+
+    fn start_matching(&mut self, mut handle_sequence: impl for<'a> FnMut(Sequence<'a>)) {
+        let window = self.window_size() as usize;
+        let end = self.history.len();
+        let mut position = self.block_start;
+        let mut literals_start = position;
+        while position + Self::MIN_MATCH <= end {
+            let slot = Self::hash(&self.history[position..]);
+            let candidate = self.table[slot] as usize;
+            self.table[slot] = position as u32 + 1;
+            // The first sequence of a block always carries a literal: ruzstd cannot build
+            // a literal-length table where every sequence has zero literals
+            if candidate > 0 && position - (candidate - 1) <= window && position > self.block_start {
+                let candidate = candidate - 1;
+                let match_len = self.history[candidate..end].iter()
+                    .zip(&self.history[position..end])
+                    .take(Self::MAX_MATCH)
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if match_len >= Self::MIN_MATCH {
+                    handle_sequence(Sequence::Triple {
+                        literals: &self.history[literals_start..position],
+                        offset: position - candidate,
+                        match_len,
+                    });
+                    self.index(position + 1, position + match_len);
+                    position += match_len;
+                    literals_start = position;
+                    continue;
+                }
+            }
+            position += 1;
+        }
+        if literals_start < end {
+            handle_sequence(Sequence::Literals { literals: &self.history[literals_start..end] });
+        }
+    }
+
+    fn reset(&mut self, _level: CompressionLevel) {
+        self.history.clear();
+        self.history.extend_from_slice(&self.dictionary);
+        self.table.iter_mut().for_each(|slot| *slot = 0);
+        self.block_start = self.history.len();
+        self.index(0, self.history.len());
+    }
+
+    fn window_size(&self) -> u64 {
+        (self.dictionary.len() + ZSTD_MAX_BLOCK).next_power_of_two() as u64
+    }
+}
+
+// Zstandard-compress the given bytes, using the current dictionary if one is set.
+// Returns the frame and the id of the dictionary used.
+pub(crate) fn zstd_compress(data: &[u8]) -> (Vec<u8>, Option<u32>) {
+    let dictionary = COMPRESSION_DICTIONARY.with(|slot| {
+        slot.borrow().as_ref().map(|dictionary| (dictionary.id, dictionary.content.clone()))
+    });
+    match dictionary {
+        None => (z85_core::compression::zstd_compress(data), None),
+        Some((id, content)) => {
+            let mut output = Vec::new();
+            let mut compressor = FrameCompressor::new_with_matcher(DictionaryMatcher::new(content), CompressionLevel::Fastest);
+            compressor.set_source(data);
+            compressor.set_drain(&mut output);
+            compressor.compress();
+            (output, Some(id))
+        }
+    }
+}
+
+// Run a `z85_core` decoder with the current dictionary and `max_output_bytes`
+pub(crate) fn with_decode_options<T>(decode: impl FnOnce(&DecodeOptions) -> T) -> T {
+    let dictionary = COMPRESSION_DICTIONARY.with(|slot| slot.borrow().as_ref().map(|dictionary| dictionary.content.clone()));
+    decode(&DecodeOptions { dictionary: dictionary.as_deref(), max_output_bytes: limits::max_output_bytes() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Decompress a Zstandard frame, using the dictionary with the given id if the frame was compressed with one
+    fn zstd_decompress(frame: &[u8], dictionary_id: Option<u32>) -> Result<Vec<u8>, Error> {
+        let decompress = |options: &DecodeOptions| z85_core::compression::decompress(frame.to_vec(), z85_core::Compression::Zstd, dictionary_id, options);
+        Ok(with_decode_options(decompress)?)
+    }
+
+    #[test]
+    fn test_deflate_roundtrip() {
+        let data = b"Hello, World! Hello, World! Hello, World!".repeat(10);
+        let compressed = deflate_raw(&data);
+        assert!(compressed.len() < data.len());
+        assert_eq!(inflate_raw(&compressed).unwrap(), data);
+    }
+
+    #[test]
+    fn test_inflate_invalid_data() {
+        let result = inflate_raw(&[0xff, 0xff, 0xff]);
+        assert!(result.err().unwrap().message.contains("Deflate decompression error"));
+    }
+
+    #[test]
+    fn test_zstd_roundtrip() {
+        set_compression_dictionary_internal(None).unwrap();
+        for data in [Vec::new(), b"a".to_vec(), b"Hello, World! ".repeat(200), (0..300_000u32).map(|i| (i * 7 % 251) as u8).collect()] {
+            let (compressed, dictionary_id) = zstd_compress(&data);
+            assert_eq!(dictionary_id, None);
+            assert_eq!(zstd_decompress(&compressed, None).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_zstd_dictionary_improves_small_messages() {
+        let dictionary = br#"{"type":"chat.message","room":"general","author":{"id":"","name":""},"body":"","sent_at":"2024-"}"#;
+        let message = br#"{"type":"chat.message","room":"general","author":{"id":"u42","name":"Ada"},"body":"hi","sent_at":"2024-05-01"}"#;
+
+        set_compression_dictionary_internal(None).unwrap();
+        let (plain, _) = zstd_compress(message);
+
+        set_compression_dictionary_internal(Some(dictionary)).unwrap();
+        let (with_dictionary, dictionary_id) = zstd_compress(message);
+        assert_eq!(dictionary_id, Some(crc32(dictionary)));
+        assert!(with_dictionary.len() < plain.len());
+        assert_eq!(zstd_decompress(&with_dictionary, dictionary_id).unwrap(), message);
+
+        // Large payloads spanning several blocks still roundtrip
+        let large: Vec<u8> = message.iter().cycle().take(400_000).copied().collect();
+        let (compressed, dictionary_id) = zstd_compress(&large);
+        assert_eq!(zstd_decompress(&compressed, dictionary_id).unwrap(), large);
+
+        // The same dictionary is required to decompress
+        set_compression_dictionary_internal(Some(b"another dictionary")).unwrap();
+        assert!(zstd_decompress(&with_dictionary, dictionary_id).err().unwrap().message.starts_with("Compression dictionary mismatch"));
+        set_compression_dictionary_internal(None).unwrap();
+        assert!(zstd_decompress(&with_dictionary, dictionary_id).err().unwrap().message.starts_with("Payload requires compression dictionary"));
+    }
+
+    #[test]
+    fn test_compression_dictionary_validation() {
+        assert!(set_compression_dictionary_internal(Some(b"")).is_err());
+        assert!(set_compression_dictionary_internal(Some(&vec![0u8; MAX_DICTIONARY_BYTES + 1])).is_err());
+        set_compression_dictionary_internal(None).unwrap();
+    }
+}
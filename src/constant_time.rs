@@ -0,0 +1,110 @@
+use wasm_bindgen::prelude::*;
+use std::hint::black_box;
+
+use crate::codec::Z85_ALPHABET;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::split_padding_suffix;
+
+// All-ones if `a == b`, zero otherwise, without branching
+fn ct_eq_mask(a: u8, b: u8) -> u32 {
+    let diff = (a ^ b) as u32;
+    // `diff - 1` underflows (setting the high bit) only when `diff == 0`
+    0u32.wrapping_sub(diff.wrapping_sub(1) >> 31)
+}
+
+// Map a character to its Z85 digit by scanning the whole alphabet.
+// Returns the digit (0 if invalid) and an all-ones mask if the character was valid.
+fn ct_digit(c: u8) -> (u32, u32) {
+    let mut digit = 0u32;
+    let mut valid = 0u32;
+    for (value, &a) in Z85_ALPHABET.iter().enumerate() {
+        let mask = black_box(ct_eq_mask(c, a));
+        digit |= mask & value as u32;
+        valid |= mask;
+    }
+    (digit, valid)
+}
+
+// Internal pure Rust function for decoding Z85 in constant time with respect to the data.
+// Every character is processed the same way and errors are only reported after the whole
+// input has been read, so timing does not reveal the contents or the position of bad input.
+// Only the input length and padding suffix (which are not secret) affect control flow.
+fn decode_z85_ct_internal(z85_data_with_padding: &str) -> Result<Vec<u8>, Error> {
+    let (z85_data, padding) = split_padding_suffix(z85_data_with_padding)?;
+    if !z85_data.len().is_multiple_of(5) {
+        return Err(z85_core::Error::InvalidDataLength { alphabet: "Z85", length: z85_data.len() }.into());
+    }
+    let decoded_len = (z85_data.len() / 5 * 4).checked_sub(padding)
+        .ok_or(z85_core::Error::InvalidPadding)?;
+
+    let mut decoded = Vec::with_capacity(z85_data.len() / 5 * 4);
+    let mut invalid = 0u32;
+    for group in z85_data.as_bytes().chunks_exact(5) {
+        let mut value = 0u64;
+        for &c in group {
+            let (digit, valid) = ct_digit(c);
+            invalid |= !valid;
+            value = value * 85 + digit as u64;
+        }
+        // Groups above u32::MAX set bits in the high half
+        invalid |= ((value >> 32) != 0) as u32;
+        decoded.extend_from_slice(&(value as u32).to_be_bytes());
+    }
+
+    if black_box(invalid) != 0 {
+        return Err(Error::new(ErrorCode::InvalidInput, "Z85 decode error: invalid input"));
+    }
+    decoded.truncate(decoded_len);
+    Ok(decoded)
+}
+
+/// Decode Z85 data with padding info in constant time with respect to the encoded contents,
+/// for keys and tokens. Errors do not reveal the position of the invalid character.
+#[wasm_bindgen]
+pub fn decode_z85_ct(z85_data_with_padding: &str) -> Result<Vec<u8>, JsValue> {
+    limits::enforce(z85_data_with_padding.len(), Vec::len, || decode_z85_ct_internal(z85_data_with_padding))
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_z85_internal;
+
+    #[test]
+    fn test_ct_eq_mask() {
+        assert_eq!(ct_eq_mask(b'a', b'a'), u32::MAX);
+        assert_eq!(ct_eq_mask(b'a', b'b'), 0);
+        assert_eq!(ct_eq_mask(0, 0xff), 0);
+        assert_eq!(ct_eq_mask(0xff, 0xff), u32::MAX);
+    }
+
+    #[test]
+    fn test_ct_digit_covers_every_byte() {
+        for c in 0..=255u8 {
+            let (digit, valid) = ct_digit(c);
+            match Z85_ALPHABET.iter().position(|&a| a == c) {
+                Some(expected) => assert_eq!((digit, valid), (expected as u32, u32::MAX)),
+                None => assert_eq!((digit, valid), (0, 0)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_z85_ct_matches_decode_z85() {
+        for len in 0..12 {
+            let data: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(73).wrapping_add(5)).collect();
+            assert_eq!(decode_z85_ct_internal(&encode_z85_internal(&data)).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_decode_z85_ct_errors_hide_position() {
+        assert_eq!(decode_z85_ct_internal("Hel~oWorld:0").err().unwrap().message, "Z85 decode error: invalid input");
+        assert_eq!(decode_z85_ct_internal("HelloWorl~:0").err().unwrap().message, "Z85 decode error: invalid input");
+        assert_eq!(decode_z85_ct_internal("#####:0").err().unwrap().message, "Z85 decode error: invalid input");
+        assert_eq!(decode_z85_ct_internal("Hell:0").err().unwrap().message, "Z85 decode error: data length (4) is not a multiple of 5");
+        assert_eq!(decode_z85_ct_internal("Hello:5").err().unwrap().message, "Invalid padding number");
+    }
+}
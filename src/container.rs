@@ -0,0 +1,317 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use tsify::Tsify;
+
+use z85_core::container;
+
+use crate::codec;
+use crate::compression::{with_decode_options, zstd_compress, Compression};
+use crate::crypto::Algorithm;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::logging::{self, LogLevel};
+use crate::mime;
+
+// Headers are parsed and written by `z85_core`; the functions below apply this crate's
+// limits, codec kernels and compression dictionary to them
+pub(crate) use z85_core::container::{crc32, ContainerHeader, V2_PREFIX};
+// File metadata stored by `encode_file_container` (returned to JS as a plain object)
+pub(crate) use z85_core::FileMetadata;
+
+// `FileMetadata` is defined in z85-core, which does not depend on tsify
+#[wasm_bindgen(typescript_custom_section)]
+const FILE_METADATA_TS: &str = "export interface FileMetadata {
+    name?: string;
+    mime?: string;
+    modified?: number;
+    size?: number;
+}";
+
+#[wasm_bindgen]
+extern "C" {
+    // A `FileMetadata` object passed from JS
+    #[wasm_bindgen(typescript_type = "FileMetadata")]
+    pub type JsFileMetadata;
+}
+
+// Parse the header of a `z85v2:` container, returning it with the Z85 data that follows.
+// Fails when the sizes it records exceed the output limit.
+pub(crate) fn parse_header(payload: &str) -> Result<(ContainerHeader, &str), Error> {
+    let (header, z85_data) = ContainerHeader::parse(payload)?;
+    // The stored payload is never larger than the data it decodes to, and neither is a
+    // block, which `encode_z85_seekable` caps at the data length
+    limits::check_output(header.length)?;
+    if let Some(block_size) = header.block_size {
+        limits::check_output(block_size)?;
+    }
+    Ok((header, z85_data))
+}
+
+// Encryption algorithm recorded in the header, if any
+pub(crate) fn encryption(header: &ContainerHeader) -> Result<Option<Algorithm>, Error> {
+    header.encryption.as_deref().map(Algorithm::from_name).transpose()
+}
+
+// Serialize the header and Z85-encode `payload` after it with this build's kernels
+pub(crate) fn encode(header: &ContainerHeader, payload: &[u8]) -> String {
+    header.encode_using(payload, codec::encode_append)
+}
+
+// Decode the Z85 data following the header into the stored payload with this build's kernels
+pub(crate) fn decode_payload(header: &ContainerHeader, z85_data: &str) -> Result<Vec<u8>, Error> {
+    Ok(header.decode_payload_using(z85_data, |groups, words| codec::decode_groups(groups, words, 0))?)
+}
+
+// Undo the compression recorded in the header, with the current dictionary and output limit
+pub(crate) fn decompress(header: &ContainerHeader, payload: Vec<u8>) -> Result<Vec<u8>, Error> {
+    Ok(with_decode_options(|options| header.decompress(payload, options))?)
+}
+
+// `z85_core::container::compress_payload_using` with the current dictionary.
+// Returns the payload, the compression applied and the id of the dictionary used.
+pub(crate) fn compress_payload(data: Vec<u8>, compression: Compression) -> (Vec<u8>, z85_core::Compression, Option<u32>) {
+    let len = data.len();
+    let (payload, applied, dictionary_id) = container::compress_payload_using(data, compression.into(), zstd_compress);
+    if compression != Compression::None && applied == z85_core::Compression::None {
+        logging::log(LogLevel::Debug, format_args!("{} did not shrink {} bytes; storing them uncompressed", compression.name(), len));
+    }
+    (payload, applied, dictionary_id)
+}
+
+// Internal pure Rust function for encoding bytes to the `z85v2:` container format.
+pub(crate) fn encode_z85_v2_internal(data: &[u8], with_checksum: bool) -> String {
+    encode_container_internal(data, with_checksum, Compression::None)
+}
+
+// Encode bytes to the `z85v2:` container format, optionally compressing the payload
+pub(crate) fn encode_container_internal(data: &[u8], with_checksum: bool, compression: Compression) -> String {
+    encode_container_with_file(data, with_checksum, compression, None)
+}
+
+// Encode bytes to the `z85v2:` container format with optional file metadata
+fn encode_container_with_file(data: &[u8], with_checksum: bool, compression: Compression, file: Option<FileMetadata>) -> String {
+    let header = ContainerHeader { length: data.len(), checksum: with_checksum.then(|| crc32(data)), file, ..ContainerHeader::default() };
+    if compression == Compression::None {
+        return encode(&header, data);
+    }
+    let (payload, compression, dictionary_id) = compress_payload(data.to_vec(), compression);
+    encode(&ContainerHeader { length: payload.len(), compression, dictionary_id, ..header }, &payload)
+}
+
+/// Encode raw bytes to the self-describing `z85v2:` container format, which records the
+/// original length and (unless `with_checksum` is `false`) a CRC-32 of the payload.
+#[wasm_bindgen]
+pub fn encode_z85_v2(data: &[u8], with_checksum: Option<bool>) -> String {
+    encode_z85_v2_internal(data, with_checksum.unwrap_or(true))
+}
+
+// Internal pure Rust function for decoding the `z85v2:` container format
+pub(crate) fn decode_z85_v2_internal(payload: &str) -> Result<Vec<u8>, Error> {
+    let (header, z85_data) = parse_header(payload)?;
+    decode_container(&header, z85_data)
+}
+
+// Decode, decompress and verify the data of an unencrypted container
+fn decode_container(header: &ContainerHeader, z85_data: &str) -> Result<Vec<u8>, Error> {
+    if let Some(algorithm) = encryption(header)? {
+        return Err(Error::new(ErrorCode::BadContainer, format!("Container is encrypted ({}): decode it with Pipeline.fromHeader and the key", algorithm.name())));
+    }
+
+    let decoded = decode_payload(header, z85_data)?;
+    let decoded = decompress(header, decoded)?;
+    header.verify_checksum(&decoded)?;
+    Ok(decoded)
+}
+
+/// Decode a `z85v2:` container, verifying its length and checksum
+#[wasm_bindgen]
+pub fn decode_z85_v2(payload: &str) -> Result<Vec<u8>, JsValue> {
+    limits::enforce(payload.len(), Vec::len, || decode_z85_v2_internal(payload))
+        .map_err(js_error)
+}
+
+/// Result of `decode_file_container` (returned to JS as a plain object)
+#[derive(Serialize, Tsify)]
+struct DecodedFileContainer {
+    #[tsify(type = "Uint8Array")]
+    data: serde_bytes::ByteBuf,
+    metadata: FileMetadata,
+}
+
+// Internal pure Rust function for storing bytes with their file name, MIME type and
+// modification time in a checksummed `z85v2:` container. The MIME type is sniffed when
+// omitted and `size` is always the length of `data`.
+pub(crate) fn encode_file_container_internal(data: &[u8], metadata: FileMetadata, compression: Compression) -> Result<String, Error> {
+    let mime = match metadata.mime {
+        Some(mime_type) => mime::validate_mime_type(&mime_type)?.to_string(),
+        None => mime::sniff_mime_type(data).to_string(),
+    };
+    let file = FileMetadata { mime: Some(mime), size: Some(data.len()), ..metadata };
+    Ok(encode_container_with_file(data, true, compression, Some(file)))
+}
+
+// Internal pure Rust function for decoding a `z85v2:` container with its file metadata. Plain
+// containers decode too, with only `size` set.
+pub(crate) fn decode_file_container_internal(payload: &str) -> Result<(Vec<u8>, FileMetadata), Error> {
+    let (header, z85_data) = parse_header(payload.trim())?;
+    let data = decode_container(&header, z85_data)?;
+    let metadata = FileMetadata { size: Some(data.len()), ..header.file.unwrap_or_default() };
+    Ok((data, metadata))
+}
+
+/// Store raw bytes together with `{ name?, mime?, modified? }` (modified in milliseconds since
+/// the Unix epoch, e.g. `File.lastModified`) in one checksummed `z85v2:` container, so the
+/// metadata cannot drift from the data it describes. The MIME type is sniffed when omitted;
+/// the original length is recorded as `size`.
+#[wasm_bindgen]
+pub fn encode_file_container(data: &[u8], metadata: Option<JsFileMetadata>, compression: Option<Compression>) -> Result<String, JsValue> {
+    let metadata: FileMetadata = match metadata {
+        None => FileMetadata::default(),
+        Some(metadata) => serde_wasm_bindgen::from_value(metadata.into())
+            .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, format!("Invalid metadata: {}", e))))?,
+    };
+    let compression = compression.unwrap_or(Compression::None);
+    limits::enforce(data.len(), String::len, || encode_file_container_internal(data, metadata, compression))
+        .map_err(js_error)
+}
+
+/// Decode a container from `encode_file_container` into
+/// `{ data: Uint8Array, metadata: { name, mime, modified, size } }`, verifying its checksum and size
+#[wasm_bindgen(unchecked_return_type = "DecodedFileContainer")]
+pub fn decode_file_container(payload: &str) -> Result<JsValue, JsValue> {
+    let (data, metadata) = limits::enforce(payload.len(), |(data, _): &(Vec<u8>, FileMetadata)| data.len(), || decode_file_container_internal(payload))
+        .map_err(js_error)?;
+    serde_wasm_bindgen::to_value(&DecodedFileContainer { data: serde_bytes::ByteBuf::from(data), metadata })
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode_z85_internal;
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_z85_v2_roundtrip() {
+        for len in 0..10 {
+            let data: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(91)).collect();
+            for with_checksum in [true, false] {
+                let encoded = encode_z85_v2_internal(&data, with_checksum);
+                assert!(encoded.starts_with("z85v2:len="));
+                assert_eq!(decode_z85_v2_internal(&encoded).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_z85_v2_format() {
+        assert_eq!(encode_z85_v2_internal(b"Hello World", false), "z85v2:len=11:nm=QNzY&b1A+]m^");
+        let encoded = encode_z85_v2_internal(b"Hello World", true);
+        assert_eq!(encoded, format!("z85v2:len=11;crc32={:08x}:nm=QNzY&b1A+]m^", crc32(b"Hello World")));
+        // Unknown header parameters are ignored
+        assert_eq!(decode_z85_v2_internal("z85v2:len=11;future=1:nm=QNzY&b1A+]m^").unwrap(), b"Hello World");
+    }
+
+    #[test]
+    fn test_z85_v2_errors() {
+        assert_eq!(decode_z85_v2_internal("nm=QNzY&b1A+]m^:1").err().unwrap().message, "Invalid format: expected 'z85v2:' prefix");
+        assert_eq!(decode_z85_v2_internal("z85v2:len=11").err().unwrap().message, "Invalid z85v2 header: missing ':' before data");
+        assert_eq!(decode_z85_v2_internal("z85v2:crc32=0:nm=QNzY&b1A+]m^").err().unwrap().message, "Invalid z85v2 header: missing len");
+        assert_eq!(decode_z85_v2_internal("z85v2:len=11:nm=QNzY&b1").err().unwrap().message, "Z85 data truncated: expected 11 bytes, got 8");
+
+        let tampered = encode_z85_v2_internal(b"Hello World", true).replace("nm=QN", "nm=QM");
+        assert!(decode_z85_v2_internal(&tampered).err().unwrap().message.starts_with("Checksum mismatch"));
+    }
+
+    #[test]
+    fn test_container_compression() {
+        let data = b"Hello, World! ".repeat(50);
+        let encoded = encode_container_internal(&data, true, Compression::Deflate);
+        assert!(encoded.contains(";compression=deflate:"));
+        assert!(encoded.len() < encode_z85_v2_internal(&data, true).len() / 4);
+        assert_eq!(decode_z85_v2_internal(&encoded).unwrap(), data);
+
+        // Incompressible payloads are stored as is
+        let encoded = encode_container_internal(b"abc", true, Compression::Deflate);
+        assert!(!encoded.contains("compression"));
+        assert_eq!(decode_z85_v2_internal(&encoded).unwrap(), b"abc");
+
+        let result = decode_z85_v2_internal("z85v2:len=0;compression=brotli:");
+        assert_eq!(result.err().unwrap().message, "Unsupported compression: brotli");
+    }
+
+    #[test]
+    fn test_container_zstd_dictionary() {
+        let data = b"Hello, World! ".repeat(50);
+        let encoded = encode_container_internal(&data, true, Compression::Zstd);
+        assert!(encoded.contains(";compression=zstd:"));
+        assert_eq!(decode_z85_v2_internal(&encoded).unwrap(), data);
+
+        let message = b"Goodbye, World! Hello, World! Hello again, World!";
+        crate::compression::set_compression_dictionary(Some(message[..40].to_vec())).unwrap();
+        let encoded = encode_container_internal(message, true, Compression::Zstd);
+        let id = crc32(&message[..40]);
+        assert!(encoded.contains(&format!(";compression=zstd;dict={:08x}:", id)));
+        assert_eq!(decode_z85_v2_internal(&encoded).unwrap(), message);
+
+        crate::compression::set_compression_dictionary(None).unwrap();
+        assert!(decode_z85_v2_internal(&encoded).err().unwrap().message.starts_with("Payload requires compression dictionary"));
+    }
+
+    #[test]
+    fn test_decode_z85_detects_v2() {
+        let encoded = encode_z85_v2_internal(b"Hello World", true);
+        assert_eq!(decode_z85_internal(&encoded).unwrap(), b"Hello World");
+        // Legacy strings still decode
+        assert_eq!(decode_z85_internal("nm=QNzY&b1A+]m^:1").unwrap(), b"Hello World");
+    }
+
+    #[test]
+    fn test_file_container_roundtrip() {
+        let data = b"%PDF-1.7 quarterly report";
+        let metadata = FileMetadata { name: Some("Q3 report; final.pdf".into()), modified: Some(1_700_000_000_000), ..FileMetadata::default() };
+        let encoded = encode_file_container_internal(data, metadata, Compression::None).unwrap();
+        assert!(encoded.contains(";name=Q3%20report%3B%20final.pdf;mime=application/pdf;mtime=1700000000000;size=25:"));
+
+        let (decoded, metadata) = decode_file_container_internal(&encoded).unwrap();
+        assert_eq!(decoded, data);
+        assert_eq!(metadata, FileMetadata {
+            name: Some("Q3 report; final.pdf".into()),
+            mime: Some("application/pdf".into()),
+            modified: Some(1_700_000_000_000),
+            size: Some(25),
+        });
+        // Still an ordinary container
+        assert_eq!(decode_z85_internal(&encoded).unwrap(), data);
+
+        // Non-ASCII names and compression
+        let text = "Grüße ".repeat(40);
+        let metadata = FileMetadata { name: Some("grüße.txt".into()), mime: Some("text/plain".into()), ..FileMetadata::default() };
+        let encoded = encode_file_container_internal(text.as_bytes(), metadata, Compression::Deflate).unwrap();
+        assert!(encoded.contains(";compression=deflate;name=gr%C3%BC%C3%9Fe.txt;mime=text/plain;size=320:"));
+        let (decoded, metadata) = decode_file_container_internal(&encoded).unwrap();
+        assert_eq!((decoded.as_slice(), metadata.name.as_deref()), (text.as_bytes(), Some("grüße.txt")));
+
+        let (_, metadata) = decode_file_container_internal(&encode_z85_v2_internal(b"abc", true)).unwrap();
+        assert_eq!(metadata, FileMetadata { size: Some(3), ..FileMetadata::default() });
+    }
+
+    #[test]
+    fn test_file_container_errors() {
+        let metadata = FileMetadata { mime: Some("not a mime type".into()), ..FileMetadata::default() };
+        assert_eq!(encode_file_container_internal(b"", metadata, Compression::None).err().unwrap().message, "Invalid MIME type: not a mime type");
+
+        let encoded = encode_file_container_internal(b"Hello World", FileMetadata::default(), Compression::None).unwrap();
+        assert_eq!(
+            decode_file_container_internal(&encoded.replace(";size=11", ";size=12")).err().unwrap().message,
+            "Container size mismatch: expected 12 bytes, got 11"
+        );
+        assert_eq!(decode_file_container_internal("z85v2:len=0;name=%FF:").err().unwrap().message, "Invalid z85v2 name: not UTF-8 after unescaping");
+        assert_eq!(decode_file_container_internal("z85v2:len=0;mtime=soon:").err().unwrap().message, "Invalid z85v2 mtime: soon");
+    }
+}
@@ -0,0 +1,566 @@
+use wasm_bindgen::prelude::*;
+use aes_gcm::aead::{AeadInPlace, KeyInit};
+use aes_gcm::Aes256Gcm;
+use argon2::{Argon2, Params, Version};
+use chacha20poly1305::XChaCha20Poly1305;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{decode_z85_internal, encode_z85_internal};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Length of an HMAC-SHA256 tag
+const HMAC_TAG_BYTES: usize = 32;
+
+/// AEAD key length (both cipher suites use 256-bit keys)
+pub(crate) const AEAD_KEY_BYTES: usize = 32;
+/// AEAD authentication tag length
+const AEAD_TAG_BYTES: usize = 16;
+
+/// Password container KDF identifier for Argon2id
+const KDF_ARGON2ID: u8 = 1;
+/// Argon2 salt length
+const KDF_SALT_BYTES: usize = 16;
+/// Password container header: kdf (1) || memory KiB (4) || iterations (4) || parallelism (4) || salt
+const KDF_HEADER_BYTES: usize = 1 + 4 + 4 + 4 + KDF_SALT_BYTES;
+/// Upper bound on the Argon2 memory cost accepted from a header (256 MiB, over ten times the
+/// default and well within the 4 GiB a 32-bit WASM instance can address)
+const KDF_MAX_MEMORY_KIB: u32 = 256 * 1024;
+/// Upper bound on the Argon2 iterations accepted from a header
+const KDF_MAX_ITERATIONS: u32 = 64;
+/// Upper bound on the Argon2 lanes accepted from a header (WASM computes them one at a time)
+const KDF_MAX_PARALLELISM: u32 = 16;
+
+/// Cipher suite for `encrypt_to_z85`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Algorithm {
+    /// AES-256-GCM with a 12-byte nonce (fast with AES hardware acceleration)
+    Aes256Gcm,
+    /// XChaCha20-Poly1305 with a 24-byte nonce (fast in pure software)
+    XChaCha20Poly1305,
+}
+
+impl Algorithm {
+    // Identifier stored in the first byte of the container
+    fn id(self) -> u8 {
+        match self {
+            Algorithm::Aes256Gcm => 1,
+            Algorithm::XChaCha20Poly1305 => 2,
+        }
+    }
+
+    fn from_id(id: u8) -> Result<Algorithm, Error> {
+        match id {
+            1 => Ok(Algorithm::Aes256Gcm),
+            2 => Ok(Algorithm::XChaCha20Poly1305),
+            _ => Err(Error::new(ErrorCode::Unsupported, format!("Unsupported encryption algorithm: {}", id))),
+        }
+    }
+
+    // Name used in `z85v2:` container headers and by `Pipeline`
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Algorithm::Aes256Gcm => "aes-gcm",
+            Algorithm::XChaCha20Poly1305 => "xchacha20-poly1305",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Result<Algorithm, Error> {
+        match name {
+            "aes-gcm" | "aes-256-gcm" => Ok(Algorithm::Aes256Gcm),
+            "xchacha20-poly1305" => Ok(Algorithm::XChaCha20Poly1305),
+            _ => Err(Error::new(ErrorCode::Unsupported, format!("Unsupported encryption algorithm: {}", name))),
+        }
+    }
+
+    fn nonce_len(self) -> usize {
+        match self {
+            Algorithm::Aes256Gcm => 12,
+            Algorithm::XChaCha20Poly1305 => 24,
+        }
+    }
+
+    // Encrypt `buffer` in place, returning the tag
+    fn seal(self, key: &[u8], nonce: &[u8], buffer: &mut [u8]) -> Result<Vec<u8>, Error> {
+        let tag = match self {
+            Algorithm::Aes256Gcm => Aes256Gcm::new_from_slice(key)
+                .map_err(|e| Error::new(ErrorCode::InvalidKey, format!("Key error: {}", e)))?
+                .encrypt_in_place_detached(nonce.into(), b"", buffer)
+                .map(|tag| tag.to_vec()),
+            Algorithm::XChaCha20Poly1305 => XChaCha20Poly1305::new_from_slice(key)
+                .map_err(|e| Error::new(ErrorCode::InvalidKey, format!("Key error: {}", e)))?
+                .encrypt_in_place_detached(nonce.into(), b"", buffer)
+                .map(|tag| tag.to_vec()),
+        };
+        tag.map_err(|_| Error::new(ErrorCode::CryptoFailed, "Encryption failed"))
+    }
+
+    // Verify `tag` and decrypt `buffer` in place
+    fn open(self, key: &[u8], nonce: &[u8], buffer: &mut [u8], tag: &[u8]) -> Result<(), Error> {
+        let result = match self {
+            Algorithm::Aes256Gcm => Aes256Gcm::new_from_slice(key)
+                .map_err(|e| Error::new(ErrorCode::InvalidKey, format!("Key error: {}", e)))?
+                .decrypt_in_place_detached(nonce.into(), b"", buffer, tag.into()),
+            Algorithm::XChaCha20Poly1305 => XChaCha20Poly1305::new_from_slice(key)
+                .map_err(|e| Error::new(ErrorCode::InvalidKey, format!("Key error: {}", e)))?
+                .decrypt_in_place_detached(nonce.into(), b"", buffer, tag.into()),
+        };
+        result.map_err(|_| Error::new(ErrorCode::CryptoFailed, "Decryption failed: wrong key or corrupted data"))
+    }
+}
+
+// Compute the HMAC-SHA256 state over `data` with `key`
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<HmacSha256, Error> {
+    if key.is_empty() {
+        return Err(Error::new(ErrorCode::InvalidKey, "HMAC key must not be empty"));
+    }
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(key)
+        .map_err(|e| Error::new(ErrorCode::InvalidKey, format!("HMAC key error: {}", e)))?;
+    mac.update(data);
+    Ok(mac)
+}
+
+// Internal pure Rust function for encoding bytes to Z85 with an appended HMAC-SHA256 tag
+fn encode_z85_authenticated_internal(data: &[u8], key: &[u8]) -> Result<String, Error> {
+    let tag = hmac_sha256(key, data)?.finalize().into_bytes();
+
+    // The tag travels inside the encoded payload: data || tag
+    let mut payload = Vec::with_capacity(data.len() + HMAC_TAG_BYTES);
+    payload.extend_from_slice(data);
+    payload.extend_from_slice(&tag);
+    Ok(encode_z85_internal(&payload))
+}
+
+/// Encode raw bytes to Z85 with padding info, appending an HMAC-SHA256 tag of the data
+/// (computed with `key`) inside the encoded payload
+#[wasm_bindgen]
+pub fn encode_z85_authenticated(data: &[u8], key: &[u8]) -> Result<String, JsValue> {
+    encode_z85_authenticated_internal(data, key)
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for decoding authenticated Z85 and verifying its HMAC-SHA256 tag
+fn decode_z85_authenticated_internal(z85_data_with_padding: &str, key: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut payload = decode_z85_internal(z85_data_with_padding)?;
+    let data_len = payload.len().checked_sub(HMAC_TAG_BYTES)
+        .ok_or_else(|| Error::new(ErrorCode::CryptoFailed, "Authentication failed: payload too short to contain a tag"))?;
+
+    // Verify in constant time before releasing any data
+    hmac_sha256(key, &payload[..data_len])?
+        .verify_slice(&payload[data_len..])
+        .map_err(|_| Error::new(ErrorCode::CryptoFailed, "Authentication failed: HMAC tag mismatch"))?;
+
+    payload.truncate(data_len);
+    Ok(payload)
+}
+
+/// Decode Z85 produced by `encode_z85_authenticated`, throwing if the HMAC-SHA256 tag
+/// does not match (the data was modified or the key is wrong)
+#[wasm_bindgen]
+pub fn decode_z85_authenticated(z85_data_with_padding: &str, key: &[u8]) -> Result<Vec<u8>, JsValue> {
+    decode_z85_authenticated_internal(z85_data_with_padding, key)
+        .map_err(js_error)
+}
+
+// Fill `length` bytes from the platform CSPRNG
+fn random_bytes(length: usize) -> Result<Vec<u8>, Error> {
+    let mut bytes = vec![0u8; length];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| Error::new(ErrorCode::CryptoFailed, format!("Random number generation failed: {}", e)))?;
+    Ok(bytes)
+}
+
+// Generate a fresh nonce, or validate a caller-supplied one
+fn resolve_nonce(nonce: Option<&[u8]>, length: usize) -> Result<Vec<u8>, Error> {
+    match nonce {
+        Some(nonce) if nonce.len() != length => {
+            Err(Error::new(ErrorCode::InvalidKey, format!("Nonce must be {} bytes, got {}", length, nonce.len())))
+        }
+        Some(nonce) => Ok(nonce.to_vec()),
+        None => random_bytes(length),
+    }
+}
+
+// Internal pure Rust function for AEAD encryption straight into a Z85 container.
+// Container layout (before Z85 encoding): algorithm (1 byte) || nonce || ciphertext || tag
+fn encrypt_to_z85_internal(data: &[u8], key: &[u8], nonce: Option<&[u8]>, algorithm: Algorithm) -> Result<String, Error> {
+    if key.len() != AEAD_KEY_BYTES {
+        return Err(Error::new(ErrorCode::InvalidKey, format!("Key must be {} bytes, got {}", AEAD_KEY_BYTES, key.len())));
+    }
+    let nonce = resolve_nonce(nonce, algorithm.nonce_len())?;
+
+    // Encrypt in place inside the container buffer
+    let header_len = 1 + nonce.len();
+    let mut container = Vec::with_capacity(header_len + data.len() + AEAD_TAG_BYTES);
+    container.push(algorithm.id());
+    container.extend_from_slice(&nonce);
+    container.extend_from_slice(data);
+    let tag = algorithm.seal(key, &nonce, &mut container[header_len..])?;
+    container.extend_from_slice(&tag);
+
+    Ok(encode_z85_internal(&container))
+}
+
+// Encrypt `data` with a fresh random nonce into nonce || ciphertext || tag, reusing its buffer
+pub(crate) fn seal_payload(algorithm: Algorithm, key: &[u8], data: Vec<u8>) -> Result<Vec<u8>, Error> {
+    if key.len() != AEAD_KEY_BYTES {
+        return Err(Error::new(ErrorCode::InvalidKey, format!("Key must be {} bytes, got {}", AEAD_KEY_BYTES, key.len())));
+    }
+    let mut payload = random_bytes(algorithm.nonce_len())?;
+    payload.reserve(data.len() + AEAD_TAG_BYTES);
+    payload.extend_from_slice(&data);
+    let (nonce, body) = payload.split_at_mut(algorithm.nonce_len());
+    let tag = algorithm.seal(key, nonce, body)?;
+    payload.extend_from_slice(&tag);
+    Ok(payload)
+}
+
+// Verify and decrypt nonce || ciphertext || tag produced by `seal_payload`
+pub(crate) fn open_payload(algorithm: Algorithm, key: &[u8], mut payload: Vec<u8>) -> Result<Vec<u8>, Error> {
+    if key.len() != AEAD_KEY_BYTES {
+        return Err(Error::new(ErrorCode::InvalidKey, format!("Key must be {} bytes, got {}", AEAD_KEY_BYTES, key.len())));
+    }
+    let nonce_len = algorithm.nonce_len();
+    let ciphertext_len = payload.len().checked_sub(nonce_len + AEAD_TAG_BYTES)
+        .ok_or_else(|| Error::new(ErrorCode::CryptoFailed, "Decryption failed: container too short"))?;
+    let (nonce, body) = payload.split_at_mut(nonce_len);
+    let (ciphertext, tag) = body.split_at_mut(ciphertext_len);
+    algorithm.open(key, nonce, ciphertext, tag)?;
+    payload.truncate(nonce_len + ciphertext_len);
+    payload.drain(..nonce_len);
+    Ok(payload)
+}
+
+/// Encrypt raw bytes and encode the result to Z85 with padding info.
+/// `key` must be 32 bytes. `algorithm` defaults to `Algorithm.Aes256Gcm`. The nonce (12 bytes for
+/// AES-GCM, 24 for XChaCha20-Poly1305) is generated randomly when omitted and must never be reused
+/// with the same key. The algorithm, nonce and tag are embedded in the output.
+#[wasm_bindgen]
+pub fn encrypt_to_z85(data: &[u8], key: &[u8], nonce: Option<Vec<u8>>, algorithm: Option<Algorithm>) -> Result<String, JsValue> {
+    encrypt_to_z85_internal(data, key, nonce.as_deref(), algorithm.unwrap_or(Algorithm::Aes256Gcm))
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for decoding and decrypting a Z85 AEAD container
+fn decrypt_from_z85_internal(z85_data_with_padding: &str, key: &[u8]) -> Result<Vec<u8>, Error> {
+    if key.len() != AEAD_KEY_BYTES {
+        return Err(Error::new(ErrorCode::InvalidKey, format!("Key must be {} bytes, got {}", AEAD_KEY_BYTES, key.len())));
+    }
+    let mut container = decode_z85_internal(z85_data_with_padding)?;
+    let algorithm = match container.first() {
+        Some(&id) => Algorithm::from_id(id)?,
+        None => return Err(Error::new(ErrorCode::CryptoFailed, "Decryption failed: container is empty")),
+    };
+
+    let header_len = 1 + algorithm.nonce_len();
+    let ciphertext_len = container.len().checked_sub(header_len + AEAD_TAG_BYTES)
+        .ok_or_else(|| Error::new(ErrorCode::CryptoFailed, "Decryption failed: container too short"))?;
+
+    // Decrypt in place and strip the header and tag
+    let (header, body) = container.split_at_mut(header_len);
+    let (ciphertext, tag) = body.split_at_mut(ciphertext_len);
+    algorithm.open(key, &header[1..], ciphertext, tag)?;
+    container.truncate(header_len + ciphertext_len);
+    container.drain(..header_len);
+
+    Ok(container)
+}
+
+/// Decode and decrypt the output of `encrypt_to_z85` with the same 32-byte key.
+/// The algorithm is read from the container. Throws if the key is wrong or the data was modified.
+#[wasm_bindgen]
+pub fn decrypt_from_z85(z85_data_with_padding: &str, key: &[u8]) -> Result<Vec<u8>, JsValue> {
+    decrypt_from_z85_internal(z85_data_with_padding, key)
+        .map_err(js_error)
+}
+
+/// Argon2id cost parameters and cipher suite for `encrypt_to_z85_with_password`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct PasswordParams {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    algorithm: Algorithm,
+}
+
+impl Default for PasswordParams {
+    // OWASP recommended Argon2id baseline (19 MiB, 2 iterations, 1 lane)
+    fn default() -> Self {
+        PasswordParams { memory_kib: 19 * 1024, iterations: 2, parallelism: 1, algorithm: Algorithm::Aes256Gcm }
+    }
+}
+
+#[wasm_bindgen]
+impl PasswordParams {
+    #[wasm_bindgen(constructor)]
+    pub fn new(memory_kib: Option<u32>, iterations: Option<u32>, parallelism: Option<u32>, algorithm: Option<Algorithm>) -> PasswordParams {
+        let defaults = PasswordParams::default();
+        PasswordParams {
+            memory_kib: memory_kib.unwrap_or(defaults.memory_kib),
+            iterations: iterations.unwrap_or(defaults.iterations),
+            parallelism: parallelism.unwrap_or(defaults.parallelism),
+            algorithm: algorithm.unwrap_or(defaults.algorithm),
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn memory_kib(&self) -> u32 {
+        self.memory_kib
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn iterations(&self) -> u32 {
+        self.iterations
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn parallelism(&self) -> u32 {
+        self.parallelism
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+}
+
+// Derive a 32-byte key from a password with Argon2id
+fn derive_key(password: &[u8], salt: &[u8], memory_kib: u32, iterations: u32, parallelism: u32) -> Result<[u8; AEAD_KEY_BYTES], Error> {
+    if memory_kib > KDF_MAX_MEMORY_KIB {
+        return Err(Error::new(ErrorCode::CryptoFailed, format!("Argon2 memory cost too large: {} KiB (max {})", memory_kib, KDF_MAX_MEMORY_KIB)));
+    }
+    if iterations > KDF_MAX_ITERATIONS {
+        return Err(Error::new(ErrorCode::CryptoFailed, format!("Argon2 iterations too large: {} (max {})", iterations, KDF_MAX_ITERATIONS)));
+    }
+    if parallelism > KDF_MAX_PARALLELISM {
+        return Err(Error::new(ErrorCode::CryptoFailed, format!("Argon2 parallelism too large: {} (max {})", parallelism, KDF_MAX_PARALLELISM)));
+    }
+    let params = Params::new(memory_kib, iterations, parallelism, Some(AEAD_KEY_BYTES))
+        .map_err(|e| Error::new(ErrorCode::CryptoFailed, format!("Invalid Argon2 parameters: {}", e)))?;
+    let mut key = [0u8; AEAD_KEY_BYTES];
+    Argon2::new(argon2::Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password, salt, &mut key)
+        .map_err(|e| Error::new(ErrorCode::CryptoFailed, format!("Key derivation failed: {}", e)))?;
+    Ok(key)
+}
+
+// Internal pure Rust function for password-based encryption to Z85.
+// Container layout (before Z85 encoding): KDF header || AEAD container (see `encrypt_to_z85_internal`)
+fn encrypt_to_z85_with_password_internal(data: &[u8], password: &str, params: PasswordParams) -> Result<String, Error> {
+    let salt = random_bytes(KDF_SALT_BYTES)?;
+    let key = derive_key(password.as_bytes(), &salt, params.memory_kib, params.iterations, params.parallelism)?;
+    let nonce = random_bytes(params.algorithm.nonce_len())?;
+
+    let mut container = Vec::with_capacity(KDF_HEADER_BYTES + 1 + nonce.len() + data.len() + AEAD_TAG_BYTES);
+    container.push(KDF_ARGON2ID);
+    container.extend_from_slice(&params.memory_kib.to_be_bytes());
+    container.extend_from_slice(&params.iterations.to_be_bytes());
+    container.extend_from_slice(&params.parallelism.to_be_bytes());
+    container.extend_from_slice(&salt);
+    container.push(params.algorithm.id());
+    container.extend_from_slice(&nonce);
+    let body_start = container.len();
+    container.extend_from_slice(data);
+    let tag = params.algorithm.seal(&key, &nonce, &mut container[body_start..])?;
+    container.extend_from_slice(&tag);
+
+    Ok(encode_z85_internal(&container))
+}
+
+/// Encrypt raw bytes with a key derived from `password` by Argon2id and encode the result to Z85.
+/// The salt and Argon2 parameters are stored in the output, so only the password is needed to decrypt.
+/// `params` defaults to 19 MiB memory, 2 iterations, 1 lane and AES-256-GCM.
+#[wasm_bindgen]
+pub fn encrypt_to_z85_with_password(data: &[u8], password: &str, params: Option<PasswordParams>) -> Result<String, JsValue> {
+    encrypt_to_z85_with_password_internal(data, password, params.unwrap_or_default())
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for decoding and decrypting a password-based Z85 container
+fn decrypt_from_z85_with_password_internal(z85_data_with_padding: &str, password: &str) -> Result<Vec<u8>, Error> {
+    let container = decode_z85_internal(z85_data_with_padding)?;
+    if container.len() < KDF_HEADER_BYTES {
+        return Err(Error::new(ErrorCode::CryptoFailed, "Decryption failed: container too short"));
+    }
+    if container[0] != KDF_ARGON2ID {
+        return Err(Error::new(ErrorCode::Unsupported, format!("Unsupported key derivation function: {}", container[0])));
+    }
+
+    // Re-derive the key from the stored parameters
+    let read_u32 = |offset: usize| u32::from_be_bytes([container[offset], container[offset + 1], container[offset + 2], container[offset + 3]]);
+    let salt = &container[13..KDF_HEADER_BYTES];
+    let key = derive_key(password.as_bytes(), salt, read_u32(1), read_u32(5), read_u32(9))?;
+
+    let mut body = container[KDF_HEADER_BYTES..].to_vec();
+    let algorithm = match body.first() {
+        Some(&id) => Algorithm::from_id(id)?,
+        None => return Err(Error::new(ErrorCode::CryptoFailed, "Decryption failed: container too short")),
+    };
+    let header_len = 1 + algorithm.nonce_len();
+    let ciphertext_len = body.len().checked_sub(header_len + AEAD_TAG_BYTES)
+        .ok_or_else(|| Error::new(ErrorCode::CryptoFailed, "Decryption failed: container too short"))?;
+
+    let (header, rest) = body.split_at_mut(header_len);
+    let (ciphertext, tag) = rest.split_at_mut(ciphertext_len);
+    algorithm.open(&key, &header[1..], ciphertext, tag)
+        .map_err(|_| Error::new(ErrorCode::CryptoFailed, "Decryption failed: wrong password or corrupted data"))?;
+    body.truncate(header_len + ciphertext_len);
+    body.drain(..header_len);
+
+    Ok(body)
+}
+
+/// Decode and decrypt the output of `encrypt_to_z85_with_password`.
+/// Throws if the password is wrong or the data was modified.
+#[wasm_bindgen]
+pub fn decrypt_from_z85_with_password(z85_data_with_padding: &str, password: &str) -> Result<Vec<u8>, JsValue> {
+    decrypt_from_z85_with_password_internal(z85_data_with_padding, password)
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hmac_sha256_rfc4231() {
+        // RFC 4231 test case 2
+        let tag = hmac_sha256(b"Jefe", b"what do ya want for nothing?").unwrap().finalize().into_bytes();
+        let hex: String = tag.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex, "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    }
+
+    #[test]
+    fn test_authenticated_roundtrip() {
+        let key = b"secret key";
+        for data in [&b""[..], b"a", b"Hello, World!"] {
+            let encoded = encode_z85_authenticated_internal(data, key).unwrap();
+            assert_eq!(decode_z85_authenticated_internal(&encoded, key).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_authenticated_detects_tampering() {
+        let encoded = encode_z85_authenticated_internal(b"Hello, World!", b"secret key").unwrap();
+
+        // Wrong key
+        let result = decode_z85_authenticated_internal(&encoded, b"other key");
+        assert_eq!(result.err().unwrap().message, "Authentication failed: HMAC tag mismatch");
+
+        // Modified data
+        let tampered = format!("{}{}", if encoded.starts_with('0') { "1" } else { "0" }, &encoded[1..]);
+        let result = decode_z85_authenticated_internal(&tampered, b"secret key");
+        assert_eq!(result.err().unwrap().message, "Authentication failed: HMAC tag mismatch");
+
+        // Too short to hold a tag
+        let short = encode_z85_internal(b"short");
+        let result = decode_z85_authenticated_internal(&short, b"secret key");
+        assert_eq!(result.err().unwrap().message, "Authentication failed: payload too short to contain a tag");
+
+        assert_eq!(encode_z85_authenticated_internal(b"data", b"").err().unwrap().message, "HMAC key must not be empty");
+    }
+
+    #[test]
+    fn test_aes_gcm_matches_nist_vector() {
+        // NIST GCM test case 13: 256-bit zero key, zero nonce, empty plaintext
+        let encoded = encrypt_to_z85_internal(b"", &[0u8; 32], Some(&[0u8; 12]), Algorithm::Aes256Gcm).unwrap();
+        let container = decode_z85_internal(&encoded).unwrap();
+        let tag: String = container[13..].iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(container[0], 1);
+        assert_eq!(tag, "530f8afbc74536b9a963b4f1c4cb738b");
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let key = [7u8; 32];
+        for algorithm in [Algorithm::Aes256Gcm, Algorithm::XChaCha20Poly1305] {
+            for data in [&b""[..], b"x", b"Hello, encrypted World!"] {
+                let encoded = encrypt_to_z85_internal(data, &key, None, algorithm).unwrap();
+                assert_eq!(decrypt_from_z85_internal(&encoded, &key).unwrap(), data);
+            }
+            // Random nonces make every ciphertext unique
+            let first = encrypt_to_z85_internal(b"same", &key, None, algorithm).unwrap();
+            let second = encrypt_to_z85_internal(b"same", &key, None, algorithm).unwrap();
+            assert_ne!(first, second);
+        }
+    }
+
+    #[test]
+    fn test_decrypt_errors() {
+        let key = [7u8; 32];
+        let encoded = encrypt_to_z85_internal(b"Hello", &key, None, Algorithm::Aes256Gcm).unwrap();
+        assert_eq!(decrypt_from_z85_internal(&encoded, &[8u8; 32]).err().unwrap().message, "Decryption failed: wrong key or corrupted data");
+        assert_eq!(decrypt_from_z85_internal(&encoded, &[7u8; 16]).err().unwrap().message, "Key must be 32 bytes, got 16");
+        assert_eq!(encrypt_to_z85_internal(b"Hello", &key, Some(&[0u8; 8]), Algorithm::Aes256Gcm).err().unwrap().message, "Nonce must be 12 bytes, got 8");
+        assert_eq!(decrypt_from_z85_internal(&encode_z85_internal(&[9, 0, 0]), &key).err().unwrap().message, "Unsupported encryption algorithm: 9");
+        assert_eq!(decrypt_from_z85_internal(&encode_z85_internal(&[1, 0, 0]), &key).err().unwrap().message, "Decryption failed: container too short");
+        let result = encrypt_to_z85_internal(b"Hello", &key, Some(&[0u8; 12]), Algorithm::XChaCha20Poly1305);
+        assert_eq!(result.err().unwrap().message, "Nonce must be 24 bytes, got 12");
+    }
+
+    #[test]
+    fn test_xchacha_container_layout() {
+        let encoded = encrypt_to_z85_internal(b"Hello", &[1u8; 32], Some(&[2u8; 24]), Algorithm::XChaCha20Poly1305).unwrap();
+        let container = decode_z85_internal(&encoded).unwrap();
+        assert_eq!(container[0], 2);
+        assert_eq!(&container[1..25], &[2u8; 24]);
+        assert_eq!(container.len(), 1 + 24 + 5 + 16);
+
+        // Flipping a ciphertext bit fails authentication
+        let mut tampered = container.clone();
+        tampered[25] ^= 1;
+        let result = decrypt_from_z85_internal(&encode_z85_internal(&tampered), &[1u8; 32]);
+        assert_eq!(result.err().unwrap().message, "Decryption failed: wrong key or corrupted data");
+    }
+
+    fn fast_params(algorithm: Algorithm) -> PasswordParams {
+        PasswordParams::new(Some(64), Some(1), Some(1), Some(algorithm))
+    }
+
+    #[test]
+    fn test_password_roundtrip() {
+        for algorithm in [Algorithm::Aes256Gcm, Algorithm::XChaCha20Poly1305] {
+            let encoded = encrypt_to_z85_with_password_internal(b"Hello, World!", "correct horse", fast_params(algorithm)).unwrap();
+            assert_eq!(decrypt_from_z85_with_password_internal(&encoded, "correct horse").unwrap(), b"Hello, World!");
+        }
+    }
+
+    #[test]
+    fn test_password_header_stores_params() {
+        let encoded = encrypt_to_z85_with_password_internal(b"", "pw", fast_params(Algorithm::XChaCha20Poly1305)).unwrap();
+        let container = decode_z85_internal(&encoded).unwrap();
+        assert_eq!(container[0], KDF_ARGON2ID);
+        assert_eq!(&container[1..5], &64u32.to_be_bytes());
+        assert_eq!(&container[5..9], &1u32.to_be_bytes());
+        assert_eq!(&container[9..13], &1u32.to_be_bytes());
+        assert_eq!(container[KDF_HEADER_BYTES], 2);
+        assert_eq!(container.len(), KDF_HEADER_BYTES + 1 + 24 + AEAD_TAG_BYTES);
+    }
+
+    #[test]
+    fn test_password_errors() {
+        let encoded = encrypt_to_z85_with_password_internal(b"secret", "right", fast_params(Algorithm::Aes256Gcm)).unwrap();
+        let result = decrypt_from_z85_with_password_internal(&encoded, "wrong");
+        assert_eq!(result.err().unwrap().message, "Decryption failed: wrong password or corrupted data");
+
+        let result = encrypt_to_z85_with_password_internal(b"", "pw", PasswordParams::new(Some(1), Some(1), Some(1), None));
+        assert!(result.err().unwrap().message.starts_with("Invalid Argon2 parameters"));
+
+        // Hostile headers cannot request unbounded memory or time
+        let original = decode_z85_internal(&encoded).unwrap();
+        for (offset, value, expected) in [
+            (1, u32::MAX, "Argon2 memory cost too large: 4294967295 KiB (max 262144)"),
+            (5, u32::MAX, "Argon2 iterations too large: 4294967295 (max 64)"),
+            (9, 17, "Argon2 parallelism too large: 17 (max 16)"),
+        ] {
+            let mut container = original.clone();
+            container[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+            let result = decrypt_from_z85_with_password_internal(&encode_z85_internal(&container), "right");
+            assert_eq!(result.err().unwrap().message, expected);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use tsify::Tsify;
+use x25519_dalek::{PublicKey, StaticSecret};
+
+use crate::codec;
+use crate::error::{js_error, Error, ErrorCode};
+
+/// CURVE keys are 32 bytes
+const CURVE_KEY_BYTES: usize = 32;
+/// ...and 40 characters in Z85
+const CURVE_KEY_CHARS: usize = 40;
+
+/// Keypair returned by `generate_curve_keypair`, both keys Z85-encoded
+#[derive(Serialize, Tsify, Debug)]
+struct CurveKeypair {
+    public_key: String,
+    secret_key: String,
+}
+
+// Internal pure Rust function for encoding a 32-byte CURVE key as 40 Z85 characters
+pub(crate) fn encode_curve_key_internal(key: &[u8]) -> Result<String, Error> {
+    if key.len() != CURVE_KEY_BYTES {
+        return Err(Error::new(ErrorCode::InvalidKey, format!("CURVE key must be {} bytes, got {}", CURVE_KEY_BYTES, key.len())));
+    }
+    let mut z85_key = String::with_capacity(CURVE_KEY_CHARS);
+    codec::encode_append(key, &mut z85_key);
+    Ok(z85_key)
+}
+
+/// Encode a 32-byte CURVE key as a 40-character Z85 string (as used by ZeroMQ `zmq_curve_keypair`)
+#[wasm_bindgen]
+pub fn encode_curve_key(key: &[u8]) -> Result<String, JsValue> {
+    encode_curve_key_internal(key)
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for decoding a 40-character Z85 CURVE key
+pub(crate) fn decode_curve_key_internal(z85_key: &str) -> Result<[u8; CURVE_KEY_BYTES], Error> {
+    if z85_key.len() != CURVE_KEY_CHARS {
+        return Err(Error::new(ErrorCode::InvalidKey, format!("CURVE key must be {} Z85 characters, got {}", CURVE_KEY_CHARS, z85_key.len())));
+    }
+    let mut key = [0u8; CURVE_KEY_BYTES];
+    codec::decode_groups(z85_key.as_bytes(), &mut key, 0)?;
+    Ok(key)
+}
+
+/// Decode a 40-character Z85 CURVE key to its 32 raw bytes
+#[wasm_bindgen]
+pub fn decode_curve_key(z85_key: &str) -> Result<Vec<u8>, JsValue> {
+    decode_curve_key_internal(z85_key)
+        .map(|key| key.to_vec())
+        .map_err(js_error)
+}
+
+// Derive the Z85 keypair for a raw X25519 secret key
+fn curve_keypair_from_secret(secret: [u8; CURVE_KEY_BYTES]) -> Result<CurveKeypair, Error> {
+    let public = PublicKey::from(&StaticSecret::from(secret));
+    Ok(CurveKeypair {
+        public_key: encode_curve_key_internal(public.as_bytes())?,
+        secret_key: encode_curve_key_internal(&secret)?,
+    })
+}
+
+// Internal pure Rust function for generating a random CURVE keypair
+fn generate_curve_keypair_internal() -> Result<CurveKeypair, Error> {
+    let mut secret = [0u8; CURVE_KEY_BYTES];
+    getrandom::getrandom(&mut secret)
+        .map_err(|e| Error::new(ErrorCode::CryptoFailed, format!("Random number generation failed: {}", e)))?;
+    curve_keypair_from_secret(secret)
+}
+
+/// Generate a CURVE (X25519) keypair using the platform CSPRNG.
+/// Returns `{ public_key, secret_key }`, both 40-character Z85 strings.
+#[wasm_bindgen(unchecked_return_type = "CurveKeypair")]
+pub fn generate_curve_keypair() -> Result<JsValue, JsValue> {
+    let keypair = generate_curve_keypair_internal()
+        .map_err(js_error)?;
+    serde_wasm_bindgen::to_value(&keypair)
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, format!("Keypair serialization error: {}", e))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> [u8; 32] {
+        core::array::from_fn(|i| u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).unwrap())
+    }
+
+    #[test]
+    fn test_curve_key_roundtrip() {
+        let key: Vec<u8> = (0..32u8).collect();
+        let encoded = encode_curve_key_internal(&key).unwrap();
+        assert_eq!(encoded.len(), 40);
+        assert_eq!(encoded, z85::encode(&key));
+        assert_eq!(decode_curve_key_internal(&encoded).unwrap().to_vec(), key);
+    }
+
+    #[test]
+    fn test_curve_key_length_errors() {
+        assert_eq!(encode_curve_key_internal(&[0u8; 31]).err().unwrap().message, "CURVE key must be 32 bytes, got 31");
+        assert_eq!(decode_curve_key_internal("HelloWorld").err().unwrap().message, "CURVE key must be 40 Z85 characters, got 10");
+        let invalid = format!("{}~", "0".repeat(39));
+        assert!(decode_curve_key_internal(&invalid).err().unwrap().message.contains("invalid character at offset 39"));
+    }
+
+    #[test]
+    fn test_curve_keypair_from_secret() {
+        // RFC 7748 section 6.1 (Alice)
+        let secret = hex("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let public = hex("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a");
+        let keypair = curve_keypair_from_secret(secret).unwrap();
+        assert_eq!(keypair.public_key, z85::encode(public));
+        assert_eq!(keypair.secret_key, z85::encode(secret));
+    }
+
+    #[test]
+    fn test_generate_curve_keypair() {
+        let first = generate_curve_keypair_internal().unwrap();
+        let second = generate_curve_keypair_internal().unwrap();
+        assert_eq!(first.public_key.len(), 40);
+        assert_eq!(first.secret_key.len(), 40);
+        assert_ne!(first.secret_key, second.secret_key);
+        let secret = decode_curve_key_internal(&first.secret_key).unwrap();
+        assert_eq!(curve_keypair_from_secret(secret).unwrap().public_key, first.public_key);
+    }
+}
@@ -0,0 +1,532 @@
+use wasm_bindgen::prelude::*;
+use base64::{DecodeError, Engine, engine::general_purpose};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use web_sys::{AbortSignal, TransformStream};
+
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::stream::{build_transform_stream, reset, Z85Encoder};
+use crate::{base64_decode_error, decode_z85_internal, encode_z85_internal, mime, TRANSCODE_WINDOW_BYTES};
+
+/// Media type assumed by RFC 2397 when a data URL omits it
+const DEFAULT_DATA_URL_MIME_TYPE: &str = "text/plain";
+
+/// Longest data URL header (`data:` up to the `,`) accepted by `DataUrlTranscoder`
+const MAX_STREAMED_HEADER_CHARS: usize = 8 * 1024;
+
+/// Payload encoding of a data URL
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DataUrlEncoding {
+    /// No encoding marker: the payload is percent-encoded text
+    Percent,
+    /// `;base64` marker
+    Base64,
+    /// `;z85` marker
+    Z85,
+}
+
+impl DataUrlEncoding {
+    // Marker written before the `,`, if any
+    fn marker(self) -> Option<&'static str> {
+        match self {
+            DataUrlEncoding::Percent => None,
+            DataUrlEncoding::Base64 => Some("base64"),
+            DataUrlEncoding::Z85 => Some("z85"),
+        }
+    }
+}
+
+/// Parsed `data:` URL (RFC 2397): `data:[<mime type>][;<name>=<value>]*[;base64|;z85],<payload>`
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataUrl {
+    /// Media type as written (may be empty)
+    pub(crate) mime_type: String,
+    /// Media type parameters in order, e.g. `charset=utf-8`
+    pub(crate) parameters: Vec<(String, String)>,
+    pub(crate) encoding: DataUrlEncoding,
+    /// Payload after the `,`, still encoded
+    pub(crate) payload: String,
+}
+
+impl DataUrl {
+    // Parse a data URL
+    pub(crate) fn parse(url: &str) -> Result<DataUrl, Error> {
+        let (data_url, payload) = DataUrl::split(url)?;
+        Ok(DataUrl { payload: payload.to_string(), ..data_url })
+    }
+
+    // Parse the header of a data URL, returning it (with an empty payload) and the borrowed
+    // payload, so converters can avoid copying multi-megabyte payloads
+    pub(crate) fn split(url: &str) -> Result<(DataUrl, &str), Error> {
+        let rest = url.get(..5)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("data:"))
+            .map(|_| &url[5..])
+            .ok_or_else(|| Error::new(ErrorCode::BadDataUrl, "Invalid data URL format"))?;
+        let (header, payload) = rest.split_once(',')
+            .ok_or_else(|| Error::new(ErrorCode::BadDataUrl, "Invalid data URL: missing ',' before the payload"))?;
+
+        let mut segments = header.split(';');
+        let mime_type = segments.next().unwrap_or_default().trim();
+        if !mime_type.is_empty() {
+            mime::validate_mime_type(mime_type)?;
+        }
+
+        // The encoding marker, if any, is the last segment and has no value
+        let mut segments: Vec<&str> = segments.collect();
+        let encoding = match segments.last().map(|segment| segment.trim()) {
+            Some(marker) if marker.eq_ignore_ascii_case("base64") => DataUrlEncoding::Base64,
+            Some(marker) if marker.eq_ignore_ascii_case("z85") => DataUrlEncoding::Z85,
+            _ => DataUrlEncoding::Percent,
+        };
+        if encoding != DataUrlEncoding::Percent {
+            segments.pop();
+        }
+
+        let parameters = segments.into_iter()
+            .map(|segment| match segment.split_once('=') {
+                Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_string(), value.trim().to_string())),
+                _ => Err(Error::new(ErrorCode::BadDataUrl, format!("Invalid data URL parameter: {}", segment))),
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok((DataUrl { mime_type: mime_type.to_string(), parameters, encoding, payload: String::new() }, payload))
+    }
+
+    // Media type with its parameters, as written before the encoding marker
+    pub(crate) fn media_type(&self) -> String {
+        let mut media_type = self.mime_type.clone();
+        for (name, value) in &self.parameters {
+            media_type.push_str(&format!(";{}={}", name, value));
+        }
+        media_type
+    }
+}
+
+impl fmt::Display for DataUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "data:{}", self.media_type())?;
+        if let Some(marker) = self.encoding.marker() {
+            write!(f, ";{}", marker)?;
+        }
+        write!(f, ",{}", self.payload)
+    }
+}
+
+// Value of an ASCII hex digit
+pub(crate) fn hex_value(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|value| value as u8)
+}
+
+// Percent-decode a data URL payload. As in the WHATWG URL standard, a `%` not followed by
+// two hex digits is kept literally.
+pub(crate) fn percent_decode(payload: &str) -> Vec<u8> {
+    let bytes = payload.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = match bytes.get(index..index + 3) {
+            Some([b'%', high, low]) => hex_value(*high).zip(hex_value(*low)).map(|(high, low)| high << 4 | low),
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    decoded
+}
+
+// Percent-encode bytes for a data URL payload, leaving RFC 3986 unreserved characters and
+// sub-delimiters readable (so inline SVG stays mostly legible)
+pub(crate) fn percent_encode(data: &[u8]) -> String {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let mut encoded = String::with_capacity(data.len());
+    for &byte in data {
+        if byte.is_ascii_alphanumeric() || b"-._~!$&'()*+,;=:@/?".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push('%');
+            encoded.push(HEX[(byte >> 4) as usize] as char);
+            encoded.push(HEX[(byte & 0x0f) as usize] as char);
+        }
+    }
+    encoded
+}
+
+#[wasm_bindgen]
+impl DataUrl {
+    /// Parse a `data:` URL
+    #[wasm_bindgen(constructor)]
+    pub fn new(url: &str) -> Result<DataUrl, JsValue> {
+        DataUrl::parse(url)
+            .map_err(js_error)
+    }
+
+    /// MIME type (`text/plain` when the URL omits it, as specified by RFC 2397)
+    #[wasm_bindgen(getter)]
+    pub fn mime_type(&self) -> String {
+        if self.mime_type.is_empty() {
+            DEFAULT_DATA_URL_MIME_TYPE.to_string()
+        } else {
+            self.mime_type.clone()
+        }
+    }
+
+    /// Media type parameters as a plain object, e.g. `{ charset: "utf-8" }`
+    #[wasm_bindgen(getter, unchecked_return_type = "Record<string, string>")]
+    pub fn parameters(&self) -> js_sys::Object {
+        let parameters = js_sys::Object::new();
+        for (name, value) in &self.parameters {
+            let _ = js_sys::Reflect::set(&parameters, &JsValue::from_str(name), &JsValue::from_str(value));
+        }
+        parameters
+    }
+
+    /// Value of a media type parameter (names are case-insensitive), or `undefined`
+    pub fn parameter(&self, name: &str) -> Option<String> {
+        self.parameters.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.clone())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn encoding(&self) -> DataUrlEncoding {
+        self.encoding
+    }
+
+    /// Payload after the `,`, still encoded
+    #[wasm_bindgen(getter)]
+    pub fn payload(&self) -> String {
+        self.payload.clone()
+    }
+
+    /// Serialize back to a `data:` URL
+    #[wasm_bindgen(js_name = to_string)]
+    pub fn to_url(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Result of `decode_data_url` (returned to JS as a plain object)
+#[derive(Serialize, Tsify)]
+struct DecodedDataUrl {
+    #[tsify(type = "Uint8Array")]
+    bytes: serde_bytes::ByteBuf,
+    mime: String,
+}
+
+// Internal pure Rust function for building a data URL straight from bytes.
+// `mime_type` may carry parameters (`text/plain;charset=utf-8`); it is sniffed when omitted.
+pub(crate) fn encode_data_url_internal(data: &[u8], mime_type: Option<&str>, encoding: DataUrlEncoding) -> Result<String, Error> {
+    let media_type = mime_type.unwrap_or_else(|| mime::sniff_mime_type(data));
+    let (mut data_url, _) = DataUrl::split(&format!("data:{},", media_type))?;
+    if data_url.encoding != DataUrlEncoding::Percent || media_type.contains(',') {
+        return Err(Error::new(ErrorCode::BadDataUrl, format!("Invalid MIME type: {}", media_type)));
+    }
+    data_url.payload = match encoding {
+        DataUrlEncoding::Z85 => encode_z85_internal(data),
+        DataUrlEncoding::Base64 => general_purpose::STANDARD.encode(data),
+        DataUrlEncoding::Percent => percent_encode(data),
+    };
+    data_url.encoding = encoding;
+    Ok(data_url.to_string())
+}
+
+/// Build a data URL from raw bytes, skipping any base64 intermediate.
+/// `mime_type` may include parameters and is detected from the magic bytes when omitted;
+/// `encoding` defaults to `DataUrlEncoding.Z85`.
+#[wasm_bindgen]
+pub fn encode_data_url(data: &[u8], mime_type: Option<String>, encoding: Option<DataUrlEncoding>) -> Result<String, JsValue> {
+    limits::enforce(data.len(), String::len, || encode_data_url_internal(data, mime_type.as_deref(), encoding.unwrap_or(DataUrlEncoding::Z85)))
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for decoding the payload of a Z85, base64 or percent-encoded data URL.
+// Returns the bytes and the media type including its parameters.
+pub(crate) fn decode_data_url_internal(url: &str) -> Result<(Vec<u8>, String), Error> {
+    let (data_url, payload) = DataUrl::split(url)?;
+    let bytes = match data_url.encoding {
+        DataUrlEncoding::Z85 => decode_z85_internal(payload)?,
+        DataUrlEncoding::Base64 => general_purpose::STANDARD.decode(payload)?,
+        DataUrlEncoding::Percent => percent_decode(payload),
+    };
+    let mut media_type = data_url.mime_type();
+    for (name, value) in &data_url.parameters {
+        media_type.push_str(&format!(";{}={}", name, value));
+    }
+    Ok((bytes, media_type))
+}
+
+/// Decode a Z85, base64 or percent-encoded data URL into `{ bytes: Uint8Array, mime: string }`.
+/// `mime` includes any parameters (e.g. `text/plain;charset=utf-8`) and defaults to `text/plain`.
+#[wasm_bindgen(unchecked_return_type = "DecodedDataUrl")]
+pub fn decode_data_url(url: &str) -> Result<JsValue, JsValue> {
+    let (bytes, mime) = limits::enforce(url.len(), |(bytes, _): &(Vec<u8>, String)| bytes.len(), || decode_data_url_internal(url))
+        .map_err(js_error)?;
+    serde_wasm_bindgen::to_value(&DecodedDataUrl { bytes: serde_bytes::ByteBuf::from(bytes), mime })
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
+}
+
+/// Streaming `data:...;base64,` to `data:...;z85,` converter for multi-megabyte data URLs.
+/// Feed slices of the URL text of any size to `update()`; the concatenation of every `update()`
+/// result and the final `finalize()` result is the converted data URL. Only a few characters
+/// are carried between calls, so the decoded payload is never held in memory.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct DataUrlTranscoder {
+    /// Whether the header up to the `,` has been converted
+    header_done: bool,
+    /// Header text while incomplete, then base64 characters not yet decoded
+    pending: String,
+    /// Number of payload characters decoded so far (for error offsets)
+    consumed: usize,
+    encoder: Z85Encoder,
+}
+
+impl DataUrlTranscoder {
+    // Accept the next slice of the data URL and return the converted text available so far
+    fn update_internal(&mut self, chunk: &str) -> Result<String, Error> {
+        let mut output = String::new();
+        self.pending.push_str(chunk);
+
+        if !self.header_done {
+            let Some(comma) = self.pending.find(',') else {
+                if self.pending.len() > MAX_STREAMED_HEADER_CHARS {
+                    return Err(Error::new(ErrorCode::BadDataUrl, "Invalid data URL: header too long"));
+                }
+                return Ok(output);
+            };
+            let (mut data_url, _) = DataUrl::split(&self.pending[..=comma])?;
+            if data_url.encoding != DataUrlEncoding::Base64 {
+                return Err(Error::new(ErrorCode::BadDataUrl, "Data URL does not contain ;base64, marker"));
+            }
+            data_url.encoding = DataUrlEncoding::Z85;
+            output.push_str(&data_url.to_string());
+            self.pending.drain(..=comma);
+            self.header_done = true;
+        }
+
+        // Withhold the last complete group, which may carry `=` padding, and any partial group
+        let groups = self.pending.len() / 4;
+        if groups < 2 {
+            return Ok(output);
+        }
+        let ready = (groups - 1) * 4;
+        if let Some(position) = self.pending[..ready].find('=') {
+            return Err(DecodeError::InvalidByte(self.consumed + position, b'=').into());
+        }
+        self.decode_into(ready, &mut output)?;
+        Ok(output)
+    }
+
+    // Decode the first `len` pending base64 characters (a multiple of 4) into Z85 text
+    fn decode_into(&mut self, len: usize, output: &mut String) -> Result<(), Error> {
+        let window_chars = TRANSCODE_WINDOW_BYTES / 3 * 4;
+        let mut window = [0u8; TRANSCODE_WINDOW_BYTES];
+        for chunk in self.pending.as_bytes()[..len].chunks(window_chars) {
+            let decoded_len = general_purpose::STANDARD.decode_slice(chunk, &mut window)
+                .map_err(|e| base64_decode_error(e, self.consumed))?;
+            output.push_str(&self.encoder.update(&window[..decoded_len]));
+            self.consumed += chunk.len();
+        }
+        self.pending.drain(..len);
+        Ok(())
+    }
+
+    // Decode the withheld characters and close the Z85 stream with its `:padding` suffix
+    fn finalize_internal(&mut self) -> Result<String, Error> {
+        let mut transcoder = std::mem::take(self);
+        if !transcoder.header_done {
+            // Report the same error as a one-shot parse of the incomplete URL
+            return Err(DataUrl::split(&transcoder.pending).err()
+                .unwrap_or_else(|| Error::new(ErrorCode::BadDataUrl, "Invalid data URL: missing ',' before the payload")));
+        }
+        let mut output = String::new();
+        let len = transcoder.pending.len();
+        transcoder.decode_into(len, &mut output)?;
+        output.push_str(&transcoder.encoder.finalize());
+        Ok(output)
+    }
+}
+
+#[wasm_bindgen]
+impl DataUrlTranscoder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> DataUrlTranscoder {
+        DataUrlTranscoder::default()
+    }
+
+    /// Convert the next slice of a `data:...;base64,` URL, returning the output available so far
+    pub fn update(&mut self, chunk: &str) -> Result<String, JsValue> {
+        self.update_internal(chunk)
+            .map_err(js_error)
+    }
+
+    /// Flush the remaining output, ending with the `:padding` suffix.
+    /// The transcoder is reset and can be reused for a new URL.
+    pub fn finalize(&mut self) -> Result<String, JsValue> {
+        self.finalize_internal()
+            .map_err(js_error)
+    }
+}
+
+/// Create a `TransformStream` turning the text chunks of a `data:...;base64,` URL into the
+/// text chunks of the equivalent `data:...;z85,` URL. `signal` cancels it as for
+/// `create_z85_encode_stream`.
+#[wasm_bindgen]
+pub fn create_data_url_transcode_stream(signal: Option<AbortSignal>) -> Result<TransformStream, JsValue> {
+    let transcoder = Rc::new(RefCell::new(DataUrlTranscoder::new()));
+    let flush_transcoder = transcoder.clone();
+    let abort_transcoder = transcoder.clone();
+    build_transform_stream(
+        move |chunk, controller| {
+            let chunk = chunk.as_string()
+                .ok_or_else(|| js_error(Error::new(ErrorCode::InvalidInput, "Expected a string chunk")))?;
+            let converted = transcoder.borrow_mut().update_internal(&chunk)
+                .map_err(js_error)?;
+            if !converted.is_empty() {
+                controller.enqueue_with_chunk(&JsValue::from_str(&converted))?;
+            }
+            Ok(())
+        },
+        move |controller| {
+            let converted = flush_transcoder.borrow_mut().finalize_internal()
+                .map_err(js_error)?;
+            controller.enqueue_with_chunk(&JsValue::from_str(&converted))
+        },
+        signal,
+        move || reset(&abort_transcoder, DataUrlTranscoder::new),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_data_url() {
+        let url = DataUrl::parse("data:text/plain;charset=utf-8;base64,SGVsbG8=").unwrap();
+        assert_eq!(url.mime_type(), "text/plain");
+        assert_eq!(url.parameter("Charset").as_deref(), Some("utf-8"));
+        assert_eq!(url.encoding, DataUrlEncoding::Base64);
+        assert_eq!(url.payload, "SGVsbG8=");
+        assert_eq!(url.to_string(), "data:text/plain;charset=utf-8;base64,SGVsbG8=");
+
+        let url = DataUrl::parse("data:image/png;z85,nm=QNzY&b1A+]m^:1").unwrap();
+        assert_eq!(url.encoding, DataUrlEncoding::Z85);
+        assert_eq!(url.payload, "nm=QNzY&b1A+]m^:1");
+        assert!(url.parameters.is_empty());
+    }
+
+    #[test]
+    fn test_parse_percent_encoded_and_defaults() {
+        let url = DataUrl::parse("data:,Hello%2C%20World").unwrap();
+        assert_eq!(url.mime_type(), "text/plain");
+        assert_eq!(url.encoding, DataUrlEncoding::Percent);
+        assert_eq!(url.payload, "Hello%2C%20World");
+        assert_eq!(url.to_string(), "data:,Hello%2C%20World");
+
+        // Commas after the first belong to the payload
+        let url = DataUrl::parse("DATA:text/csv,a,b,c").unwrap();
+        assert_eq!(url.payload, "a,b,c");
+        assert_eq!(url.to_string(), "data:text/csv,a,b,c");
+
+        let url = DataUrl::parse("data:;charset=utf-8;BASE64,").unwrap();
+        assert_eq!(url.encoding, DataUrlEncoding::Base64);
+        assert_eq!(url.media_type(), ";charset=utf-8");
+    }
+
+    #[test]
+    fn test_percent_encoding() {
+        assert_eq!(percent_decode("%3Ch1%3EHi%20there%3c/h1%3E"), b"<h1>Hi there</h1>");
+        // Malformed escapes are kept literally
+        assert_eq!(percent_decode("100%25 %zz %4"), b"100% %zz %4");
+        assert_eq!(percent_decode(""), b"");
+
+        let svg = "<svg xmlns='http://www.w3.org/2000/svg'><path d=\"M0 0\"/></svg>#\u{e9}";
+        let encoded = percent_encode(svg.as_bytes());
+        assert_eq!(encoded, "%3Csvg%20xmlns='http://www.w3.org/2000/svg'%3E%3Cpath%20d=%22M0%200%22/%3E%3C/svg%3E%23%C3%A9");
+        assert_eq!(percent_decode(&encoded), svg.as_bytes());
+    }
+
+    #[test]
+    fn test_encode_decode_data_url() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        let url = encode_data_url_internal(png, None, DataUrlEncoding::Z85).unwrap();
+        assert_eq!(url, format!("data:image/png;z85,{}", encode_z85_internal(png)));
+        assert_eq!(decode_data_url_internal(&url).unwrap(), (png.to_vec(), "image/png".to_string()));
+
+        let url = encode_data_url_internal(b"Hello, World", Some("text/plain;charset=utf-8"), DataUrlEncoding::Base64).unwrap();
+        assert_eq!(url, "data:text/plain;charset=utf-8;base64,SGVsbG8sIFdvcmxk");
+        assert_eq!(decode_data_url_internal(&url).unwrap(), (b"Hello, World".to_vec(), "text/plain;charset=utf-8".to_string()));
+
+        let url = encode_data_url_internal(b"<b>hi</b>", Some("text/html"), DataUrlEncoding::Percent).unwrap();
+        assert_eq!(url, "data:text/html,%3Cb%3Ehi%3C/b%3E");
+        assert_eq!(decode_data_url_internal(&url).unwrap().0, b"<b>hi</b>");
+        assert_eq!(decode_data_url_internal("data:,A%20B").unwrap(), (b"A B".to_vec(), "text/plain".to_string()));
+    }
+
+    #[test]
+    fn test_encode_decode_data_url_errors() {
+        assert_eq!(encode_data_url_internal(b"x", Some("text"), DataUrlEncoding::Z85).err().unwrap().message, "Invalid MIME type: text");
+        assert_eq!(encode_data_url_internal(b"x", Some("text/plain;base64"), DataUrlEncoding::Z85).err().unwrap().message, "Invalid MIME type: text/plain;base64");
+        assert_eq!(encode_data_url_internal(b"x", Some("text/plain,"), DataUrlEncoding::Z85).err().unwrap().message, "Invalid MIME type: text/plain,");
+        assert!(decode_data_url_internal("data:;base64,!!!!").err().unwrap().message.starts_with("Base64 decode error"));
+        assert!(decode_data_url_internal("data:;z85,~~~~~:0").err().unwrap().message.starts_with("Z85 decode error"));
+    }
+
+    fn transcode_in_chunks(url: &str, chunk_size: usize) -> Result<String, Error> {
+        let mut transcoder = DataUrlTranscoder::new();
+        let mut output = String::new();
+        for chunk in url.as_bytes().chunks(chunk_size) {
+            output.push_str(&transcoder.update_internal(std::str::from_utf8(chunk).unwrap())?);
+        }
+        output.push_str(&transcoder.finalize_internal()?);
+        Ok(output)
+    }
+
+    #[test]
+    fn test_transcoder_matches_one_shot() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i * 7) as u8).collect();
+        for len in [0, 1, 2, 3, 4, 5, 10_000] {
+            let url = format!("data:video/mp4;name=clip;base64,{}", general_purpose::STANDARD.encode(&data[..len]));
+            let expected = format!("data:video/mp4;name=clip;z85,{}", crate::encode_z85_internal(&data[..len]));
+            for chunk_size in [1, 3, 7, 64, 4096, url.len()] {
+                assert_eq!(transcode_in_chunks(&url, chunk_size).unwrap(), expected, "len {} chunk size {}", len, chunk_size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transcoder_errors() {
+        assert_eq!(transcode_in_chunks("data:text/plain,abc", 4).err().unwrap().message, "Data URL does not contain ;base64, marker");
+        assert_eq!(transcode_in_chunks("data:text/plain;base64", 4).err().unwrap().message, "Invalid data URL: missing ',' before the payload");
+        assert_eq!(transcode_in_chunks("nope", 4).err().unwrap().message, "Invalid data URL format");
+        assert_eq!(transcode_in_chunks("data:;base64,QQ==QUJD", 4).err().unwrap().message, "Base64 decode error: Invalid symbol 61, offset 2.");
+        assert!(transcode_in_chunks("data:;base64,QUJD!UJD", 3).err().unwrap().message.starts_with("Base64 decode error"));
+        let long_header = format!("data:text/plain;x={}", "a".repeat(MAX_STREAMED_HEADER_CHARS));
+        assert_eq!(transcode_in_chunks(&long_header, 1024).err().unwrap().message, "Invalid data URL: header too long");
+    }
+
+    #[test]
+    fn test_parse_data_url_errors() {
+        assert_eq!(DataUrl::parse("http://example.com").err().unwrap().message, "Invalid data URL format");
+        assert_eq!(DataUrl::parse("data:text/plain").err().unwrap().message, "Invalid data URL: missing ',' before the payload");
+        assert_eq!(DataUrl::parse("data:text/plain;charset,x").err().unwrap().message, "Invalid data URL parameter: charset");
+        assert_eq!(DataUrl::parse("data:text;base64,x").err().unwrap().message, "Invalid MIME type: text");
+        assert_eq!(DataUrl::parse("dat").err().unwrap().message, "Invalid data URL format");
+    }
+}
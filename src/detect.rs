@@ -0,0 +1,221 @@
+use wasm_bindgen::prelude::*;
+use base64::{Engine, engine::general_purpose};
+
+use crate::container;
+use crate::data_url::decode_data_url_internal;
+use crate::encoding::{transcode_internal, Base64Variant, Encoding};
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::{decode_z85_internal, decode_z85_strict_internal, ConversionOptions, DataType};
+
+/// Input formats recognised by `detect_encoding`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DetectedFormat {
+    /// None of the formats below
+    Unknown,
+    /// Z85 with a `:padding` or `#length` suffix, or a `z85v2:` container
+    Z85,
+    /// Bare Z85 as in ZeroMQ RFC 32: a multiple of 5 characters without a suffix
+    Z85Unpadded,
+    /// RFC 4648 base64 with `=` padding
+    Base64,
+    /// RFC 4648 URL-safe base64, with or without padding
+    Base64Url,
+    /// Hexadecimal in either case
+    Hex,
+    /// A `data:` URL with a base64, Z85 or percent-encoded payload
+    DataUrl,
+}
+
+/// Result of `detect_encoding`: the most likely format and a confidence between 0 and 1
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DetectionResult {
+    format: DetectedFormat,
+    confidence: f64,
+}
+
+#[wasm_bindgen]
+impl DetectionResult {
+    #[wasm_bindgen(getter)]
+    pub fn format(&self) -> DetectedFormat {
+        self.format
+    }
+
+    /// 1 for self-describing input (data URLs, `z85v2:` containers), lower when the text is
+    /// also valid in other formats, 0 for `DetectedFormat.Unknown`
+    #[wasm_bindgen(getter)]
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+
+    /// The `transcode` encoding of the detected format (`undefined` for bare Z85, data URLs
+    /// and unknown input)
+    #[wasm_bindgen(getter)]
+    pub fn encoding(&self) -> Option<Encoding> {
+        match self.format {
+            DetectedFormat::Z85 => Some(Encoding::Z85),
+            DetectedFormat::Base64 => Some(Encoding::Base64),
+            DetectedFormat::Base64Url => Some(Encoding::Base64Url),
+            DetectedFormat::Hex => Some(Encoding::Hex),
+            DetectedFormat::Z85Unpadded | DetectedFormat::DataUrl | DetectedFormat::Unknown => None,
+        }
+    }
+}
+
+// Whether every character of `text` is in the base64 alphabet with the given two extra symbols
+fn is_base64_alphabet(text: &str, extra: [u8; 2]) -> bool {
+    text.trim_end_matches('=').bytes().all(|c| c.is_ascii_alphanumeric() || extra.contains(&c))
+}
+
+// Confidence that `text` is each format. Input that is valid in a format but uses only
+// characters shared with other formats gets a lower score, ordered so that plain alphanumeric
+// text prefers hex, then base64, then bare Z85, then unpadded base64url.
+fn score(text: &str, format: DetectedFormat) -> f64 {
+    let has = |symbols: &[u8]| text.bytes().any(|c| symbols.contains(&c));
+    match format {
+        DetectedFormat::Unknown => 0.0,
+        DetectedFormat::DataUrl => {
+            let is_data_url = text.get(..5).is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+                && decode_data_url_internal(text).is_ok();
+            if is_data_url { 1.0 } else { 0.0 }
+        }
+        DetectedFormat::Z85 => {
+            if text.starts_with(container::V2_PREFIX) {
+                return if decode_z85_internal(text).is_ok() { 1.0 } else { 0.0 };
+            }
+            let has_suffix = text.rfind([':', '#']).is_some_and(|separator| {
+                let suffix = &text[separator + 1..];
+                !suffix.is_empty() && suffix.bytes().all(|c| c.is_ascii_digit())
+            });
+            if has_suffix && decode_z85_internal(text).is_ok() { 0.95 } else { 0.0 }
+        }
+        DetectedFormat::Z85Unpadded => {
+            if text.is_empty() || decode_z85_strict_internal(text).is_err() {
+                0.0
+            } else if text.bytes().any(|c| !c.is_ascii_alphanumeric() && !b"+/=-_".contains(&c)) {
+                0.85
+            } else {
+                0.5
+            }
+        }
+        DetectedFormat::Base64 => {
+            if text.is_empty() || !is_base64_alphabet(text, *b"+/") || general_purpose::STANDARD.decode(text).is_err() {
+                0.0
+            } else if has(b"+/=") {
+                0.9
+            } else {
+                0.6
+            }
+        }
+        DetectedFormat::Base64Url => {
+            if text.is_empty() || !is_base64_alphabet(text, *b"-_") || Base64Variant::UrlSafeNoPad.engine().decode(text).is_err() {
+                0.0
+            } else if has(b"-_") {
+                0.9
+            } else if text.len().is_multiple_of(4) {
+                0.55
+            } else {
+                0.45
+            }
+        }
+        DetectedFormat::Hex => {
+            if text.is_empty() || !text.len().is_multiple_of(2) || !text.bytes().all(|c| c.is_ascii_hexdigit()) {
+                0.0
+            } else if text.bytes().any(|c| c.is_ascii_digit()) && text.bytes().any(|c| c.is_ascii_alphabetic()) {
+                0.9
+            } else {
+                0.7
+            }
+        }
+    }
+}
+
+// Internal pure Rust function for guessing the format of pasted text (surrounding whitespace ignored)
+fn detect_encoding_internal(data: &str) -> DetectionResult {
+    let text = data.trim();
+    let formats = [
+        DetectedFormat::DataUrl, DetectedFormat::Z85, DetectedFormat::Hex, DetectedFormat::Base64,
+        DetectedFormat::Base64Url, DetectedFormat::Z85Unpadded,
+    ];
+    formats.into_iter()
+        .map(|format| DetectionResult { format, confidence: score(text, format) })
+        .fold(DetectionResult { format: DetectedFormat::Unknown, confidence: 0.0 }, |best, candidate| {
+            if candidate.confidence > best.confidence { candidate } else { best }
+        })
+}
+
+// Internal pure Rust function for converting text of a detected format to `to`
+fn transcode_auto_internal(data: &str, to: Encoding, options: &ConversionOptions) -> Result<String, Error> {
+    let text = data.trim();
+    let detected = detect_encoding_internal(text);
+    match detected.format {
+        DetectedFormat::Unknown => Err(Error::new(ErrorCode::InvalidInput, "Could not detect the input encoding")),
+        DetectedFormat::DataUrl => to.encode(&decode_data_url_internal(text)?.0, options),
+        DetectedFormat::Z85Unpadded => to.encode(&decode_z85_strict_internal(text)?, options),
+        _ => transcode_internal(text, detected.encoding().unwrap(), to, options),
+    }
+}
+
+/// Guess whether `data` is Z85 (with or without a padding suffix), base64, base64url, hex or a
+/// data URL. Surrounding whitespace is ignored.
+#[wasm_bindgen]
+pub fn detect_encoding(data: &str) -> DetectionResult {
+    detect_encoding_internal(data)
+}
+
+/// Convert `data` to `to`, detecting its format with `detect_encoding`. Data URLs contribute
+/// their decoded payload. `options` applies as in `transcode`.
+#[wasm_bindgen]
+pub fn transcode_auto(data: &str, to: Encoding, options: Option<ConversionOptions>) -> Result<String, JsValue> {
+    let opts = options.unwrap_or(ConversionOptions::new(DataType::Raw, DataType::Raw));
+    limits::enforce(data.len(), String::len, || transcode_auto_internal(data, to, &opts))
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(data: &str) -> (DetectedFormat, f64) {
+        let result = detect_encoding_internal(data);
+        (result.format, result.confidence)
+    }
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(detect("data:text/plain;base64,SGVsbG8="), (DetectedFormat::DataUrl, 1.0));
+        assert_eq!(detect("nm=QNzVx+q:3").0, DetectedFormat::Z85);
+        assert_eq!(detect("nm=QNzVx+q#5").0, DetectedFormat::Z85);
+        assert_eq!(detect("HelloWorld").0, DetectedFormat::Z85Unpadded);
+        assert_eq!(detect("nm=QNzY&b1").0, DetectedFormat::Z85Unpadded);
+        assert_eq!(detect("SGVsbG8=").0, DetectedFormat::Base64);
+        assert_eq!(detect("SGVsbG8sIFdvcmxk-_8").0, DetectedFormat::Base64Url);
+        assert_eq!(detect("  48656c6c6f\n").0, DetectedFormat::Hex);
+        assert_eq!(detect("deadbeef").0, DetectedFormat::Hex);
+        assert_eq!(detect("not an encoding!"), (DetectedFormat::Unknown, 0.0));
+        assert_eq!(detect(""), (DetectedFormat::Unknown, 0.0));
+    }
+
+    #[test]
+    fn test_detection_confidence() {
+        // Characters exclusive to one format raise the confidence
+        assert!(detect("nm=QNzY&b1").1 > detect("HelloWorld").1);
+        assert!(detect("SGVsbG8=").1 > detect("SGVsbG8h").1);
+        assert!(detect("48656c6c6f").1 > detect("12345678").1);
+
+        let z85v2 = crate::encode_z85_with_options_internal(&[7; 500], crate::PaddingFormat::Count, crate::compression::Compression::Deflate);
+        assert_eq!(detect(&z85v2), (DetectedFormat::Z85, 1.0));
+    }
+
+    #[test]
+    fn test_transcode_auto() {
+        let options = ConversionOptions::new(DataType::Raw, DataType::Raw);
+        for input in ["SGVsbG8=", "SGVsbG8", "48656c6c6f", "nm=QNzVx+q:3", "data:,Hello", "data:text/plain;base64,SGVsbG8="] {
+            assert_eq!(transcode_auto_internal(input, Encoding::Hex, &options).unwrap(), "48656c6c6f", "{}", input);
+        }
+        assert_eq!(transcode_auto_internal("HelloWorld", Encoding::Hex, &options).unwrap(), "864fd26fb559f75b");
+        assert_eq!(transcode_auto_internal("???", Encoding::Hex, &options).err().unwrap().message, "Could not detect the input encoding");
+    }
+}
@@ -0,0 +1,65 @@
+use wasm_bindgen::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::codec;
+
+// Encode a 32-byte digest as 40 Z85 characters (no padding suffix needed)
+pub(crate) fn digest_to_z85(digest: &[u8]) -> String {
+    let mut z85_digest = String::with_capacity(digest.len() / 4 * 5);
+    codec::encode_append(digest, &mut z85_digest);
+    z85_digest
+}
+
+// Internal pure Rust function for computing the SHA-256 digest of bytes as Z85
+pub(crate) fn sha256_z85_internal(data: &[u8]) -> String {
+    digest_to_z85(&Sha256::digest(data))
+}
+
+/// Compute the SHA-256 digest of raw bytes, encoded as a 40-character Z85 string
+#[wasm_bindgen]
+pub fn sha256_z85(data: &[u8]) -> String {
+    sha256_z85_internal(data)
+}
+
+// Compare two byte strings without an early exit on the first difference
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Check that `digest` (40-character Z85) is the SHA-256 digest of `data`
+#[wasm_bindgen]
+pub fn verify_sha256_z85(data: &[u8], digest: &str) -> bool {
+    constant_time_eq(sha256_z85_internal(data).as_bytes(), digest.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256_z85() {
+        // SHA-256("abc") from FIPS 180-2
+        let expected: Vec<u8> = (0..32)
+            .map(|i| u8::from_str_radix(&"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"[i * 2..i * 2 + 2], 16).unwrap())
+            .collect();
+        let digest = sha256_z85_internal(b"abc");
+        assert_eq!(digest.len(), 40);
+        assert_eq!(digest, z85::encode(expected));
+    }
+
+    #[test]
+    fn test_verify_sha256_z85() {
+        let digest = sha256_z85_internal(b"Hello World");
+        assert!(verify_sha256_z85(b"Hello World", &digest));
+        assert!(!verify_sha256_z85(b"Hello World!", &digest));
+        assert!(!verify_sha256_z85(b"Hello World", &digest[..39]));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+    }
+}
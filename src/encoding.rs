@@ -0,0 +1,346 @@
+use wasm_bindgen::prelude::*;
+use std::borrow::Cow;
+use base64::{Engine, alphabet, engine::{general_purpose, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig}};
+
+use crate::alphabet::{decode_base85, encode_base85};
+use crate::ascii85::{decode_ascii85_internal, encode_ascii85_internal};
+use crate::base32::{decode_base32_crockford_internal, decode_base32_internal, encode_base32_crockford_internal, encode_base32_with, RFC4648_ALPHABET};
+use crate::base45::{decode_base45_internal, encode_base45_internal};
+use crate::base58::{decode_base58, decode_base58check, encode_base58, encode_base58check};
+use crate::base91::{decode_base91, encode_base91};
+use crate::bech32::{decode_bech32_variant, encode_bech32_internal, Bech32Variant};
+use crate::codec;
+use crate::compression::Compression;
+use crate::data_url::hex_value;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::logging::{self, LogLevel};
+use crate::{base64_to_z85_compressed_internal, decode_z85_internal, encode_z85_with_options_internal, telemetry, z85_to_base64_internal, z85_to_base64_with_variant_internal, ConversionOptions, DataType, PaddingFormat};
+
+/// URL-safe base64 written with `=` padding, read with or without it
+const URL_SAFE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(true)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// URL-safe base64 written without padding, read with or without it
+const URL_SAFE_NO_PAD: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Base64 alphabet and padding used on the base64 side of a conversion
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Base64Variant {
+    /// RFC 4648 `+` and `/` alphabet with `=` padding
+    Standard,
+    /// RFC 4648 URL-safe `-` and `_` alphabet, written with `=` padding
+    UrlSafe,
+    /// RFC 4648 URL-safe `-` and `_` alphabet, written without padding (as in JWTs)
+    UrlSafeNoPad,
+}
+
+impl Base64Variant {
+    // Engine for this variant. Both URL-safe variants read input with or without padding.
+    pub(crate) fn engine(self) -> &'static GeneralPurpose {
+        match self {
+            Base64Variant::Standard => &general_purpose::STANDARD,
+            Base64Variant::UrlSafe => &URL_SAFE,
+            Base64Variant::UrlSafeNoPad => &URL_SAFE_NO_PAD,
+        }
+    }
+}
+
+/// Text encodings accepted by `transcode`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    /// Z85 with a `:padding` or `#length` suffix, or a `z85v2:` container
+    Z85,
+    /// RFC 4648 base64 with `=` padding
+    Base64,
+    /// RFC 4648 URL-safe base64 (`-` and `_`), written without padding
+    Base64Url,
+    /// Hexadecimal, read in either case and written in lowercase
+    Hex,
+    /// Adobe Ascii85, written with `<~` `~>` delimiters and read with or without them
+    Ascii85,
+    /// RFC 1924 base85 alphabet with Z85's grouping and padding suffix
+    Rfc1924,
+    /// basE91, denser than Z85 and needing no padding suffix
+    Base91,
+    /// RFC 9285 Base45, for QR codes in alphanumeric mode
+    Base45,
+    /// Bitcoin Base58 alphabet, with leading zero bytes written as `1`
+    Base58,
+    /// Base58 with a trailing 4-byte double-SHA-256 checksum, verified when decoding
+    Base58Check,
+    /// RFC 4648 base32, written uppercase with `=` padding and read in either case with or without it
+    Base32,
+    /// Crockford base32 without a check symbol, written uppercase and read ignoring case and `-`
+    Base32Crockford,
+    /// BIP 173 Bech32; written under `ConversionOptions.hrp`, read with any HRP
+    Bech32,
+    /// BIP 350 Bech32m; written under `ConversionOptions.hrp`, read with any HRP
+    Bech32m,
+}
+
+impl Encoding {
+    // Lowercase name used in reports
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Encoding::Z85 => "z85",
+            Encoding::Base64 => "base64",
+            Encoding::Base64Url => "base64url",
+            Encoding::Hex => "hex",
+            Encoding::Ascii85 => "ascii85",
+            Encoding::Rfc1924 => "rfc1924",
+            Encoding::Base91 => "base91",
+            Encoding::Base45 => "base45",
+            Encoding::Base58 => "base58",
+            Encoding::Base58Check => "base58check",
+            Encoding::Base32 => "base32",
+            Encoding::Base32Crockford => "base32crockford",
+            Encoding::Bech32 => "bech32",
+            Encoding::Bech32m => "bech32m",
+        }
+    }
+
+    // Decode `text` in this encoding to bytes
+    pub(crate) fn decode(self, text: &str) -> Result<Vec<u8>, Error> {
+        match self {
+            Encoding::Z85 => decode_z85_internal(text),
+            Encoding::Base64 => Ok(general_purpose::STANDARD.decode(text)?),
+            Encoding::Base64Url => Ok(URL_SAFE_NO_PAD.decode(text)?),
+            Encoding::Hex => hex_decode(text),
+            Encoding::Ascii85 => decode_ascii85_internal(text),
+            Encoding::Rfc1924 => decode_base85(&codec::RFC1924, text),
+            Encoding::Base91 => decode_base91(text),
+            Encoding::Base45 => decode_base45_internal(text),
+            Encoding::Base58 => decode_base58(text),
+            Encoding::Base58Check => decode_base58check(text),
+            Encoding::Base32 => decode_base32_internal(text),
+            Encoding::Base32Crockford => decode_base32_crockford_internal(text, false),
+            Encoding::Bech32 => decode_bech32_variant(text, Bech32Variant::Bech32),
+            Encoding::Bech32m => decode_bech32_variant(text, Bech32Variant::Bech32m),
+        }
+    }
+
+    // Encode bytes in this encoding. Z85 output uses the options' padding suffix and compression,
+    // RFC 1924 output the padding suffix, and Bech32/Bech32m output the HRP.
+    pub(crate) fn encode(self, data: &[u8], options: &ConversionOptions) -> Result<String, Error> {
+        Ok(match self {
+            Encoding::Z85 => encode_z85_with_options_internal(data, options.padding_format, options.compression),
+            Encoding::Base64 => general_purpose::STANDARD.encode(data),
+            Encoding::Base64Url => URL_SAFE_NO_PAD.encode(data),
+            Encoding::Hex => hex_encode(data, false),
+            Encoding::Ascii85 => encode_ascii85_internal(data, true, false),
+            Encoding::Rfc1924 => encode_base85(&codec::RFC1924, data, options.padding_format),
+            Encoding::Base91 => encode_base91(data),
+            Encoding::Base45 => encode_base45_internal(data),
+            Encoding::Base58 => encode_base58(data),
+            Encoding::Base58Check => encode_base58check(data),
+            Encoding::Base32 => encode_base32_with(RFC4648_ALPHABET, data, true),
+            Encoding::Base32Crockford => encode_base32_crockford_internal(data, false),
+            Encoding::Bech32 | Encoding::Bech32m => {
+                let variant = if self == Encoding::Bech32 { Bech32Variant::Bech32 } else { Bech32Variant::Bech32m };
+                let hrp = options.hrp.as_deref()
+                    .ok_or_else(|| Error::new(ErrorCode::InvalidOptions, format!("{} encode error: ConversionOptions.hrp is required", variant.name())))?;
+                encode_bech32_internal(hrp, data, variant)?
+            }
+        })
+    }
+}
+
+// Normalize base64 for lenient parsing: drop ASCII whitespace (MIME or PEM line breaks) and
+// restore missing `=` padding. Input that needs neither is borrowed unchanged.
+pub(crate) fn normalize_base64(base64_data: &str) -> Cow<'_, str> {
+    let has_whitespace = base64_data.bytes().any(|b| b.is_ascii_whitespace());
+    let unpadded_len = base64_data.bytes().filter(|b| !b.is_ascii_whitespace()).count();
+    let missing_padding = match unpadded_len % 4 {
+        2 => "==",
+        3 => "=",
+        _ => "",
+    };
+    if !has_whitespace && missing_padding.is_empty() {
+        return Cow::Borrowed(base64_data);
+    }
+    let mut normalized: String = base64_data.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    logging::log(LogLevel::Debug, format_args!(
+        "Lenient base64: removed {} whitespace characters, restored {} padding characters",
+        base64_data.len() - unpadded_len,
+        missing_padding.len(),
+    ));
+    normalized.push_str(missing_padding);
+    Cow::Owned(normalized)
+}
+
+// Encode bytes as hex in the requested case
+pub(crate) fn hex_encode(data: &[u8], uppercase: bool) -> String {
+    let digits = if uppercase { b"0123456789ABCDEF" } else { b"0123456789abcdef" };
+    let mut hex = String::with_capacity(data.len() * 2);
+    for &byte in data {
+        hex.push(digits[(byte >> 4) as usize] as char);
+        hex.push(digits[(byte & 0x0f) as usize] as char);
+    }
+    hex
+}
+
+// Decode hex in either case, reporting the offset of the first invalid digit
+fn hex_decode(hex: &str) -> Result<Vec<u8>, Error> {
+    let digits = hex.as_bytes();
+    if !digits.len().is_multiple_of(2) {
+        return Err(Error::new(ErrorCode::InvalidLength, format!("Hex decode error: odd number of digits ({})", digits.len())));
+    }
+    let digit = |offset: usize| hex_value(digits[offset])
+        .ok_or_else(|| Error::new(ErrorCode::InvalidChar, format!("Hex decode error: invalid character at offset {}", offset)).at(offset));
+    (0..digits.len()).step_by(2)
+        .map(|offset| Ok(digit(offset)? << 4 | digit(offset + 1)?))
+        .collect()
+}
+
+// Internal pure Rust function for converting text between two encodings.
+// Base64 (either alphabet) ⇄ Z85 use the windowed transcoders; every other pair goes through bytes.
+pub(crate) fn transcode_internal(data: &str, from: Encoding, to: Encoding, options: &ConversionOptions) -> Result<String, Error> {
+    let ConversionOptions { padding_format, compression, .. } = *options;
+    match (from, to) {
+        (Encoding::Base64, Encoding::Z85) => base64_to_z85_compressed_internal(data, padding_format, compression, Base64Variant::Standard),
+        (Encoding::Base64Url, Encoding::Z85) => base64_to_z85_compressed_internal(data, padding_format, compression, Base64Variant::UrlSafeNoPad),
+        (Encoding::Z85, Encoding::Base64) => z85_to_base64_internal(data),
+        (Encoding::Z85, Encoding::Base64Url) => z85_to_base64_with_variant_internal(data, Base64Variant::UrlSafeNoPad),
+        _ => to.encode(&from.decode(data)?, options),
+    }
+}
+
+/// Convert `data` from one encoding to another.
+/// Of `options`, only `padding_format` and `compression` (Z85 output) and `hrp` (Bech32/Bech32m
+/// output) apply.
+#[wasm_bindgen]
+pub fn transcode(data: &str, from: Encoding, to: Encoding, options: Option<ConversionOptions>) -> Result<String, JsValue> {
+    let opts = options.unwrap_or(ConversionOptions::new(DataType::Raw, DataType::Raw));
+    telemetry::instrument("transcode", data.len(), String::len, || {
+        transcode_internal(data, from, to, &opts)
+    })
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for hex to Z85 conversion
+fn hex_to_z85_internal(hex: &str) -> Result<String, Error> {
+    Ok(encode_z85_with_options_internal(&hex_decode(hex)?, PaddingFormat::Count, Compression::None))
+}
+
+/// Convert hex (either case) to Z85 with padding info
+#[wasm_bindgen]
+pub fn hex_to_z85(hex: &str) -> Result<String, JsValue> {
+    telemetry::instrument("hex_to_z85", hex.len(), String::len, || hex_to_z85_internal(hex))
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for Z85 to hex conversion
+fn z85_to_hex_internal(z85_data_with_padding: &str, uppercase: bool) -> Result<String, Error> {
+    Ok(hex_encode(&decode_z85_internal(z85_data_with_padding)?, uppercase))
+}
+
+/// Convert Z85 data with padding info (or a `z85v2:` container) to hex.
+/// Digits are lowercase unless `uppercase` is true.
+#[wasm_bindgen]
+pub fn z85_to_hex(z85_data_with_padding: &str, uppercase: Option<bool>) -> Result<String, JsValue> {
+    telemetry::instrument("z85_to_hex", z85_data_with_padding.len(), String::len, || {
+        z85_to_hex_internal(z85_data_with_padding, uppercase.unwrap_or(false))
+    })
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw() -> ConversionOptions {
+        ConversionOptions::new(DataType::Raw, DataType::Raw)
+    }
+
+    const ENCODINGS: [Encoding; 14] = [
+        Encoding::Z85, Encoding::Base64, Encoding::Base64Url, Encoding::Hex, Encoding::Ascii85, Encoding::Rfc1924, Encoding::Base91,
+        Encoding::Base45, Encoding::Base58, Encoding::Base58Check, Encoding::Base32, Encoding::Base32Crockford, Encoding::Bech32,
+        Encoding::Bech32m,
+    ];
+
+    #[test]
+    fn test_transcode_all_pairs() {
+        let data: Vec<u8> = (0..=255u8).chain([0xfb, 0xff]).collect();
+        let options = ConversionOptions { hrp: Some("test".to_string()), ..raw() };
+        for from in ENCODINGS {
+            let input = from.encode(&data, &options).unwrap();
+            for to in ENCODINGS {
+                let output = transcode_internal(&input, from, to, &options).unwrap();
+                assert_eq!(output, to.encode(&data, &options).unwrap(), "{:?} -> {:?}", from, to);
+                assert_eq!(to.decode(&output).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transcode_encodings() {
+        let hello = b"Hello, World\xfb\xff";
+        assert_eq!(transcode_internal("48656c6c6f2c20576f726c64fbff", Encoding::Hex, Encoding::Base64Url, &raw()).unwrap(), "SGVsbG8sIFdvcmxk-_8");
+        assert_eq!(Encoding::Base64Url.decode("SGVsbG8sIFdvcmxk-_8=").unwrap(), hello);
+        assert_eq!(Encoding::Hex.decode("48656C6C6F2C20576F726C64FBFF").unwrap(), hello);
+        assert_eq!(transcode_internal("SGVsbG8=", Encoding::Base64, Encoding::Z85, &ConversionOptions { padding_format: PaddingFormat::Length, ..raw() }).unwrap(), "nm=QNzVx+q#5");
+
+        let compressed = transcode_internal(&hex_encode(&[7; 500], false), Encoding::Hex, Encoding::Z85, &ConversionOptions { compression: Compression::Deflate, ..raw() }).unwrap();
+        assert!(compressed.starts_with("z85v2:"));
+        assert_eq!(transcode_internal(&compressed, Encoding::Z85, Encoding::Hex, &raw()).unwrap(), hex_encode(&[7; 500], false));
+
+        // basE91 carries no padding suffix; the Z85 side gets whichever convention is asked for
+        let base91 = transcode_internal(&compressed, Encoding::Z85, Encoding::Base91, &raw()).unwrap();
+        let length_form = transcode_internal(&base91, Encoding::Base91, Encoding::Z85, &ConversionOptions { padding_format: PaddingFormat::Length, ..raw() }).unwrap();
+        assert_eq!(length_form, encode_z85_with_options_internal(&[7; 500], PaddingFormat::Length, Compression::None));
+
+        // Base58Check input is verified before anything is encoded
+        let address = "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2";
+        let hex = transcode_internal(address, Encoding::Base58Check, Encoding::Hex, &raw()).unwrap();
+        assert_eq!(transcode_internal(&hex, Encoding::Hex, Encoding::Base58Check, &raw()).unwrap(), address);
+        assert_eq!(transcode_internal(address, Encoding::Base58, Encoding::Hex, &raw()).unwrap().len(), hex.len() + 8);
+
+        // Bech32 output needs an HRP; input of either variant must match the one asked for
+        let bech32m = transcode_internal(&hex, Encoding::Hex, Encoding::Bech32m, &ConversionOptions { hrp: Some("desc".to_string()), ..raw() }).unwrap();
+        assert!(bech32m.starts_with("desc1"));
+        assert_eq!(transcode_internal(&bech32m, Encoding::Bech32m, Encoding::Hex, &raw()).unwrap(), hex);
+        assert_eq!(transcode_internal(&hex, Encoding::Hex, Encoding::Bech32, &raw()).err().unwrap().message, "Bech32 encode error: ConversionOptions.hrp is required");
+        assert_eq!(transcode_internal(&bech32m, Encoding::Bech32, Encoding::Hex, &raw()).err().unwrap().message, "Bech32 decode error: checksum is Bech32m");
+    }
+
+    #[test]
+    fn test_hex_z85() {
+        let z85 = hex_to_z85_internal("48656C6c6f2c20576f726c64fbff").unwrap();
+        assert_eq!(z85, encode_z85_with_options_internal(b"Hello, World\xfb\xff", PaddingFormat::Count, Compression::None));
+        assert_eq!(z85_to_hex_internal(&z85, false).unwrap(), "48656c6c6f2c20576f726c64fbff");
+        assert_eq!(z85_to_hex_internal(&z85, true).unwrap(), "48656C6C6F2C20576F726C64FBFF");
+        assert_eq!(hex_to_z85_internal("").unwrap(), ":0");
+        assert_eq!(hex_to_z85_internal("0x00").err().unwrap().message, "Hex decode error: invalid character at offset 1");
+        assert_eq!(z85_to_hex_internal("nm=QN", false).err().unwrap().message, "Invalid format: expected 'z85_data:padding'");
+    }
+
+    #[test]
+    fn test_normalize_base64() {
+        assert!(matches!(normalize_base64("SGVsbG8="), Cow::Borrowed("SGVsbG8=")));
+        assert_eq!(normalize_base64("SGVs\r\nbG8"), "SGVsbG8=");
+        assert_eq!(normalize_base64(" SGVs bG8g\n\tV28 "), "SGVsbG8gV28=");
+        assert_eq!(normalize_base64("SGVsbA"), "SGVsbA==");
+        assert_eq!(normalize_base64("SGVsb"), "SGVsb");
+    }
+
+    #[test]
+    fn test_transcode_errors() {
+        let transcode = |data, from| transcode_internal(data, from, Encoding::Z85, &raw());
+        assert_eq!(transcode("abc", Encoding::Hex).err().unwrap().message, "Hex decode error: odd number of digits (3)");
+        assert_eq!(transcode("0g", Encoding::Hex).err().unwrap().message, "Hex decode error: invalid character at offset 1");
+        assert!(transcode("SGVsbG8+", Encoding::Base64Url).err().unwrap().message.starts_with("Base64 decode error"));
+        assert!(transcode("SGVsbG8-", Encoding::Base64).err().unwrap().message.starts_with("Base64 decode error"));
+        assert_eq!(transcode("nm=QNzY&b1", Encoding::Z85).err().unwrap().message, "Invalid format: expected 'z85_data:padding'");
+    }
+}
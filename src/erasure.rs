@@ -0,0 +1,175 @@
+//! Systematic Reed-Solomon erasure coding over GF(2^8): K data shards plus M parity shards, any K
+//! of which rebuild the data. Parity rows form a Cauchy matrix, so every K×K submatrix of the
+//! encoding matrix is invertible.
+
+/// Largest number of data plus parity shards (distinct Cauchy points in GF(2^8))
+pub(crate) const MAX_SHARDS: usize = 256;
+
+/// GF(2^8) with the polynomial x^8 + x^4 + x^3 + x^2 + 1
+struct Gf256 {
+    exp: [u8; 512],
+    log: [u8; 256],
+}
+
+impl Gf256 {
+    const fn new() -> Gf256 {
+        let mut exp = [0u8; 512];
+        let mut log = [0u8; 256];
+        let mut x: u16 = 1;
+        let mut i = 0;
+        while i < 255 {
+            exp[i] = x as u8;
+            exp[i + 255] = x as u8;
+            log[x as usize] = i as u8;
+            x <<= 1;
+            if x & 0x100 != 0 {
+                x ^= 0x11d;
+            }
+            i += 1;
+        }
+        Gf256 { exp, log }
+    }
+
+    fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            0
+        } else {
+            self.exp[self.log[a as usize] as usize + self.log[b as usize] as usize]
+        }
+    }
+
+    fn inv(&self, a: u8) -> u8 {
+        self.exp[255 - self.log[a as usize] as usize]
+    }
+}
+
+static GF: Gf256 = Gf256::new();
+
+// Row `shard` of the encoding matrix: an identity row for data shards, a Cauchy row
+// 1 / (x_j + y_i) with x_j = data_count + j and y_i = i for parity shard j
+fn matrix_row(shard: usize, data_count: usize) -> Vec<u8> {
+    if shard < data_count {
+        (0..data_count).map(|i| (i == shard) as u8).collect()
+    } else {
+        (0..data_count).map(|i| GF.inv((shard ^ i) as u8)).collect()
+    }
+}
+
+// Add `coefficient * source` to `target`
+fn mul_add(target: &mut [u8], source: &[u8], coefficient: u8) {
+    for (t, &s) in target.iter_mut().zip(source) {
+        *t ^= GF.mul(coefficient, s);
+    }
+}
+
+// Parity shards for equally sized data shards
+pub(crate) fn encode_parity(data: &[Vec<u8>], parity_count: usize) -> Vec<Vec<u8>> {
+    let shard_len = data.first().map_or(0, Vec::len);
+    (data.len()..data.len() + parity_count)
+        .map(|shard| {
+            let mut parity = vec![0u8; shard_len];
+            for (coefficient, source) in matrix_row(shard, data.len()).into_iter().zip(data) {
+                mul_add(&mut parity, source, coefficient);
+            }
+            parity
+        })
+        .collect()
+}
+
+// Invert a square matrix by Gauss-Jordan elimination, or return `None` if it is singular.
+// Submatrices of the encoding matrix are invertible as long as the shard count stays within
+// `MAX_SHARDS`; callers check that, but a bad count must not panic.
+fn invert(mut matrix: Vec<Vec<u8>>) -> Option<Vec<Vec<u8>>> {
+    let n = matrix.len();
+    let mut inverse: Vec<Vec<u8>> = (0..n).map(|row| matrix_row(row, n)).collect();
+    for column in 0..n {
+        let pivot = (column..n).find(|&row| matrix[row][column] != 0)?;
+        matrix.swap(column, pivot);
+        inverse.swap(column, pivot);
+        let scale = GF.inv(matrix[column][column]);
+        for value in matrix[column].iter_mut().chain(inverse[column].iter_mut()) {
+            *value = GF.mul(*value, scale);
+        }
+        for row in 0..n {
+            let factor = matrix[row][column];
+            if row != column && factor != 0 {
+                let (pivot_row, pivot_inverse) = (matrix[column].clone(), inverse[column].clone());
+                mul_add(&mut matrix[row], &pivot_row, factor);
+                mul_add(&mut inverse[row], &pivot_inverse, factor);
+            }
+        }
+    }
+    Some(inverse)
+}
+
+// Rebuild the `data_count` data shards from any `data_count` of the equally sized `shards`
+// (data shards first, then parity), returning them in order, or `None` if fewer are present
+// or there are more than `MAX_SHARDS` shards
+pub(crate) fn reconstruct(shards: &[Option<Vec<u8>>], data_count: usize) -> Option<Vec<Vec<u8>>> {
+    if shards.len() > MAX_SHARDS {
+        return None;
+    }
+    let available: Vec<usize> = (0..shards.len()).filter(|&shard| shards[shard].is_some()).take(data_count).collect();
+    if available.len() < data_count {
+        return None;
+    }
+    if available.iter().enumerate().all(|(position, &shard)| position == shard) {
+        return Some(shards[..data_count].iter().flatten().cloned().collect());
+    }
+
+    let decode = invert(available.iter().map(|&shard| matrix_row(shard, data_count)).collect())?;
+    let shard_len = shards[available[0]].as_ref().map_or(0, Vec::len);
+    Some(decode.iter()
+        .map(|row| {
+            let mut data = vec![0u8; shard_len];
+            for (&coefficient, &shard) in row.iter().zip(&available) {
+                mul_add(&mut data, shards[shard].as_ref().unwrap(), coefficient);
+            }
+            data
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gf256() {
+        for a in 1..=255u8 {
+            assert_eq!(GF.mul(a, GF.inv(a)), 1);
+        }
+        assert_eq!(GF.mul(0x80, 2), 0x1d);
+    }
+
+    #[test]
+    fn test_reconstruct_from_any_subset() {
+        let data: Vec<Vec<u8>> = (0..4u8).map(|i| (0..8).map(|j| i * 16 + j).collect()).collect();
+        let parity = encode_parity(&data, 3);
+        let all: Vec<Vec<u8>> = data.iter().chain(&parity).cloned().collect();
+
+        // Every choice of 3 erasures among the 7 shards
+        for mask in 0u32..128 {
+            if mask.count_ones() != 3 {
+                continue;
+            }
+            let shards: Vec<Option<Vec<u8>>> = all.iter().enumerate()
+                .map(|(shard, bytes)| (mask & (1 << shard) == 0).then(|| bytes.clone()))
+                .collect();
+            assert_eq!(reconstruct(&shards, 4).unwrap(), data, "mask {:07b}", mask);
+        }
+
+        let mut shards: Vec<Option<Vec<u8>>> = all.into_iter().map(Some).collect();
+        shards[0] = None;
+        shards[2] = None;
+        shards[4] = None;
+        shards[6] = None;
+        assert_eq!(reconstruct(&shards, 4), None);
+    }
+
+    #[test]
+    fn test_invert_singular() {
+        assert_eq!(invert(vec![vec![1, 2], vec![1, 2]]), None);
+        assert_eq!(invert(vec![vec![0, 1], vec![1, 0]]), Some(vec![vec![0, 1], vec![1, 0]]));
+    }
+}
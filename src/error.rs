@@ -0,0 +1,256 @@
+#[cfg(not(feature = "min-size"))]
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+#[cfg(not(feature = "min-size"))]
+use serde::Serialize;
+#[cfg(not(feature = "min-size"))]
+use tsify::Tsify;
+
+// Declarations for the errors thrown by every function; `Z85Error` itself is the inline JS class
+// below
+#[wasm_bindgen(typescript_custom_section)]
+const Z85_ERROR_TS: &str = r#"
+export type Z85ErrorCode =
+    | "INVALID_CHAR" | "INVALID_GROUP" | "INVALID_LENGTH" | "INVALID_PADDING" | "INVALID_FORMAT"
+    | "CHECKSUM_MISMATCH" | "BAD_DATA_URL" | "BAD_CONTAINER" | "DECOMPRESSION_FAILED"
+    | "INVALID_KEY" | "CRYPTO_FAILED" | "BUFFER_TOO_SMALL" | "LIMIT_EXCEEDED" | "UNSUPPORTED"
+    | "FETCH_FAILED" | "ABORTED" | "INVALID_OPTIONS" | "INVALID_INPUT" | "PANIC";
+
+export interface Z85Error extends Error {
+    name: "Z85Error";
+    code: Z85ErrorCode;
+    offset?: number;
+}
+"#;
+
+#[wasm_bindgen(inline_js = "
+export class Z85Error extends Error {
+    constructor(message, code, offset) {
+        super(message);
+        this.name = 'Z85Error';
+        this.code = code;
+        this.offset = offset;
+    }
+}
+")]
+extern "C" {
+    type Z85Error;
+
+    #[wasm_bindgen(constructor)]
+    fn new(message: &str, code: &str, offset: Option<u32>) -> Z85Error;
+}
+
+/// Code of a thrown `Z85Error` (`Z85ErrorCode` in TypeScript)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+// The min-size build only raises the codes of the core codec and base64
+#[cfg_attr(feature = "min-size", allow(dead_code))]
+pub(crate) enum ErrorCode {
+    InvalidChar,
+    InvalidGroup,
+    InvalidLength,
+    InvalidPadding,
+    InvalidFormat,
+    ChecksumMismatch,
+    BadDataUrl,
+    BadContainer,
+    DecompressionFailed,
+    InvalidKey,
+    CryptoFailed,
+    BufferTooSmall,
+    LimitExceeded,
+    Unsupported,
+    FetchFailed,
+    Aborted,
+    InvalidOptions,
+    InvalidInput,
+    Panic,
+}
+
+impl ErrorCode {
+    // The code as JS sees it
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ErrorCode::InvalidChar => "INVALID_CHAR",
+            ErrorCode::InvalidGroup => "INVALID_GROUP",
+            ErrorCode::InvalidLength => "INVALID_LENGTH",
+            ErrorCode::InvalidPadding => "INVALID_PADDING",
+            ErrorCode::InvalidFormat => "INVALID_FORMAT",
+            ErrorCode::ChecksumMismatch => "CHECKSUM_MISMATCH",
+            ErrorCode::BadDataUrl => "BAD_DATA_URL",
+            ErrorCode::BadContainer => "BAD_CONTAINER",
+            ErrorCode::DecompressionFailed => "DECOMPRESSION_FAILED",
+            ErrorCode::InvalidKey => "INVALID_KEY",
+            ErrorCode::CryptoFailed => "CRYPTO_FAILED",
+            ErrorCode::BufferTooSmall => "BUFFER_TOO_SMALL",
+            ErrorCode::LimitExceeded => "LIMIT_EXCEEDED",
+            ErrorCode::Unsupported => "UNSUPPORTED",
+            ErrorCode::FetchFailed => "FETCH_FAILED",
+            ErrorCode::Aborted => "ABORTED",
+            ErrorCode::InvalidOptions => "INVALID_OPTIONS",
+            ErrorCode::InvalidInput => "INVALID_INPUT",
+            ErrorCode::Panic => "PANIC",
+        }
+    }
+
+    // Code of a `z85_core` error, by variant
+    pub(crate) fn of(error: &z85_core::Error) -> ErrorCode {
+        use z85_core::Error as CoreError;
+        match error {
+            CoreError::InvalidCharacter { .. } => ErrorCode::InvalidChar,
+            CoreError::InvalidGroup { .. } => ErrorCode::InvalidGroup,
+            CoreError::InvalidDataLength { .. } | CoreError::StrictLength { .. } => ErrorCode::InvalidLength,
+            CoreError::MissingSuffix => ErrorCode::InvalidFormat,
+            CoreError::InvalidPadding
+            | CoreError::StrictPadding { .. }
+            | CoreError::InvalidLengthSuffix
+            | CoreError::Truncated { .. }
+            | CoreError::LengthMismatch { .. } => ErrorCode::InvalidPadding,
+            CoreError::OutputTooSmall { .. } => ErrorCode::BufferTooSmall,
+            CoreError::InvalidContainer(_) | CoreError::Encrypted { .. } | CoreError::SizeMismatch { .. } => ErrorCode::BadContainer,
+            CoreError::UnsupportedCompression(_) => ErrorCode::Unsupported,
+            CoreError::ChecksumMismatch { .. } => ErrorCode::ChecksumMismatch,
+            CoreError::Decompression(_) | CoreError::DictionaryRequired { .. } | CoreError::DictionaryMismatch { .. } => {
+                ErrorCode::DecompressionFailed
+            }
+            CoreError::DecompressedTooLarge { .. } => ErrorCode::LimitExceeded,
+            _ => ErrorCode::InvalidInput,
+        }
+    }
+
+    // Code of a base64 decode error
+    pub(crate) fn of_base64(error: &base64::DecodeError) -> ErrorCode {
+        match error {
+            base64::DecodeError::InvalidLength(_) => ErrorCode::InvalidLength,
+            base64::DecodeError::InvalidPadding => ErrorCode::InvalidPadding,
+            base64::DecodeError::InvalidByte(..) | base64::DecodeError::InvalidLastSymbol(..) => ErrorCode::InvalidInput,
+        }
+    }
+}
+
+// Offset named by a `z85_core` error, for `Z85Error.offset`
+pub(crate) fn core_error_offset(error: &z85_core::Error) -> Option<u32> {
+    match *error {
+        z85_core::Error::InvalidCharacter { offset, .. } | z85_core::Error::InvalidGroup { offset, .. } => u32::try_from(offset).ok(),
+        _ => None,
+    }
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Error of the internal functions: the message thrown to JS with its code and, for errors at a
+/// position in the input, the offset
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Error {
+    pub(crate) code: ErrorCode,
+    pub(crate) message: String,
+    pub(crate) offset: Option<u32>,
+}
+
+#[cfg(not(feature = "min-size"))]
+impl Error {
+    pub(crate) fn new(code: ErrorCode, message: impl Into<String>) -> Error {
+        Error { code, message: message.into(), offset: None }
+    }
+
+    // This error, located at `offset` in the input
+    pub(crate) fn at(self, offset: usize) -> Error {
+        Error { offset: u32::try_from(offset).ok(), ..self }
+    }
+}
+
+#[cfg(not(feature = "min-size"))]
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(not(feature = "min-size"))]
+impl From<z85_core::Error> for Error {
+    fn from(error: z85_core::Error) -> Error {
+        Error { code: ErrorCode::of(&error), offset: core_error_offset(&error), message: error.to_string() }
+    }
+}
+
+#[cfg(not(feature = "min-size"))]
+impl From<base64::DecodeError> for Error {
+    fn from(error: base64::DecodeError) -> Error {
+        Error::new(ErrorCode::of_base64(&error), format!("Base64 decode error: {}", error))
+    }
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Error details as a plain object, used by the non-throwing `try_*` functions
+#[derive(Serialize, Tsify, Debug, PartialEq)]
+pub(crate) struct ErrorInfo {
+    #[tsify(type = "Z85ErrorCode")]
+    code: &'static str,
+    message: String,
+    #[tsify(optional)]
+    offset: Option<u32>,
+}
+
+#[cfg(not(feature = "min-size"))]
+impl From<Error> for ErrorInfo {
+    fn from(error: Error) -> ErrorInfo {
+        ErrorInfo { code: error.code.name(), message: error.message, offset: error.offset }
+    }
+}
+
+// Convert an internal error into a thrown `Z85Error` (a JS `Error` subclass) carrying its code
+// and offset
+#[cfg(not(feature = "min-size"))]
+pub(crate) fn js_error(error: Error) -> JsValue {
+    coded_error(&error.message, error.code, error.offset)
+}
+
+// A thrown-style `Z85Error` with an explicit code and offset. `min-size` builds use it directly
+// with short fixed messages.
+pub(crate) fn coded_error(message: &str, code: ErrorCode, offset: Option<u32>) -> JsValue {
+    Z85Error::new(message, code.name(), offset).into()
+}
+
+// A thrown-style `Z85Error` with code `PANIC`. Created inside the panic hook, so its `stack`
+// includes the WASM frames that panicked.
+pub(crate) fn panic_error(message: &str) -> JsValue {
+    coded_error(message, ErrorCode::Panic, None)
+}
+
+#[cfg(all(test, not(feature = "min-size")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_core_error_codes() {
+        let cases = [
+            (z85_core::Error::InvalidCharacter { alphabet: "Z85", offset: 3 }, "INVALID_CHAR", Some(3)),
+            (z85_core::Error::InvalidGroup { alphabet: "Z85", offset: 5 }, "INVALID_GROUP", Some(5)),
+            (z85_core::Error::InvalidDataLength { alphabet: "Z85", length: 7 }, "INVALID_LENGTH", None),
+            (z85_core::Error::InvalidPadding, "INVALID_PADDING", None),
+            (z85_core::Error::LengthMismatch { length: 9, available: 4 }, "INVALID_PADDING", None),
+            (z85_core::Error::MissingSuffix, "INVALID_FORMAT", None),
+            (z85_core::Error::InvalidContainer("Invalid z85v2 checksum: zz".to_string()), "BAD_CONTAINER", None),
+            (z85_core::Error::ChecksumMismatch { expected: 1, actual: 2 }, "CHECKSUM_MISMATCH", None),
+            (z85_core::Error::OutputTooSmall { needed: 8, actual: 4 }, "BUFFER_TOO_SMALL", None),
+            (z85_core::Error::Decompression("Zstd decompression error: malformed frame header".to_string()), "DECOMPRESSION_FAILED", None),
+            (z85_core::Error::UnsupportedCompression("lz4".to_string()), "UNSUPPORTED", None),
+            (z85_core::Error::DecompressedTooLarge { limit: 8 }, "LIMIT_EXCEEDED", None),
+        ];
+        for (error, code, offset) in cases {
+            let error = Error::from(error);
+            assert_eq!((error.code.name(), error.offset), (code, offset), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_error_codes_do_not_depend_on_the_message() {
+        // Option errors mentioning data URLs or the padding suffix are still option errors
+        let options = crate::ConversionOptions { data_url_encoding: crate::DataUrlEncoding::Z85, ..crate::ConversionOptions::new(crate::DataType::Raw, crate::DataType::DataURL) };
+        let error = crate::z85_to_base64_with_options_internal("nm=QNzY&b1A+]m^:1", &options).err().unwrap();
+        assert_eq!((error.code, error.message.as_str()), (ErrorCode::InvalidOptions, "Data URL output encoding must be Base64 or Percent"));
+        assert_eq!(crate::decode_z85_lenient_internal("nm=QN zY&b1A+]m^:1", ":").err().unwrap().code, ErrorCode::InvalidOptions);
+
+        let info = ErrorInfo::from(Error::new(ErrorCode::InvalidInput, "Could not detect the input encoding"));
+        assert_eq!((info.code, info.offset), ("INVALID_INPUT", None));
+    }
+}
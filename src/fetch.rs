@@ -0,0 +1,310 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use std::cell::RefCell;
+use std::rc::Rc;
+use web_sys::{
+    Blob, BlobPropertyBag, Headers, ReadableStream, ReadableStreamDefaultController, ReadableStreamDefaultReader, Response,
+    ResponseInit, UnderlyingSource,
+};
+
+use crate::container;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::mime;
+use crate::stream::Z85Decoder;
+use crate::decode_z85_internal;
+
+#[wasm_bindgen]
+extern "C" {
+    // The host's global `fetch` (browsers, workers, Node.js 18+)
+    #[wasm_bindgen(js_name = fetch)]
+    fn global_fetch(url: &str, init: &JsValue) -> js_sys::Promise;
+}
+
+/// How a response body is being decoded
+enum BodyState {
+    /// Too little text yet to tell a `z85v2:` container from plain Z85
+    Undetermined(String),
+    /// Plain Z85 with padding info, decoded as it arrives
+    Plain(Z85Decoder),
+    /// A `z85v2:` container, whose header covers the whole payload, so it is buffered
+    Container(String),
+}
+
+/// Incremental decoder for the text of a fetched `.z85` resource
+struct BodyDecoder {
+    state: BodyState,
+    /// Decoded bytes not yet taken
+    decoded: Vec<u8>,
+    bytes_read: usize,
+    bytes_decoded: usize,
+}
+
+impl BodyDecoder {
+    fn new() -> BodyDecoder {
+        BodyDecoder { state: BodyState::Undetermined(String::new()), decoded: Vec::new(), bytes_read: 0, bytes_decoded: 0 }
+    }
+
+    // Accept the next chunk of the body. Whitespace (such as line breaks or a trailing
+    // newline) is never valid Z85, so it is skipped.
+    fn push(&mut self, chunk: &[u8]) -> Result<(), Error> {
+        self.bytes_read += chunk.len();
+        limits::check_input(self.bytes_read)?;
+        if let Some(position) = chunk.iter().position(|byte| !byte.is_ascii()) {
+            let offset = self.bytes_read - chunk.len() + position;
+            return Err(Error::new(ErrorCode::InvalidChar, format!("Z85 decode error: non-ASCII response body at offset {}", offset)).at(offset));
+        }
+        let text: String = chunk.iter().filter(|byte| !byte.is_ascii_whitespace()).map(|&byte| byte as char).collect();
+
+        match &mut self.state {
+            BodyState::Undetermined(start) => {
+                start.push_str(&text);
+                if start.len() >= container::V2_PREFIX.len() {
+                    let start = std::mem::take(start);
+                    if start.starts_with(container::V2_PREFIX) {
+                        self.state = BodyState::Container(start);
+                    } else {
+                        let mut decoder = Z85Decoder::new();
+                        let decoded = decoder.update_internal(&start)?;
+                        self.state = BodyState::Plain(decoder);
+                        return self.emit(decoded);
+                    }
+                }
+                Ok(())
+            }
+            BodyState::Plain(decoder) => {
+                let decoded = decoder.update_internal(&text)?;
+                self.emit(decoded)
+            }
+            BodyState::Container(text_so_far) => {
+                text_so_far.push_str(&text);
+                Ok(())
+            }
+        }
+    }
+
+    fn emit(&mut self, decoded: Vec<u8>) -> Result<(), Error> {
+        self.bytes_decoded += decoded.len();
+        limits::check_output(self.bytes_decoded)?;
+        self.decoded.extend_from_slice(&decoded);
+        Ok(())
+    }
+
+    // Decoded bytes so far, leaving them out of what `take` and `finish` return later
+    fn take(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.decoded)
+    }
+
+    // Decode the rest of the body and return the decoded bytes not yet taken
+    fn finish(&mut self) -> Result<Vec<u8>, Error> {
+        let decoded = match std::mem::replace(&mut self.state, BodyState::Undetermined(String::new())) {
+            BodyState::Undetermined(text) | BodyState::Container(text) => decode_z85_internal(&text)?,
+            BodyState::Plain(mut decoder) => decoder.finalize_internal(None)?,
+        };
+        self.emit(decoded)?;
+        Ok(self.take())
+    }
+}
+
+// Fetch `url` and decode its body as it streams in, returning the bytes and the response's
+// `Content-Type`
+async fn fetch_and_decode(url: &str, init: Option<JsValue>) -> Result<(Vec<u8>, String), JsValue> {
+    let response: Response = JsFuture::from(global_fetch(url, &init.unwrap_or(JsValue::UNDEFINED))).await?
+        .dyn_into()?;
+    if !response.ok() {
+        return Err(js_error(Error::new(ErrorCode::FetchFailed, format!("Fetch failed: HTTP {} {} for {}", response.status(), response.status_text(), url))));
+    }
+    let content_type = response.headers().get("content-type")?.unwrap_or_default();
+
+    let mut body = BodyDecoder::new();
+    if let Some(stream) = response.body() {
+        let reader: ReadableStreamDefaultReader = stream.get_reader().unchecked_into();
+        while let Some(chunk) = read_chunk(&reader).await? {
+            push_chunk(&mut body, &reader, &chunk)?;
+        }
+    }
+    Ok((body.finish().map_err(js_error)?, content_type))
+}
+
+// Read the next chunk of a byte stream, or `None` at its end
+async fn read_chunk(reader: &ReadableStreamDefaultReader) -> Result<Option<Vec<u8>>, JsValue> {
+    let result = JsFuture::from(reader.read()).await?;
+    if js_sys::Reflect::get(&result, &JsValue::from_str("done"))?.is_truthy() {
+        return Ok(None);
+    }
+    let chunk = js_sys::Uint8Array::new(&js_sys::Reflect::get(&result, &JsValue::from_str("value"))?);
+    Ok(Some(chunk.to_vec()))
+}
+
+// Decode the next chunk of the body read by `reader`, cancelling the download if it is invalid
+fn push_chunk(body: &mut BodyDecoder, reader: &ReadableStreamDefaultReader, chunk: &[u8]) -> Result<(), JsValue> {
+    body.push(chunk).map_err(|e| {
+        let _ = reader.cancel();
+        js_error(e)
+    })
+}
+
+// A `ReadableStream` that yields `head`, then decodes the rest of the body read by `reader` as
+// the consumer pulls it
+fn decoded_body_stream(head: Vec<u8>, body: BodyDecoder, reader: ReadableStreamDefaultReader) -> Result<ReadableStream, JsValue> {
+    let body = Rc::new(RefCell::new(body));
+    let source = UnderlyingSource::new();
+    let start = Closure::once_into_js(move |controller: ReadableStreamDefaultController| {
+        controller.enqueue_with_chunk(&js_sys::Uint8Array::from(head.as_slice()))
+    });
+    let pull_reader = reader.clone();
+    let pull = Closure::<dyn FnMut(ReadableStreamDefaultController) -> js_sys::Promise>::new(move |controller: ReadableStreamDefaultController| {
+        let (body, reader) = (body.clone(), pull_reader.clone());
+        future_to_promise(async move {
+            // Read until there is something to hand over, as containers only decode at the end
+            loop {
+                let decoded = match read_chunk(&reader).await? {
+                    Some(chunk) => {
+                        push_chunk(&mut body.borrow_mut(), &reader, &chunk)?;
+                        body.borrow_mut().take()
+                    }
+                    None => {
+                        let rest = body.borrow_mut().finish().map_err(js_error)?;
+                        if !rest.is_empty() {
+                            controller.enqueue_with_chunk(&js_sys::Uint8Array::from(rest.as_slice()))?;
+                        }
+                        controller.close()?;
+                        return Ok(JsValue::UNDEFINED);
+                    }
+                };
+                if !decoded.is_empty() {
+                    controller.enqueue_with_chunk(&js_sys::Uint8Array::from(decoded.as_slice()))?;
+                    return Ok(JsValue::UNDEFINED);
+                }
+            }
+        })
+    });
+    let cancel = Closure::once_into_js(move |reason: JsValue| reader.cancel_with_reason(&reason));
+    source.set_start(start.unchecked_ref());
+    source.set_pull(pull.into_js_value().unchecked_ref());
+    source.set_cancel(cancel.unchecked_ref());
+    ReadableStream::new_with_underlying_source(&source)
+}
+
+/// Fetch a `.z85` resource and decode it while the body streams in, so the encoded text is
+/// never held in full. The body is Z85 with padding info or a `z85v2:` container (buffered
+/// until complete); whitespace is ignored. `init` is passed to `fetch`, so its `signal` also
+/// cancels the download. Rejects with code `FETCH_FAILED` for a non-2xx status.
+#[wasm_bindgen]
+pub async fn fetch_z85(
+    url: String,
+    #[wasm_bindgen(unchecked_param_type = "RequestInit")] init: Option<JsValue>,
+) -> Result<Vec<u8>, JsValue> {
+    Ok(fetch_and_decode(&url, init).await?.0)
+}
+
+/// `fetch_z85` resolving to a `Blob` of the decoded bytes, typed with the response's
+/// `Content-Type` (or `mime` when given)
+#[wasm_bindgen]
+pub async fn fetch_z85_blob(
+    url: String,
+    #[wasm_bindgen(unchecked_param_type = "RequestInit")] init: Option<JsValue>,
+    mime: Option<String>,
+) -> Result<Blob, JsValue> {
+    let (data, content_type) = fetch_and_decode(&url, init).await?;
+    let options = BlobPropertyBag::new();
+    options.set_type(&mime.unwrap_or(content_type));
+    Blob::new_with_u8_array_sequence_and_options(&js_sys::Array::of1(&js_sys::Uint8Array::from(data.as_slice())), &options)
+}
+
+/// Turn a `Response` whose body is Z85 (e.g. a `.z85` asset from a CDN) into a `Response` of
+/// the decoded bytes, for `event.respondWith` in a service worker. The body is decoded as the
+/// page reads it, not buffered; it may be Z85 with padding info or a `z85v2:` container, as
+/// for `fetch_z85`. The new `Content-Type` is `mime` or sniffed from the first bytes.
+/// `Content-Length` is exact when the whole body arrives with the first read, and dropped
+/// otherwise. Responses with an error status or no body are returned unchanged.
+#[wasm_bindgen]
+pub async fn decode_z85_response(response: Response, mime: Option<String>) -> Result<Response, JsValue> {
+    let Some(stream) = response.body().filter(|_| response.ok()) else {
+        return Ok(response);
+    };
+    if let Some(mime_type) = &mime {
+        mime::validate_mime_type(mime_type).map_err(js_error)?;
+    }
+
+    // Decode enough of the body to sniff its type
+    let reader: ReadableStreamDefaultReader = stream.get_reader().unchecked_into();
+    let mut body = BodyDecoder::new();
+    let mut complete = false;
+    while body.decoded.len() < mime::SNIFF_BYTES {
+        match read_chunk(&reader).await? {
+            Some(chunk) => push_chunk(&mut body, &reader, &chunk)?,
+            None => {
+                complete = true;
+                break;
+            }
+        }
+    }
+    let mut head = if complete { body.finish().map_err(js_error)? } else { body.take() };
+
+    let headers = Headers::new_with_headers(&response.headers())?;
+    headers.set("content-type", &mime.unwrap_or_else(|| mime::sniff_mime_type(&head).to_string()))?;
+    if complete {
+        headers.set("content-length", &head.len().to_string())?;
+    } else {
+        headers.delete("content-length")?;
+    }
+    let init = ResponseInit::new();
+    init.set_status(response.status());
+    init.set_status_text(&response.status_text());
+    init.set_headers(&headers);
+
+    if complete {
+        Response::new_with_opt_u8_array_and_init(Some(&mut head), &init)
+    } else {
+        Response::new_with_opt_readable_stream_and_init(Some(&decoded_body_stream(head, body, reader)?), &init)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_z85_internal;
+
+    fn decode_in_chunks(body: &[u8], chunk_size: usize) -> Result<Vec<u8>, Error> {
+        let mut decoder = BodyDecoder::new();
+        for chunk in body.chunks(chunk_size) {
+            decoder.push(chunk)?;
+        }
+        decoder.finish()
+    }
+
+    #[test]
+    fn test_body_decoder() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 13) as u8).collect();
+        let plain = format!("{}\n", encode_z85_internal(&data));
+        let container = container::encode_container_internal(&data, true, crate::compression::Compression::Deflate);
+        for chunk_size in [1, 3, 7, 100, 5000] {
+            assert_eq!(decode_in_chunks(plain.as_bytes(), chunk_size).unwrap(), data, "{}", chunk_size);
+            assert_eq!(decode_in_chunks(container.as_bytes(), chunk_size).unwrap(), data, "{}", chunk_size);
+        }
+        assert_eq!(decode_in_chunks(b"nm=Q\r\nNzVx+q:3", 4).unwrap(), b"Hello");
+        assert_eq!(decode_in_chunks(b":0", 2).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_body_decoder_take() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7) as u8).collect();
+        let mut decoder = BodyDecoder::new();
+        let mut taken = Vec::new();
+        for chunk in encode_z85_internal(&data).as_bytes().chunks(64) {
+            decoder.push(chunk).unwrap();
+            taken.extend(decoder.take());
+        }
+        assert!(!taken.is_empty());
+        taken.extend(decoder.finish().unwrap());
+        assert_eq!(taken, data);
+    }
+
+    #[test]
+    fn test_body_decoder_errors() {
+        assert_eq!(decode_in_chunks("HelloWorld€:0".as_bytes(), 4).err().unwrap().message, "Z85 decode error: non-ASCII response body at offset 10");
+        assert!(decode_in_chunks(b"Hello~orld:0", 4).is_err());
+        assert!(decode_in_chunks(b"", 4).is_err());
+    }
+}
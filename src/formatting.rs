@@ -0,0 +1,163 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{decode_z85_internal, encode_z85_internal, strip_z85_formatting, telemetry};
+
+// Reject group separators that would be read as the padding suffix
+fn check_separator(separator: &str) -> Result<(), Error> {
+    if separator.contains([':', '#']) {
+        return Err(Error::new(ErrorCode::InvalidOptions, "Invalid group separator: ':' and '#' introduce the padding suffix"));
+    }
+    Ok(())
+}
+
+// Join `units` into lines of at most `line_width` characters. A unit is never split, so one
+// longer than `line_width` gets a line of its own.
+pub(crate) fn wrap_units<S: AsRef<str>>(units: &[S], line_width: usize) -> String {
+    let mut output = String::new();
+    let mut line_len = 0;
+    for unit in units {
+        let unit = unit.as_ref();
+        if line_len > 0 && line_len + unit.len() > line_width {
+            output.push('\n');
+            line_len = 0;
+        }
+        output.push_str(unit);
+        line_len += unit.len();
+    }
+    output
+}
+
+// Internal pure Rust function for laying out Z85 text for humans: `group_separator` after every
+// 5-character group but the last, and lines of at most `line_width` characters. Grouped text
+// breaks lines after a separator and keeps the padding suffix with the last group; ungrouped
+// text breaks every `line_width` characters.
+pub(crate) fn format_z85_internal(z85_data_with_padding: &str, line_width: Option<usize>, group_separator: &str) -> Result<String, Error> {
+    check_separator(group_separator)?;
+    if line_width == Some(0) {
+        return Err(Error::new(ErrorCode::InvalidOptions, "Line width must be greater than zero"));
+    }
+    if !z85_data_with_padding.is_ascii() {
+        return Err(Error::new(ErrorCode::InvalidChar, "Z85 decode error: input contains non-ASCII characters"));
+    }
+
+    // Units kept together on a line
+    let units: Vec<String> = if group_separator.is_empty() {
+        z85_data_with_padding.chars().map(String::from).collect()
+    } else {
+        let data_end = z85_data_with_padding.rfind([':', '#']).unwrap_or(z85_data_with_padding.len());
+        let (data, suffix) = z85_data_with_padding.split_at(data_end);
+        let group_count = data.len().div_ceil(5);
+        let mut units: Vec<String> = (0..group_count)
+            .map(|index| {
+                let group = &data[index * 5..(index * 5 + 5).min(data.len())];
+                let after = if index + 1 < group_count { group_separator } else { suffix };
+                format!("{}{}", group, after)
+            })
+            .collect();
+        if units.is_empty() {
+            units.push(suffix.to_string());
+        }
+        units
+    };
+    Ok(match line_width {
+        Some(line_width) => wrap_units(&units, line_width),
+        None => units.concat(),
+    })
+}
+
+// Internal pure Rust function for undoing `format_z85_internal`: whitespace is dropped and
+// `group_separator` is expected after every 5-character group but the last. Separators are
+// matched by position, so data characters equal to the separator are kept.
+fn unformat_z85_internal(text: &str, group_separator: &str) -> Result<String, Error> {
+    check_separator(group_separator)?;
+    let text = strip_z85_formatting(text, "");
+    let separator: String = group_separator.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    if separator.is_empty() {
+        return Ok(text.into_owned());
+    }
+    if !text.is_ascii() {
+        return Err(Error::new(ErrorCode::InvalidChar, "Z85 decode error: input contains non-ASCII characters"));
+    }
+
+    // Groups may start with ':' or '#', so only a bare suffix means there is no data
+    let is_suffix = |rest: &str| rest.len() > 1 && rest.starts_with([':', '#']) && rest[1..].bytes().all(|c| c.is_ascii_digit());
+    let mut output = String::with_capacity(text.len());
+    let mut rest: &str = &text;
+    if !is_suffix(rest) {
+        while !rest.is_empty() {
+            let group = &rest[..rest.len().min(5)];
+            output.push_str(group);
+            rest = &rest[group.len()..];
+            match rest.strip_prefix(separator.as_str()) {
+                Some(after) => rest = after,
+                None => break,
+            }
+        }
+    }
+    if !rest.is_empty() && !rest.starts_with([':', '#']) {
+        let offset = text.len() - rest.len();
+        return Err(Error::new(ErrorCode::InvalidFormat, format!("Z85 format error: expected '{}' at offset {}", separator, offset)).at(offset));
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Encode raw bytes to Z85 with padding info laid out for reading or printing, e.g. recovery
+/// codes: `group_separator` (default none) after every 5-character group but the last, and
+/// lines of at most `line_width` characters (default unwrapped). `decode_z85_formatted` reads it back.
+#[wasm_bindgen]
+pub fn encode_z85_formatted(data: &[u8], line_width: Option<usize>, group_separator: Option<String>) -> Result<String, JsValue> {
+    telemetry::instrument("encode_z85_formatted", data.len(), String::len, || {
+        format_z85_internal(&encode_z85_internal(data), line_width, group_separator.as_deref().unwrap_or(""))
+    })
+        .map_err(js_error)
+}
+
+/// Decode the output of `encode_z85_formatted`, given the same `group_separator`. Line breaks
+/// and other whitespace may be anywhere; error offsets refer to the text without them.
+#[wasm_bindgen]
+pub fn decode_z85_formatted(text: &str, group_separator: Option<String>) -> Result<Vec<u8>, JsValue> {
+    telemetry::instrument("decode_z85_formatted", text.len(), Vec::len, || {
+        decode_z85_internal(&unformat_z85_internal(text, group_separator.as_deref().unwrap_or(""))?)
+    })
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_z85() {
+        let encoded = "HelloWorld0000000000:2";
+        assert_eq!(format_z85_internal(encoded, None, "-").unwrap(), "Hello-World-00000-00000:2");
+        assert_eq!(format_z85_internal(encoded, Some(13), "-").unwrap(), "Hello-World-\n00000-00000:2");
+        assert_eq!(format_z85_internal(encoded, Some(8), "").unwrap(), "HelloWor\nld000000\n0000:2");
+        assert_eq!(format_z85_internal(encoded, Some(3), " ").unwrap(), "Hello \nWorld \n00000 \n00000:2");
+        assert_eq!(format_z85_internal(":0", Some(4), "-").unwrap(), ":0");
+        assert_eq!(format_z85_internal(encoded, Some(0), "").err().unwrap().message, "Line width must be greater than zero");
+        assert_eq!(format_z85_internal(encoded, None, ":").err().unwrap().message, "Invalid group separator: ':' and '#' introduce the padding suffix");
+    }
+
+    #[test]
+    fn test_formatted_roundtrip() {
+        let data: Vec<u8> = (0..=255u8).collect();
+        for size in [0, 1, 4, 7, 64, 256] {
+            let encoded = encode_z85_internal(&data[..size]);
+            for (line_width, separator) in [(None, ""), (Some(40), ""), (Some(24), "-"), (None, "."), (Some(30), " ")] {
+                let formatted = format_z85_internal(&encoded, line_width, separator).unwrap();
+                assert_eq!(unformat_z85_internal(&formatted, separator).unwrap(), encoded, "{:?}", formatted);
+                assert!(line_width.is_none_or(|width| formatted.lines().all(|line| line.len() <= width.max(7))));
+            }
+        }
+    }
+
+    #[test]
+    fn test_unformat_z85_by_position() {
+        // '-' and '.' are Z85 characters: only the ones between groups are separators
+        assert_eq!(unformat_z85_internal("a-b-c-d.e.-\r\n-fghij:0", "-").unwrap(), "a-b-cd.e.-fghij:0");
+        assert_eq!(unformat_z85_internal("Hello World:0", "").unwrap(), "HelloWorld:0");
+        assert_eq!(unformat_z85_internal("HelloWorld:0", "-").err().unwrap().message, "Z85 format error: expected '-' at offset 5");
+    }
+}
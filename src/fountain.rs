@@ -0,0 +1,457 @@
+use wasm_bindgen::prelude::*;
+use std::collections::HashSet;
+use sha2::{Digest, Sha256};
+
+use crate::container::crc32;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::{decode_z85_internal, encode_z85_internal};
+
+/// Prefix identifying one fountain-coded part
+const PART_PREFIX: &str = "z85f:";
+
+/// xoshiro256** generator, seeded identically by the encoder and decoder of a part so both
+/// derive the same set of mixed fragments from its sequence number
+struct Xoshiro256 {
+    state: [u64; 4],
+}
+
+impl Xoshiro256 {
+    // Seed from the SHA-256 digest of the sequence number and the message checksum
+    fn for_part(seq: u32, checksum: u32) -> Xoshiro256 {
+        let mut seed = [0u8; 8];
+        seed[..4].copy_from_slice(&seq.to_be_bytes());
+        seed[4..].copy_from_slice(&checksum.to_be_bytes());
+        let digest = Sha256::digest(seed);
+        let mut state = [0u64; 4];
+        for (word, bytes) in state.iter_mut().zip(digest.chunks_exact(8)) {
+            *word = u64::from_be_bytes(bytes.try_into().unwrap());
+        }
+        Xoshiro256 { state }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+        result
+    }
+
+    // Uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// Fragment indexes XORed into part `seq` (1-based). The first `fragment_count` parts carry one
+// fragment each in order; later parts mix a random number of fragments, with degree `d`
+// chosen with weight 1/d as in BC-UR.
+fn choose_fragments(seq: u32, fragment_count: usize, checksum: u32) -> Vec<usize> {
+    if seq as usize <= fragment_count {
+        return vec![seq as usize - 1];
+    }
+    let mut rng = Xoshiro256::for_part(seq, checksum);
+    let total: f64 = (1..=fragment_count).map(|d| 1.0 / d as f64).sum();
+    let mut target = rng.next_f64() * total;
+    let mut degree = fragment_count;
+    for d in 1..=fragment_count {
+        target -= 1.0 / d as f64;
+        if target < 0.0 {
+            degree = d;
+            break;
+        }
+    }
+
+    let mut indexes: Vec<usize> = (0..fragment_count).collect();
+    for i in (1..fragment_count).rev() {
+        indexes.swap(i, (rng.next_u64() % (i as u64 + 1)) as usize);
+    }
+    indexes.truncate(degree);
+    indexes.sort_unstable();
+    indexes
+}
+
+fn xor_into(target: &mut [u8], source: &[u8]) {
+    for (t, s) in target.iter_mut().zip(source) {
+        *t ^= s;
+    }
+}
+
+/// Header of one part: `z85f:seq=<n>;k=<fragments>;len=<bytes>;crc32=<hex>:` before its Z85 data
+#[derive(Debug, PartialEq)]
+struct PartHeader {
+    /// 1-based sequence number
+    seq: u32,
+    /// Number of fragments the message was split into
+    fragment_count: usize,
+    /// Length of the message in bytes
+    length: usize,
+    /// CRC-32 of the message
+    checksum: u32,
+}
+
+impl PartHeader {
+    // Parse a part, returning its header and Z85 data with padding info.
+    // Unknown parameters are ignored so later versions can add fields.
+    fn parse(part: &str) -> Result<(PartHeader, &str), Error> {
+        let rest = part.trim().strip_prefix(PART_PREFIX)
+            .ok_or_else(|| Error::new(ErrorCode::BadContainer, format!("Fountain error: expected '{}' prefix", PART_PREFIX)))?;
+        let (header, z85_data) = rest.split_once(':')
+            .ok_or_else(|| Error::new(ErrorCode::BadContainer, "Fountain error: missing ':' before data"))?;
+
+        let (mut seq, mut fragment_count, mut length, mut checksum) = (None, None, None, None);
+        for param in header.split(';') {
+            match param.split_once('=') {
+                Some(("seq", value)) => seq = value.parse::<u32>().ok().filter(|&seq| seq > 0),
+                Some(("k", value)) => fragment_count = value.parse::<usize>().ok().filter(|&k| k > 0),
+                Some(("len", value)) => length = value.parse::<usize>().ok(),
+                Some(("crc32", value)) if value.len() == 8 => checksum = u32::from_str_radix(value, 16).ok(),
+                _ => {}
+            }
+        }
+        let missing = |name: &str| Error::new(ErrorCode::BadContainer, format!("Fountain error: invalid or missing {} in '{}'", name, header));
+        let seq = seq.ok_or_else(|| missing("seq"))?;
+        let length = length.ok_or_else(|| missing("len"))?;
+        // Every fragment holds at least one Z85 group, which bounds `k` by the length
+        let fragment_count = fragment_count.filter(|&k| k <= length.div_ceil(4).max(1))
+            .ok_or_else(|| missing("k"))?;
+        Ok((PartHeader {
+            seq,
+            fragment_count,
+            length,
+            checksum: checksum.ok_or_else(|| missing("crc32"))?,
+        }, z85_data))
+    }
+
+    fn encode(&self, z85_data: &str) -> String {
+        format!(
+            "{}seq={};k={};len={};crc32={:08x}:{}",
+            PART_PREFIX, self.seq, self.fragment_count, self.length, self.checksum, z85_data
+        )
+    }
+
+    // Bytes per fragment: the message length spread evenly, rounded up to whole Z85 groups
+    fn fragment_len(&self) -> usize {
+        self.length.div_ceil(self.fragment_count).div_ceil(4).max(1) * 4
+    }
+}
+
+/// Luby-transform fountain encoder for transfers over lossy one-way channels such as animated
+/// QR codes. `next_part()` never runs out: after one part per fragment it keeps producing
+/// random XOR mixes of fragments, so a receiver that missed some parts just keeps scanning.
+#[wasm_bindgen]
+pub struct FountainEncoder {
+    header: PartHeader,
+    fragments: Vec<Vec<u8>>,
+}
+
+impl FountainEncoder {
+    fn new_internal(data: &[u8], max_fragment_len: usize) -> Result<FountainEncoder, Error> {
+        limits::check_input(data.len())?;
+        let max_fragment_len = max_fragment_len / 4 * 4;
+        if max_fragment_len == 0 {
+            return Err(Error::new(ErrorCode::InvalidOptions, "Fountain error: max_fragment_len must be at least 4"));
+        }
+        let header = PartHeader {
+            seq: 0,
+            fragment_count: data.len().div_ceil(max_fragment_len).max(1),
+            length: data.len(),
+            checksum: crc32(data),
+        };
+        let fragment_len = header.fragment_len();
+        let fragments = (0..header.fragment_count)
+            .map(|index| {
+                let start = (index * fragment_len).min(data.len());
+                let mut fragment = data[start..(start + fragment_len).min(data.len())].to_vec();
+                fragment.resize(fragment_len, 0);
+                fragment
+            })
+            .collect();
+        Ok(FountainEncoder { header, fragments })
+    }
+
+    fn part(&self, seq: u32) -> String {
+        let mut mixed = vec![0u8; self.header.fragment_len()];
+        for index in choose_fragments(seq, self.header.fragment_count, self.header.checksum) {
+            xor_into(&mut mixed, &self.fragments[index]);
+        }
+        PartHeader { seq, ..self.header }.encode(&encode_z85_internal(&mixed))
+    }
+}
+
+#[wasm_bindgen]
+impl FountainEncoder {
+    /// Split `data` into fragments of at most `max_fragment_len` bytes (rounded down to a
+    /// multiple of 4, the Z85 group size)
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[u8], max_fragment_len: usize) -> Result<FountainEncoder, JsValue> {
+        FountainEncoder::new_internal(data, max_fragment_len)
+            .map_err(js_error)
+    }
+
+    /// The next part, `z85f:seq=<n>;k=<fragments>;len=<bytes>;crc32=<hex>:<z85>`
+    pub fn next_part(&mut self) -> String {
+        self.header.seq = self.header.seq.wrapping_add(1).max(1);
+        self.part(self.header.seq)
+    }
+
+    /// Sequence number of the last part returned by `next_part()` (0 before the first)
+    #[wasm_bindgen(getter)]
+    pub fn seq_num(&self) -> u32 {
+        self.header.seq
+    }
+
+    /// Number of fragments; at least this many parts are needed to reconstruct the message
+    #[wasm_bindgen(getter)]
+    pub fn fragment_count(&self) -> usize {
+        self.header.fragment_count
+    }
+}
+
+/// Decoder for the parts of a `FountainEncoder`, received in any order and with any losses.
+/// Feed every scanned part to `receive()` until `is_complete`, then call `result()`.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct FountainDecoder {
+    /// Header of the first part received; every later part must describe the same message
+    message: Option<PartHeader>,
+    /// Recovered fragments by index
+    fragments: Vec<Option<Vec<u8>>>,
+    recovered: usize,
+    /// Mixed parts not yet reduced to a single fragment: remaining indexes and XORed data
+    mixed: Vec<(Vec<usize>, Vec<u8>)>,
+    seen: HashSet<u32>,
+}
+
+impl FountainDecoder {
+    fn receive_internal(&mut self, part: &str) -> Result<f64, Error> {
+        let (header, z85_data) = PartHeader::parse(part)?;
+        limits::check_output(header.length)?;
+        match &self.message {
+            None => {
+                // The fragment count is untrusted: fail rather than abort if it cannot be allocated
+                let mut fragments = Vec::new();
+                fragments.try_reserve_exact(header.fragment_count)
+                    .map_err(|_| Error::new(ErrorCode::BadContainer, format!("Fountain error: cannot allocate {} fragments", header.fragment_count)))?;
+                fragments.resize(header.fragment_count, None);
+                self.fragments = fragments;
+                self.message = Some(PartHeader { seq: 0, ..header });
+            }
+            Some(message) if (message.fragment_count, message.length, message.checksum)
+                != (header.fragment_count, header.length, header.checksum) => {
+                return Err(Error::new(ErrorCode::BadContainer, format!(
+                    "Fountain error: part {} (crc32={:08x}) belongs to a different message",
+                    header.seq, header.checksum
+                )));
+            }
+            Some(_) => {}
+        }
+
+        let fragment_len = header.fragment_len();
+        let data = decode_z85_internal(z85_data)?;
+        if data.len() != fragment_len {
+            return Err(Error::new(ErrorCode::BadContainer, format!("Fountain error: part {} holds {} bytes, expected {}", header.seq, data.len(), fragment_len)));
+        }
+        if !self.is_complete() && self.seen.insert(header.seq) {
+            self.add(choose_fragments(header.seq, header.fragment_count, header.checksum), data);
+        }
+        Ok(self.progress())
+    }
+
+    // Peeling decoder: XOR out every known fragment; a part left with one fragment recovers it,
+    // which in turn may reduce parts received earlier
+    fn add(&mut self, indexes: Vec<usize>, data: Vec<u8>) {
+        let mut queue = vec![(indexes, data)];
+        while let Some((mut indexes, mut data)) = queue.pop() {
+            indexes.retain(|&index| match &self.fragments[index] {
+                Some(fragment) => {
+                    xor_into(&mut data, fragment);
+                    false
+                }
+                None => true,
+            });
+            match indexes[..] {
+                [] => {}
+                [index] => {
+                    self.fragments[index] = Some(data);
+                    self.recovered += 1;
+                    let (reducible, pending) = std::mem::take(&mut self.mixed).into_iter()
+                        .partition(|(indexes, _)| indexes.contains(&index));
+                    self.mixed = pending;
+                    queue.extend::<Vec<_>>(reducible);
+                }
+                _ => self.mixed.push((indexes, data)),
+            }
+        }
+    }
+
+    fn result_internal(&self) -> Result<Vec<u8>, Error> {
+        let message = self.message.as_ref()
+            .filter(|_| self.is_complete())
+            .ok_or_else(|| Error::new(ErrorCode::BadContainer, format!("Fountain error: message incomplete ({} of {} fragments)", self.recovered, self.fragments.len())))?;
+        let mut data: Vec<u8> = self.fragments.iter().flatten().flatten().copied().collect();
+        data.truncate(message.length);
+        if crc32(&data) != message.checksum {
+            return Err(Error::new(ErrorCode::ChecksumMismatch, "Fountain error: checksum mismatch"));
+        }
+        Ok(data)
+    }
+}
+
+#[wasm_bindgen]
+impl FountainDecoder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> FountainDecoder {
+        FountainDecoder::default()
+    }
+
+    /// Add a part from `FountainEncoder.next_part()`, returning the fraction of fragments
+    /// recovered so far (1 once the message can be reconstructed). Repeated parts are ignored.
+    pub fn receive(&mut self, part: &str) -> Result<f64, JsValue> {
+        self.receive_internal(part)
+            .map_err(js_error)
+    }
+
+    /// Fraction of fragments recovered, between 0 and 1
+    #[wasm_bindgen(getter)]
+    pub fn progress(&self) -> f64 {
+        if self.fragments.is_empty() { 0.0 } else { self.recovered as f64 / self.fragments.len() as f64 }
+    }
+
+    /// Whether every fragment has been recovered
+    #[wasm_bindgen(getter)]
+    pub fn is_complete(&self) -> bool {
+        !self.fragments.is_empty() && self.recovered == self.fragments.len()
+    }
+
+    /// Number of distinct parts received
+    #[wasm_bindgen(getter)]
+    pub fn parts_received(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// The reconstructed message. Fails until `is_complete`, or if it does not match the checksum.
+    pub fn result(&self) -> Result<Vec<u8>, JsValue> {
+        self.result_internal()
+            .map_err(js_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message() -> Vec<u8> {
+        (0..1000u32).map(|i| (i * 37 % 253) as u8).collect()
+    }
+
+    #[test]
+    fn test_fountain_lossless() {
+        let data = message();
+        let mut encoder = FountainEncoder::new_internal(&data, 100).unwrap();
+        assert_eq!(encoder.fragment_count(), 10);
+        let first = encoder.next_part();
+        assert!(first.starts_with(&format!("z85f:seq=1;k=10;len=1000;crc32={:08x}:", crc32(&data))));
+
+        let mut decoder = FountainDecoder::new();
+        assert_eq!(decoder.receive_internal(&first).unwrap(), 0.1);
+        for _ in 1..10 {
+            decoder.receive_internal(&encoder.next_part()).unwrap();
+        }
+        assert!(decoder.is_complete());
+        assert_eq!(decoder.result_internal().unwrap(), data);
+    }
+
+    #[test]
+    fn test_fountain_recovers_from_losses() {
+        let data = message();
+        let mut encoder = FountainEncoder::new_internal(&data, 64).unwrap();
+        let mut decoder = FountainDecoder::new();
+        let mut progress = 0.0;
+        // Drop every third part, including a third of the plain fragments
+        while !decoder.is_complete() {
+            let part = encoder.next_part();
+            assert!(encoder.seq_num() < 200, "no reconstruction after 200 parts");
+            if !encoder.seq_num().is_multiple_of(3) {
+                let next = decoder.receive_internal(&part).unwrap();
+                assert!(next >= progress);
+                progress = next;
+            }
+        }
+        assert_eq!(progress, 1.0);
+        assert_eq!(decoder.result_internal().unwrap(), data);
+
+        // Mixed parts alone are enough too
+        let mut decoder = FountainDecoder::new();
+        let mut seq = encoder.fragment_count() as u32;
+        while !decoder.is_complete() {
+            seq += 1;
+            assert!(seq < 300, "no reconstruction from mixed parts");
+            decoder.receive_internal(&encoder.part(seq)).unwrap();
+        }
+        assert_eq!(decoder.result_internal().unwrap(), data);
+    }
+
+    #[test]
+    fn test_fountain_small_messages() {
+        for data in [&b""[..], b"a", b"Hello, World!"] {
+            let mut encoder = FountainEncoder::new_internal(data, 8).unwrap();
+            let mut decoder = FountainDecoder::new();
+            while !decoder.is_complete() {
+                decoder.receive_internal(&encoder.next_part()).unwrap();
+            }
+            assert_eq!(decoder.result_internal().unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_fountain_errors() {
+        assert_eq!(FountainEncoder::new_internal(b"data", 3).err().unwrap().message, "Fountain error: max_fragment_len must be at least 4");
+
+        let mut encoder = FountainEncoder::new_internal(&message(), 100).unwrap();
+        let mut decoder = FountainDecoder::new();
+        assert_eq!(decoder.result_internal().err().unwrap().message, "Fountain error: message incomplete (0 of 0 fragments)");
+        decoder.receive_internal(&encoder.next_part()).unwrap();
+        assert_eq!(decoder.result_internal().err().unwrap().message, "Fountain error: message incomplete (1 of 10 fragments)");
+
+        // Repeats are ignored
+        assert_eq!(decoder.receive_internal(&encoder.part(1)).unwrap(), 0.1);
+        assert_eq!(decoder.parts_received(), 1);
+
+        let mut other = FountainEncoder::new_internal(b"another message", 100).unwrap();
+        assert!(decoder.receive_internal(&other.next_part()).err().unwrap().message.contains("belongs to a different message"));
+        assert_eq!(
+            decoder.receive_internal("z85f:seq=0;k=10;len=1000;crc32=00000000:0:0").err().unwrap().message,
+            "Fountain error: invalid or missing seq in 'seq=0;k=10;len=1000;crc32=00000000'"
+        );
+        assert_eq!(decoder.receive_internal("z85p:part=1/1").err().unwrap().message, "Fountain error: expected 'z85f:' prefix");
+    }
+
+    #[test]
+    fn test_hostile_headers() {
+        // More fragments than the length allows is rejected before anything is allocated
+        let mut decoder = FountainDecoder::new();
+        assert_eq!(
+            decoder.receive_internal("z85f:seq=1;k=99999999999999;len=4;crc32=00000000:0:0").err().unwrap().message,
+            "Fountain error: invalid or missing k in 'seq=1;k=99999999999999;len=4;crc32=00000000'"
+        );
+        assert!(decoder.message.is_none());
+
+        // A consistent but huge header fails cleanly
+        let huge = format!("z85f:seq=1;k={};len={};crc32=00000000:00000:0", usize::MAX / 4, usize::MAX / 4 * 4);
+        assert!(decoder.receive_internal(&huge).err().unwrap().message.starts_with("Fountain error: cannot allocate"));
+    }
+
+    #[test]
+    fn test_fragment_choice_is_deterministic() {
+        assert_eq!(choose_fragments(3, 10, 0x1234), vec![2]);
+        let mixed = choose_fragments(11, 10, 0x1234);
+        assert_eq!(mixed, choose_fragments(11, 10, 0x1234));
+        assert!(!mixed.is_empty() && mixed.windows(2).all(|pair| pair[0] < pair[1]) && mixed.iter().all(|&i| i < 10));
+    }
+}
@@ -0,0 +1,79 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::Clamped;
+use web_sys::ImageData;
+
+use crate::compression::Compression;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{decode_z85_internal, encode_z85_with_options_internal, telemetry, PaddingFormat};
+
+// Check that `len` bytes are exactly one RGBA frame of `width` × `height` pixels
+fn check_frame_size(len: usize, width: u32, height: u32) -> Result<(), Error> {
+    if width == 0 || height == 0 {
+        return Err(Error::new(ErrorCode::InvalidInput, format!("Image size must be non-zero, got {}x{}", width, height)));
+    }
+    let expected = width as u128 * height as u128 * 4;
+    if expected != len as u128 {
+        return Err(Error::new(ErrorCode::InvalidLength, format!("Image data length mismatch: {}x{} RGBA needs {} bytes, got {}", width, height, expected, len)));
+    }
+    Ok(())
+}
+
+// Internal pure Rust function for decoding Z85 text into the pixels of a `width` × `height` frame
+fn decode_frame_internal(z85_text: &str, width: u32, height: u32) -> Result<Vec<u8>, Error> {
+    let pixels = decode_z85_internal(z85_text)?;
+    check_frame_size(pixels.len(), width, height)?;
+    Ok(pixels)
+}
+
+/// Encode the RGBA pixels of an `ImageData` (e.g. from `ctx.getImageData`) to Z85 with padding
+/// info, without an intermediate PNG. The size is not recorded; pass it to
+/// `decode_z85_to_image_data`. With `compression`, the output is a `z85v2:` container, which
+/// suits frames with large flat areas such as whiteboards.
+#[wasm_bindgen]
+pub fn encode_image_data_z85(image_data: &ImageData, compression: Option<Compression>) -> Result<String, JsValue> {
+    let Clamped(pixels) = image_data.data();
+    let compression = compression.unwrap_or(Compression::None);
+    telemetry::instrument("encode_image_data_z85", pixels.len(), String::len, || {
+        Ok(encode_z85_with_options_internal(&pixels, PaddingFormat::Count, compression))
+    })
+        .map_err(js_error)
+}
+
+/// Decode Z85 text from `encode_image_data_z85` into an `ImageData` of `width` × `height`
+/// pixels, ready for `ctx.putImageData`. Fails unless the data is exactly `width * height * 4` bytes.
+#[wasm_bindgen]
+pub fn decode_z85_to_image_data(z85_text: &str, width: u32, height: u32) -> Result<ImageData, JsValue> {
+    let pixels = telemetry::instrument("decode_z85_to_image_data", z85_text.len(), Vec::len, || {
+        decode_frame_internal(z85_text, width, height)
+    })
+        .map_err(js_error)?;
+    // `ImageData` keeps the array it is given, so hand it a JS copy rather than a view of WASM memory
+    ImageData::new_with_js_u8_clamped_array_and_sh(&js_sys::Uint8ClampedArray::from(pixels.as_slice()), width, height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode_z85_internal;
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let frame: Vec<u8> = (0..3 * 2 * 4).map(|i| i as u8 * 10).collect();
+        assert_eq!(decode_frame_internal(&encode_z85_internal(&frame), 3, 2).unwrap(), frame);
+
+        let compressed = encode_z85_with_options_internal(&[255; 64 * 64 * 4], PaddingFormat::Count, Compression::Deflate);
+        assert!(compressed.len() < 1000);
+        assert_eq!(decode_frame_internal(&compressed, 64, 64).unwrap().len(), 64 * 64 * 4);
+    }
+
+    #[test]
+    fn test_frame_size_errors() {
+        let encoded = encode_z85_internal(&[0; 24]);
+        assert_eq!(
+            decode_frame_internal(&encoded, 2, 2).err().unwrap().message,
+            "Image data length mismatch: 2x2 RGBA needs 16 bytes, got 24"
+        );
+        assert_eq!(decode_frame_internal(&encoded, 0, 6).err().unwrap().message, "Image size must be non-zero, got 0x6");
+        assert!(check_frame_size(0, u32::MAX, u32::MAX).err().unwrap().message.contains("needs 73786976260478468100 bytes"));
+    }
+}
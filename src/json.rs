@@ -0,0 +1,62 @@
+use wasm_bindgen::prelude::*;
+
+use crate::compression::Compression;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::text::decode_z85_to_text_internal;
+use crate::{encode_z85_with_options_internal, telemetry, PaddingFormat};
+
+// Message of an exception thrown by `JSON.stringify` or `JSON.parse`
+fn exception_message(exception: &JsValue) -> String {
+    match exception.dyn_ref::<js_sys::Error>() {
+        Some(error) => error.message().into(),
+        None => format!("{:?}", exception),
+    }
+}
+
+// Internal pure Rust function for encoding JSON text to Z85 with padding info, or to a
+// `z85v2:` container unless `compression` is `None`
+fn encode_json_text_internal(json: &str, compression: Compression) -> String {
+    encode_z85_with_options_internal(json.as_bytes(), PaddingFormat::Count, compression)
+}
+
+/// Serialize a JS value with `JSON.stringify` and encode the UTF-8 JSON text to Z85 with padding
+/// info, or into a `z85v2:` container when `compression` is given (worthwhile for larger state).
+/// Throws for values JSON cannot represent: `undefined`, functions, symbols, `BigInt`s and
+/// cyclic objects.
+#[wasm_bindgen]
+pub fn encode_json_z85(value: JsValue, compression: Option<Compression>) -> Result<String, JsValue> {
+    let json = js_sys::JSON::stringify(&value)
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, format!("JSON encode error: {}", exception_message(&e)))))?
+        .as_string()
+        .ok_or_else(|| js_error(Error::new(ErrorCode::InvalidInput, "JSON encode error: value has no JSON representation")))?;
+    let compression = compression.unwrap_or(Compression::None);
+    telemetry::instrument("encode_json_z85", json.len(), String::len, || Ok(encode_json_text_internal(&json, compression)))
+        .map_err(js_error)
+}
+
+/// Decode Z85 data from `encode_json_z85` (with padding info, or a `z85v2:` container) and parse
+/// the JSON text with `JSON.parse`
+#[wasm_bindgen]
+pub fn decode_z85_to_json(z85_data_with_padding: &str) -> Result<JsValue, JsValue> {
+    let json = telemetry::instrument("decode_z85_to_json", z85_data_with_padding.len(), String::len, || {
+        decode_z85_to_text_internal(z85_data_with_padding, false)
+    })
+        .map_err(js_error)?;
+    js_sys::JSON::parse(&json)
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, format!("JSON decode error: {}", exception_message(&e)))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_text_roundtrip() {
+        let json = r#"{"tab":"settings","zoom":1.5,"tags":["a","ü"]}"#;
+        for compression in [Compression::None, Compression::Deflate] {
+            let encoded = encode_json_text_internal(json, compression);
+            assert_eq!(decode_z85_to_text_internal(&encoded, false).unwrap(), json);
+        }
+        assert!(encode_json_text_internal(json, Compression::Deflate).starts_with("z85v2:"));
+    }
+}
@@ -1,16 +1,165 @@
+#[cfg(not(any(feature = "full", feature = "min-size")))]
+compile_error!("enable the `full` feature (on by default) or `min-size`");
+
 use wasm_bindgen::prelude::*;
-use base64::{Engine, engine::general_purpose};
+#[cfg(not(feature = "min-size"))]
+use base64::{engine::general_purpose, DecodeError, DecodeSliceError, Engine};
+#[cfg(not(feature = "min-size"))]
+use serde::Serialize;
+#[cfg(not(feature = "min-size"))]
+use std::borrow::Cow;
+use std::cell::Cell;
+#[cfg(not(feature = "min-size"))]
+use tsify::Tsify;
+#[cfg(not(feature = "min-size"))]
+use z85_core::padding;
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = console)]
-    fn log(s: &str);
+#[cfg(not(feature = "min-size"))]
+use compression::Compression;
+#[cfg(not(feature = "min-size"))]
+use data_url::{DataUrl, DataUrlEncoding};
+#[cfg(not(feature = "min-size"))]
+use encoding::{Base64Variant, Encoding};
+#[cfg(not(feature = "min-size"))]
+use error::{js_error, Error, ErrorCode};
+use logging::LogLevel;
+
+#[cfg(not(feature = "min-size"))]
+mod alphabet;
+#[cfg(not(feature = "min-size"))]
+mod archive;
+#[cfg(not(feature = "min-size"))]
+mod armor;
+#[cfg(not(feature = "min-size"))]
+mod ascii85;
+#[cfg(not(feature = "min-size"))]
+mod base32;
+#[cfg(not(feature = "min-size"))]
+mod base45;
+#[cfg(not(feature = "min-size"))]
+mod base58;
+#[cfg(not(feature = "min-size"))]
+mod base91;
+#[cfg(not(feature = "min-size"))]
+mod batch;
+#[cfg(not(feature = "min-size"))]
+mod bench;
+#[cfg(not(feature = "min-size"))]
+mod bech32;
+#[cfg(not(feature = "min-size"))]
+mod blob;
+#[cfg(not(feature = "min-size"))]
+mod async_ops;
+#[cfg(not(feature = "min-size"))]
+mod cbor;
+#[cfg(not(feature = "min-size"))]
+mod cid;
+#[cfg(not(feature = "min-size"))]
+mod codec;
+#[cfg(not(feature = "min-size"))]
+mod compression;
+#[cfg(not(feature = "min-size"))]
+mod constant_time;
+#[cfg(not(feature = "min-size"))]
+mod container;
+#[cfg(not(feature = "min-size"))]
+mod crypto;
+#[cfg(not(feature = "min-size"))]
+mod curve;
+#[cfg(not(feature = "min-size"))]
+mod data_url;
+#[cfg(not(feature = "min-size"))]
+mod detect;
+#[cfg(not(feature = "min-size"))]
+mod digest;
+#[cfg(not(feature = "min-size"))]
+mod encoding;
+#[cfg(not(feature = "min-size"))]
+mod erasure;
+mod error;
+#[cfg(not(feature = "min-size"))]
+mod fetch;
+#[cfg(not(feature = "min-size"))]
+mod formatting;
+#[cfg(not(feature = "min-size"))]
+mod fountain;
+#[cfg(not(feature = "min-size"))]
+mod image;
+#[cfg(not(feature = "min-size"))]
+mod json;
+#[cfg(not(feature = "min-size"))]
+mod limits;
+mod logging;
+#[cfg(not(feature = "min-size"))]
+mod memory;
+#[cfg(not(feature = "min-size"))]
+mod mime;
+#[cfg(feature = "min-size")]
+mod min_size;
+#[cfg(not(feature = "min-size"))]
+mod multipart;
+#[cfg(all(feature = "node", not(feature = "min-size")))]
+mod node;
+#[cfg(not(feature = "min-size"))]
+mod options;
+mod panic_hook;
+#[cfg(all(feature = "threads", not(feature = "min-size")))]
+mod parallel;
+#[cfg(not(feature = "min-size"))]
+mod pipeline;
+#[cfg(not(feature = "min-size"))]
+mod progress;
+#[cfg(not(feature = "min-size"))]
+mod scheduler;
+#[cfg(not(feature = "min-size"))]
+mod secure;
+#[cfg(not(feature = "min-size"))]
+mod seekable;
+#[cfg(not(feature = "min-size"))]
+mod session;
+#[cfg(not(feature = "min-size"))]
+mod signing;
+#[cfg(all(feature = "simd", not(feature = "min-size"), target_arch = "wasm32", target_feature = "simd128"))]
+mod simd;
+#[cfg(not(feature = "min-size"))]
+pub mod stdio;
+#[cfg(not(feature = "min-size"))]
+mod stream;
+#[cfg(not(feature = "min-size"))]
+mod telemetry;
+#[cfg(not(feature = "min-size"))]
+mod text;
+#[cfg(not(feature = "min-size"))]
+mod try_ops;
+#[cfg(not(feature = "min-size"))]
+mod uuid;
+#[cfg(not(feature = "min-size"))]
+mod validate;
+#[cfg(not(feature = "min-size"))]
+mod version;
+#[cfg(not(feature = "min-size"))]
+mod view;
+
+/// The padding, strict and `z85v2:` container formats as plain Rust, for native code that must
+/// read and write exactly what this module does
+pub use z85_core;
+
+// Current wall-clock time in milliseconds (JS `Date.now()` inside WASM hosted by JavaScript)
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi"), not(feature = "min-size")))]
+pub(crate) fn now_ms() -> f64 {
+    js_sys::Date::now()
 }
 
-macro_rules! console_log {
-    ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
+// Current wall-clock time in milliseconds
+#[cfg(all(any(not(target_arch = "wasm32"), target_os = "wasi"), not(feature = "min-size")))]
+pub(crate) fn now_ms() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64() * 1000.0)
+        .unwrap_or(0.0)
 }
 
+#[cfg(not(feature = "min-size"))]
 /// Data type for conversion
 #[wasm_bindgen]
 #[derive(Clone, Copy, Debug)]
@@ -21,18 +170,49 @@ pub enum DataType {
     DataURL,
 }
 
+#[cfg(not(feature = "min-size"))]
+/// How the Z85 output records the final partial group
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaddingFormat {
+    /// Number of padding bytes (z85data:padding)
+    Count,
+    /// Original byte length (z85data#length)
+    Length,
+}
+
+#[cfg(not(feature = "min-size"))]
+impl From<PaddingFormat> for padding::PaddingFormat {
+    fn from(format: PaddingFormat) -> Self {
+        match format {
+            PaddingFormat::Count => padding::PaddingFormat::Count,
+            PaddingFormat::Length => padding::PaddingFormat::Length,
+        }
+    }
+}
+
+#[cfg(not(feature = "min-size"))]
 /// Conversion options
 #[wasm_bindgen]
 pub struct ConversionOptions {
     input: DataType,
     output: DataType,
+    padding_format: PaddingFormat,
+    compression: Compression,
+    mime_type: Option<String>,
+    data_url_encoding: DataUrlEncoding,
+    base64_variant: Base64Variant,
+    lenient: bool,
+    hrp: Option<String>,
+    with_stats: bool,
 }
 
+#[cfg(not(feature = "min-size"))]
 #[wasm_bindgen]
 impl ConversionOptions {
     #[wasm_bindgen(constructor)]
     pub fn new(input: DataType, output: DataType) -> ConversionOptions {
-        ConversionOptions { input, output }
+        ConversionOptions { input, output, padding_format: PaddingFormat::Count, compression: Compression::None, mime_type: None, data_url_encoding: DataUrlEncoding::Base64, base64_variant: Base64Variant::Standard, lenient: false, hrp: None, with_stats: false }
     }
 
     #[wasm_bindgen(getter)]
@@ -54,268 +234,1141 @@ impl ConversionOptions {
     pub fn set_output(&mut self, output: DataType) {
         self.output = output;
     }
+
+    /// Padding suffix written by `base64_to_z85_with_options` (default `PaddingFormat.Count`)
+    #[wasm_bindgen(getter)]
+    pub fn padding_format(&self) -> PaddingFormat {
+        self.padding_format
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_padding_format(&mut self, padding_format: PaddingFormat) {
+        self.padding_format = padding_format;
+    }
+
+    /// Compression applied by `base64_to_z85_with_options` before encoding (default `Compression.None`).
+    /// Compressed output uses the `z85v2:` container, which records the compression.
+    #[wasm_bindgen(getter)]
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.compression = compression;
+    }
+
+    /// MIME type for Raw → DataURL conversions (default: detected from the payload's magic bytes)
+    #[wasm_bindgen(getter)]
+    pub fn mime_type(&self) -> Option<String> {
+        self.mime_type.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_mime_type(&mut self, mime_type: Option<String>) {
+        self.mime_type = mime_type;
+    }
+
+    /// Payload encoding of data URLs written by `z85_to_base64_with_options`:
+    /// `DataUrlEncoding.Base64` (default) or `DataUrlEncoding.Percent`
+    #[wasm_bindgen(getter)]
+    pub fn data_url_encoding(&self) -> DataUrlEncoding {
+        self.data_url_encoding
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_data_url_encoding(&mut self, data_url_encoding: DataUrlEncoding) {
+        self.data_url_encoding = data_url_encoding;
+    }
+
+    /// Alphabet of raw (non data URL) base64 input and output (default `Base64Variant.Standard`).
+    /// Data URL payloads always use the standard alphabet.
+    #[wasm_bindgen(getter)]
+    pub fn base64_variant(&self) -> Base64Variant {
+        self.base64_variant
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_base64_variant(&mut self, base64_variant: Base64Variant) {
+        self.base64_variant = base64_variant;
+    }
+
+    /// Let `base64_to_z85_with_options` accept base64 with embedded whitespace or line breaks
+    /// (MIME, PEM) and missing `=` padding, and `z85_to_base64_with_options` accept raw Z85 with
+    /// embedded whitespace or line breaks (default `false`). Error offsets then refer to the
+    /// input with whitespace removed.
+    #[wasm_bindgen(getter)]
+    pub fn lenient(&self) -> bool {
+        self.lenient
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Human-readable part of Bech32/Bech32m output written by `transcode` (no default; required
+    /// when transcoding to `Encoding.Bech32` or `Encoding.Bech32m`)
+    #[wasm_bindgen(getter)]
+    pub fn hrp(&self) -> Option<String> {
+        self.hrp.clone()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_hrp(&mut self, hrp: Option<String>) {
+        self.hrp = hrp;
+    }
+
+    /// Make the `*_with_options` conversions return a `ConversionResult` instead of the bare
+    /// output string (default `false`)
+    #[wasm_bindgen(getter)]
+    pub fn with_stats(&self) -> bool {
+        self.with_stats
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_with_stats(&mut self, with_stats: bool) {
+        self.with_stats = with_stats;
+    }
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Output of a `*_with_options` conversion with its statistics, returned when
+/// `ConversionOptions.with_stats` is set
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConversionResult {
+    output: String,
+    input_size: usize,
+    padding: Option<usize>,
+    elapsed_ms: f64,
+    mime_type: Option<String>,
+}
+
+#[cfg(not(feature = "min-size"))]
+impl ConversionResult {
+    // Gather the statistics of a conversion from its input and output, given which side is Z85
+    fn new(input: &str, output: String, z85_is_input: bool, elapsed_ms: f64) -> ConversionResult {
+        let z85_text = if z85_is_input { input } else { &output };
+        let padding = z85_padding(z85_text);
+        // A data URL on either side carries the MIME type, given or detected; prefer the output's
+        let mime_type = [output.as_str(), input].into_iter()
+            .find_map(|text| DataUrl::split(text).ok())
+            .map(|(data_url, _)| data_url.mime_type);
+        ConversionResult { input_size: input.len(), output, padding, elapsed_ms, mime_type }
+    }
+}
+
+#[cfg(not(feature = "min-size"))]
+#[wasm_bindgen]
+impl ConversionResult {
+    /// The converted string, as returned without `with_stats`
+    #[wasm_bindgen(getter)]
+    pub fn output(&self) -> String {
+        self.output.clone()
+    }
+
+    /// Length of the input string in bytes
+    #[wasm_bindgen(getter)]
+    pub fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    /// Length of the output string in bytes
+    #[wasm_bindgen(getter)]
+    pub fn output_size(&self) -> usize {
+        self.output.len()
+    }
+
+    /// Zero bytes padding the final Z85 group on the Z85 side of the conversion
+    #[wasm_bindgen(getter)]
+    pub fn padding(&self) -> Option<usize> {
+        self.padding
+    }
+
+    /// `output_size` over `input_size`, or 0 for empty input
+    #[wasm_bindgen(getter)]
+    pub fn ratio(&self) -> f64 {
+        if self.input_size == 0 {
+            return 0.0;
+        }
+        self.output.len() as f64 / self.input_size as f64
+    }
+
+    /// Wall-clock time spent converting, in milliseconds
+    #[wasm_bindgen(getter)]
+    pub fn elapsed_ms(&self) -> f64 {
+        self.elapsed_ms
+    }
+
+    /// MIME type of the data URL on either side (as given or detected), if there is one
+    #[wasm_bindgen(getter)]
+    pub fn mime_type(&self) -> Option<String> {
+        self.mime_type.clone()
+    }
+}
+
+// Padding of the final group of Z85 text, which may be inside a data URL or a `z85v2:` container
+#[cfg(not(feature = "min-size"))]
+fn z85_padding(z85_text: &str) -> Option<usize> {
+    let z85_text = DataUrl::split(z85_text).map_or(z85_text, |(_, payload)| payload);
+    if z85_text.starts_with(container::V2_PREFIX) {
+        let (header, z85_data) = container::parse_header(z85_text).ok()?;
+        return (z85_data.len() / 5 * 4).checked_sub(header.length);
+    }
+    split_padding_suffix(z85_text).ok().map(|(_, padding)| padding)
+}
+
+// Run a `*_with_options` conversion, returning the output string, or a `ConversionResult` when
+// `options.with_stats` is set
+#[cfg(not(feature = "min-size"))]
+fn convert_with_options(
+    operation: &'static str,
+    data: &str,
+    options: &ConversionOptions,
+    z85_is_input: bool,
+    convert: impl FnOnce() -> Result<String, Error>,
+) -> Result<JsValue, JsValue> {
+    let started = now_ms();
+    let output = telemetry::instrument(operation, data.len(), String::len, convert)
+        .map_err(js_error)?;
+    if !options.with_stats {
+        return Ok(JsValue::from_str(&output));
+    }
+    Ok(ConversionResult::new(data, output, z85_is_input, now_ms() - started).into())
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Window size for the fused Z85/base64 transcoders (a multiple of both 3 and 4)
+const TRANSCODE_WINDOW_BYTES: usize = 3072;
+
+thread_local! {
+    static STRICT_PADDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Reject `:padding` suffixes above 3 when decoding (default `false`). The encoder never writes
+/// more than 3; larger values drop whole groups and are only accepted for compatibility.
+#[wasm_bindgen]
+pub fn set_strict_padding(strict: bool) {
+    STRICT_PADDING.with(|cell| cell.set(strict));
+}
+
+// Split `z85_data:padding` or `z85_data#length` into the Z85 data and the padding count.
+// The padding never exceeds the bytes the data decodes to (nor 3 with strict padding).
+#[cfg(not(feature = "min-size"))]
+pub(crate) fn split_padding_suffix(z85_data_with_padding: &str) -> Result<(&str, usize), Error> {
+    Ok(padding::split_padding_suffix(z85_data_with_padding, STRICT_PADDING.with(Cell::get))?)
+}
+
+// Append the padding suffix for Z85 data decoding to `z85_data.len() / 5 * 4 - padding` bytes
+#[cfg(not(feature = "min-size"))]
+fn push_padding_suffix(z85_data: &mut String, padding: usize, format: PaddingFormat) {
+    padding::push_padding_suffix(z85_data, padding, format.into());
 }
 
 // Internal pure Rust function for Z85 to base64 conversion
-fn z85_to_base64_internal(z85_data_with_padding: &str) -> Result<String, String> {
-    // Parse Z85 data and padding info - split by the LAST colon
-    let last_colon_pos = z85_data_with_padding.rfind(':');
-    if last_colon_pos.is_none() {
-        return Err("Invalid format: expected 'z85_data:padding'".to_string());
+#[cfg(not(feature = "min-size"))]
+fn z85_to_base64_internal(z85_data_with_padding: &str) -> Result<String, Error> {
+    z85_to_base64_with_variant_internal(z85_data_with_padding, Base64Variant::Standard)
+}
+
+// Internal pure Rust function for Z85 to base64 conversion in the given alphabet.
+// Z85 groups are decoded window by window straight into the base64 encoder, so no buffer
+// holding the whole decoded payload is ever allocated.
+#[cfg(not(feature = "min-size"))]
+fn z85_to_base64_with_variant_internal(z85_data_with_padding: &str, variant: Base64Variant) -> Result<String, Error> {
+    let mut base64_data = String::new();
+    z85_to_base64_into(z85_data_with_padding, variant, &mut base64_data)?;
+    Ok(base64_data)
+}
+
+// Append the base64 conversion of Z85 data with padding info to `base64_data`
+#[cfg(not(feature = "min-size"))]
+pub(crate) fn z85_to_base64_into(z85_data_with_padding: &str, variant: Base64Variant, base64_data: &mut String) -> Result<(), Error> {
+    let engine = variant.engine();
+    if z85_data_with_padding.starts_with(container::V2_PREFIX) {
+        let decoded_data = container::decode_z85_v2_internal(z85_data_with_padding)?;
+        engine.encode_string(decoded_data, base64_data);
+        return Ok(());
     }
     
-    let colon_pos = last_colon_pos.unwrap();
-    let z85_data = &z85_data_with_padding[..colon_pos];
-    let padding_str = &z85_data_with_padding[colon_pos + 1..];
-    
-    let padding: usize = padding_str.parse()
-        .map_err(|_| "Invalid padding number".to_string())?;
-    
-    // Decode Z85 data
-    let decoded_data = z85::decode(z85_data)
-        .map_err(|e| format!("Z85 decode error: {}", e))?;
+    let (z85_data, padding) = split_padding_suffix(z85_data_with_padding)?;
+    let z85_bytes = z85_data.as_bytes();
+    if !z85_bytes.len().is_multiple_of(5) {
+        return Err(z85_core::Error::InvalidDataLength { alphabet: "Z85", length: z85_bytes.len() }.into());
+    }
     
     // Remove padding
-    let original_length = decoded_data.len() - padding;
-    let trimmed_data = &decoded_data[..original_length];
+    let original_length = (z85_bytes.len() / 5 * 4).checked_sub(padding)
+        .ok_or(z85_core::Error::InvalidPadding)?;
     
-    // Encode to base64
-    let base64_data = general_purpose::STANDARD.encode(trimmed_data);
+    // Decode Z85 data and encode to base64, one window at a time
+    let window_chars = TRANSCODE_WINDOW_BYTES / 4 * 5;
+    let mut window = [0u8; TRANSCODE_WINDOW_BYTES];
+    base64_data.reserve(original_length.div_ceil(3) * 4);
+    let mut produced = 0;
+    for (index, chunk) in z85_bytes.chunks(window_chars).enumerate() {
+        let decoded = &mut window[..chunk.len() / 5 * 4];
+        codec::decode_groups(chunk, decoded, index * window_chars)?;
+        let take = decoded.len().min(original_length - produced);
+        engine.encode_string(&decoded[..take], base64_data);
+        produced += take;
+    }
     
-    Ok(base64_data)
+    Ok(())
 }
 
-/// Convert Z85 encoded data with padding info to base64
+#[cfg(not(feature = "min-size"))]
+/// Convert Z85 encoded data with padding info to base64.
+/// `on_progress(bytes_processed, total_bytes)` is called periodically when given.
+/// `variant` selects the output alphabet and padding (default `Base64Variant.Standard`).
 #[wasm_bindgen]
-pub fn z85_to_base64(z85_data_with_padding: &str) -> Result<String, JsValue> {
-    z85_to_base64_internal(z85_data_with_padding)
-        .map_err(|e| JsValue::from_str(&e))
+pub fn z85_to_base64(z85_data_with_padding: &str, on_progress: Option<progress::ProgressCallback>, variant: Option<Base64Variant>) -> Result<String, JsValue> {
+    let variant = variant.unwrap_or(Base64Variant::Standard);
+    telemetry::instrument("z85_to_base64", z85_data_with_padding.len(), String::len, || match &on_progress {
+        Some(callback) => progress::z85_to_base64_with_progress_internal(z85_data_with_padding, variant, &mut progress::js_progress_reporter(callback)),
+        None => z85_to_base64_with_variant_internal(z85_data_with_padding, variant),
+    })
+        .map_err(js_error)
 }
 
 // Internal pure Rust function for Z85 to base64 conversion with options
-fn z85_to_base64_with_options_internal(data: &str, input_type: DataType, output_type: DataType) -> Result<String, String> {
-    match (input_type, output_type) {
+#[cfg(not(feature = "min-size"))]
+fn z85_to_base64_with_options_internal(data: &str, options: &ConversionOptions) -> Result<String, Error> {
+    // Lenient raw Z85 may be wrapped across lines
+    let lenient_data = match options.input {
+        DataType::Raw if options.lenient => strip_z85_formatting(data, ""),
+        _ => Cow::Borrowed(data),
+    };
+    let data: &str = &lenient_data;
+    match (options.input, options.output) {
         (DataType::Raw, DataType::Raw) => {
             // Use existing logic
-            z85_to_base64_internal(data)
+            z85_to_base64_with_variant_internal(data, options.base64_variant)
         }
         (DataType::DataURL, DataType::DataURL) => {
-            // Parse data URL
-            if !data.starts_with("data:") {
-                return Err("Invalid data URL format".to_string());
+            // Parse data URL, keeping the MIME type and its parameters
+            let (mut data_url, z85_data) = DataUrl::split(data)?;
+            if data_url.encoding != DataUrlEncoding::Z85 {
+                return Err(Error::new(ErrorCode::BadDataUrl, "Data URL does not contain ;z85, marker"));
             }
             
-            // Find ;z85,
-            if let Some(z85_pos) = data.find(";z85,") {
-                let mime_type = &data[5..z85_pos];
-                let z85_data = &data[z85_pos + 5..];
-                
-                // Convert Z85 to base64
-                let base64_data = z85_to_base64_internal(z85_data)?;
-                
-                // Reconstruct data URL with base64
-                Ok(format!("data:{};base64,{}", mime_type, base64_data))
-            } else {
-                Err("Data URL does not contain ;z85, marker".to_string())
-            }
+            // Convert Z85 to base64 (or percent-encoding) and reconstruct the data URL
+            data_url.payload = match options.data_url_encoding {
+                DataUrlEncoding::Base64 => z85_to_base64_internal(z85_data)?,
+                DataUrlEncoding::Percent => data_url::percent_encode(&decode_z85_internal(z85_data)?),
+                DataUrlEncoding::Z85 => return Err(Error::new(ErrorCode::InvalidOptions, "Data URL output encoding must be Base64 or Percent")),
+            };
+            data_url.encoding = options.data_url_encoding;
+            Ok(data_url.to_string())
         }
         (DataType::DataURL, DataType::Raw) => {
             // Extract Z85 data from data URL and convert to raw base64
-            if !data.starts_with("data:") {
-                return Err("Invalid data URL format".to_string());
-            }
-            
-            if let Some(z85_pos) = data.find(";z85,") {
-                let z85_data = &data[z85_pos + 5..];
-                z85_to_base64_internal(z85_data)
-            } else {
-                Err("Data URL does not contain ;z85, marker".to_string())
+            let (data_url, z85_data) = DataUrl::split(data)?;
+            if data_url.encoding != DataUrlEncoding::Z85 {
+                return Err(Error::new(ErrorCode::BadDataUrl, "Data URL does not contain ;z85, marker"));
             }
+            z85_to_base64_with_variant_internal(z85_data, options.base64_variant)
         }
         (DataType::Raw, DataType::DataURL) => {
-            Err("Cannot convert raw to data URL: MIME type unknown".to_string())
+            // Use the given MIME type, or detect it from the decoded payload
+            let mime_type = options.mime_type.as_deref().map(mime::validate_mime_type).transpose()
+                .map_err(|e| Error { code: ErrorCode::InvalidOptions, ..e })?;
+            match options.data_url_encoding {
+                DataUrlEncoding::Base64 => {
+                    let base64_data = z85_to_base64_internal(data)?;
+                    let mime_type = mime_type.unwrap_or_else(|| mime::sniff_base64_mime_type(&base64_data, Base64Variant::Standard));
+                    Ok(format!("data:{};base64,{}", mime_type, base64_data))
+                }
+                DataUrlEncoding::Percent => {
+                    let decoded_data = decode_z85_internal(data)?;
+                    let mime_type = mime_type.unwrap_or_else(|| mime::sniff_mime_type(&decoded_data));
+                    Ok(format!("data:{},{}", mime_type, data_url::percent_encode(&decoded_data)))
+                }
+                DataUrlEncoding::Z85 => Err(Error::new(ErrorCode::InvalidOptions, "Data URL output encoding must be Base64 or Percent")),
+            }
         }
     }
 }
 
-/// Convert Z85 encoded data to base64 with options
-#[wasm_bindgen]
-pub fn z85_to_base64_with_options(data: &str, options: Option<ConversionOptions>) -> Result<String, JsValue> {
+#[cfg(not(feature = "min-size"))]
+/// Convert Z85 encoded data to base64 with options. Returns a `ConversionResult` instead of the
+/// string when `options.with_stats` is set.
+#[wasm_bindgen(unchecked_return_type = "string | ConversionResult")]
+pub fn z85_to_base64_with_options(data: &str, options: Option<ConversionOptions>) -> Result<JsValue, JsValue> {
     let opts = options.unwrap_or(ConversionOptions::new(DataType::Raw, DataType::Raw));
-    z85_to_base64_with_options_internal(data, opts.input, opts.output)
-        .map_err(|e| JsValue::from_str(&e))
+    convert_with_options("z85_to_base64_with_options", data, &opts, true, || {
+        z85_to_base64_with_options_internal(data, &opts)
+    })
 }
 
-// Internal pure Rust function for base64 to Z85 conversion
-fn base64_to_z85_internal(base64_data: &str) -> Result<String, String> {
-    // Decode base64 data
-    let decoded_data = general_purpose::STANDARD.decode(base64_data)
-        .map_err(|e| format!("Base64 decode error: {}", e))?;
-    
-    // Calculate padding needed (Z85 requires length divisible by 4)
-    let padding_needed = (4 - (decoded_data.len() % 4)) % 4;
-    let mut padded_data = decoded_data.clone();
-    
-    // Add padding bytes
-    for _ in 0..padding_needed {
-        padded_data.push(0);
+// Format a windowed base64 decode error with offsets relative to the whole input
+#[cfg(not(feature = "min-size"))]
+fn base64_decode_error(error: DecodeSliceError, base_offset: usize) -> Error {
+    match error {
+        DecodeSliceError::DecodeError(DecodeError::InvalidByte(offset, byte)) => DecodeError::InvalidByte(base_offset + offset, byte).into(),
+        DecodeSliceError::DecodeError(DecodeError::InvalidLastSymbol(offset, byte)) => {
+            DecodeError::InvalidLastSymbol(base_offset + offset, byte).into()
+        }
+        DecodeSliceError::DecodeError(e) => e.into(),
+        DecodeSliceError::OutputSliceTooSmall => Error::new(ErrorCode::BufferTooSmall, "Base64 decode error: output window too small"),
     }
+}
+
+// Internal pure Rust function for base64 to Z85 conversion.
+// Base64 is decoded window by window straight into the Z85 encoder, so no buffer
+// holding the whole decoded payload is ever allocated.
+#[cfg(not(feature = "min-size"))]
+fn base64_to_z85_internal(base64_data: &str, variant: Base64Variant) -> Result<String, Error> {
+    base64_to_z85_with_format_internal(base64_data, PaddingFormat::Count, variant)
+}
+
+// Internal pure Rust function for base64 to Z85 conversion with the given alphabet and padding suffix
+#[cfg(not(feature = "min-size"))]
+fn base64_to_z85_with_format_internal(base64_data: &str, padding_format: PaddingFormat, variant: Base64Variant) -> Result<String, Error> {
+    let mut z85_data = String::new();
+    base64_to_z85_into(base64_data, padding_format, variant, &mut z85_data)?;
+    Ok(z85_data)
+}
+
+// Append the Z85 conversion (with the given padding suffix) of base64 data to `z85_data`
+#[cfg(not(feature = "min-size"))]
+pub(crate) fn base64_to_z85_into(base64_data: &str, padding_format: PaddingFormat, variant: Base64Variant, z85_data: &mut String) -> Result<(), Error> {
+    let input = base64_data.as_bytes();
+    let window_chars = TRANSCODE_WINDOW_BYTES / 3 * 4;
+    let mut window = [0u8; TRANSCODE_WINDOW_BYTES];
+    z85_data.reserve(input.len() / 4 * 3 / 4 * 5 + 7);
+    let mut padding_needed = 0;
     
-    // Encode to Z85
-    let z85_data = z85::encode(&padded_data);
+    for (index, chunk) in input.chunks(window_chars).enumerate() {
+        // Decode base64 data
+        let decoded_len = variant.engine().decode_slice(chunk, &mut window)
+            .map_err(|e| base64_decode_error(e, index * window_chars))?;
+        
+        // Encode whole words to Z85
+        let aligned = decoded_len / 4 * 4;
+        codec::encode_append(&window[..aligned], z85_data);
+        
+        // Only the final window can end in a partial word: pad it (Z85 requires length divisible by 4)
+        padding_needed = codec::encode_padded_tail(&window[aligned..decoded_len], z85_data);
+    }
     
-    // Return with padding info
-    Ok(format!("{}:{}", z85_data, padding_needed))
+    // Finish with padding info
+    push_padding_suffix(z85_data, padding_needed, padding_format);
+    Ok(())
+}
+
+// Internal pure Rust function for base64 to Z85 conversion, compressing the payload into a
+// `z85v2:` container unless `compression` is `None`
+#[cfg(not(feature = "min-size"))]
+fn base64_to_z85_compressed_internal(base64_data: &str, padding_format: PaddingFormat, compression: Compression, variant: Base64Variant) -> Result<String, Error> {
+    if compression == Compression::None {
+        return base64_to_z85_with_format_internal(base64_data, padding_format, variant);
+    }
+    let decoded_data = variant.engine().decode(base64_data)?;
+    Ok(encode_z85_with_options_internal(&decoded_data, padding_format, compression))
+}
+
+// Internal pure Rust function for encoding bytes to Z85 with the given padding suffix,
+// compressing them into a `z85v2:` container unless `compression` is `None`
+#[cfg(not(feature = "min-size"))]
+fn encode_z85_with_options_internal(data: &[u8], padding_format: PaddingFormat, compression: Compression) -> String {
+    if compression != Compression::None {
+        return container::encode_container_internal(data, true, compression);
+    }
+    let mut z85_data = String::new();
+    encode_z85_into(data, padding_format, &mut z85_data);
+    z85_data
+}
+
+// Append the Z85 encoding of `data` with the given padding suffix to `z85_data`
+#[cfg(not(feature = "min-size"))]
+pub(crate) fn encode_z85_into(data: &[u8], padding_format: PaddingFormat, z85_data: &mut String) {
+    let aligned = data.len() / 4 * 4;
+    z85_data.reserve(data.len().div_ceil(4) * 5 + 7);
+    codec::encode_append(&data[..aligned], z85_data);
+    let padding_needed = codec::encode_padded_tail(&data[aligned..], z85_data);
+    push_padding_suffix(z85_data, padding_needed, padding_format);
+}
+
+// Convert the payload of a base64 or percent-encoded data URL to Z85
+#[cfg(not(feature = "min-size"))]
+fn data_url_payload_to_z85(encoding: DataUrlEncoding, payload: &str, padding_format: PaddingFormat, compression: Compression) -> Result<String, Error> {
+    match encoding {
+        DataUrlEncoding::Base64 => base64_to_z85_compressed_internal(payload, padding_format, compression, Base64Variant::Standard),
+        DataUrlEncoding::Percent => {
+            let decoded_data = data_url::percent_decode(payload);
+            Ok(encode_z85_with_options_internal(&decoded_data, padding_format, compression))
+        }
+        DataUrlEncoding::Z85 => Err(Error::new(ErrorCode::BadDataUrl, "Data URL does not contain ;base64, marker")),
+    }
 }
 
-/// Convert base64 data to Z85 with padding info
+#[cfg(not(feature = "min-size"))]
+/// Convert base64 data to Z85 with padding info.
+/// `on_progress(bytes_processed, total_bytes)` is called periodically when given.
+/// `variant` selects the input alphabet (default `Base64Variant.Standard`); the URL-safe
+/// variants accept input with or without `=` padding.
 #[wasm_bindgen]
-pub fn base64_to_z85(base64_data: &str) -> Result<String, JsValue> {
-    base64_to_z85_internal(base64_data)
-        .map_err(|e| JsValue::from_str(&e))
+pub fn base64_to_z85(base64_data: &str, on_progress: Option<progress::ProgressCallback>, variant: Option<Base64Variant>) -> Result<String, JsValue> {
+    let variant = variant.unwrap_or(Base64Variant::Standard);
+    telemetry::instrument("base64_to_z85", base64_data.len(), String::len, || match &on_progress {
+        Some(callback) => progress::base64_to_z85_with_progress_internal(base64_data, variant, &mut progress::js_progress_reporter(callback)),
+        None => base64_to_z85_internal(base64_data, variant),
+    })
+        .map_err(js_error)
 }
 
 // Internal pure Rust function for base64 to Z85 conversion with options
-fn base64_to_z85_with_options_internal(data: &str, input_type: DataType, output_type: DataType) -> Result<String, String> {
-    match (input_type, output_type) {
+#[cfg(not(feature = "min-size"))]
+fn base64_to_z85_with_options_internal(data: &str, options: &ConversionOptions) -> Result<String, Error> {
+    let ConversionOptions { padding_format, compression, base64_variant, .. } = *options;
+    
+    // Lenient parsing strips whitespace and restores missing padding of base64 input
+    let base64_input = |base64_data| if options.lenient { encoding::normalize_base64(base64_data) } else { Cow::Borrowed(base64_data) };
+    match (options.input, options.output) {
         (DataType::Raw, DataType::Raw) => {
             // Use existing logic
-            base64_to_z85_internal(data)
+            base64_to_z85_compressed_internal(&base64_input(data), padding_format, compression, base64_variant)
         }
         (DataType::DataURL, DataType::DataURL) => {
-            // Parse data URL
-            if !data.starts_with("data:") {
-                return Err("Invalid data URL format".to_string());
-            }
+            // Parse data URL, keeping the MIME type and its parameters
+            let (mut data_url, payload) = DataUrl::split(data)?;
+            let payload = if data_url.encoding == DataUrlEncoding::Base64 { base64_input(payload) } else { Cow::Borrowed(payload) };
             
-            // Find ;base64,
-            if let Some(base64_pos) = data.find(";base64,") {
-                let mime_type = &data[5..base64_pos];
-                let base64_data = &data[base64_pos + 8..];
-                
-                // Convert base64 to Z85
-                let z85_data = base64_to_z85_internal(base64_data)?;
-                
-                // Reconstruct data URL with z85
-                Ok(format!("data:{};z85,{}", mime_type, z85_data))
-            } else {
-                Err("Data URL does not contain ;base64, marker".to_string())
-            }
+            // Convert the base64 (or percent-encoded) payload to Z85 and reconstruct the data URL
+            data_url.payload = data_url_payload_to_z85(data_url.encoding, &payload, padding_format, compression)?;
+            data_url.encoding = DataUrlEncoding::Z85;
+            Ok(data_url.to_string())
         }
         (DataType::DataURL, DataType::Raw) => {
-            // Extract base64 data from data URL and convert to raw Z85
-            if !data.starts_with("data:") {
-                return Err("Invalid data URL format".to_string());
-            }
-            
-            if let Some(base64_pos) = data.find(";base64,") {
-                let base64_data = &data[base64_pos + 8..];
-                base64_to_z85_internal(base64_data)
-            } else {
-                Err("Data URL does not contain ;base64, marker".to_string())
-            }
+            // Extract the payload from data URL and convert to raw Z85
+            let (data_url, payload) = DataUrl::split(data)?;
+            let payload = if data_url.encoding == DataUrlEncoding::Base64 { base64_input(payload) } else { Cow::Borrowed(payload) };
+            data_url_payload_to_z85(data_url.encoding, &payload, padding_format, compression)
         }
         (DataType::Raw, DataType::DataURL) => {
-            Err("Cannot convert raw to data URL: MIME type unknown".to_string())
+            let data = base64_input(data);
+            
+            // Use the given MIME type, or detect it from the leading bytes of the payload
+            let mime_type = match options.mime_type.as_deref() {
+                Some(mime_type) => mime::validate_mime_type(mime_type).map_err(|e| Error { code: ErrorCode::InvalidOptions, ..e })?,
+                None => mime::sniff_base64_mime_type(&data, base64_variant),
+            };
+            let z85_data = base64_to_z85_compressed_internal(&data, padding_format, compression, base64_variant)?;
+            Ok(format!("data:{};z85,{}", mime_type, z85_data))
         }
     }
 }
 
-/// Convert base64 data to Z85 with options
-#[wasm_bindgen]
-pub fn base64_to_z85_with_options(data: &str, options: Option<ConversionOptions>) -> Result<String, JsValue> {
+#[cfg(not(feature = "min-size"))]
+/// Convert base64 data to Z85 with options. Returns a `ConversionResult` instead of the string
+/// when `options.with_stats` is set.
+#[wasm_bindgen(unchecked_return_type = "string | ConversionResult")]
+pub fn base64_to_z85_with_options(data: &str, options: Option<ConversionOptions>) -> Result<JsValue, JsValue> {
     let opts = options.unwrap_or(ConversionOptions::new(DataType::Raw, DataType::Raw));
-    base64_to_z85_with_options_internal(data, opts.input, opts.output)
-        .map_err(|e| JsValue::from_str(&e))
+    convert_with_options("base64_to_z85_with_options", data, &opts, false, || {
+        base64_to_z85_with_options_internal(data, &opts)
+    })
 }
 
-// Internal pure Rust function for encoding bytes to Z85
+// Internal pure Rust function for encoding bytes to Z85.
+// Whole words are encoded in place; only the final partial word is copied and padded.
+#[cfg(not(feature = "min-size"))]
 fn encode_z85_internal(data: &[u8]) -> String {
-    // Calculate padding needed (Z85 requires length divisible by 4)
-    let padding_needed = (4 - (data.len() % 4)) % 4;
-    let mut padded_data = data.to_vec();
-    
-    // Add padding bytes
-    for _ in 0..padding_needed {
-        padded_data.push(0);
-    }
+    let aligned = data.len() / 4 * 4;
+    let mut z85_data = String::with_capacity(data.len().div_ceil(4) * 5 + 2);
     
     // Encode to Z85
-    let z85_data = z85::encode(&padded_data);
+    codec::encode_append(&data[..aligned], &mut z85_data);
+    
+    // Add padding bytes (Z85 requires length divisible by 4)
+    let padding_needed = codec::encode_padded_tail(&data[aligned..], &mut z85_data);
     
     // Return with padding info
-    format!("{}:{}", z85_data, padding_needed)
+    z85_data.push_str(&format!(":{}", padding_needed));
+    z85_data
 }
 
-/// Encode raw bytes to Z85 with padding info
+#[cfg(not(feature = "min-size"))]
+/// Encode raw bytes to Z85 with padding info.
+/// `on_progress(bytes_processed, total_bytes)` is called periodically when given.
+/// With `compression` other than `Compression.None`, the payload is compressed first and the
+/// output is a `z85v2:` container recording the compression (decoded transparently by `decode_z85`).
 #[wasm_bindgen]
-pub fn encode_z85(data: &[u8]) -> Result<String, JsValue> {
-    Ok(encode_z85_internal(data))
+pub fn encode_z85(data: &[u8], on_progress: Option<progress::ProgressCallback>, compression: Option<Compression>) -> Result<String, JsValue> {
+    let compression = compression.unwrap_or(Compression::None);
+    telemetry::instrument("encode_z85", data.len(), String::len, || match (&on_progress, compression) {
+        (Some(callback), Compression::None) => Ok(progress::encode_z85_with_progress_internal(data, &mut progress::js_progress_reporter(callback))),
+        (None, Compression::None) => Ok(encode_z85_internal(data)),
+        (_, compression) => {
+            let encoded = container::encode_container_internal(data, true, compression);
+            if let Some(callback) = &on_progress {
+                progress::js_progress_reporter(callback)(data.len(), data.len());
+            }
+            Ok(encoded)
+        }
+    })
+        .map_err(js_error)
 }
 
 // Internal pure Rust function for decoding Z85 to bytes
-fn decode_z85_internal(z85_data_with_padding: &str) -> Result<Vec<u8>, String> {
-    // Parse Z85 data and padding info - split by the LAST colon
-    let last_colon_pos = z85_data_with_padding.rfind(':');
-    if last_colon_pos.is_none() {
-        return Err("Invalid format: expected 'z85_data:padding'".to_string());
+#[cfg(not(feature = "min-size"))]
+fn decode_z85_internal(z85_data_with_padding: &str) -> Result<Vec<u8>, Error> {
+    if z85_data_with_padding.starts_with(container::V2_PREFIX) {
+        return container::decode_z85_v2_internal(z85_data_with_padding);
     }
     
-    let colon_pos = last_colon_pos.unwrap();
-    let z85_data = &z85_data_with_padding[..colon_pos];
-    let padding_str = &z85_data_with_padding[colon_pos + 1..];
+    let (z85_data, padding) = split_padding_suffix(z85_data_with_padding)?;
     
-    let padding: usize = padding_str.parse()
-        .map_err(|_| "Invalid padding number".to_string())?;
+    // Decode Z85 data through the lookup-table decoder, dropping padding
+    let mut decoded_data = vec![0u8; (z85_data.len() / 5 * 4).saturating_sub(padding)];
+    let written = codec::decode_into(z85_data.as_bytes(), padding, &mut decoded_data)?;
+    decoded_data.truncate(written);
     
-    // Decode Z85 data
-    let mut decoded_data = z85::decode(z85_data)
-        .map_err(|e| format!("Z85 decode error: {}", e))?;
+    Ok(decoded_data)
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Decode Z85 data with padding info to raw bytes.
+/// `on_progress(bytes_processed, total_bytes)` is called periodically when given.
+#[wasm_bindgen]
+pub fn decode_z85(z85_data_with_padding: &str, on_progress: Option<progress::ProgressCallback>) -> Result<Vec<u8>, JsValue> {
+    telemetry::instrument("decode_z85", z85_data_with_padding.len(), Vec::len, || match &on_progress {
+        Some(callback) => progress::decode_z85_with_progress_internal(z85_data_with_padding, &mut progress::js_progress_reporter(callback)),
+        None => decode_z85_internal(z85_data_with_padding),
+    })
+        .map_err(js_error)
+}
+
+#[cfg(not(feature = "min-size"))]
+/// `encode_z85` returning the ASCII bytes of the Z85 text instead of a string, for writing to
+/// files or sockets without a UTF-16 JS string of the whole payload in between
+#[wasm_bindgen]
+pub fn encode_z85_bytes(data: &[u8], compression: Option<Compression>) -> Result<Vec<u8>, JsValue> {
+    let compression = compression.unwrap_or(Compression::None);
+    telemetry::instrument("encode_z85_bytes", data.len(), Vec::len, || {
+        Ok(encode_z85_with_options_internal(data, PaddingFormat::Count, compression).into_bytes())
+    })
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for decoding Z85 given as ASCII bytes
+#[cfg(not(feature = "min-size"))]
+fn decode_z85_from_bytes_internal(ascii: &[u8]) -> Result<Vec<u8>, Error> {
+    match std::str::from_utf8(ascii) {
+        Ok(text) if text.is_ascii() => decode_z85_internal(text),
+        _ => {
+            let offset = ascii.iter().position(|byte| !byte.is_ascii()).unwrap_or_default();
+            Err(Error::new(ErrorCode::InvalidChar, format!("Z85 decode error: non-ASCII byte at offset {}", offset)).at(offset))
+        }
+    }
+}
+
+#[cfg(not(feature = "min-size"))]
+/// `decode_z85` for Z85 text given as ASCII bytes (e.g. read from a file or socket), skipping
+/// the JS string
+#[wasm_bindgen]
+pub fn decode_z85_from_bytes(ascii: &[u8]) -> Result<Vec<u8>, JsValue> {
+    telemetry::instrument("decode_z85_from_bytes", ascii.len(), Vec::len, || decode_z85_from_bytes_internal(ascii))
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for computing the decoded size of Z85 data with padding info
+#[cfg(not(feature = "min-size"))]
+pub(crate) fn decoded_size_hint_internal(z85_data_with_padding: &str) -> Result<usize, Error> {
+    if z85_data_with_padding.starts_with(container::V2_PREFIX) {
+        // The header gives the size of plain payloads; compressed ones must be inflated to know it
+        let (header, _) = container::parse_header(z85_data_with_padding)?;
+        if header.compression == z85_core::Compression::None && header.encryption.is_none() {
+            return Ok(header.length);
+        }
+        return container::decode_z85_v2_internal(z85_data_with_padding).map(|data| data.len());
+    }
+    let (z85_data, padding) = split_padding_suffix(z85_data_with_padding)?;
+    Ok((z85_data.len() / 5 * 4).checked_sub(padding)
+        .ok_or(z85_core::Error::InvalidPadding)?)
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Get the exact number of bytes `decode_z85_into` will write for the given Z85 data with padding
+/// info or `z85v2:` container (which, when compressed, is decompressed to find out)
+#[wasm_bindgen]
+pub fn decoded_size_hint(z85_data_with_padding: &str) -> Result<usize, JsValue> {
+    decoded_size_hint_internal(z85_data_with_padding)
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for decoding Z85 into a caller-provided buffer
+#[cfg(not(feature = "min-size"))]
+pub(crate) fn decode_z85_into_internal(z85_data_with_padding: &str, out: &mut [u8]) -> Result<usize, Error> {
+    if z85_data_with_padding.starts_with(container::V2_PREFIX) {
+        let decoded_data = container::decode_z85_v2_internal(z85_data_with_padding)?;
+        if out.len() < decoded_data.len() {
+            return Err(z85_core::Error::OutputTooSmall { needed: decoded_data.len(), actual: out.len() }.into());
+        }
+        out[..decoded_data.len()].copy_from_slice(&decoded_data);
+        return Ok(decoded_data.len());
+    }
+    let (z85_data, padding) = split_padding_suffix(z85_data_with_padding)?;
+    codec::decode_into(z85_data.as_bytes(), padding, out)
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Decode Z85 data with padding info (or a `z85v2:` container) into a preallocated buffer,
+/// returning the number of bytes written. Use `decoded_size_hint` to size the buffer.
+/// Containers are decoded in a temporary buffer first, then copied.
+#[wasm_bindgen]
+pub fn decode_z85_into(z85_data_with_padding: &str, out: &mut [u8]) -> Result<usize, JsValue> {
+    telemetry::instrument("decode_z85_into", z85_data_with_padding.len(), |written: &usize| *written, || {
+        decode_z85_into_internal(z85_data_with_padding, out)
+    })
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for ZMQ RFC 32 encoding (no padding suffix)
+#[cfg(not(feature = "min-size"))]
+fn encode_z85_strict_internal(data: &[u8]) -> Result<String, Error> {
+    if !data.len().is_multiple_of(4) {
+        return Err(z85_core::Error::StrictLength { length: data.len(), multiple: 4 }.into());
+    }
     
-    // Remove padding
-    if padding > 0 {
-        decoded_data.truncate(decoded_data.len() - padding);
+    let mut z85_data = String::with_capacity(data.len() / 4 * 5);
+    codec::encode_append(data, &mut z85_data);
+    Ok(z85_data)
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Encode raw bytes to Z85 exactly as specified by ZeroMQ RFC 32 (compatible with `zmq_z85_encode`).
+/// The input length must be a multiple of 4; no `:padding` suffix is appended.
+#[wasm_bindgen]
+pub fn encode_z85_strict(data: &[u8]) -> Result<String, JsValue> {
+    telemetry::instrument("encode_z85_strict", data.len(), String::len, || encode_z85_strict_internal(data))
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for ZMQ RFC 32 decoding (no padding suffix)
+#[cfg(not(feature = "min-size"))]
+fn decode_z85_strict_internal(z85_data: &str) -> Result<Vec<u8>, Error> {
+    if !z85_data.len().is_multiple_of(5) {
+        return Err(z85_core::Error::StrictLength { length: z85_data.len(), multiple: 5 }.into());
     }
     
+    let mut decoded_data = vec![0u8; z85_data.len() / 5 * 4];
+    codec::decode_groups(z85_data.as_bytes(), &mut decoded_data, 0)?;
     Ok(decoded_data)
 }
 
-/// Decode Z85 data with padding info to raw bytes
+#[cfg(not(feature = "min-size"))]
+/// Decode Z85 text exactly as specified by ZeroMQ RFC 32 (compatible with `zmq_z85_decode`).
+/// The input length must be a multiple of 5 and must not carry a `:padding` suffix.
 #[wasm_bindgen]
-pub fn decode_z85(z85_data_with_padding: &str) -> Result<Vec<u8>, JsValue> {
-    decode_z85_internal(z85_data_with_padding)
-        .map_err(|e| JsValue::from_str(&e))
+pub fn decode_z85_strict(z85_data: &str) -> Result<Vec<u8>, JsValue> {
+    telemetry::instrument("decode_z85_strict", z85_data.len(), Vec::len, || decode_z85_strict_internal(z85_data))
+        .map_err(js_error)
 }
 
-// Internal pure Rust function for calculating encoding efficiency
-fn get_encoding_efficiency_internal(original_size: usize) -> serde_json::Value {
-    let base64_size = (original_size + 2) / 3 * 4; // Base64: 3 bytes -> 4 chars
-    let z85_size = (original_size + 3) / 4 * 5;     // Z85: 4 bytes -> 5 chars
-    
-    let efficiency_ratio = z85_size as f64 / base64_size as f64;
-    
-    serde_json::json!({
-        "original_size": original_size,
-        "base64_size": base64_size,
-        "z85_size": z85_size,
-        "efficiency_ratio": efficiency_ratio,
-        "bandwidth_saving": (1.0 - efficiency_ratio) * 100.0
+// Remove ASCII whitespace and every character of `separators` from Z85 text. Text containing
+// neither is borrowed unchanged.
+#[cfg(not(feature = "min-size"))]
+pub(crate) fn strip_z85_formatting<'a>(text: &'a str, separators: &str) -> Cow<'a, str> {
+    let is_formatting = |c: char| c.is_ascii_whitespace() || separators.contains(c);
+    if !text.contains(is_formatting) {
+        return Cow::Borrowed(text);
+    }
+    let stripped: String = text.chars().filter(|&c| !is_formatting(c)).collect();
+    logging::log(LogLevel::Debug, format_args!("Lenient Z85: removed {} formatting characters", text.chars().count() - stripped.chars().count()));
+    Cow::Owned(stripped)
+}
+
+// Internal pure Rust function for decoding Z85 text copied from emails, chat apps or wrapped
+// terminals: whitespace, line breaks and the given separator characters are dropped first
+#[cfg(not(feature = "min-size"))]
+fn decode_z85_lenient_internal(text: &str, separators: &str) -> Result<Vec<u8>, Error> {
+    if separators.contains([':', '#']) {
+        return Err(Error::new(ErrorCode::InvalidOptions, "Invalid separators: ':' and '#' introduce the padding suffix"));
+    }
+    decode_z85_internal(&strip_z85_formatting(text, separators))
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Decode Z85 data with padding info like `decode_z85`, ignoring whitespace, line breaks and any
+/// character in `separators` (default none; e.g. `"_,"`). Separators are removed wherever they
+/// appear, so Z85 characters such as `-` are only safe if the data cannot contain them (use
+/// `decode_z85_formatted` for grouped output). Error offsets refer to the text with that
+/// formatting removed.
+#[wasm_bindgen]
+pub fn decode_z85_lenient(text: &str, separators: Option<String>) -> Result<Vec<u8>, JsValue> {
+    telemetry::instrument("decode_z85_lenient", text.len(), Vec::len, || {
+        decode_z85_lenient_internal(text, separators.as_deref().unwrap_or(""))
     })
+        .map_err(js_error)
+}
+
+/// Sizes of the base64 and Z85 forms of the same data, returned by `get_encoding_efficiency` and
+/// `get_encoding_efficiency_for`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EfficiencyReport {
+    original_size: usize,
+    base64_size: usize,
+    z85_size: usize,
+}
+
+impl EfficiencyReport {
+    fn new(original_size: usize, base64_size: usize, z85_size: usize) -> EfficiencyReport {
+        EfficiencyReport { original_size, base64_size, z85_size }
+    }
+}
+
+#[wasm_bindgen]
+impl EfficiencyReport {
+    /// Size of the data in bytes
+    #[wasm_bindgen(getter)]
+    pub fn original_size(&self) -> usize {
+        self.original_size
+    }
+
+    /// Length of the base64 form
+    #[wasm_bindgen(getter)]
+    pub fn base64_size(&self) -> usize {
+        self.base64_size
+    }
+
+    /// Length of the Z85 form
+    #[wasm_bindgen(getter)]
+    pub fn z85_size(&self) -> usize {
+        self.z85_size
+    }
+
+    /// `z85_size` over `base64_size`
+    #[wasm_bindgen(getter)]
+    pub fn efficiency_ratio(&self) -> f64 {
+        self.z85_size as f64 / self.base64_size as f64
+    }
+
+    /// Percentage of the base64 size saved by Z85 (negative when Z85 is larger)
+    #[wasm_bindgen(getter)]
+    pub fn bandwidth_saving(&self) -> f64 {
+        (1.0 - self.efficiency_ratio()) * 100.0
+    }
+
+    /// The fields as a plain object, so `JSON.stringify` and logging show the sizes
+    #[wasm_bindgen(js_name = toJSON, unchecked_return_type = "{ original_size: number, base64_size: number, z85_size: number, efficiency_ratio: number, bandwidth_saving: number }")]
+    pub fn to_json(&self) -> JsValue {
+        let object = js_sys::Object::new();
+        for (key, value) in [
+            ("original_size", self.original_size as f64),
+            ("base64_size", self.base64_size as f64),
+            ("z85_size", self.z85_size as f64),
+            ("efficiency_ratio", self.efficiency_ratio()),
+            ("bandwidth_saving", self.bandwidth_saving()),
+        ] {
+            // Setting a property of a fresh plain object cannot fail
+            let _ = js_sys::Reflect::set(&object, &JsValue::from_str(key), &JsValue::from_f64(value));
+        }
+        object.into()
+    }
 }
 
+// Internal pure Rust function for calculating encoding efficiency
+fn get_encoding_efficiency_internal(original_size: usize) -> EfficiencyReport {
+    let base64_size = original_size.div_ceil(3) * 4; // Base64: 3 bytes -> 4 chars
+    let z85_size = original_size.div_ceil(4) * 5;     // Z85: 4 bytes -> 5 chars
+    EfficiencyReport::new(original_size, base64_size, z85_size)
+}
+
+#[cfg(not(feature = "min-size"))]
 /// Get encoding efficiency comparison
 #[wasm_bindgen]
-pub fn get_encoding_efficiency(original_size: usize) -> JsValue {
-    serde_wasm_bindgen::to_value(&get_encoding_efficiency_internal(original_size)).unwrap()
+pub fn get_encoding_efficiency(original_size: usize) -> EfficiencyReport {
+    get_encoding_efficiency_internal(original_size)
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Where encoded output is embedded, for `get_encoding_efficiency_for`
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EfficiencyContext {
+    /// The encoded text on its own, with the Z85 `:padding` suffix
+    Raw,
+    /// A data URL with the sniffed MIME type (`data:<mime>;base64,` vs `data:<mime>;z85,`)
+    DataUrl,
+    /// A JSON string literal, with its quotes and escapes
+    Json,
+    /// A URL component escaped as `encodeURIComponent` does; base64 uses the URL-safe alphabet without padding
+    Url,
+}
+
+// Whether `encodeURIComponent` leaves this ASCII byte as is
+#[cfg(not(feature = "min-size"))]
+fn is_url_component_safe(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"-_.!~*'()".contains(&byte)
+}
+
+// Length of `text` once escaped by `encodeURIComponent`
+#[cfg(not(feature = "min-size"))]
+fn url_component_len(text: &str) -> usize {
+    text.bytes()
+        .map(|byte| if is_url_component_safe(byte) { 1 } else { 3 })
+        .sum()
+}
+
+// `text` escaped as `encodeURIComponent` does
+#[cfg(not(feature = "min-size"))]
+fn escape_url_component(text: &str) -> String {
+    let mut escaped = String::with_capacity(url_component_len(text));
+    for byte in text.bytes() {
+        if is_url_component_safe(byte) {
+            escaped.push(byte as char);
+        } else {
+            escaped.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    escaped
+}
+
+// `text` as a JSON string literal, with its quotes and escapes
+#[cfg(not(feature = "min-size"))]
+fn escape_json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{c}' => escaped.push_str("\\f"),
+            c if c < ' ' => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+// Internal pure Rust function for calculating the encoding efficiency of actual data
+#[cfg(not(feature = "min-size"))]
+fn get_encoding_efficiency_for_internal(data: &[u8], context: EfficiencyContext) -> Result<EfficiencyReport, Error> {
+    let (base64_size, z85_size) = match context {
+        EfficiencyContext::Raw => (general_purpose::STANDARD.encode(data).len(), encode_z85_internal(data).len()),
+        EfficiencyContext::DataUrl => (
+            data_url::encode_data_url_internal(data, None, DataUrlEncoding::Base64)?.len(),
+            data_url::encode_data_url_internal(data, None, DataUrlEncoding::Z85)?.len(),
+        ),
+        EfficiencyContext::Json => (
+            escape_json_string(&general_purpose::STANDARD.encode(data)).len(),
+            escape_json_string(&encode_z85_internal(data)).len(),
+        ),
+        EfficiencyContext::Url => (
+            url_component_len(&general_purpose::URL_SAFE_NO_PAD.encode(data)),
+            url_component_len(&encode_z85_internal(data)),
+        ),
+    };
+    Ok(EfficiencyReport::new(data.len(), base64_size, z85_size))
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Like `get_encoding_efficiency`, but with the actual sizes of `data` encoded and embedded as
+/// `context` says: the Z85 padding suffix, data URL prefixes, JSON escaping and URL escaping
+/// are all counted.
+#[wasm_bindgen]
+pub fn get_encoding_efficiency_for(data: &[u8], context: EfficiencyContext) -> Result<EfficiencyReport, JsValue> {
+    get_encoding_efficiency_for_internal(data, context)
+        .map_err(js_error)
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Encodings compared by `compare_encodings`, in the order of its rows
+const COMPARED_ENCODINGS: [Encoding; 7] = [
+    Encoding::Z85, Encoding::Base64, Encoding::Base64Url, Encoding::Base32, Encoding::Hex, Encoding::Base45, Encoding::Base91,
+];
+
+#[cfg(not(feature = "min-size"))]
+/// gzip header (without a file name) and trailer around the deflate stream
+const GZIP_OVERHEAD: usize = 18;
+
+#[cfg(not(feature = "min-size"))]
+/// One row of `compare_encodings`
+#[derive(Serialize, Tsify, Debug)]
+struct EncodingComparison {
+    encoding: &'static str,
+    size: usize,
+    /// `size` over the original size
+    ratio: f64,
+    /// Size after gzip at the default level, when asked for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gzip_size: Option<usize>,
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Result of `compare_encodings`
+#[derive(Serialize, Tsify, Debug)]
+struct EncodingMatrix {
+    original_size: usize,
+    encodings: Vec<EncodingComparison>,
+    /// Name of the encoding with the smallest `size`
+    smallest: &'static str,
+}
+
+// Internal pure Rust function for comparing the sizes of `data` in several encodings
+#[cfg(not(feature = "min-size"))]
+fn compare_encodings_internal(data: &[u8], context: EfficiencyContext, gzip: bool) -> Result<EncodingMatrix, Error> {
+    let options = ConversionOptions::new(DataType::Raw, DataType::Raw);
+    let mut encodings = Vec::with_capacity(COMPARED_ENCODINGS.len());
+    for encoding in COMPARED_ENCODINGS {
+        let encoded = encoding.encode(data, &options)?;
+        let embedded = match context {
+            EfficiencyContext::Raw => encoded,
+            EfficiencyContext::DataUrl => {
+                return Err(Error::new(ErrorCode::InvalidOptions, "Data URLs only carry base64 or Z85; use get_encoding_efficiency_for"));
+            }
+            EfficiencyContext::Json => escape_json_string(&encoded),
+            EfficiencyContext::Url => escape_url_component(&encoded),
+        };
+        encodings.push(EncodingComparison {
+            encoding: encoding.name(),
+            size: embedded.len(),
+            ratio: embedded.len() as f64 / data.len() as f64,
+            gzip_size: gzip.then(|| compression::deflate_raw(embedded.as_bytes()).len() + GZIP_OVERHEAD),
+        });
+    }
+    let smallest = encodings.iter().min_by_key(|row| row.size).map_or("z85", |row| row.encoding);
+    Ok(EncodingMatrix { original_size: data.len(), encodings, smallest })
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Compare the size of `data` in Z85, base64, base64url, base32, hex, Base45 and basE91, embedded
+/// as `context` says (`Raw` by default; `DataUrl` is not supported), and optionally after gzip.
+/// Returns `{ original_size, encodings: [{ encoding, size, ratio, gzip_size? }], smallest }`.
+#[wasm_bindgen(unchecked_return_type = "EncodingMatrix")]
+pub fn compare_encodings(data: &[u8], context: Option<EfficiencyContext>, gzip: Option<bool>) -> Result<JsValue, JsValue> {
+    let matrix = compare_encodings_internal(data, context.unwrap_or(EfficiencyContext::Raw), gzip.unwrap_or(false))
+        .map_err(js_error)?;
+    matrix.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Bytes deflated by `estimate_compressed_size` unless told otherwise
+const DEFAULT_COMPRESSION_SAMPLE: usize = 64 * 1024;
+#[cfg(not(feature = "min-size"))]
+/// Slices spread over the data that make up a compression sample
+const COMPRESSION_SAMPLE_SLICES: usize = 4;
+
+#[cfg(not(feature = "min-size"))]
+/// Result of `estimate_compressed_size`
+#[derive(Serialize, Tsify, Debug)]
+struct CompressionEstimate {
+    original_size: usize,
+    /// Bytes actually deflated to estimate the ratio
+    sampled_bytes: usize,
+    /// Whether the whole input was deflated, so the sizes below are exact
+    exact: bool,
+    /// Deflated size over original size, as measured on the sample
+    deflate_ratio: f64,
+    /// `encode_z85` output
+    z85_size: usize,
+    /// Plain base64
+    base64_size: usize,
+    /// `encode_z85` with `Compression.Deflate` (a `z85v2:` container)
+    deflate_z85_size: usize,
+    /// Base64 of the gzip-compressed data
+    gzip_base64_size: usize,
+    /// Whether `Compression.Deflate` makes the Z85 output smaller
+    should_compress: bool,
+    /// `"z85"`, `"base64"`, `"deflate_z85"` or `"gzip_base64"`, whichever is smallest
+    #[tsify(type = "\"z85\" | \"base64\" | \"deflate_z85\" | \"gzip_base64\"")]
+    smallest: &'static str,
+}
+
+// Length of a checksummed `z85v2:` container holding a `payload_len`-byte payload
+#[cfg(not(feature = "min-size"))]
+fn container_len(payload_len: usize, compression: Compression) -> usize {
+    let mut header = format!("{}len={};crc32=00000000", container::V2_PREFIX, payload_len);
+    if compression != Compression::None {
+        header.push_str(";compression=");
+        header.push_str(compression.name());
+    }
+    header.len() + 1 + payload_len.div_ceil(4) * 5
+}
+
+// Deflated size of `data`, exactly when it fits in `sample_size` and otherwise extrapolated from
+// evenly spaced slices. Returns the size and the number of bytes deflated.
+#[cfg(not(feature = "min-size"))]
+fn deflated_size_estimate(data: &[u8], sample_size: usize) -> (usize, usize) {
+    if data.len() <= sample_size {
+        return (compression::deflate_raw(data).len(), data.len());
+    }
+    let slice_len = (sample_size / COMPRESSION_SAMPLE_SLICES).max(1);
+    let stride = (data.len() - slice_len) / (COMPRESSION_SAMPLE_SLICES - 1);
+    let deflated: usize = (0..COMPRESSION_SAMPLE_SLICES)
+        .map(|index| compression::deflate_raw(&data[index * stride..][..slice_len]).len())
+        .sum();
+    let sampled = slice_len * COMPRESSION_SAMPLE_SLICES;
+    ((data.len() as f64 * deflated as f64 / sampled as f64).ceil() as usize, sampled)
+}
+
+// Internal pure Rust function for estimating whether compressing `data` before encoding pays off
+#[cfg(not(feature = "min-size"))]
+fn estimate_compressed_size_internal(data: &[u8], sample_size: usize) -> Result<CompressionEstimate, Error> {
+    if sample_size == 0 {
+        return Err(Error::new(ErrorCode::InvalidOptions, "Compression sample size must be non-zero"));
+    }
+    let (deflated_size, sampled_bytes) = deflated_size_estimate(data, sample_size);
+    // The container stores the data as is when deflate doesn't shrink it
+    let deflate_z85_size = if deflated_size < data.len() {
+        container_len(deflated_size, Compression::Deflate)
+    } else {
+        container_len(data.len(), Compression::None)
+    };
+    let z85_size = data.len().div_ceil(4) * 5 + 2;
+    let candidates = [
+        ("z85", z85_size),
+        ("base64", data.len().div_ceil(3) * 4),
+        ("deflate_z85", deflate_z85_size),
+        ("gzip_base64", (deflated_size + GZIP_OVERHEAD).div_ceil(3) * 4),
+    ];
+    let smallest = candidates.iter().min_by_key(|(_, size)| *size).map_or("z85", |(name, _)| name);
+
+    Ok(CompressionEstimate {
+        original_size: data.len(),
+        sampled_bytes,
+        exact: sampled_bytes == data.len(),
+        deflate_ratio: deflated_size as f64 / data.len() as f64,
+        z85_size,
+        base64_size: candidates[1].1,
+        deflate_z85_size,
+        gzip_base64_size: candidates[3].1,
+        should_compress: deflate_z85_size < z85_size,
+        smallest,
+    })
+}
+
+#[cfg(not(feature = "min-size"))]
+/// Estimate the sizes of `data` encoded with and without compression by deflating a sample of up
+/// to `sample_size` bytes (64 KiB by default) taken from slices spread over the data, answering
+/// whether to compress before encoding. Returns `{ original_size, sampled_bytes, exact, deflate_ratio,
+/// z85_size, base64_size, deflate_z85_size, gzip_base64_size, should_compress, smallest }`.
+#[wasm_bindgen(unchecked_return_type = "CompressionEstimate")]
+pub fn estimate_compressed_size(data: &[u8], sample_size: Option<usize>) -> Result<JsValue, JsValue> {
+    let estimate = estimate_compressed_size_internal(data, sample_size.unwrap_or(DEFAULT_COMPRESSION_SAMPLE))
+        .map_err(js_error)?;
+    serde_wasm_bindgen::to_value(&estimate)
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
 }
 
 /// Initialize the WASM module
 #[wasm_bindgen(start)]
 pub fn init_wasm() {
-    console_log!("Z85 encoder/decoder WASM module initialized");
+    panic_hook::install();
+    logging::log(LogLevel::Info, format_args!("Z85 encoder/decoder WASM module initialized"));
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(feature = "min-size")))]
 mod tests {
     use super::*;
     
@@ -332,7 +1385,7 @@ mod tests {
     #[test]
     fn test_base64_to_z85_basic() {
         let base64 = "SGVsbG8gV29ybGQ=";
-        let result = base64_to_z85_internal(base64).unwrap();
+        let result = base64_to_z85_internal(base64, Base64Variant::Standard).unwrap();
         // Verify it's in the format "z85data:padding"
         assert!(result.contains(':'));
         // Verify roundtrip
@@ -369,24 +1422,24 @@ mod tests {
     #[test]
     fn test_z85_to_base64_with_options_all_branches() {
         let base64 = "SGVsbG8gV29ybGQ=";
-        let z85_data = base64_to_z85_internal(base64).unwrap();
+        let z85_data = base64_to_z85_internal(base64, Base64Variant::Standard).unwrap();
         
         // Test Raw -> Raw (default behavior)
-        let result = z85_to_base64_with_options_internal(&z85_data, DataType::Raw, DataType::Raw).unwrap();
+        let result = z85_to_base64_with_options_internal(&z85_data, &ConversionOptions::new(DataType::Raw, DataType::Raw)).unwrap();
         assert_eq!(result, base64);
         
         // Test DataURL -> DataURL
         let input = format!("data:image/png;z85,{}", z85_data);
-        let result = z85_to_base64_with_options_internal(&input, DataType::DataURL, DataType::DataURL).unwrap();
+        let result = z85_to_base64_with_options_internal(&input, &ConversionOptions::new(DataType::DataURL, DataType::DataURL)).unwrap();
         assert_eq!(result, "data:image/png;base64,SGVsbG8gV29ybGQ=");
         
         // Test DataURL -> Raw
-        let result = z85_to_base64_with_options_internal(&input, DataType::DataURL, DataType::Raw).unwrap();
+        let result = z85_to_base64_with_options_internal(&input, &ConversionOptions::new(DataType::DataURL, DataType::Raw)).unwrap();
         assert_eq!(result, base64);
         
-        // Test Raw -> DataURL (should error)
-        let result = z85_to_base64_with_options_internal(&z85_data, DataType::Raw, DataType::DataURL);
-        assert!(result.is_err());
+        // Test Raw -> DataURL (MIME type sniffed, octet-stream for plain text)
+        let result = z85_to_base64_with_options_internal(&z85_data, &ConversionOptions::new(DataType::Raw, DataType::DataURL)).unwrap();
+        assert_eq!(result, "data:application/octet-stream;base64,SGVsbG8gV29ybGQ=");
     }
     
     #[test]
@@ -394,43 +1447,108 @@ mod tests {
         let base64 = "SGVsbG8gV29ybGQ=";
         
         // Test Raw -> Raw (default behavior)
-        let result = base64_to_z85_with_options_internal(base64, DataType::Raw, DataType::Raw).unwrap();
+        let result = base64_to_z85_with_options_internal(base64, &ConversionOptions::new(DataType::Raw, DataType::Raw)).unwrap();
         assert!(result.contains(':'));
         let back = z85_to_base64_internal(&result).unwrap();
         assert_eq!(back, base64);
         
         // Test DataURL -> DataURL
         let input = format!("data:image/jpeg;base64,{}", base64);
-        let result = base64_to_z85_with_options_internal(&input, DataType::DataURL, DataType::DataURL).unwrap();
+        let result = base64_to_z85_with_options_internal(&input, &ConversionOptions::new(DataType::DataURL, DataType::DataURL)).unwrap();
         assert!(result.starts_with("data:image/jpeg;z85,"));
         assert!(result.contains(':'));
         
         // Test DataURL -> Raw
-        let result = base64_to_z85_with_options_internal(&input, DataType::DataURL, DataType::Raw).unwrap();
+        let result = base64_to_z85_with_options_internal(&input, &ConversionOptions::new(DataType::DataURL, DataType::Raw)).unwrap();
         assert!(!result.starts_with("data:"));
         assert!(result.contains(':'));
         
-        // Test Raw -> DataURL (should error)
-        let result = base64_to_z85_with_options_internal(base64, DataType::Raw, DataType::DataURL);
-        assert!(result.is_err());
+        // Test Raw -> DataURL (MIME type sniffed, octet-stream for plain text)
+        let result = base64_to_z85_with_options_internal(base64, &ConversionOptions::new(DataType::Raw, DataType::DataURL)).unwrap();
+        assert_eq!(result, "data:application/octet-stream;z85,nm=QNzY&b1A+]m^:1");
+    }
+    
+    #[test]
+    fn test_dataurl_parameters_preserved() {
+        let input = "data:text/plain;charset=utf-8;base64,SGVsbG8gV29ybGQ=";
+        let z85 = base64_to_z85_with_options_internal(input, &ConversionOptions::new(DataType::DataURL, DataType::DataURL)).unwrap();
+        assert_eq!(z85, "data:text/plain;charset=utf-8;z85,nm=QNzY&b1A+]m^:1");
+        let back = z85_to_base64_with_options_internal(&z85, &ConversionOptions::new(DataType::DataURL, DataType::DataURL)).unwrap();
+        assert_eq!(back, input);
+        
+        let raw = z85_to_base64_with_options_internal(&z85, &ConversionOptions::new(DataType::DataURL, DataType::Raw)).unwrap();
+        assert_eq!(raw, "SGVsbG8gV29ybGQ=");
+        let raw = base64_to_z85_with_options_internal(input, &ConversionOptions::new(DataType::DataURL, DataType::Raw)).unwrap();
+        assert_eq!(raw, "nm=QNzY&b1A+]m^:1");
+    }
+    
+    #[test]
+    fn test_percent_encoded_dataurl() {
+        let input = "data:text/html;charset=utf-8,%3Ch1%3EHi%3C/h1%3E";
+        let z85 = base64_to_z85_with_options_internal(input, &ConversionOptions::new(DataType::DataURL, DataType::DataURL)).unwrap();
+        assert_eq!(z85, format!("data:text/html;charset=utf-8;z85,{}", encode_z85_internal(b"<h1>Hi</h1>")));
+        let raw = base64_to_z85_with_options_internal(input, &ConversionOptions::new(DataType::DataURL, DataType::Raw)).unwrap();
+        assert_eq!(decode_z85_internal(&raw).unwrap(), b"<h1>Hi</h1>");
+        
+        // Back to percent-encoding or base64
+        let back = z85_to_base64_with_options_internal(&z85, &ConversionOptions { data_url_encoding: DataUrlEncoding::Percent, ..ConversionOptions::new(DataType::DataURL, DataType::DataURL) }).unwrap();
+        assert_eq!(back, input);
+        let back = z85_to_base64_with_options_internal(&z85, &ConversionOptions::new(DataType::DataURL, DataType::DataURL)).unwrap();
+        assert_eq!(back, "data:text/html;charset=utf-8;base64,PGgxPkhpPC9oMT4=");
+        
+        // Raw Z85 to a percent-encoded data URL sniffs the MIME type from the decoded bytes
+        let svg = encode_z85_internal(b"<svg xmlns='http://www.w3.org/2000/svg'/>");
+        let url = z85_to_base64_with_options_internal(&svg, &ConversionOptions { data_url_encoding: DataUrlEncoding::Percent, ..ConversionOptions::new(DataType::Raw, DataType::DataURL) }).unwrap();
+        assert_eq!(url, "data:image/svg+xml,%3Csvg%20xmlns='http://www.w3.org/2000/svg'/%3E");
+        
+        let result = z85_to_base64_with_options_internal(&z85, &ConversionOptions { data_url_encoding: DataUrlEncoding::Z85, ..ConversionOptions::new(DataType::DataURL, DataType::DataURL) });
+        assert_eq!(result.err().unwrap().message, "Data URL output encoding must be Base64 or Percent");
+    }
+    
+    #[test]
+    fn test_raw_to_dataurl_with_mime_type() {
+        let base64 = "SGVsbG8gV29ybGQ=";
+        let mut opts = ConversionOptions::new(DataType::Raw, DataType::DataURL);
+        assert_eq!(opts.mime_type(), None);
+        opts.set_mime_type(Some("text/plain".to_string()));
+        assert_eq!(opts.mime_type().as_deref(), Some("text/plain"));
+        
+        let z85 = base64_to_z85_with_options_internal(base64, &opts).unwrap();
+        assert_eq!(z85, "data:text/plain;z85,nm=QNzY&b1A+]m^:1");
+        let back = z85_to_base64_with_options_internal("nm=QNzY&b1A+]m^:1", &ConversionOptions { mime_type: Some("text/plain".to_string()), ..ConversionOptions::new(DataType::Raw, DataType::DataURL) }).unwrap();
+        assert_eq!(back, format!("data:text/plain;base64,{}", base64));
+        
+        let result = base64_to_z85_with_options_internal(base64, &ConversionOptions { mime_type: Some("text/plain,evil".to_string()), ..ConversionOptions::new(DataType::Raw, DataType::DataURL) });
+        assert_eq!(result.err().unwrap().message, "Invalid MIME type: text/plain,evil");
+    }
+    
+    #[test]
+    fn test_raw_to_dataurl_sniffs_mime_type() {
+        let png = general_purpose::STANDARD.encode(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR");
+        let z85 = base64_to_z85_with_options_internal(&png, &ConversionOptions::new(DataType::Raw, DataType::DataURL)).unwrap();
+        assert!(z85.starts_with("data:image/png;z85,"));
+        
+        let raw_z85 = base64_to_z85_internal(&png, Base64Variant::Standard).unwrap();
+        let result = z85_to_base64_with_options_internal(&raw_z85, &ConversionOptions::new(DataType::Raw, DataType::DataURL)).unwrap();
+        assert_eq!(result, format!("data:image/png;base64,{}", png));
     }
     
     #[test]
     fn test_dataurl_error_cases() {
         // Invalid data URL format
-        let result = z85_to_base64_with_options_internal("not_a_dataurl", DataType::DataURL, DataType::DataURL);
+        let result = z85_to_base64_with_options_internal("not_a_dataurl", &ConversionOptions::new(DataType::DataURL, DataType::DataURL));
         assert!(result.is_err());
         
         // Missing ;z85, marker
-        let result = z85_to_base64_with_options_internal("data:image/png;base64,data", DataType::DataURL, DataType::DataURL);
+        let result = z85_to_base64_with_options_internal("data:image/png;base64,data", &ConversionOptions::new(DataType::DataURL, DataType::DataURL));
         assert!(result.is_err());
         
         // Invalid data URL for base64
-        let result = base64_to_z85_with_options_internal("not_a_dataurl", DataType::DataURL, DataType::DataURL);
+        let result = base64_to_z85_with_options_internal("not_a_dataurl", &ConversionOptions::new(DataType::DataURL, DataType::DataURL));
         assert!(result.is_err());
         
         // Missing ;base64, marker
-        let result = base64_to_z85_with_options_internal("data:image/png;z85,data", DataType::DataURL, DataType::DataURL);
+        let result = base64_to_z85_with_options_internal("data:image/png;z85,data", &ConversionOptions::new(DataType::DataURL, DataType::DataURL));
         assert!(result.is_err());
     }
     
@@ -445,18 +1563,243 @@ mod tests {
         assert!(result.is_ok());
     }
     
+    #[test]
+    fn test_decode_z85_from_bytes() {
+        let encoded = encode_z85_internal(b"Hello, World!");
+        assert_eq!(decode_z85_from_bytes_internal(encoded.as_bytes()).unwrap(), b"Hello, World!");
+        let container = encode_z85_with_options_internal(&[1; 100], PaddingFormat::Count, Compression::Deflate);
+        assert_eq!(decode_z85_from_bytes_internal(container.as_bytes()).unwrap(), [1; 100]);
+
+        assert_eq!(decode_z85_from_bytes_internal(b"Hello\xffWorld:0").err().unwrap().message, "Z85 decode error: non-ASCII byte at offset 5");
+        assert_eq!(decode_z85_from_bytes_internal("HelloWorl\u{e9}:0".as_bytes()).err().unwrap().message, "Z85 decode error: non-ASCII byte at offset 9");
+    }
+
+    #[test]
+    fn test_decode_z85_into() {
+        let data = b"Hello, World!";
+        let encoded = encode_z85_internal(data);
+        let size = decoded_size_hint_internal(&encoded).unwrap();
+        assert_eq!(size, data.len());
+
+        // Larger buffers are fine, only the decoded prefix is written
+        let mut out = vec![0u8; size + 4];
+        let written = decode_z85_into_internal(&encoded, &mut out).unwrap();
+        assert_eq!(&out[..written], data);
+
+        let result = decode_z85_into_internal(&encoded, &mut out[..4]);
+        assert!(result.err().unwrap().message.contains("Output buffer too small"));
+
+        let result = decoded_size_hint_internal("no_colon_here");
+        assert_eq!(result.err().unwrap().message, "Invalid format: expected 'z85_data:padding'");
+    }
+
+    #[test]
+    fn test_decode_z85_into_v2() {
+        let data = b"Hello, World! Hello, World! Hello, World!";
+        for compression in [Compression::None, Compression::Deflate] {
+            let encoded = container::encode_container_internal(data, true, compression);
+            assert_eq!(decoded_size_hint_internal(&encoded).unwrap(), data.len());
+
+            let mut out = vec![0u8; data.len() + 4];
+            let written = decode_z85_into_internal(&encoded, &mut out).unwrap();
+            assert_eq!(&out[..written], data);
+            assert_eq!(
+                decode_z85_into_internal(&encoded, &mut out[..4]).err().unwrap().message,
+                format!("Output buffer too small: need {} bytes, got 4", data.len())
+            );
+        }
+    }
+    
+    #[test]
+    fn test_fused_transcoding_across_windows() {
+        // Sizes around the window boundary, with every padding amount
+        for size in [0, 1, 2, 3, 4, 3071, 3072, 3073, 3075, 6144, 10_000] {
+            let data: Vec<u8> = (0..size).map(|i| (i * 7 + 3) as u8).collect();
+            let base64 = general_purpose::STANDARD.encode(&data);
+            let z85 = base64_to_z85_internal(&base64, Base64Variant::Standard).unwrap();
+            assert_eq!(z85, encode_z85_internal(&data), "size {}", size);
+            assert_eq!(z85_to_base64_internal(&z85).unwrap(), base64, "size {}", size);
+        }
+    }
+    
+    #[test]
+    fn test_base64_variants() {
+        // "\xfb\xff\xfe" needs both the '+' / '/' and '-' / '_' characters
+        let data = b"Hello\xfb\xff\xfe";
+        let z85 = encode_z85_internal(data);
+        for (variant, base64) in [
+            (Base64Variant::Standard, "SGVsbG/7//4="),
+            (Base64Variant::UrlSafe, "SGVsbG_7__4="),
+            (Base64Variant::UrlSafeNoPad, "SGVsbG_7__4"),
+        ] {
+            assert_eq!(z85_to_base64_with_variant_internal(&z85, variant).unwrap(), base64);
+            assert_eq!(base64_to_z85_with_format_internal(base64, PaddingFormat::Count, variant).unwrap(), z85);
+            assert_eq!(z85_to_base64_with_options_internal(&z85, &ConversionOptions { base64_variant: variant, ..ConversionOptions::new(DataType::Raw, DataType::Raw) }).unwrap(), base64);
+            assert_eq!(base64_to_z85_with_options_internal(base64, &ConversionOptions { base64_variant: variant, ..ConversionOptions::new(DataType::Raw, DataType::Raw) }).unwrap(), z85);
+        }
+        
+        // URL-safe input is accepted with or without padding; the alphabets do not mix
+        assert_eq!(base64_to_z85_with_format_internal("SGVsbG_7__4=", PaddingFormat::Count, Base64Variant::UrlSafeNoPad).unwrap(), z85);
+        assert_eq!(base64_to_z85_with_format_internal("SGVsbG_7__4", PaddingFormat::Count, Base64Variant::UrlSafe).unwrap(), z85);
+        assert!(base64_to_z85_with_format_internal("SGVsbG/7//4=", PaddingFormat::Count, Base64Variant::UrlSafe).err().unwrap().message.contains("Invalid symbol 47, offset 6"));
+        assert!(base64_to_z85_internal("SGVsbG_7__4=", Base64Variant::Standard).is_err());
+        
+        // Raw URL-safe input still sniffs, while data URL payloads stay standard base64
+        let png = Base64Variant::UrlSafeNoPad.engine().encode(b"\x89PNG\r\n\x1a\n\xff\xff\xff");
+        let url = base64_to_z85_with_options_internal(&png, &ConversionOptions { base64_variant: Base64Variant::UrlSafeNoPad, ..ConversionOptions::new(DataType::Raw, DataType::DataURL) }).unwrap();
+        assert!(url.starts_with("data:image/png;z85,"));
+        let back = z85_to_base64_with_options_internal(&url, &ConversionOptions { base64_variant: Base64Variant::UrlSafeNoPad, ..ConversionOptions::new(DataType::DataURL, DataType::DataURL) }).unwrap();
+        assert_eq!(back, "data:image/png;base64,iVBORw0KGgr///8=");
+    }
+    
+    #[test]
+    fn test_lenient_base64() {
+        let expected = base64_to_z85_internal("SGVsbG8gV29ybGQ=", Base64Variant::Standard).unwrap();
+        let wrapped = "SGVsbG8g\r\nV29y\n  bGQ";
+        let convert = |data: &str, input, output, lenient| {
+            base64_to_z85_with_options_internal(data, &ConversionOptions { lenient, ..ConversionOptions::new(input, output) })
+        };
+        
+        assert!(convert(wrapped, DataType::Raw, DataType::Raw, false).is_err());
+        assert_eq!(convert(wrapped, DataType::Raw, DataType::Raw, true).unwrap(), expected);
+        assert_eq!(convert("SGVsbG8gV29ybGQ", DataType::Raw, DataType::Raw, true).unwrap(), expected);
+        assert_eq!(convert(&format!("data:text/plain;base64,{}", wrapped), DataType::DataURL, DataType::Raw, true).unwrap(), expected);
+        assert_eq!(
+            convert(&format!("data:text/plain;base64,{}", wrapped), DataType::DataURL, DataType::DataURL, true).unwrap(),
+            format!("data:text/plain;z85,{}", expected)
+        );
+        
+        // Whitespace-free and unpadded URL-safe input, and errors still reported
+        let url_safe = base64_to_z85_with_options_internal("SGVsbG_7\n__4", &ConversionOptions { base64_variant: Base64Variant::UrlSafe, lenient: true, ..ConversionOptions::new(DataType::Raw, DataType::Raw) });
+        assert_eq!(url_safe.unwrap(), encode_z85_internal(b"Hello\xfb\xff\xfe"));
+        assert!(convert("SGVs\nbG8!", DataType::Raw, DataType::Raw, true).err().unwrap().message.contains("Invalid symbol 33, offset 7"));
+    }
+    
+    #[test]
+    fn test_fused_transcoding_error_offsets() {
+        // Invalid base64 character in the second window
+        let mut base64 = "A".repeat(TRANSCODE_WINDOW_BYTES / 3 * 4 * 2);
+        base64.replace_range(4100..4101, "!");
+        let result = base64_to_z85_internal(&base64, Base64Variant::Standard);
+        assert_eq!(result.err().unwrap().message, "Base64 decode error: Invalid symbol 33, offset 4100.");
+        
+        // Invalid Z85 character in the second window
+        let mut z85 = "0".repeat(TRANSCODE_WINDOW_BYTES / 4 * 5 * 2);
+        z85.replace_range(3900..3901, "~");
+        let result = z85_to_base64_internal(&format!("{}:0", z85));
+        assert_eq!(result.err().unwrap().message, "Z85 decode error: invalid character at offset 3900");
+        
+        // Padding larger than the decoded data
+        let result = z85_to_base64_internal("Hello:5");
+        assert_eq!(result.err().unwrap().message, "Invalid padding number");
+    }
+    
+    #[test]
+    fn test_get_encoding_efficiency_for() {
+        let hello = b"Hello, World!";
+        let raw = get_encoding_efficiency_for_internal(hello, EfficiencyContext::Raw).unwrap();
+        assert_eq!((raw.base64_size(), raw.z85_size()), (20, 22));
+
+        let data_url = get_encoding_efficiency_for_internal(hello, EfficiencyContext::DataUrl).unwrap();
+        // "data:application/octet-stream;base64," and "data:application/octet-stream;z85,"
+        assert_eq!((data_url.base64_size(), data_url.z85_size()), (57, 56));
+
+        let json = get_encoding_efficiency_for_internal(hello, EfficiencyContext::Json).unwrap();
+        assert_eq!((json.base64_size(), json.z85_size()), (22, 24));
+
+        // URL-safe base64 needs no escaping, while Z85 symbols expand to three characters each
+        let url = get_encoding_efficiency_for_internal(&[0xff; 300], EfficiencyContext::Url).unwrap();
+        assert_eq!(url.base64_size(), 400);
+        let z85 = encode_z85_internal(&[0xff; 300]);
+        assert_eq!(url.z85_size(), url_component_len(&z85));
+        assert!(url.z85_size() > z85.len());
+    }
+
+    #[test]
+    fn test_escape_json_string() {
+        // Same literal as serde_json, including the characters basE91 output can contain
+        for text in ["", "nm=QNzY&b1A+]m^:1", "a\"b\\c", "\n\r\t\u{8}\u{c}\u{1}\u{1f}", "\u{7f}\u{e9}"] {
+            assert_eq!(escape_json_string(text), serde_json::to_string(text).unwrap(), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn test_conversion_result() {
+        let result = ConversionResult::new("SGVsbG8gV29ybGQ=", "nm=QNzY&b1A+]m^:1".to_string(), false, 0.5);
+        assert_eq!((result.input_size(), result.output_size(), result.padding()), (16, 17, Some(1)));
+        assert_eq!(result.ratio(), 17.0 / 16.0);
+        assert_eq!(result.mime_type(), None);
+
+        // Empty input has no meaningful ratio, but it must not be NaN or infinite
+        let result = ConversionResult::new("", ":0".to_string(), false, 0.0);
+        assert_eq!((result.input_size(), result.ratio()), (0, 0.0));
+
+        let result = ConversionResult::new("data:image/png;z85,nm=QNzY&b1A+]m^#11", "data:image/png;base64,SGVsbG8gV29ybGQ=".to_string(), true, 0.5);
+        assert_eq!((result.padding(), result.mime_type().as_deref()), (Some(1), Some("image/png")));
+
+        let container = encode_z85_with_options_internal(&[7; 9], PaddingFormat::Count, Compression::None);
+        assert_eq!(z85_padding(&container::encode_z85_v2_internal(&[7; 9], true)), Some(3));
+        assert_eq!(z85_padding(&container), Some(3));
+        assert_eq!(z85_padding("not z85"), None);
+    }
+
+    #[test]
+    fn test_compare_encodings() {
+        let hello = b"Hello, World!";
+        let raw = compare_encodings_internal(hello, EfficiencyContext::Raw, false).unwrap();
+        let sizes: Vec<_> = raw.encodings.iter().map(|row| (row.encoding, row.size)).collect();
+        assert_eq!(sizes, [("z85", 22), ("base64", 20), ("base64url", 18), ("base32", 24), ("hex", 26), ("base45", 20), ("base91", 16)]);
+        assert_eq!(raw.smallest, "base91");
+        assert!(raw.encodings.iter().all(|row| row.gzip_size.is_none()));
+
+        let url = compare_encodings_internal(&[0xff; 300], EfficiencyContext::Url, true).unwrap();
+        assert_eq!(url.encodings[2].size, 400);
+        assert_eq!(url.encodings[0].size, url_component_len(&encode_z85_internal(&[0xff; 300])));
+        // A run of identical bytes compresses well in every encoding
+        assert!(url.encodings.iter().all(|row| row.gzip_size.unwrap() < row.size));
+        assert_eq!(escape_url_component("a b/~"), "a%20b%2F~");
+
+        assert!(compare_encodings_internal(hello, EfficiencyContext::DataUrl, false).is_err());
+    }
+
+    #[test]
+    fn test_estimate_compressed_size() {
+        let text = "The quick brown fox jumps over the lazy dog. ".repeat(100);
+        let exact = estimate_compressed_size_internal(text.as_bytes(), DEFAULT_COMPRESSION_SAMPLE).unwrap();
+        assert!(exact.exact && exact.should_compress);
+        assert_eq!(exact.z85_size, encode_z85_internal(text.as_bytes()).len());
+        assert_eq!(exact.deflate_z85_size, encode_z85_with_options_internal(text.as_bytes(), PaddingFormat::Count, Compression::Deflate).len());
+        // Text this repetitive deflates so far that the container header outweighs Z85's density
+        assert_eq!(exact.smallest, "gzip_base64");
+
+        // Slices of repetitive text compress about as well as the whole of it
+        let estimate = estimate_compressed_size_internal(text.as_bytes(), 1024).unwrap();
+        assert!(!estimate.exact && estimate.sampled_bytes == 1024);
+        assert!(estimate.deflate_ratio < 0.5 && estimate.should_compress);
+
+        // Incompressible data is stored as is, so the container only adds its header
+        let mut state = 1u32;
+        let noise: Vec<u8> = (0..4096).map(|_| { state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345); (state >> 16) as u8 }).collect();
+        let noisy = estimate_compressed_size_internal(&noise, DEFAULT_COMPRESSION_SAMPLE).unwrap();
+        assert!(!noisy.should_compress);
+        assert_eq!(noisy.deflate_z85_size, encode_z85_with_options_internal(&noise, PaddingFormat::Count, Compression::Deflate).len());
+        assert_eq!(noisy.smallest, "z85");
+
+        assert!(estimate_compressed_size_internal(&noise, 0).is_err());
+    }
+
     #[test]
     fn test_get_encoding_efficiency_internal() {
         // Test the internal function
         let stats = get_encoding_efficiency_internal(1000);
-        assert_eq!(stats["original_size"], 1000);
-        assert_eq!(stats["base64_size"], 1336);
-        assert_eq!(stats["z85_size"], 1250);
+        assert_eq!(stats.original_size(), 1000);
+        assert_eq!(stats.base64_size(), 1336);
+        assert_eq!(stats.z85_size(), 1250);
         
-        let efficiency_ratio = stats["efficiency_ratio"].as_f64().unwrap();
+        let efficiency_ratio = stats.efficiency_ratio();
         assert!(efficiency_ratio > 0.93 && efficiency_ratio < 0.94);
         
-        let bandwidth_saving = stats["bandwidth_saving"].as_f64().unwrap();
+        let bandwidth_saving = stats.bandwidth_saving();
         assert!(bandwidth_saving > 6.0 && bandwidth_saving < 7.0);
     }
     
@@ -521,25 +1864,85 @@ mod tests {
         // Test missing colon
         let result = z85_to_base64_internal("no_colon_here");
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap(), "Invalid format: expected 'z85_data:padding'");
+        assert_eq!(result.err().unwrap().message, "Invalid format: expected 'z85_data:padding'");
         
         // Test invalid padding number
         let result = z85_to_base64_internal("data:not_a_number");
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap(), "Invalid padding number");
+        assert_eq!(result.err().unwrap().message, "Invalid padding number");
         
         // Test invalid Z85 data
         let result = z85_to_base64_internal("invalid_z85!:0");
         assert!(result.is_err());
-        assert!(result.err().unwrap().contains("Z85 decode error"));
+        assert!(result.err().unwrap().message.contains("Z85 decode error"));
     }
     
     #[test]
     fn test_base64_to_z85_errors() {
         // Test invalid base64
-        let result = base64_to_z85_internal("not valid base64!");
+        let result = base64_to_z85_internal("not valid base64!", Base64Variant::Standard);
         assert!(result.is_err());
-        assert!(result.err().unwrap().contains("Base64 decode error"));
+        assert!(result.err().unwrap().message.contains("Base64 decode error"));
+    }
+    
+    #[test]
+    fn test_length_padding_format() {
+        let base64 = "SGVsbG8gV29ybGQ=";
+        let count_form = base64_to_z85_with_options_internal(base64, &ConversionOptions::new(DataType::Raw, DataType::Raw)).unwrap();
+        let length_form = base64_to_z85_with_options_internal(base64, &ConversionOptions { padding_format: PaddingFormat::Length, ..ConversionOptions::new(DataType::Raw, DataType::Raw) }).unwrap();
+        assert_eq!(count_form, "nm=QNzY&b1A+]m^:1");
+        assert_eq!(length_form, "nm=QNzY&b1A+]m^#11");
+        
+        // The decoders accept both forms
+        assert_eq!(z85_to_base64_internal(&length_form).unwrap(), base64);
+        assert_eq!(decode_z85_internal(&length_form).unwrap(), b"Hello World");
+        assert_eq!(decoded_size_hint_internal(&length_form).unwrap(), 11);
+        
+        // '#' inside the Z85 data is not mistaken for the suffix
+        let data = [0, 0, 0, 84, 0, 0, 0, 84];
+        let encoded = format!("{}#8", z85::encode(data));
+        assert!(encoded[..10].contains('#'));
+        assert_eq!(decode_z85_internal(&encoded).unwrap(), data);
+    }
+    
+    #[test]
+    fn test_base64_to_z85_with_compression() {
+        let text = "The quick brown fox jumps over the lazy dog. ".repeat(40);
+        let base64 = general_purpose::STANDARD.encode(&text);
+        let compressed = base64_to_z85_with_options_internal(&base64, &ConversionOptions { compression: Compression::Deflate, ..ConversionOptions::new(DataType::Raw, DataType::Raw) }).unwrap();
+        let plain = base64_to_z85_with_options_internal(&base64, &ConversionOptions::new(DataType::Raw, DataType::Raw)).unwrap();
+        assert!(compressed.starts_with("z85v2:"));
+        assert!(compressed.len() * 5 < plain.len());
+        
+        // The reverse paths decompress transparently
+        assert_eq!(z85_to_base64_internal(&compressed).unwrap(), base64);
+        assert_eq!(decode_z85_internal(&compressed).unwrap(), text.as_bytes());
+        
+        let input = format!("data:text/plain;base64,{}", base64);
+        let data_url = base64_to_z85_with_options_internal(&input, &ConversionOptions { compression: Compression::Deflate, ..ConversionOptions::new(DataType::DataURL, DataType::DataURL) }).unwrap();
+        assert!(data_url.starts_with("data:text/plain;z85,z85v2:"));
+        assert_eq!(z85_to_base64_with_options_internal(&data_url, &ConversionOptions::new(DataType::DataURL, DataType::DataURL)).unwrap(), input);
+    }
+    
+    #[test]
+    fn test_length_padding_format_errors() {
+        // Truncated payloads are detected
+        assert_eq!(decode_z85_internal("nm=QNzY&b1#11").err().unwrap().message, "Z85 data truncated: expected 11 bytes, got 8");
+        assert_eq!(decode_z85_internal("nm=QNzY&b1A+]m^#4").err().unwrap().message, "Length suffix (4) does not match Z85 data (12 bytes)");
+        assert_eq!(decode_z85_internal("nm=QNzY&b1A+]m^#x").err().unwrap().message, "Invalid length number");
+    }
+    
+    #[test]
+    fn test_z85_strict_mode() {
+        // Test vector from ZeroMQ RFC 32
+        let data = [0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B];
+        assert_eq!(encode_z85_strict_internal(&data).unwrap(), "HelloWorld");
+        assert_eq!(decode_z85_strict_internal("HelloWorld").unwrap(), data);
+        assert_eq!(encode_z85_strict_internal(b"").unwrap(), "");
+        
+        assert_eq!(encode_z85_strict_internal(b"abc").err().unwrap().message, "Z85 strict mode: input length (3) is not a multiple of 4");
+        assert_eq!(decode_z85_strict_internal("HelloWorld:0").err().unwrap().message, "Z85 strict mode: input length (12) is not a multiple of 5");
+        assert_eq!(decode_z85_strict_internal("Hello~orld").err().unwrap().message, "Z85 decode error: invalid character at offset 5");
     }
     
     #[test]
@@ -547,40 +1950,87 @@ mod tests {
         // Test missing colon
         let result = decode_z85_internal("no_colon_here");
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap(), "Invalid format: expected 'z85_data:padding'");
+        assert_eq!(result.err().unwrap().message, "Invalid format: expected 'z85_data:padding'");
         
         // Test invalid padding number
         let result = decode_z85_internal("data:not_a_number");
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap(), "Invalid padding number");
+        assert_eq!(result.err().unwrap().message, "Invalid padding number");
         
         // Test invalid Z85 data
         let result = decode_z85_internal("invalid_z85!:0");
         assert!(result.is_err());
-        assert!(result.err().unwrap().contains("Z85 decode error"));
+        assert!(result.err().unwrap().message.contains("Z85 decode error"));
+    }
+    
+    #[test]
+    fn test_decode_z85_reports_invalid_character_offset() {
+        let mut encoded = encode_z85_internal(&[7u8; 400]);
+        encoded.replace_range(123..124, "~");
+        let result = decode_z85_internal(&encoded);
+        assert_eq!(result.err().unwrap().message, "Z85 decode error: invalid character at offset 123");
+        
+        // Padding larger than the decoded data is rejected instead of panicking
+        let result = decode_z85_internal("HelloWorld:9");
+        assert_eq!(result.err().unwrap().message, "Invalid padding number");
+    }
+    
+    #[test]
+    fn test_decode_z85_lenient() {
+        let data = [0x86, 0x4f, 0xd2, 0x6f, 0xb5, 0x59, 0xf7, 0x5b];
+        assert_eq!(decode_z85_lenient_internal("  Hello\r\n\tWorld:0\n", "").unwrap(), data);
+        assert_eq!(decode_z85_lenient_internal("Hello-World_:0", "-_").unwrap(), data);
+        assert_eq!(decode_z85_lenient_internal("Hello World:0", "").unwrap(), decode_z85_internal("HelloWorld:0").unwrap());
+        
+        // Strict decoding is unchanged, and the suffix markers cannot be separators
+        assert!(decode_z85_internal("Hello World:0").is_err());
+        assert_eq!(decode_z85_lenient_internal("Hello\n~orld:0", "").err().unwrap().message, "Z85 decode error: invalid character at offset 5");
+        assert_eq!(decode_z85_lenient_internal("HelloWorld:0", ":").err().unwrap().message, "Invalid separators: ':' and '#' introduce the padding suffix");
+        
+        let lenient = ConversionOptions { lenient: true, ..ConversionOptions::new(DataType::Raw, DataType::Raw) };
+        assert_eq!(z85_to_base64_with_options_internal("Hello\nWorld:0", &lenient).unwrap(), "hk/Sb7VZ91s=");
+    }
+    
+    #[test]
+    fn test_padding_guards() {
+        // Oversized padding is an error on every decode path, never an underflow
+        for input in ["HelloWorld:9", "HelloWorld:18446744073709551615", "Hello:5", ":1"] {
+            assert_eq!(decode_z85_internal(input).err().unwrap().message, "Invalid padding number", "{}", input);
+            assert_eq!(z85_to_base64_internal(input).err().unwrap().message, "Invalid padding number", "{}", input);
+            assert_eq!(decoded_size_hint_internal(input).err().unwrap().message, "Invalid padding number", "{}", input);
+        }
+        assert_eq!(decode_z85_internal("HelloWorld:99999999999999999999999").err().unwrap().message, "Invalid padding number");
+        
+        // Padding that drops whole groups is accepted unless strict padding is enabled
+        assert_eq!(decode_z85_internal("HelloWorld:4").unwrap(), vec![0x86, 0x4f, 0xd2, 0x6f]);
+        set_strict_padding(true);
+        let strict = [decode_z85_internal("HelloWorld:4"), z85_to_base64_internal("HelloWorld:8").map(String::into_bytes)];
+        set_strict_padding(false);
+        assert_eq!(strict[0].clone().err().unwrap().message, "Invalid padding number: 4 is more than 3 (strict padding)");
+        assert_eq!(strict[1].clone().err().unwrap().message, "Invalid padding number: 8 is more than 3 (strict padding)");
     }
     
     #[test]
     fn test_dataurl_edge_cases() {
         // Test DataURL -> Raw with invalid format
-        let result = z85_to_base64_with_options_internal("not_data_url", DataType::DataURL, DataType::Raw);
+        let result = z85_to_base64_with_options_internal("not_data_url", &ConversionOptions::new(DataType::DataURL, DataType::Raw));
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap(), "Invalid data URL format");
+        assert_eq!(result.err().unwrap().message, "Invalid data URL format");
         
         // Test DataURL -> Raw with missing marker
-        let result = z85_to_base64_with_options_internal("data:image/png;base64,data", DataType::DataURL, DataType::Raw);
+        let result = z85_to_base64_with_options_internal("data:image/png;base64,data", &ConversionOptions::new(DataType::DataURL, DataType::Raw));
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap(), "Data URL does not contain ;z85, marker");
+        assert_eq!(result.err().unwrap().message, "Data URL does not contain ;z85, marker");
         
         // Test base64 DataURL -> Raw with invalid format
-        let result = base64_to_z85_with_options_internal("not_data_url", DataType::DataURL, DataType::Raw);
+        let result = base64_to_z85_with_options_internal("not_data_url", &ConversionOptions::new(DataType::DataURL, DataType::Raw));
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap(), "Invalid data URL format");
+        assert_eq!(result.err().unwrap().message, "Invalid data URL format");
         
         // Test base64 DataURL -> Raw with missing marker
-        let result = base64_to_z85_with_options_internal("data:image/png;z85,data", DataType::DataURL, DataType::Raw);
+        let result = base64_to_z85_with_options_internal("data:image/png;z85,data", &ConversionOptions::new(DataType::DataURL, DataType::Raw));
         assert!(result.is_err());
-        assert_eq!(result.err().unwrap(), "Data URL does not contain ;base64, marker");
+        assert_eq!(result.err().unwrap().message, "Data URL does not contain ;base64, marker");
     }
     
     #[test]
@@ -597,6 +2047,14 @@ mod tests {
         
         opts.set_output(DataType::DataURL);
         assert!(matches!(opts.output(), DataType::DataURL));
+        
+        assert_eq!(opts.base64_variant(), Base64Variant::Standard);
+        opts.set_base64_variant(Base64Variant::UrlSafeNoPad);
+        assert_eq!(opts.base64_variant(), Base64Variant::UrlSafeNoPad);
+        
+        assert!(!opts.lenient());
+        opts.set_lenient(true);
+        assert!(opts.lenient());
     }
     
     #[test]
@@ -611,14 +2069,14 @@ mod tests {
         
         for mime_type in mime_types {
             // Test Z85 DataURL -> Base64 DataURL
-            let z85_data = base64_to_z85_internal("SGVsbG8gV29ybGQ=").unwrap();
+            let z85_data = base64_to_z85_internal("SGVsbG8gV29ybGQ=", Base64Variant::Standard).unwrap();
             let input = format!("data:{};z85,{}", mime_type, z85_data);
-            let result = z85_to_base64_with_options_internal(&input, DataType::DataURL, DataType::DataURL).unwrap();
+            let result = z85_to_base64_with_options_internal(&input, &ConversionOptions::new(DataType::DataURL, DataType::DataURL)).unwrap();
             assert_eq!(result, format!("data:{};base64,SGVsbG8gV29ybGQ=", mime_type));
             
             // Test Base64 DataURL -> Z85 DataURL
             let input = format!("data:{};base64,SGVsbG8gV29ybGQ=", mime_type);
-            let result = base64_to_z85_with_options_internal(&input, DataType::DataURL, DataType::DataURL).unwrap();
+            let result = base64_to_z85_with_options_internal(&input, &ConversionOptions::new(DataType::DataURL, DataType::DataURL)).unwrap();
             assert!(result.starts_with(&format!("data:{};z85,", mime_type)));
         }
     }
@@ -653,7 +2111,7 @@ mod tests {
             
             // Test base64 conversion roundtrip
             let base64 = general_purpose::STANDARD.encode(data);
-            let z85 = base64_to_z85_internal(&base64).unwrap();
+            let z85 = base64_to_z85_internal(&base64, Base64Variant::Standard).unwrap();
             let back_to_base64 = z85_to_base64_internal(&z85).unwrap();
             assert_eq!(base64, back_to_base64, "Failed base64 roundtrip for data size: {}", data.len());
         }
@@ -668,16 +2126,16 @@ mod tests {
         #[wasm_bindgen_test]
         fn wasm_test_basic_roundtrip() {
             let original = "SGVsbG8gV29ybGQ=";
-            let z85_result = base64_to_z85(original).unwrap();
-            let back_to_base64 = z85_to_base64(&z85_result).unwrap();
+            let z85_result = base64_to_z85(original, None, None).unwrap();
+            let back_to_base64 = z85_to_base64(&z85_result, None, None).unwrap();
             assert_eq!(original, back_to_base64);
         }
         
         #[wasm_bindgen_test]
         fn wasm_test_encode_decode() {
             let data = b"Hello, World!";
-            let encoded = encode_z85(data).unwrap();
-            let decoded = decode_z85(&encoded).unwrap();
+            let encoded = encode_z85(data, None, None).unwrap();
+            let decoded = decode_z85(&encoded, None).unwrap();
             assert_eq!(data, decoded.as_slice());
         }
         
@@ -686,14 +2144,13 @@ mod tests {
             let opts = ConversionOptions::new(DataType::DataURL, DataType::DataURL);
             let input = "data:image/png;z85,nm=QNzY&b1A+]m^:1";
             let result = z85_to_base64_with_options(input, Some(opts)).unwrap();
-            assert_eq!(result, "data:image/png;base64,SGVsbG8gV29ybGQ=");
+            assert_eq!(result.as_string().unwrap(), "data:image/png;base64,SGVsbG8gV29ybGQ=");
         }
         
         #[wasm_bindgen_test]
         fn wasm_test_efficiency() {
             let result = get_encoding_efficiency(1000);
-            // Just verify it doesn't panic
-            assert!(result.is_object());
+            assert_eq!(result.z85_size(), 1250);
         }
     }
 }
\ No newline at end of file
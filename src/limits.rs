@@ -0,0 +1,123 @@
+use wasm_bindgen::prelude::*;
+use serde::Deserialize;
+use std::cell::Cell;
+use tsify::Tsify;
+
+use crate::error::{js_error, Error, ErrorCode};
+
+/// Limits set by `set_limits`; a missing field means no limit
+#[derive(Deserialize, Tsify, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(default)]
+struct Limits {
+    #[tsify(optional)]
+    max_input_bytes: Option<usize>,
+    #[tsify(optional)]
+    max_output_bytes: Option<usize>,
+}
+
+#[wasm_bindgen]
+extern "C" {
+    // A `Limits` object passed from JS
+    #[wasm_bindgen(typescript_type = "Limits")]
+    pub type JsLimits;
+}
+
+thread_local! {
+    static LIMITS: Cell<Limits> = const { Cell::new(Limits { max_input_bytes: None, max_output_bytes: None }) };
+}
+
+fn current() -> Limits {
+    LIMITS.with(Cell::get)
+}
+
+// Run `f` under the given limits, then remove them
+#[cfg(test)]
+pub(crate) fn with_limits<T>(max_input_bytes: Option<usize>, max_output_bytes: Option<usize>, f: impl FnOnce() -> T) -> T {
+    LIMITS.with(|cell| cell.set(Limits { max_input_bytes, max_output_bytes }));
+    let result = f();
+    LIMITS.with(|cell| cell.set(Limits::default()));
+    result
+}
+
+// Reject input longer than `max_input_bytes`
+pub(crate) fn check_input(len: usize) -> Result<(), Error> {
+    match current().max_input_bytes {
+        Some(max) if len > max => Err(Error::new(ErrorCode::LimitExceeded, format!("Input size limit exceeded: {} bytes (max_input_bytes is {})", len, max))),
+        _ => Ok(()),
+    }
+}
+
+// Reject output longer than `max_output_bytes`
+pub(crate) fn check_output(len: usize) -> Result<(), Error> {
+    match current().max_output_bytes {
+        Some(max) if len > max => Err(Error::new(ErrorCode::LimitExceeded, format!("Output size limit exceeded: {} bytes (max_output_bytes is {})", len, max))),
+        _ => Ok(()),
+    }
+}
+
+// Largest output allowed, for decoders that must stop before producing more (decompression)
+pub(crate) fn max_output_bytes() -> Option<usize> {
+    current().max_output_bytes
+}
+
+// Run a conversion under the current limits: the input is checked before `f` runs and the
+// output after. Codecs grow their input by at most 25%, so the input check bounds allocation.
+pub(crate) fn enforce<T>(bytes_in: usize, output_len: impl Fn(&T) -> usize, f: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+    check_input(bytes_in)?;
+    let output = f()?;
+    check_output(output_len(&output))?;
+    Ok(output)
+}
+
+/// Cap the size of the input to and output of every encode/decode function, so untrusted data
+/// fails with `LIMIT_EXCEEDED` instead of exhausting memory. Takes
+/// `{ max_input_bytes?: number, max_output_bytes?: number }`; omitted fields (or `undefined`)
+/// remove the limit. Streaming encoders and decoders hold one chunk at a time and are not limited.
+#[wasm_bindgen]
+pub fn set_limits(limits: Option<JsLimits>) -> Result<(), JsValue> {
+    let limits = match limits {
+        None => Limits::default(),
+        Some(limits) => serde_wasm_bindgen::from_value(limits.into())
+            .map_err(|e| js_error(Error::new(ErrorCode::InvalidOptions, format!("Invalid limits: {}", e))))?,
+    };
+    LIMITS.with(|cell| cell.set(limits));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::Compression;
+
+    #[test]
+    fn test_enforce() {
+        with_limits(Some(8), Some(10), || {
+            assert_eq!(enforce(8, String::len, || Ok("HelloWorld".to_string())).unwrap(), "HelloWorld");
+            assert_eq!(
+                enforce(9, String::len, || -> Result<String, Error> { panic!("must not run") }).err().unwrap().message,
+                "Input size limit exceeded: 9 bytes (max_input_bytes is 8)"
+            );
+            assert_eq!(
+                enforce(4, String::len, || Ok("HelloWorld:0".to_string())).err().unwrap().message,
+                "Output size limit exceeded: 12 bytes (max_output_bytes is 10)"
+            );
+        });
+        assert!(enforce(1 << 20, Vec::len, || Ok(vec![0u8; 1 << 20])).is_ok());
+    }
+
+    #[test]
+    fn test_limits_apply_to_conversions() {
+        let bombs = [Compression::Deflate, Compression::Zstd]
+            .map(|compression| crate::encode_z85_with_options_internal(&[0; 1000], crate::PaddingFormat::Count, compression));
+        let (encoded, decoded, inflated) = with_limits(Some(12), Some(64), || (
+            crate::telemetry::instrument("encode_z85", 13, String::len, || Ok(crate::encode_z85_internal(&[0; 13]))),
+            crate::telemetry::instrument("decode_z85", 12, Vec::len, || crate::decode_z85_internal("HelloWorld:0")),
+            bombs.map(|bomb| crate::decode_z85_internal(&bomb)),
+        ));
+        assert_eq!(encoded.err().unwrap().message, "Input size limit exceeded: 13 bytes (max_input_bytes is 12)");
+        assert_eq!(decoded.unwrap().len(), 8);
+        for result in inflated {
+            assert_eq!(result.err().unwrap().message, "Output size limit exceeded: decompressed data is larger than 64 bytes");
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Diagnostics logging.
+//!
+//! Messages at or above the level set by `set_log_level` (`LogLevel.Warn` by default) go to the
+//! logger registered with `set_logger`, or to the console (stderr under WASI). Messages are only
+//! formatted when their level is enabled, so debug diagnostics cost nothing in production.
+//! Builds without the default `console` feature import no `console` functions and drop
+//! messages when no logger is registered.
+
+use wasm_bindgen::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+/// Verbosity of the module's diagnostics
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    /// Log nothing
+    Off,
+    /// Failures the module recovered from
+    Error,
+    /// Suspicious input or configuration (the default)
+    Warn,
+    /// Lifecycle messages, such as initialization
+    Info,
+    /// Slow paths taken and lenient-mode fixups applied
+    Debug,
+}
+
+impl LogLevel {
+    // Name passed to loggers
+    fn name(self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        }
+    }
+}
+
+#[cfg(all(feature = "console", target_arch = "wasm32", not(target_os = "wasi")))]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console, js_name = error)]
+    fn console_error(s: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = warn)]
+    fn console_warn(s: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = info)]
+    fn console_info(s: &str);
+    #[wasm_bindgen(js_namespace = console, js_name = debug)]
+    fn console_debug(s: &str);
+}
+
+type LogSink = Box<dyn Fn(LogLevel, &str)>;
+
+#[wasm_bindgen]
+extern "C" {
+    // A `set_logger` callback
+    #[wasm_bindgen(typescript_type = "((level: \"error\" | \"warn\" | \"info\" | \"debug\", message: string) => void)")]
+    pub type Logger;
+}
+
+thread_local! {
+    static LOG_LEVEL: Cell<LogLevel> = const { Cell::new(LogLevel::Warn) };
+    static LOGGER: RefCell<Option<LogSink>> = RefCell::new(None);
+}
+
+// Replace the current logger
+fn set_sink(sink: Option<LogSink>) {
+    LOGGER.with(|cell| *cell.borrow_mut() = sink);
+}
+
+// Write to the console method matching the level
+#[cfg(all(feature = "console", target_arch = "wasm32", not(target_os = "wasi")))]
+fn console(level: LogLevel, message: &str) {
+    match level {
+        LogLevel::Off => {}
+        LogLevel::Error => console_error(message),
+        LogLevel::Warn => console_warn(message),
+        LogLevel::Info => console_info(message),
+        LogLevel::Debug => console_debug(message),
+    }
+}
+
+// Write to stderr, as there is no console in native and WASI builds
+#[cfg(any(not(target_arch = "wasm32"), target_os = "wasi"))]
+fn console(level: LogLevel, message: &str) {
+    eprintln!("[z85-wasm {}] {}", level.name(), message);
+}
+
+// Drop the message: without the `console` feature only a `set_logger` logger receives it
+#[cfg(all(not(feature = "console"), target_arch = "wasm32", not(target_os = "wasi")))]
+fn console(_level: LogLevel, _message: &str) {}
+
+// Log a message if `level` is enabled
+pub(crate) fn log(level: LogLevel, args: fmt::Arguments) {
+    if level == LogLevel::Off || level > LOG_LEVEL.with(Cell::get) {
+        return;
+    }
+    let message = args.to_string();
+    LOGGER.with(|cell| match cell.borrow().as_ref() {
+        Some(sink) => sink(level, &message),
+        None => console(level, &message),
+    });
+}
+
+/// Only log messages at `level` or more severe (default `LogLevel.Warn`; `LogLevel.Off` silences
+/// the module)
+#[wasm_bindgen]
+pub fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.with(|cell| cell.set(level));
+}
+
+/// Send log messages to `logger(level, message)` instead of the console, where `level` is
+/// `"error"`, `"warn"`, `"info"` or `"debug"`; pass `undefined` to go back to the console.
+/// Exceptions thrown by the logger are ignored.
+#[wasm_bindgen]
+pub fn set_logger(logger: Option<Logger>) {
+    set_sink(logger.map(|logger| -> LogSink {
+        let logger: js_sys::Function = logger.unchecked_into();
+        Box::new(move |level, message| {
+            let _ = logger.call2(&JsValue::NULL, &JsValue::from_str(level.name()), &JsValue::from_str(message));
+        })
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_log_levels() {
+        let messages = Rc::new(RefCell::new(Vec::new()));
+        let sink_messages = messages.clone();
+        set_sink(Some(Box::new(move |level, message| sink_messages.borrow_mut().push(format!("{}: {}", level.name(), message)))));
+
+        log(LogLevel::Warn, format_args!("shown {}", 1));
+        log(LogLevel::Debug, format_args!("hidden"));
+        set_log_level(LogLevel::Debug);
+        log(LogLevel::Debug, format_args!("shown {}", 2));
+        set_log_level(LogLevel::Off);
+        log(LogLevel::Error, format_args!("hidden"));
+        set_log_level(LogLevel::Warn);
+        set_sink(None);
+
+        assert_eq!(*messages.borrow(), ["warn: shown 1", "debug: shown 2"]);
+    }
+}
@@ -0,0 +1,48 @@
+//! `z85-wasm` command: streams stdin to stdout through the module's encoder or decoder.
+//! Built for WASI it runs under wasmtime or other WASI runtimes:
+//!
+//! ```text
+//! cargo build --release --target wasm32-wasip1 --bin z85-wasm
+//! wasmtime target/wasm32-wasip1/release/z85-wasm.wasm encode < file.bin > file.z85
+//! ```
+
+#[cfg(not(feature = "min-size"))]
+use std::io;
+use std::process::ExitCode;
+
+#[cfg(not(feature = "min-size"))]
+use z85_wasm::stdio::{decode_stream, encode_stream};
+
+#[cfg(not(feature = "min-size"))]
+const USAGE: &str = "Usage: z85-wasm encode|decode < input > output";
+
+#[cfg(not(feature = "min-size"))]
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["encode"] => encode_stream(io::stdin().lock(), io::stdout().lock()),
+        ["decode"] => decode_stream(io::stdin().lock(), io::stdout().lock()),
+        ["-h" | "--help"] => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("z85-wasm: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// `min-size` builds leave out the stream encoder and decoder
+#[cfg(feature = "min-size")]
+fn main() -> ExitCode {
+    eprintln!("z85-wasm: the min-size build has no stdio mode");
+    ExitCode::FAILURE
+}
@@ -0,0 +1,147 @@
+//! Memory usage introspection.
+//!
+//! Heap usage is counted by a global allocator wrapping the system one, so it covers every
+//! allocation in the module. Sessions report the capacity of their buffers as it changes.
+
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tsify::Tsify;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::view;
+
+/// System allocator that counts the bytes in use and their peak
+struct CountingAllocator;
+
+static HEAP_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_HEAP_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn record_alloc(size: usize) {
+    let in_use = HEAP_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK_HEAP_BYTES.fetch_max(in_use, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        HEAP_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                record_alloc(new_size - layout.size());
+            } else {
+                HEAP_BYTES.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+            }
+        }
+        new_ptr
+    }
+}
+
+thread_local! {
+    static SESSION_BYTES: Cell<usize> = const { Cell::new(0) };
+}
+
+// Record that a session's buffers went from `old` to `new` bytes of capacity
+pub(crate) fn adjust_session_bytes(old: usize, new: usize) {
+    // Sessions dropped while the module is torn down have nothing left to report to
+    let _ = SESSION_BYTES.try_with(|bytes| bytes.set(bytes.get() + new - old));
+}
+
+// Size of the WASM linear memory; zero outside WASM
+fn linear_memory_bytes() -> usize {
+    #[cfg(target_arch = "wasm32")]
+    return core::arch::wasm32::memory_size(0) * 65536;
+    #[cfg(not(target_arch = "wasm32"))]
+    0
+}
+
+/// Snapshot returned by `memory_stats`
+#[derive(Serialize, Tsify, Debug)]
+struct MemoryStats {
+    /// Size of the WASM linear memory, which grows but never shrinks
+    memory_bytes: usize,
+    /// Heap bytes allocated and not yet freed
+    heap_bytes: usize,
+    /// Most heap bytes in use at once since `reset_peak` (or startup)
+    peak_heap_bytes: usize,
+    /// Buffer capacity held by live `Z85Session`s
+    session_bytes: usize,
+    /// Bytes held for `EncodedView`s and reused decode buffers
+    arena_bytes: usize,
+}
+
+// Internal pure Rust function for taking a memory snapshot
+fn memory_stats_internal() -> MemoryStats {
+    #[cfg(feature = "node")]
+    let scratch_bytes = crate::node::scratch_bytes();
+    #[cfg(not(feature = "node"))]
+    let scratch_bytes = 0;
+    MemoryStats {
+        memory_bytes: linear_memory_bytes(),
+        heap_bytes: HEAP_BYTES.load(Ordering::Relaxed),
+        peak_heap_bytes: PEAK_HEAP_BYTES.load(Ordering::Relaxed),
+        session_bytes: SESSION_BYTES.with(Cell::get),
+        arena_bytes: view::arena_bytes() + scratch_bytes,
+    }
+}
+
+/// Current memory usage: `{ memory_bytes, heap_bytes, peak_heap_bytes, session_bytes, arena_bytes }`
+#[wasm_bindgen(unchecked_return_type = "MemoryStats")]
+pub fn memory_stats() -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&memory_stats_internal())
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
+}
+
+/// Restart `peak_heap_bytes` from the current heap usage, e.g. before a workload to measure
+#[wasm_bindgen]
+pub fn reset_peak() {
+    PEAK_HEAP_BYTES.store(HEAP_BYTES.load(Ordering::Relaxed), Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::Z85Session;
+
+    #[test]
+    fn test_memory_stats() {
+        reset_peak();
+        let buffer = vec![1u8; 32 << 20];
+        let stats = memory_stats_internal();
+        // Other tests allocate concurrently, so only lower bounds hold
+        assert!(stats.heap_bytes >= buffer.len() && stats.peak_heap_bytes >= buffer.len());
+        drop(buffer);
+
+        let before = memory_stats_internal().session_bytes;
+        let mut session = Z85Session::new();
+        session.reserve(1 << 20);
+        assert!(memory_stats_internal().session_bytes >= before + (1 << 20));
+        drop(session);
+        assert_eq!(memory_stats_internal().session_bytes, before);
+    }
+}
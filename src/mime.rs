@@ -0,0 +1,183 @@
+use base64::Engine;
+
+use crate::encoding::Base64Variant;
+use crate::error::{Error, ErrorCode};
+
+/// MIME type used when no signature matches
+pub(crate) const DEFAULT_MIME_TYPE: &str = "application/octet-stream";
+
+/// Number of leading bytes inspected when sniffing
+pub(crate) const SNIFF_BYTES: usize = 256;
+
+/// Fixed signatures: (offset, magic bytes, MIME type)
+const SIGNATURES: &[(usize, &[u8], &str)] = &[
+    (0, b"\x89PNG\r\n\x1a\n", "image/png"),
+    (0, b"\xff\xd8\xff", "image/jpeg"),
+    (0, b"GIF87a", "image/gif"),
+    (0, b"GIF89a", "image/gif"),
+    (0, b"II*\0", "image/tiff"),
+    (0, b"MM\0*", "image/tiff"),
+    (0, b"\0\0\x01\0", "image/x-icon"),
+    (0, b"BM", "image/bmp"),
+    (0, b"%PDF-", "application/pdf"),
+    (0, b"PK\x03\x04", "application/zip"),
+    (0, b"\x1f\x8b", "application/gzip"),
+    (0, b"\x28\xb5\x2f\xfd", "application/zstd"),
+    (0, b"\0asm", "application/wasm"),
+    (0, b"wOF2", "font/woff2"),
+    (0, b"wOFF", "font/woff"),
+    (0, b"OTTO", "font/otf"),
+    (0, b"\0\x01\0\0", "font/ttf"),
+    (0, b"ID3", "audio/mpeg"),
+    (0, b"\xff\xfb", "audio/mpeg"),
+    (0, b"\xff\xf3", "audio/mpeg"),
+    (0, b"\xff\xf2", "audio/mpeg"),
+    (0, b"OggS", "audio/ogg"),
+    (0, b"fLaC", "audio/flac"),
+    (0, b"\x1a\x45\xdf\xa3", "video/webm"),
+];
+
+// Sniff the MIME type of a RIFF container (WebP, WAV, AVI)
+fn sniff_riff(data: &[u8]) -> Option<&'static str> {
+    if !data.starts_with(b"RIFF") {
+        return None;
+    }
+    match data.get(8..12)? {
+        b"WEBP" => Some("image/webp"),
+        b"WAVE" => Some("audio/wav"),
+        b"AVI " => Some("video/x-msvideo"),
+        _ => None,
+    }
+}
+
+// Sniff the MIME type of an ISO base media file (MP4, AVIF, HEIC, QuickTime) from its `ftyp` brand
+fn sniff_iso_media(data: &[u8]) -> Option<&'static str> {
+    if data.get(4..8)? != b"ftyp" {
+        return None;
+    }
+    match data.get(8..12)? {
+        b"avif" | b"avis" => Some("image/avif"),
+        b"heic" | b"heix" | b"mif1" => Some("image/heic"),
+        b"qt  " => Some("video/quicktime"),
+        b"M4A " => Some("audio/mp4"),
+        _ => Some("video/mp4"),
+    }
+}
+
+// Sniff markup (SVG, HTML, XML) after an optional BOM and leading whitespace
+fn sniff_markup(data: &[u8]) -> Option<&'static str> {
+    let text = data.strip_prefix(b"\xef\xbb\xbf").unwrap_or(data);
+    let start = text.iter().position(|b| !b.is_ascii_whitespace())?;
+    let text = &text[start..];
+    let starts_with_ignore_case = |prefix: &[u8]| {
+        text.len() >= prefix.len() && text[..prefix.len()].eq_ignore_ascii_case(prefix)
+    };
+
+    if starts_with_ignore_case(b"<svg") {
+        Some("image/svg+xml")
+    } else if starts_with_ignore_case(b"<!doctype html") || starts_with_ignore_case(b"<html") {
+        Some("text/html")
+    } else if text.starts_with(b"<?xml") {
+        // An XML declaration followed by an SVG root within the sniffed window
+        let is_svg = text.windows(4).any(|window| window == b"<svg");
+        Some(if is_svg { "image/svg+xml" } else { "application/xml" })
+    } else {
+        None
+    }
+}
+
+// Whether `text` is an RFC 2045 token (printable ASCII without separators)
+fn is_token(text: &str) -> bool {
+    !text.is_empty() && text.bytes().all(|b| b.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?=".contains(&b))
+}
+
+// Check a caller-supplied `type/subtype` MIME type before it is written into a data URL
+pub(crate) fn validate_mime_type(mime_type: &str) -> Result<&str, Error> {
+    match mime_type.split_once('/') {
+        Some((kind, subtype)) if is_token(kind) && is_token(subtype) => Ok(mime_type),
+        _ => Err(Error::new(ErrorCode::BadDataUrl, format!("Invalid MIME type: {}", mime_type))),
+    }
+}
+
+// Detect the MIME type of raw bytes from their leading magic bytes,
+// falling back to `application/octet-stream`
+pub(crate) fn sniff_mime_type(data: &[u8]) -> &'static str {
+    let data = &data[..data.len().min(SNIFF_BYTES)];
+    SIGNATURES.iter()
+        .find(|(offset, magic, _)| data.get(*offset..).is_some_and(|rest| rest.starts_with(magic)))
+        .map(|(_, _, mime_type)| *mime_type)
+        .or_else(|| sniff_riff(data))
+        .or_else(|| sniff_iso_media(data))
+        .or_else(|| sniff_markup(data))
+        .unwrap_or(DEFAULT_MIME_TYPE)
+}
+
+// Detect the MIME type of base64 data, decoding only the leading characters needed.
+// Invalid base64 sniffs as `application/octet-stream`; the conversion itself reports the error.
+pub(crate) fn sniff_base64_mime_type(base64_data: &str, variant: Base64Variant) -> &'static str {
+    let prefix_chars = SNIFF_BYTES / 3 * 4;
+    let prefix = if base64_data.len() <= prefix_chars {
+        base64_data
+    } else {
+        base64_data.get(..prefix_chars).unwrap_or_default()
+    };
+    match variant.engine().decode(prefix) {
+        Ok(bytes) => sniff_mime_type(&bytes),
+        Err(_) => DEFAULT_MIME_TYPE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_signatures() {
+        let cases: &[(&[u8], &str)] = &[
+            (b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", "image/png"),
+            (b"\xff\xd8\xff\xe0\0\x10JFIF", "image/jpeg"),
+            (b"GIF89a\x01\0\x01\0", "image/gif"),
+            (b"RIFF\x24\0\0\0WEBPVP8 ", "image/webp"),
+            (b"RIFF\x24\0\0\0WAVEfmt ", "audio/wav"),
+            (b"%PDF-1.7\n", "application/pdf"),
+            (b"\0\0\0\x20ftypisom\0\0\x02\0", "video/mp4"),
+            (b"\0\0\0\x1cftypavif\0\0\0\0", "image/avif"),
+            (b"wOF2\0\x01\0\0", "font/woff2"),
+            (b"PK\x03\x04\x14\0", "application/zip"),
+            (b"\0asm\x01\0\0\0", "application/wasm"),
+            (b"\xef\xbb\xbf  <svg xmlns=\"http://www.w3.org/2000/svg\"/>", "image/svg+xml"),
+            (b"<?xml version=\"1.0\"?>\n<svg/>", "image/svg+xml"),
+            (b"<?xml version=\"1.0\"?>\n<feed/>", "application/xml"),
+            (b"<!DOCTYPE html><html>", "text/html"),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(sniff_mime_type(data), *expected, "{:?}", data);
+        }
+    }
+
+    #[test]
+    fn test_sniff_fallback() {
+        assert_eq!(sniff_mime_type(b""), DEFAULT_MIME_TYPE);
+        assert_eq!(sniff_mime_type(b"Hello World"), DEFAULT_MIME_TYPE);
+        assert_eq!(sniff_mime_type(b"RIFF\0\0\0\0XXXX"), DEFAULT_MIME_TYPE);
+    }
+
+    #[test]
+    fn test_sniff_base64() {
+        let png = [b"\x89PNG\r\n\x1a\n".as_slice(), &[0u8; 1000]].concat();
+        assert_eq!(sniff_base64_mime_type(&Base64Variant::Standard.engine().encode(&png), Base64Variant::Standard), "image/png");
+        assert_eq!(sniff_base64_mime_type(&Base64Variant::UrlSafeNoPad.engine().encode(&png), Base64Variant::UrlSafeNoPad), "image/png");
+        assert_eq!(sniff_base64_mime_type("R0lGODlh", Base64Variant::Standard), "image/gif");
+        assert_eq!(sniff_base64_mime_type("not valid base64!", Base64Variant::Standard), DEFAULT_MIME_TYPE);
+        assert_eq!(sniff_base64_mime_type("", Base64Variant::Standard), DEFAULT_MIME_TYPE);
+    }
+
+    #[test]
+    fn test_validate_mime_type() {
+        assert_eq!(validate_mime_type("image/svg+xml").unwrap(), "image/svg+xml");
+        assert_eq!(validate_mime_type("application/vnd.ms-excel").unwrap(), "application/vnd.ms-excel");
+        for invalid in ["", "image", "image/", "/png", "image/png;charset=utf-8", "text/plain,x", "a/b/c", "image/p ng"] {
+            assert_eq!(validate_mime_type(invalid).err().unwrap().message, format!("Invalid MIME type: {}", invalid));
+        }
+    }
+}
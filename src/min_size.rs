@@ -0,0 +1,150 @@
+//! Exports of `min-size` builds: Z85 with padding suffixes, base64 conversions, strict ZeroMQ
+//! Z85 and `get_encoding_efficiency`, built directly on `z85_core`.
+//!
+//! Nothing here formats an error message or serializes through serde, so neither the detailed
+//! messages nor serde are linked: errors are `Z85Error`s with the `code` and `offset` of the full
+//! build and a short fixed message. `z85v2:` containers are rejected with `UNSUPPORTED`, which keeps the deflate and
+//! Zstandard code out of the module.
+
+use wasm_bindgen::prelude::*;
+use base64::{engine::general_purpose, DecodeError, Engine};
+use std::cell::Cell;
+use z85_core::codec::{self, Z85};
+use z85_core::container::V2_PREFIX;
+use z85_core::{padding, Error};
+
+use crate::error::{coded_error, core_error_offset, ErrorCode};
+
+// Message, code and offset of the `Z85Error` for a core error; the code and offset are those of
+// the full build
+fn z85_error_parts(error: Error) -> (&'static str, ErrorCode, Option<u32>) {
+    let message = match error {
+        Error::InvalidCharacter { .. } => "Z85 decode error: invalid character",
+        Error::InvalidGroup { .. } => "Z85 decode error: invalid group",
+        Error::InvalidDataLength { .. } | Error::StrictLength { .. } => "Z85 error: invalid length",
+        Error::MissingSuffix => "Invalid format: expected 'z85_data:padding'",
+        Error::InvalidPadding | Error::StrictPadding { .. } | Error::InvalidLengthSuffix | Error::Truncated { .. } | Error::LengthMismatch { .. } => {
+            "Invalid padding number"
+        }
+        _ => "Invalid input",
+    };
+    (message, ErrorCode::of(&error), core_error_offset(&error))
+}
+
+fn z85_error(error: Error) -> JsValue {
+    let (message, code, offset) = z85_error_parts(error);
+    coded_error(message, code, offset)
+}
+
+// Message and code of the `Z85Error` for a base64 decode error; the code is that of the full build
+fn base64_error_parts(error: DecodeError) -> (&'static str, ErrorCode) {
+    let message = match error {
+        DecodeError::InvalidLength(_) => "Base64 decode error: invalid length",
+        DecodeError::InvalidPadding => "Base64 decode error: invalid padding",
+        DecodeError::InvalidByte(..) | DecodeError::InvalidLastSymbol(..) => "Base64 decode error: invalid symbol",
+    };
+    (message, ErrorCode::of_base64(&error))
+}
+
+fn base64_error(error: DecodeError) -> JsValue {
+    let (message, code) = base64_error_parts(error);
+    coded_error(message, code, None)
+}
+
+// Decode Z85 with a `:padding` or `#length` suffix, honouring `set_strict_padding`
+fn decode(z85_data_with_padding: &str) -> Result<Vec<u8>, JsValue> {
+    if z85_data_with_padding.starts_with(V2_PREFIX) {
+        return Err(coded_error("Unsupported: z85v2 containers need a build without min-size", ErrorCode::Unsupported, None));
+    }
+    let strict = crate::STRICT_PADDING.with(Cell::get);
+    let (z85_data, padding) = padding::split_padding_suffix(z85_data_with_padding, strict)
+        .map_err(z85_error)?;
+    let mut decoded = vec![0u8; z85_data.len() / 5 * 4 - padding];
+    codec::decode_into_with(&Z85, z85_data.as_bytes(), padding, &mut decoded)
+        .map_err(z85_error)?;
+    Ok(decoded)
+}
+
+/// Encode raw bytes to Z85 with padding info
+#[wasm_bindgen]
+pub fn encode_z85(data: &[u8]) -> String {
+    z85_core::encode(data)
+}
+
+/// Decode Z85 data with padding info to raw bytes
+#[wasm_bindgen]
+pub fn decode_z85(z85_data_with_padding: &str) -> Result<Vec<u8>, JsValue> {
+    decode(z85_data_with_padding)
+}
+
+/// Convert Z85 encoded data with padding info to standard base64
+#[wasm_bindgen]
+pub fn z85_to_base64(z85_data_with_padding: &str) -> Result<String, JsValue> {
+    Ok(general_purpose::STANDARD.encode(decode(z85_data_with_padding)?))
+}
+
+/// Convert standard base64 data to Z85 with padding info
+#[wasm_bindgen]
+pub fn base64_to_z85(base64_data: &str) -> Result<String, JsValue> {
+    let decoded = general_purpose::STANDARD.decode(base64_data)
+        .map_err(base64_error)?;
+    Ok(z85_core::encode(&decoded))
+}
+
+/// Encode raw bytes to Z85 exactly as specified by ZeroMQ RFC 32 (compatible with `zmq_z85_encode`).
+/// The input length must be a multiple of 4; no `:padding` suffix is appended.
+#[wasm_bindgen]
+pub fn encode_z85_strict(data: &[u8]) -> Result<String, JsValue> {
+    z85_core::encode_strict(data)
+        .map_err(z85_error)
+}
+
+/// Decode Z85 text exactly as specified by ZeroMQ RFC 32 (compatible with `zmq_z85_decode`).
+/// The input length must be a multiple of 5 and must not carry a `:padding` suffix.
+#[wasm_bindgen]
+pub fn decode_z85_strict(z85_data: &str) -> Result<Vec<u8>, JsValue> {
+    z85_core::decode_strict(z85_data)
+        .map_err(z85_error)
+}
+
+/// Get encoding efficiency comparison
+#[wasm_bindgen]
+pub fn get_encoding_efficiency(original_size: usize) -> crate::EfficiencyReport {
+    crate::get_encoding_efficiency_internal(original_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_size_roundtrip() {
+        assert_eq!(encode_z85(b"Hello World"), "nm=QNzY&b1A+]m^:1");
+        assert_eq!(decode_z85("nm=QNzY&b1A+]m^:1").unwrap(), b"Hello World");
+        assert_eq!(decode_z85("nm=QNzY&b1A+]m^#11").unwrap(), b"Hello World");
+        assert_eq!(z85_to_base64("nm=QNzY&b1A+]m^:1").unwrap(), "SGVsbG8gV29ybGQ=");
+        assert_eq!(base64_to_z85("SGVsbG8gV29ybGQ=").unwrap(), "nm=QNzY&b1A+]m^:1");
+        assert_eq!(decode_z85_strict(&encode_z85_strict(b"abcd").unwrap()).unwrap(), b"abcd");
+    }
+
+    #[test]
+    fn test_min_size_error_codes() {
+        // Codes and offsets match those of the full build
+        let cases = [
+            (z85_core::decode("nm=Q~zY&b1A+]m^:1").unwrap_err(), "INVALID_CHAR", Some(4)),
+            (z85_core::decode("%%%%%:0").unwrap_err(), "INVALID_GROUP", Some(0)),
+            (z85_core::decode("nm=Q:0").unwrap_err(), "INVALID_LENGTH", None),
+            (z85_core::decode("nm=QN").unwrap_err(), "INVALID_FORMAT", None),
+            (z85_core::decode("nm=QN:9").unwrap_err(), "INVALID_PADDING", None),
+            (z85_core::decode("nm=QN#9").unwrap_err(), "INVALID_PADDING", None),
+        ];
+        for (error, code, offset) in cases {
+            let (_, actual_code, actual_offset) = z85_error_parts(error.clone());
+            assert_eq!((actual_code.name(), actual_offset), (code, offset), "{}", error);
+        }
+
+        let decode_base64 = |text: &str| general_purpose::STANDARD.decode(text).unwrap_err();
+        assert_eq!(base64_error_parts(decode_base64("SGVsb")).1, ErrorCode::InvalidLength);
+        assert_eq!(base64_error_parts(decode_base64("SG!s")).1, ErrorCode::InvalidInput);
+    }
+}
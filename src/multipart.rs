@@ -0,0 +1,335 @@
+use wasm_bindgen::prelude::*;
+use sha2::{Digest, Sha256};
+
+use crate::erasure;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::{decode_z85_internal, encode_z85_internal};
+
+/// Prefix identifying one part of a split payload
+const PART_PREFIX: &str = "z85p:";
+
+/// Hex digits of the SHA-256 prefix identifying the whole payload in every part
+const HASH_HEX_LEN: usize = 16;
+
+// First 8 bytes of the SHA-256 digest of the whole payload, as hex
+fn payload_hash(data: &[u8]) -> String {
+    Sha256::digest(data)[..HASH_HEX_LEN / 2].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Header of one part: `z85p:part=<index>/<total>;sha256=<hash>:` before its Z85 data, with
+/// `;parity=<count>;len=<bytes>` after the hash when the last `parity` parts are Reed-Solomon parity
+#[derive(Debug, PartialEq)]
+struct PartHeader {
+    /// 1-based position of the part
+    index: usize,
+    total: usize,
+    hash: String,
+    /// Number of parity parts among `total` (0 without erasure coding)
+    parity: usize,
+    /// Payload length, needed to trim the zero padding of the data parts when `parity` > 0
+    length: Option<usize>,
+}
+
+impl PartHeader {
+    // Parse a part, returning its header and Z85 data with padding info.
+    // Unknown parameters are ignored so later versions can add fields.
+    fn parse(part: &str) -> Result<(PartHeader, &str), Error> {
+        let rest = part.trim().strip_prefix(PART_PREFIX)
+            .ok_or_else(|| Error::new(ErrorCode::BadContainer, format!("Multi-part error: expected '{}' prefix", PART_PREFIX)))?;
+        let (header, z85_data) = rest.split_once(':')
+            .ok_or_else(|| Error::new(ErrorCode::BadContainer, "Multi-part error: missing ':' before data"))?;
+
+        let mut position = None;
+        let mut hash = None;
+        let mut parity = Some(0);
+        let mut length = None;
+        for param in header.split(';') {
+            match param.split_once('=') {
+                Some(("part", value)) => position = value.split_once('/')
+                    .and_then(|(index, total)| Some((index.parse::<usize>().ok()?, total.parse::<usize>().ok()?)))
+                    .filter(|&(index, total)| (1..=total).contains(&index)),
+                Some(("sha256", value)) if value.len() == HASH_HEX_LEN && value.bytes().all(|c| c.is_ascii_hexdigit()) => {
+                    hash = Some(value.to_ascii_lowercase());
+                }
+                Some(("parity", value)) => parity = value.parse::<usize>().ok(),
+                Some(("len", value)) => length = value.parse::<usize>().ok(),
+                _ => {}
+            }
+        }
+        let (index, total) = position.ok_or_else(|| Error::new(ErrorCode::BadContainer, format!("Multi-part error: invalid or missing part number in '{}'", header)))?;
+        let hash = hash.ok_or_else(|| Error::new(ErrorCode::BadContainer, format!("Multi-part error: invalid or missing sha256 in '{}'", header)))?;
+        let parity = parity.filter(|&parity| parity < total && (parity == 0 || length.is_some()))
+            .ok_or_else(|| Error::new(ErrorCode::BadContainer, format!("Multi-part error: invalid parity or len in '{}'", header)))?;
+        if parity > 0 && total > erasure::MAX_SHARDS {
+            return Err(Error::new(ErrorCode::BadContainer, format!(
+                "Multi-part error: {} parts with parity exceed the limit of {}",
+                total, erasure::MAX_SHARDS
+            )));
+        }
+        Ok((PartHeader { index, total, hash, parity, length }, z85_data))
+    }
+
+    fn encode(&self, z85_data: &str) -> String {
+        let mut part = format!("{}part={}/{};sha256={}", PART_PREFIX, self.index, self.total, self.hash);
+        if let Some(length) = self.length.filter(|_| self.parity > 0) {
+            part.push_str(&format!(";parity={};len={}", self.parity, length));
+        }
+        format!("{}:{}", part, z85_data)
+    }
+}
+
+// Internal pure Rust function for splitting bytes into Z85 parts of at most `max_part_size`
+// characters each, header included. Every part but the last carries a multiple of 4 bytes.
+// With `parity` > 0, that many Reed-Solomon parity parts follow the data parts (which are then
+// zero-padded to equal size), and any `total - parity` parts rebuild the payload.
+fn split_z85_internal(data: &[u8], max_part_size: usize, parity: usize) -> Result<Vec<String>, Error> {
+    let hash = payload_hash(data);
+    let length = (parity > 0).then_some(data.len());
+    let parity_param_len = length.map_or(0, |length| format!(";parity={};len={}", parity, length).len());
+
+    // The header grows with the number of digits in the part count, which depends on the chunk size
+    let mut digits = 1;
+    loop {
+        let header_len = PART_PREFIX.len() + "part=/;sha256=:".len() + 2 * digits + HASH_HEX_LEN + parity_param_len;
+        // Each part ends with a one-digit `:padding` suffix
+        let chunk_len = max_part_size.saturating_sub(header_len + 2) / 5 * 4;
+        if chunk_len == 0 {
+            return Err(Error::new(ErrorCode::InvalidOptions, format!("Multi-part error: max_part_size ({}) leaves no room for data", max_part_size)));
+        }
+        let total = data.len().div_ceil(chunk_len).max(1) + parity;
+        if total.to_string().len() > digits {
+            digits += 1;
+            continue;
+        }
+        if parity > 0 && total > erasure::MAX_SHARDS {
+            return Err(Error::new(ErrorCode::InvalidOptions, format!(
+                "Multi-part error: {} parts with parity exceed the limit of {}",
+                total, erasure::MAX_SHARDS
+            )));
+        }
+
+        let mut chunks: Vec<Vec<u8>> = if data.is_empty() { vec![Vec::new()] } else { data.chunks(chunk_len).map(<[u8]>::to_vec).collect() };
+        if parity > 0 {
+            let shard_len = chunks[0].len().div_ceil(4) * 4;
+            for chunk in &mut chunks {
+                chunk.resize(shard_len, 0);
+            }
+            let parity_chunks = erasure::encode_parity(&chunks, parity);
+            chunks.extend(parity_chunks);
+        }
+        return Ok(chunks.iter().enumerate()
+            .map(|(index, chunk)| {
+                let header = PartHeader { index: index + 1, total, hash: hash.clone(), parity, length };
+                header.encode(&encode_z85_internal(chunk))
+            })
+            .collect());
+    }
+}
+
+// Internal pure Rust function for reassembling parts produced by `split_z85_internal`, in any
+// order. Repeated parts are allowed if identical; every data part must be present (or, with
+// parity, any `total - parity` parts) and the reassembled payload must match the hash.
+// The part count comes from untrusted headers, so it is checked against the parts given
+// before anything is allocated for it.
+fn join_z85_parts_internal<S: AsRef<str>>(parts: &[S]) -> Result<Vec<u8>, Error> {
+    let mut received = Vec::with_capacity(parts.len());
+    let mut payload: Option<PartHeader> = None;
+    for part in parts {
+        let (header, z85_data) = PartHeader::parse(part.as_ref())?;
+        match &payload {
+            None => payload = Some(PartHeader { index: 0, hash: header.hash.clone(), ..header }),
+            Some(expected) if (expected.total, &expected.hash, expected.parity, expected.length)
+                != (header.total, &header.hash, header.parity, header.length) => {
+                return Err(Error::new(ErrorCode::BadContainer, format!(
+                    "Multi-part error: part {}/{} (sha256={}) belongs to a different payload",
+                    header.index, header.total, header.hash
+                )));
+            }
+            Some(_) => {}
+        }
+        received.push((header.index, z85_data));
+    }
+
+    let payload = payload.ok_or_else(|| Error::new(ErrorCode::BadContainer, "Multi-part error: no parts given"))?;
+    let needed = payload.total - payload.parity;
+    if needed > received.len() {
+        return Err(Error::new(ErrorCode::BadContainer, format!(
+            "Multi-part error: only {} of {} parts received, need {}",
+            received.len(), payload.total, needed
+        )));
+    }
+    let mut chunks: Vec<Option<&str>> = vec![None; payload.total];
+    for (index, z85_data) in received {
+        let slot = &mut chunks[index - 1];
+        if slot.is_some_and(|existing| existing != z85_data) {
+            return Err(Error::new(ErrorCode::BadContainer, format!("Multi-part error: part {} appears twice with different data", index)));
+        }
+        *slot = Some(z85_data);
+    }
+
+    let data = if payload.parity > 0 {
+        join_with_parity(&chunks, &payload)?
+    } else {
+        let missing: Vec<String> = chunks.iter().enumerate()
+            .filter(|(_, chunk)| chunk.is_none())
+            .map(|(index, _)| (index + 1).to_string())
+            .collect();
+        if !missing.is_empty() {
+            let noun = if missing.len() == 1 { "part" } else { "parts" };
+            return Err(Error::new(ErrorCode::BadContainer, format!("Multi-part error: missing {} {} of {}", noun, missing.join(", "), chunks.len())));
+        }
+
+        let mut data = Vec::new();
+        for chunk in chunks.into_iter().flatten() {
+            data.extend_from_slice(&decode_z85_internal(chunk)?);
+        }
+        data
+    };
+    if payload_hash(&data) != payload.hash {
+        return Err(Error::new(ErrorCode::ChecksumMismatch, "Multi-part error: checksum mismatch"));
+    }
+    Ok(data)
+}
+
+// Rebuild an erasure-coded payload from any `total - parity` of its parts
+fn join_with_parity(chunks: &[Option<&str>], payload: &PartHeader) -> Result<Vec<u8>, Error> {
+    let data_count = payload.total - payload.parity;
+    let shards = chunks.iter()
+        .map(|chunk| chunk.map(decode_z85_internal).transpose())
+        .collect::<Result<Vec<_>, Error>>()?;
+    let mut sizes = shards.iter().flatten().map(Vec::len);
+    let shard_len = sizes.next().unwrap_or(0);
+    if sizes.any(|size| size != shard_len) || payload.length.unwrap_or(0) > data_count * shard_len {
+        return Err(Error::new(ErrorCode::BadContainer, "Multi-part error: parts differ in size"));
+    }
+
+    let data_shards = erasure::reconstruct(&shards, data_count).ok_or_else(|| Error::new(ErrorCode::BadContainer, format!(
+        "Multi-part error: only {} of {} parts received, need {}",
+        shards.iter().flatten().count(), payload.total, data_count
+    )))?;
+    let mut data = data_shards.concat();
+    data.truncate(payload.length.unwrap_or(0));
+    Ok(data)
+}
+
+/// Split raw bytes into Z85 parts of at most `max_part_size` characters each, for channels with
+/// a hard size cap such as SMS or QR codes. Every part starts with
+/// `z85p:part=<index>/<total>;sha256=<hash>:`, where `hash` identifies the whole payload.
+/// With `parity_parts`, that many Reed-Solomon parity parts are appended so that any
+/// `total - parity_parts` of the parts rebuild the payload (at most 256 parts in all).
+#[wasm_bindgen]
+pub fn split_z85(data: &[u8], max_part_size: usize, parity_parts: Option<usize>) -> Result<Vec<String>, JsValue> {
+    let parity = parity_parts.unwrap_or(0);
+    limits::enforce(data.len(), |parts: &Vec<String>| parts.iter().map(String::len).sum(), || split_z85_internal(data, max_part_size, parity))
+        .map_err(js_error)
+}
+
+/// Reassemble the parts produced by `split_z85`, in any order. Fails when a part is missing
+/// (beyond what the parity parts can replace), parts of different payloads are mixed or the
+/// result does not match the payload hash.
+#[wasm_bindgen]
+pub fn join_z85_parts(parts: Vec<String>) -> Result<Vec<u8>, JsValue> {
+    let bytes_in = parts.iter().map(String::len).sum();
+    limits::enforce(bytes_in, Vec::len, || join_z85_parts_internal(&parts))
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_join() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 31 % 251) as u8).collect();
+        for max_part_size in [50, 64, 160, 2000] {
+            let parts = split_z85_internal(&data, max_part_size, 0).unwrap();
+            assert!(parts.iter().all(|part| part.len() <= max_part_size), "{}", max_part_size);
+            assert_eq!(join_z85_parts_internal(&parts).unwrap(), data);
+
+            // Arrival order does not matter, and duplicates are ignored
+            let mut shuffled: Vec<&String> = parts.iter().rev().collect();
+            shuffled.push(&parts[0]);
+            assert_eq!(join_z85_parts_internal(&shuffled).unwrap(), data);
+        }
+
+        // 18 characters of data per part once the part count needs two digits
+        let parts = split_z85_internal(&data, 60, 0).unwrap();
+        assert_eq!(parts.len(), 84);
+        assert!(parts[0].starts_with(&format!("z85p:part=1/84;sha256={}:", payload_hash(&data))));
+
+        let empty = split_z85_internal(b"", 45, 0).unwrap();
+        assert_eq!(empty.len(), 1);
+        assert_eq!(join_z85_parts_internal(&empty).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_join_errors() {
+        let data = b"Hello, World! This payload needs several parts.";
+        let mut parts = split_z85_internal(data, 60, 0).unwrap();
+        assert_eq!(parts.len(), 3);
+
+        let incomplete = [&parts[1], &parts[1], &parts[1]];
+        assert_eq!(join_z85_parts_internal(&incomplete).err().unwrap().message, "Multi-part error: missing parts 1, 3 of 3");
+        let incomplete = [&parts[1], &parts[2], &parts[2]];
+        assert_eq!(join_z85_parts_internal(&incomplete).err().unwrap().message, "Multi-part error: missing part 1 of 3");
+        assert_eq!(join_z85_parts_internal(&parts[1..]).err().unwrap().message, "Multi-part error: only 2 of 3 parts received, need 3");
+
+        let other = split_z85_internal(b"other payload", 60, 0).unwrap();
+        let mixed = [&parts[0], &other[0]];
+        assert!(join_z85_parts_internal(&mixed).err().unwrap().message.contains("belongs to a different payload"));
+
+        // Part 2's header with the data of a payload differing in one byte
+        let mut altered = *data;
+        altered[20] ^= 1;
+        let altered_parts = split_z85_internal(&altered, 60, 0).unwrap();
+        let header_end = |part: &str| PART_PREFIX.len() + part[PART_PREFIX.len()..].find(':').unwrap() + 1;
+        parts[1] = format!("{}{}", &parts[1][..header_end(&parts[1])], &altered_parts[1][header_end(&altered_parts[1])..]);
+        assert_eq!(join_z85_parts_internal(&parts).err().unwrap().message, "Multi-part error: checksum mismatch");
+
+        assert_eq!(split_z85_internal(data, 44, 0).err().unwrap().message, "Multi-part error: max_part_size (44) leaves no room for data");
+        assert_eq!(join_z85_parts_internal::<&str>(&[]).err().unwrap().message, "Multi-part error: no parts given");
+        // A hostile part count is rejected before anything is allocated for it
+        assert_eq!(
+            join_z85_parts_internal(&["z85p:part=1/99999999999999;sha256=0000000000000000:"]).err().unwrap().message,
+            "Multi-part error: only 1 of 99999999999999 parts received, need 99999999999999"
+        );
+        assert_eq!(join_z85_parts_internal(&["z85p:part=3/2;sha256=0011223344556677:0:0"]).err().unwrap().message, "Multi-part error: invalid or missing part number in 'part=3/2;sha256=0011223344556677'");
+    }
+
+    #[test]
+    fn test_split_with_parity() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7 % 241) as u8).collect();
+        let parts = split_z85_internal(&data, 120, 3).unwrap();
+        assert!(parts.iter().all(|part| part.len() <= 120));
+        assert!(parts[0].starts_with(&format!("z85p:part=1/24;sha256={};parity=3;len=1000:", payload_hash(&data))));
+        assert_eq!(join_z85_parts_internal(&parts).unwrap(), data);
+
+        // Any 21 of the 24 parts are enough
+        for dropped in [[0, 1, 2], [4, 9, 15], [21, 22, 23], [0, 12, 23]] {
+            let received: Vec<&String> = parts.iter().enumerate()
+                .filter(|(index, _)| !dropped.contains(index))
+                .map(|(_, part)| part)
+                .rev()
+                .collect();
+            assert_eq!(join_z85_parts_internal(&received).unwrap(), data, "{:?}", dropped);
+        }
+        assert_eq!(join_z85_parts_internal(&parts[4..]).err().unwrap().message, "Multi-part error: only 20 of 24 parts received, need 21");
+
+        let empty = split_z85_internal(b"", 60, 2).unwrap();
+        assert_eq!(join_z85_parts_internal(&empty[2..]).unwrap(), b"");
+
+        // Parity changes the payload description, so plain and erasure-coded parts never mix
+        let plain = split_z85_internal(&data, 120, 0).unwrap();
+        assert!(join_z85_parts_internal(&[&parts[0], &plain[1]]).err().unwrap().message.contains("belongs to a different payload"));
+        assert_eq!(split_z85_internal(&data, 100, 250).err().unwrap().message, "Multi-part error: 286 parts with parity exceed the limit of 256");
+        assert_eq!(
+            join_z85_parts_internal(&["z85p:part=1/2;sha256=0011223344556677;parity=1:0:0"]).err().unwrap().message,
+            "Multi-part error: invalid parity or len in 'part=1/2;sha256=0011223344556677;parity=1'"
+        );
+        assert_eq!(
+            join_z85_parts_internal(&["z85p:part=1/99999999999999;sha256=0000000000000000;parity=99999999999998;len=4:"]).err().unwrap().message,
+            "Multi-part error: 99999999999999 parts with parity exceed the limit of 256"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Node.js `Buffer` outputs for server-side hot paths.
+//!
+//! Compiled only with the `node` feature (enabled by `npm run build:node`). Byte inputs need no
+//! special handling, as a `Buffer` is a `Uint8Array`. Outputs are copied out of WASM memory
+//! exactly once, straight into `Buffer`s, so callers skip the `Buffer.from(uint8Array)` copy
+//! and small results come from Node's pre-allocated pool.
+
+use wasm_bindgen::prelude::*;
+use std::cell::RefCell;
+
+use crate::batch::{self, Joined};
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{decode_z85_into_internal, decoded_size_hint_internal, telemetry};
+
+#[wasm_bindgen(inline_js = "
+export function split_buffers(view, lengths) {
+    // `view` is WASM memory that is freed on return: copy it once, then hand out views
+    const bytes = Buffer.from(view);
+    const items = new Array(lengths.length);
+    let offset = bytes.byteOffset;
+    lengths.forEach((length, index) => {
+        items[index] = Buffer.from(bytes.buffer, offset, length);
+        offset += length;
+    });
+    return items;
+}
+")]
+extern "C" {
+    fn split_buffers(view: &[u8], lengths: &[u32]) -> js_sys::Array;
+}
+
+#[wasm_bindgen]
+extern "C" {
+    /// Node.js `Buffer`
+    #[wasm_bindgen(extends = js_sys::Uint8Array, typescript_type = "Buffer")]
+    pub type Buffer;
+
+    #[wasm_bindgen(static_method_of = Buffer, js_name = allocUnsafe)]
+    fn alloc_unsafe(size: u32) -> Buffer;
+}
+
+thread_local! {
+    /// Decode target reused across calls, so hot loops don't allocate in WASM memory each time
+    static SCRATCH: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+// Bytes held by the scratch buffer
+pub(crate) fn scratch_bytes() -> usize {
+    SCRATCH.with_borrow(Vec::capacity)
+}
+
+// Decode into the scratch buffer and copy the result into a new `Buffer`
+fn decode_to_buffer(z85_data_with_padding: &str) -> Result<Buffer, Error> {
+    let size = decoded_size_hint_internal(z85_data_with_padding)?;
+    SCRATCH.with_borrow_mut(|scratch| {
+        scratch.resize(size, 0);
+        let written = decode_z85_into_internal(z85_data_with_padding, scratch)?;
+        let buffer = Buffer::alloc_unsafe(written as u32);
+        buffer.copy_from(&scratch[..written]);
+        Ok(buffer)
+    })
+}
+
+/// `decode_z85` returning a Node.js `Buffer` (`node` builds only)
+#[wasm_bindgen]
+pub fn decode_z85_buffer(z85_data_with_padding: &str) -> Result<Buffer, JsValue> {
+    telemetry::instrument("decode_z85_buffer", z85_data_with_padding.len(), |buffer: &Buffer| buffer.length() as usize, || {
+        decode_to_buffer(z85_data_with_padding)
+    })
+        .map_err(js_error)
+}
+
+/// `decode_z85_batch` returning Node.js `Buffer`s that share one allocation (`node` builds only)
+#[wasm_bindgen(unchecked_return_type = "Buffer[]")]
+pub fn decode_z85_batch_buffers(#[wasm_bindgen(unchecked_param_type = "string[]")] items: js_sys::Array) -> Result<js_sys::Array, JsValue> {
+    let (joined, lengths) = batch::strings_from_js(&items).map_err(js_error)?;
+    telemetry::instrument("decode_z85_batch_buffers", joined.len(), |(decoded, _): &Joined<Vec<u8>>| decoded.len(), || {
+        batch::decode_z85_batch_internal(&batch::split_items(joined.as_bytes(), &lengths)?)
+    })
+        .map(|(decoded, lengths)| split_buffers(&decoded, &lengths))
+        .map_err(js_error)
+}
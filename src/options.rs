@@ -0,0 +1,171 @@
+use wasm_bindgen::prelude::*;
+
+use crate::bech32::validate_hrp;
+use crate::compression::Compression;
+use crate::data_url::DataUrlEncoding;
+use crate::encoding::Base64Variant;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{mime, ConversionOptions, DataType, PaddingFormat};
+
+#[wasm_bindgen]
+impl ConversionOptions {
+    /// Start a `ConversionOptionsBuilder` from Raw → Raw and the default options
+    pub fn builder() -> ConversionOptionsBuilder {
+        ConversionOptionsBuilder { options: ConversionOptions::new(DataType::Raw, DataType::Raw) }
+    }
+}
+
+/// Fluent builder for `ConversionOptions`, e.g.
+/// `ConversionOptions.builder().input(DataType.Raw).output(DataType.DataURL).mime("image/png").build()`.
+/// Each method consumes the builder and returns it, so chain the calls rather than reusing an
+/// earlier builder. `build()` rejects combinations no conversion can honour, naming the field.
+#[wasm_bindgen]
+pub struct ConversionOptionsBuilder {
+    options: ConversionOptions,
+}
+
+#[wasm_bindgen]
+impl ConversionOptionsBuilder {
+    pub fn input(mut self, input: DataType) -> ConversionOptionsBuilder {
+        self.options.input = input;
+        self
+    }
+
+    pub fn output(mut self, output: DataType) -> ConversionOptionsBuilder {
+        self.options.output = output;
+        self
+    }
+
+    pub fn padding_format(mut self, padding_format: PaddingFormat) -> ConversionOptionsBuilder {
+        self.options.padding_format = padding_format;
+        self
+    }
+
+    pub fn compression(mut self, compression: Compression) -> ConversionOptionsBuilder {
+        self.options.compression = compression;
+        self
+    }
+
+    /// Set `mime_type`
+    pub fn mime(mut self, mime_type: String) -> ConversionOptionsBuilder {
+        self.options.mime_type = Some(mime_type);
+        self
+    }
+
+    pub fn data_url_encoding(mut self, data_url_encoding: DataUrlEncoding) -> ConversionOptionsBuilder {
+        self.options.data_url_encoding = data_url_encoding;
+        self
+    }
+
+    pub fn base64_variant(mut self, base64_variant: Base64Variant) -> ConversionOptionsBuilder {
+        self.options.base64_variant = base64_variant;
+        self
+    }
+
+    pub fn lenient(mut self, lenient: bool) -> ConversionOptionsBuilder {
+        self.options.lenient = lenient;
+        self
+    }
+
+    pub fn hrp(mut self, hrp: String) -> ConversionOptionsBuilder {
+        self.options.hrp = Some(hrp);
+        self
+    }
+
+    pub fn with_stats(mut self, with_stats: bool) -> ConversionOptionsBuilder {
+        self.options.with_stats = with_stats;
+        self
+    }
+
+    /// Check the options and return them, or throw a `Z85Error` with code `INVALID_OPTIONS`
+    /// whose message names the offending field
+    pub fn build(self) -> Result<ConversionOptions, JsValue> {
+        check_options(&self.options).map_err(js_error)?;
+        Ok(self.options)
+    }
+}
+
+// Reject options that every `*_with_options` conversion would ignore or fail on
+fn check_options(options: &ConversionOptions) -> Result<(), Error> {
+    let invalid = |field: &str, reason: &str| Err(Error::new(ErrorCode::InvalidOptions, format!("Invalid ConversionOptions.{}: {}", field, reason)));
+    let raw_to_data_url = matches!((options.input, options.output), (DataType::Raw, DataType::DataURL));
+    if let Some(mime_type) = options.mime_type.as_deref() {
+        if !raw_to_data_url {
+            return invalid("mime_type", "only Raw input converted to DataURL output takes a MIME type");
+        }
+        mime::validate_mime_type(mime_type)
+            .map_err(|e| Error::new(ErrorCode::InvalidOptions, format!("Invalid ConversionOptions.mime_type: {}", e)))?;
+    }
+    match options.data_url_encoding {
+        DataUrlEncoding::Z85 => return invalid("data_url_encoding", "must be Base64 or Percent"),
+        DataUrlEncoding::Percent if matches!(options.output, DataType::Raw) => {
+            return invalid("data_url_encoding", "Percent needs DataURL output");
+        }
+        _ => {}
+    }
+    if options.base64_variant != Base64Variant::Standard && matches!((options.input, options.output), (DataType::DataURL, DataType::DataURL)) {
+        return invalid("base64_variant", "data URL payloads always use the standard alphabet");
+    }
+    if options.compression != Compression::None && options.padding_format != PaddingFormat::Count {
+        return invalid("padding_format", "compressed output is a z85v2: container, which has no padding suffix");
+    }
+    if let Some(hrp) = options.hrp.as_deref() {
+        validate_hrp(hrp, "Invalid ConversionOptions.hrp")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_error(builder: ConversionOptionsBuilder) -> String {
+        check_options(&builder.options).unwrap_err().message
+    }
+
+    #[test]
+    fn test_builder_sets_fields() {
+        let builder = ConversionOptions::builder()
+            .output(DataType::DataURL)
+            .mime("image/png".to_string())
+            .lenient(true)
+            .hrp("test".to_string())
+            .with_stats(true);
+        assert_eq!(check_options(&builder.options), Ok(()));
+        let options = builder.options;
+        assert!(matches!((options.input, options.output), (DataType::Raw, DataType::DataURL)));
+        assert_eq!(options.mime_type.as_deref(), Some("image/png"));
+        assert!(options.lenient && options.with_stats);
+        assert_eq!(options.hrp.as_deref(), Some("test"));
+    }
+
+    #[test]
+    fn test_builder_rejects_impossible_combinations() {
+        let builder = ConversionOptions::builder;
+        assert_eq!(
+            build_error(builder().input(DataType::DataURL).output(DataType::DataURL).mime("image/png".to_string())),
+            "Invalid ConversionOptions.mime_type: only Raw input converted to DataURL output takes a MIME type"
+        );
+        assert_eq!(
+            build_error(builder().output(DataType::DataURL).mime("image".to_string())),
+            "Invalid ConversionOptions.mime_type: Invalid MIME type: image"
+        );
+        assert_eq!(
+            build_error(builder().data_url_encoding(DataUrlEncoding::Percent)),
+            "Invalid ConversionOptions.data_url_encoding: Percent needs DataURL output"
+        );
+        assert_eq!(
+            build_error(builder().output(DataType::DataURL).data_url_encoding(DataUrlEncoding::Z85)),
+            "Invalid ConversionOptions.data_url_encoding: must be Base64 or Percent"
+        );
+        assert_eq!(
+            build_error(builder().input(DataType::DataURL).output(DataType::DataURL).base64_variant(Base64Variant::UrlSafe)),
+            "Invalid ConversionOptions.base64_variant: data URL payloads always use the standard alphabet"
+        );
+        assert_eq!(
+            build_error(builder().compression(Compression::Deflate).padding_format(PaddingFormat::Length)),
+            "Invalid ConversionOptions.padding_format: compressed output is a z85v2: container, which has no padding suffix"
+        );
+        assert_eq!(build_error(builder().hrp("Ab".to_string())), "Invalid ConversionOptions.hrp: mixed-case HRP");
+    }
+}
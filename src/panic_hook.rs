@@ -0,0 +1,70 @@
+use wasm_bindgen::prelude::*;
+use std::cell::RefCell;
+use std::panic::{Location, PanicHookInfo};
+
+use crate::error::panic_error;
+
+#[wasm_bindgen]
+extern "C" {
+    // A `set_panic_handler` callback
+    #[wasm_bindgen(typescript_type = "((error: Z85Error) => void)")]
+    pub type PanicHandler;
+}
+
+thread_local! {
+    static PANIC_HANDLER: RefCell<Option<js_sys::Function>> = const { RefCell::new(None) };
+}
+
+// Crash report message, e.g. `Z85 WASM panic: attempt to subtract with overflow (src/lib.rs:12:5)`
+fn panic_message(payload: Option<&str>, location: Option<&Location>) -> String {
+    let message = payload.unwrap_or("non-string panic payload");
+    match location {
+        Some(location) => format!("Z85 WASM panic: {} ({}:{}:{})", message, location.file(), location.line(), location.column()),
+        None => format!("Z85 WASM panic: {}", message),
+    }
+}
+
+// Log the panic with its stack to `console.error` (with the `console` feature) and hand a
+// `Z85Error` to the registered handler. The module then traps as before, so the calling JS
+// function still throws.
+fn hook(info: &PanicHookInfo) {
+    #[cfg(feature = "console")]
+    console_error_panic_hook::hook(info);
+
+    PANIC_HANDLER.with(|cell| {
+        if let Some(handler) = cell.try_borrow().ok().as_deref().and_then(Option::as_ref) {
+            let error = panic_error(&panic_message(info.payload_as_str(), info.location()));
+            let _ = handler.call1(&JsValue::NULL, &error);
+        }
+    });
+}
+
+// Install the panic hook (called when the module is initialized)
+pub(crate) fn install() {
+    std::panic::set_hook(Box::new(hook));
+}
+
+/// Register a callback receiving a `Z85Error` with code `PANIC` when Rust code panics, or pass
+/// `undefined` to remove it. Its `message` names the panic and source location and its `stack`
+/// includes the WASM frames. Panics are also logged to `console.error`, unless the module was
+/// built without the default `console` feature; the call that panicked still throws a
+/// `WebAssembly.RuntimeError`, after which the module instance should be discarded.
+#[wasm_bindgen]
+pub fn set_panic_handler(handler: Option<PanicHandler>) {
+    PANIC_HANDLER.with(|cell| *cell.borrow_mut() = handler.map(JsCast::unchecked_into));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panic_message() {
+        let location = Location::caller();
+        assert_eq!(
+            panic_message(Some("attempt to subtract with overflow"), Some(location)),
+            format!("Z85 WASM panic: attempt to subtract with overflow (src/panic_hook.rs:{}:{})", location.line(), location.column())
+        );
+        assert_eq!(panic_message(None, None), "Z85 WASM panic: non-string panic payload");
+    }
+}
@@ -0,0 +1,112 @@
+//! Multi-threaded Z85 kernels and batch conversions on a rayon thread pool.
+//!
+//! Compiled only with the `threads` feature. On `wasm32` the pool runs on Web Workers sharing
+//! the module's memory (a `SharedArrayBuffer`) and must be started with `init_thread_pool`
+//! before the first parallel conversion. Work is split into independent chunks whose results
+//! are joined in order, so output and error messages (including the first failing offset or
+//! item) match the single-threaded build.
+
+use rayon::prelude::*;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+use crate::codec;
+use crate::error::Error;
+
+/// Inputs smaller than this are converted on the calling thread
+pub(crate) const PARALLEL_THRESHOLD: usize = 1 << 20;
+
+/// Bytes of input per task: a multiple of 4 (one Z85 group) and below `PARALLEL_THRESHOLD`
+const CHUNK_BYTES: usize = 256 * 1024;
+
+// `codec::encode_append` split across the pool
+pub(crate) fn encode_append(data: &[u8], out: &mut String) {
+    let chunks: Vec<String> = data.par_chunks(CHUNK_BYTES)
+        .map(|chunk| {
+            let mut encoded = String::new();
+            codec::encode_append(chunk, &mut encoded);
+            encoded
+        })
+        .collect();
+    out.reserve(data.len() / 4 * 5);
+    for chunk in chunks {
+        out.push_str(&chunk);
+    }
+}
+
+// `codec::decode_groups` split across the pool
+pub(crate) fn decode_groups(z85_data: &[u8], out: &mut [u8], base_offset: usize) -> Result<(), z85_core::Error> {
+    let chunk_chars = CHUNK_BYTES / 4 * 5;
+    let results: Vec<Result<(), z85_core::Error>> = z85_data.par_chunks(chunk_chars)
+        .zip(out.par_chunks_mut(CHUNK_BYTES))
+        .enumerate()
+        .map(|(index, (chunk, words))| codec::decode_groups(chunk, words, base_offset + index * chunk_chars))
+        .collect();
+    results.into_iter().collect()
+}
+
+// Apply `convert` to runs of consecutive items in parallel, passing each run's first index.
+// Returns the results in order, or the error of the earliest failing run.
+pub(crate) fn map_runs<I: Sync, T: Send>(
+    items: &[I],
+    convert: impl Fn(usize, &[I]) -> Result<T, Error> + Sync,
+) -> Result<Vec<T>, Error> {
+    // A few runs per thread, so uneven items still balance
+    let run_len = items.len().div_ceil(rayon::current_num_threads() * 4).max(1);
+    let results: Vec<Result<T, Error>> = items.par_chunks(run_len)
+        .enumerate()
+        .map(|(index, run)| convert(index * run_len, run))
+        .collect();
+    results.into_iter().collect()
+}
+
+/// Start `threads` Web Workers for the batch and large-buffer functions (`threads` builds
+/// only). Resolves once the workers are ready; call it once, before the first conversion.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub fn init_thread_pool(threads: usize) -> js_sys::Promise {
+    wasm_bindgen_rayon::init_thread_pool(threads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    #[test]
+    fn test_parallel_kernels_match_serial() {
+        let data: Vec<u8> = (0..PARALLEL_THRESHOLD as u32 + 4000).map(|i| (i * 7 % 251) as u8).collect();
+        let mut serial = String::new();
+        codec::encode_append_with(&codec::Z85, &data, &mut serial);
+        let mut parallel = String::new();
+        encode_append(&data, &mut parallel);
+        assert_eq!(parallel, serial);
+
+        let mut decoded = vec![0u8; data.len()];
+        decode_groups(serial.as_bytes(), &mut decoded, 0).unwrap();
+        assert_eq!(decoded, data);
+
+        // The reported offset is the first invalid character, even in a later chunk
+        let mut corrupt = serial.into_bytes();
+        corrupt[CHUNK_BYTES / 4 * 5 * 2 + 3] = b'~';
+        corrupt[CHUNK_BYTES / 4 * 5 * 3] = b'~';
+        assert_eq!(
+            decode_groups(&corrupt, &mut decoded, 10).err().unwrap().to_string(),
+            format!("Z85 decode error: invalid character at offset {}", CHUNK_BYTES / 4 * 5 * 2 + 13)
+        );
+    }
+
+    #[test]
+    fn test_map_runs_reports_first_error() {
+        let items: Vec<usize> = (0..1000).collect();
+        let sums = map_runs(&items, |first, run| Ok((first, run.len()))).unwrap();
+        assert_eq!(sums.iter().map(|&(_, len)| len).sum::<usize>(), 1000);
+        assert!(sums.windows(2).all(|pair| pair[0].0 + pair[0].1 == pair[1].0));
+
+        let result: Result<Vec<()>, Error> = map_runs(&items, |_, run| match run.iter().find(|&&i| i % 300 == 299) {
+            Some(i) => Err(Error::new(ErrorCode::InvalidInput, format!("item {}", i))),
+            None => Ok(()),
+        });
+        assert_eq!(result.err().unwrap().message, "item 299");
+    }
+}
@@ -0,0 +1,208 @@
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+use crate::compression::Compression;
+use crate::container::{self, compress_payload, crc32, ContainerHeader};
+use crate::crypto::{open_payload, seal_payload, Algorithm, AEAD_KEY_BYTES};
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+
+/// Compression, encryption and Z85 encoding chained in a single pass.
+/// `run()` produces a `z85v2:` container whose header records every stage applied,
+/// so `Pipeline.fromHeader()` can configure the inverse pipeline from the output alone.
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Pipeline {
+    compression: Compression,
+    encryption: Option<Algorithm>,
+    key: Option<Zeroizing<Vec<u8>>>,
+}
+
+impl Pipeline {
+    // Compress, encrypt and encode `data`, taking ownership so each stage can reuse its buffer
+    fn run_internal(&self, data: Vec<u8>) -> Result<String, Error> {
+        // AEAD already authenticates the payload; a plaintext CRC would only leak information
+        let checksum = match self.encryption {
+            Some(_) => None,
+            None => Some(crc32(&data)),
+        };
+        let (payload, compression, dictionary_id) = compress_payload(data, self.compression);
+        let payload = match (self.encryption, &self.key) {
+            (Some(algorithm), Some(key)) => seal_payload(algorithm, key, payload)?,
+            _ => payload,
+        };
+
+        let encryption = self.encryption.map(|algorithm| algorithm.name().to_string());
+        let header = ContainerHeader { length: payload.len(), checksum, compression, dictionary_id, encryption, ..ContainerHeader::default() };
+        Ok(container::encode(&header, &payload))
+    }
+
+    // Decode, decrypt and decompress a container produced by `run`
+    fn reverse_internal(&self, encoded: &str) -> Result<Vec<u8>, Error> {
+        let (header, z85_data) = container::parse_header(encoded)?;
+        let payload = container::decode_payload(&header, z85_data)?;
+        let payload = match (container::encryption(&header)?, &self.key) {
+            (None, _) => payload,
+            (Some(algorithm), _) if self.encryption != Some(algorithm) => {
+                return Err(Error::new(ErrorCode::InvalidOptions, format!("Pipeline does not match container: encrypted with {}", algorithm.name())));
+            }
+            (Some(_), None) => return Err(Error::new(ErrorCode::InvalidKey, "Pipeline has no key for the encrypted container")),
+            (Some(algorithm), Some(key)) => open_payload(algorithm, key, payload)?,
+        };
+
+        // Compression comes from the header: payloads that did not shrink are stored as is
+        let data = container::decompress(&header, payload)?;
+        header.verify_checksum(&data)?;
+        Ok(data)
+    }
+
+    // Configure the inverse pipeline of a container from its header
+    fn from_header_internal(encoded: &str, key: Option<Vec<u8>>) -> Result<Pipeline, Error> {
+        let (header, _) = container::parse_header(encoded)?;
+        let encryption = container::encryption(&header)?;
+        if encryption.is_some() && key.is_none() {
+            return Err(Error::new(ErrorCode::InvalidKey, "Container is encrypted: a key is required"));
+        }
+        Ok(Pipeline {
+            compression: header.compression.into(),
+            encryption,
+            key: encryption.and(key).map(Zeroizing::new),
+        })
+    }
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Pipeline { compression: Compression::None, encryption: None, key: None }
+    }
+}
+
+#[wasm_bindgen]
+impl Pipeline {
+    /// Create an empty pipeline (Z85 encoding only)
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Pipeline {
+        Pipeline::default()
+    }
+
+    /// Add a compression stage: `"deflate"`, `"zstd"` or `"none"`
+    pub fn compress(mut self, algorithm: &str) -> Result<Pipeline, JsValue> {
+        self.compression = Compression::from_name(algorithm)
+            .map_err(js_error)?;
+        Ok(self)
+    }
+
+    /// Add an encryption stage: `"aes-gcm"` or `"xchacha20-poly1305"` with a 32-byte key.
+    /// A fresh random nonce is generated on every `run()`.
+    pub fn encrypt(mut self, algorithm: &str, key: Vec<u8>) -> Result<Pipeline, JsValue> {
+        let algorithm = Algorithm::from_name(algorithm)
+            .map_err(js_error)?;
+        if key.len() != AEAD_KEY_BYTES {
+            return Err(js_error(Error::new(ErrorCode::InvalidKey, format!("Key must be {} bytes, got {}", AEAD_KEY_BYTES, key.len()))));
+        }
+        self.encryption = Some(algorithm);
+        self.key = Some(Zeroizing::new(key));
+        Ok(self)
+    }
+
+    /// Set the final encoding stage. Only `"z85"` is supported.
+    pub fn encode(self, encoding: &str) -> Result<Pipeline, JsValue> {
+        match encoding {
+            "z85" => Ok(self),
+            _ => Err(js_error(Error::new(ErrorCode::Unsupported, format!("Unsupported encoding: {}", encoding)))),
+        }
+    }
+
+    /// Configure the inverse of the pipeline that produced `encoded` from its container header.
+    /// `key` is required when the container is encrypted.
+    #[wasm_bindgen(js_name = fromHeader)]
+    pub fn from_header(encoded: &str, key: Option<Vec<u8>>) -> Result<Pipeline, JsValue> {
+        Pipeline::from_header_internal(encoded, key)
+            .map_err(js_error)
+    }
+
+    /// Compression stage of the pipeline
+    #[wasm_bindgen(getter)]
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Encryption stage of the pipeline, or `undefined`
+    #[wasm_bindgen(getter)]
+    pub fn encryption(&self) -> Option<Algorithm> {
+        self.encryption
+    }
+
+    /// Run every stage over `data`, returning a `z85v2:` container
+    pub fn run(&self, data: Vec<u8>) -> Result<String, JsValue> {
+        limits::enforce(data.len(), String::len, || self.run_internal(data))
+            .map_err(js_error)
+    }
+
+    /// Undo every stage of a container produced by `run()`
+    pub fn reverse(&self, encoded: &str) -> Result<Vec<u8>, JsValue> {
+        limits::enforce(encoded.len(), Vec::len, || self.reverse_internal(encoded))
+            .map_err(js_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode_z85_internal;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    fn pipeline(compression: Compression, encryption: Option<Algorithm>) -> Pipeline {
+        Pipeline { compression, encryption, key: encryption.map(|_| Zeroizing::new(KEY.to_vec())) }
+    }
+
+    #[test]
+    fn test_pipeline_roundtrip() {
+        let data = b"Hello, World! ".repeat(40);
+        for compression in [Compression::None, Compression::Deflate, Compression::Zstd] {
+            for encryption in [None, Some(Algorithm::Aes256Gcm), Some(Algorithm::XChaCha20Poly1305)] {
+                let forward = pipeline(compression, encryption);
+                let encoded = forward.run_internal(data.clone()).unwrap();
+                assert!(encoded.starts_with("z85v2:"));
+
+                let inverse = Pipeline::from_header_internal(&encoded, Some(KEY.to_vec())).unwrap();
+                assert_eq!(inverse.compression, compression);
+                assert_eq!(inverse.encryption, encryption);
+                assert_eq!(inverse.reverse_internal(&encoded).unwrap(), data);
+                assert_eq!(forward.reverse_internal(&encoded).unwrap(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pipeline_header() {
+        let encoded = pipeline(Compression::Zstd, Some(Algorithm::Aes256Gcm)).run_internal(b"a".repeat(100)).unwrap();
+        assert!(encoded.contains(";compression=zstd;encryption=aes-gcm:"));
+        assert!(!encoded.contains("crc32"));
+
+        // Plain pipelines are ordinary containers
+        let encoded = pipeline(Compression::Deflate, None).run_internal(b"a".repeat(100)).unwrap();
+        assert_eq!(decode_z85_internal(&encoded).unwrap(), b"a".repeat(100));
+
+        // Incompressible payloads are stored as is and still reverse
+        let encoded = pipeline(Compression::Deflate, None).run_internal(b"abc".to_vec()).unwrap();
+        assert!(!encoded.contains("compression"));
+        assert_eq!(pipeline(Compression::Deflate, None).reverse_internal(&encoded).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn test_pipeline_errors() {
+        let encoded = pipeline(Compression::None, Some(Algorithm::Aes256Gcm)).run_internal(b"secret".to_vec()).unwrap();
+        assert!(decode_z85_internal(&encoded).err().unwrap().message.starts_with("Container is encrypted (aes-gcm)"));
+        assert_eq!(Pipeline::from_header_internal(&encoded, None).err().unwrap().message, "Container is encrypted: a key is required");
+        assert_eq!(pipeline(Compression::None, None).reverse_internal(&encoded).err().unwrap().message, "Pipeline does not match container: encrypted with aes-gcm");
+
+        let wrong_key = Pipeline { key: Some(Zeroizing::new(vec![8; 32])), ..pipeline(Compression::None, Some(Algorithm::Aes256Gcm)) };
+        assert_eq!(wrong_key.reverse_internal(&encoded).err().unwrap().message, "Decryption failed: wrong key or corrupted data");
+
+        assert_eq!(Pipeline::from_header_internal("nm=QNzY&b1A+]m^:1", None).err().unwrap().message, "Invalid format: expected 'z85v2:' prefix");
+        assert_eq!(Compression::from_name("brotli").err().unwrap().message, "Unsupported compression: brotli");
+        assert_eq!(Algorithm::from_name("rot13").err().unwrap().message, "Unsupported encryption algorithm: rot13");
+    }
+}
@@ -0,0 +1,175 @@
+use wasm_bindgen::prelude::*;
+use base64::Engine;
+
+use crate::encoding::Base64Variant;
+use crate::error::{Error, ErrorCode};
+use crate::{codec, container, split_padding_suffix};
+use crate::stream::Z85Encoder;
+
+/// Number of 4-byte groups processed between progress reports
+const PROGRESS_SLICE_GROUPS: usize = 16 * 1024;
+
+#[wasm_bindgen]
+extern "C" {
+    // An `on_progress` callback
+    #[wasm_bindgen(typescript_type = "((bytes_processed: number, total_bytes: number) => void)")]
+    pub type ProgressCallback;
+}
+
+// Wrap an optional JS callback as a `(bytes_processed, total_bytes)` reporter.
+// Exceptions thrown by the callback are ignored.
+pub(crate) fn js_progress_reporter(callback: &ProgressCallback) -> impl FnMut(usize, usize) + '_ {
+    let callback: &js_sys::Function = callback.unchecked_ref();
+    move |processed, total| {
+        let _ = callback.call2(
+            &JsValue::NULL,
+            &JsValue::from_f64(processed as f64),
+            &JsValue::from_f64(total as f64),
+        );
+    }
+}
+
+// Internal pure Rust function for encoding bytes to Z85, reporting progress per slice
+pub(crate) fn encode_z85_with_progress_internal(data: &[u8], progress: &mut dyn FnMut(usize, usize)) -> String {
+    let mut encoder = Z85Encoder::new();
+    let mut output = String::with_capacity(data.len().div_ceil(4) * 5 + 2);
+    let mut processed = 0;
+    for chunk in data.chunks(PROGRESS_SLICE_GROUPS * 4) {
+        output.push_str(&encoder.update(chunk));
+        processed += chunk.len();
+        progress(processed, data.len());
+    }
+    output.push_str(&encoder.finalize());
+    if data.is_empty() {
+        progress(0, 0);
+    }
+    output
+}
+
+// Internal pure Rust function for decoding Z85 to bytes, reporting progress per slice
+pub(crate) fn decode_z85_with_progress_internal(z85_data_with_padding: &str, progress: &mut dyn FnMut(usize, usize)) -> Result<Vec<u8>, Error> {
+    if z85_data_with_padding.starts_with(container::V2_PREFIX) {
+        let decoded_data = container::decode_z85_v2_internal(z85_data_with_padding)?;
+        progress(z85_data_with_padding.len(), z85_data_with_padding.len());
+        return Ok(decoded_data);
+    }
+
+    let (z85_data, padding) = split_padding_suffix(z85_data_with_padding)?;
+    if !z85_data.len().is_multiple_of(5) {
+        return Err(z85_core::Error::InvalidDataLength { alphabet: "Z85", length: z85_data.len() }.into());
+    }
+
+    // Decode Z85 data slice by slice
+    let total = z85_data_with_padding.len();
+    let mut decoded_data = vec![0u8; z85_data.len() / 5 * 4];
+    let mut processed = 0;
+    let slices = z85_data.as_bytes().chunks(PROGRESS_SLICE_GROUPS * 5)
+        .zip(decoded_data.chunks_mut(PROGRESS_SLICE_GROUPS * 4));
+    for (chunk, out) in slices {
+        codec::decode_groups(chunk, out, processed)?;
+        processed += chunk.len();
+        progress(processed, total);
+    }
+
+    // Remove padding
+    let original_length = decoded_data.len().checked_sub(padding)
+        .ok_or(z85_core::Error::InvalidPadding)?;
+    decoded_data.truncate(original_length);
+    progress(total, total);
+
+    Ok(decoded_data)
+}
+
+// Internal pure Rust function for Z85 to base64 conversion, reporting progress per slice
+pub(crate) fn z85_to_base64_with_progress_internal(z85_data_with_padding: &str, variant: Base64Variant, progress: &mut dyn FnMut(usize, usize)) -> Result<String, Error> {
+    let decoded_data = decode_z85_with_progress_internal(z85_data_with_padding, progress)?;
+    Ok(variant.engine().encode(decoded_data))
+}
+
+// Internal pure Rust function for base64 to Z85 conversion, reporting progress per slice
+pub(crate) fn base64_to_z85_with_progress_internal(base64_data: &str, variant: Base64Variant, progress: &mut dyn FnMut(usize, usize)) -> Result<String, Error> {
+    let total = base64_data.len();
+    if !base64_data.is_ascii() {
+        return Err(Error::new(ErrorCode::InvalidChar, "Base64 decode error: input contains non-ASCII characters"));
+    }
+
+    // Base64 decodes independently in 4-char units, so slices of a multiple of 4 can be streamed
+    let mut encoder = Z85Encoder::new();
+    let mut output = String::with_capacity(base64_data.len() / 4 * 3 * 5 / 4 + 7);
+    let mut processed = 0;
+    for chunk in base64_data.as_bytes().chunks(PROGRESS_SLICE_GROUPS * 4) {
+        let decoded = variant.engine().decode(chunk)?;
+        output.push_str(&encoder.update(&decoded));
+        processed += chunk.len();
+        progress(processed, total);
+    }
+    output.push_str(&encoder.finalize());
+    if base64_data.is_empty() {
+        progress(0, 0);
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{base64_to_z85_internal, decode_z85_internal, encode_z85_internal, z85_to_base64_internal};
+
+    fn sample_data() -> Vec<u8> {
+        (0..(PROGRESS_SLICE_GROUPS * 4 * 2 + 5)).map(|i| (i * 13) as u8).collect()
+    }
+
+    #[test]
+    fn test_encode_with_progress() {
+        let data = sample_data();
+        let mut reports = Vec::new();
+        let encoded = encode_z85_with_progress_internal(&data, &mut |processed, total| reports.push((processed, total)));
+
+        assert_eq!(encoded, encode_z85_internal(&data));
+        assert_eq!(reports.len(), 3);
+        assert!(reports.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(*reports.last().unwrap(), (data.len(), data.len()));
+    }
+
+    #[test]
+    fn test_decode_with_progress() {
+        let data = sample_data();
+        let encoded = encode_z85_internal(&data);
+        let mut reports = Vec::new();
+        let decoded = decode_z85_with_progress_internal(&encoded, &mut |processed, total| reports.push((processed, total))).unwrap();
+
+        assert_eq!(decoded, decode_z85_internal(&encoded).unwrap());
+        assert_eq!(*reports.last().unwrap(), (encoded.len(), encoded.len()));
+    }
+
+    #[test]
+    fn test_base64_paths_with_progress() {
+        let base64 = Base64Variant::Standard.engine().encode(sample_data());
+        let mut calls = 0;
+        let z85 = base64_to_z85_with_progress_internal(&base64, Base64Variant::Standard, &mut |_, _| calls += 1).unwrap();
+        assert_eq!(z85, base64_to_z85_internal(&base64, Base64Variant::Standard).unwrap());
+        assert!(calls > 1);
+
+        let back = z85_to_base64_with_progress_internal(&z85, Base64Variant::Standard, &mut |_, _| {}).unwrap();
+        assert_eq!(back, z85_to_base64_internal(&z85).unwrap());
+    }
+
+    #[test]
+    fn test_progress_empty_and_errors() {
+        let mut reports = Vec::new();
+        assert_eq!(encode_z85_with_progress_internal(b"", &mut |p, t| reports.push((p, t))), ":0");
+        assert_eq!(reports, vec![(0, 0)]);
+
+        let result = decode_z85_with_progress_internal("no_colon_here", &mut |_, _| {});
+        assert_eq!(result.err().unwrap().message, "Invalid format: expected 'z85_data:padding'");
+
+        let mut encoded = encode_z85_internal(&sample_data());
+        encoded.replace_range(PROGRESS_SLICE_GROUPS * 5 + 7..PROGRESS_SLICE_GROUPS * 5 + 8, "~");
+        let result = decode_z85_with_progress_internal(&encoded, &mut |_, _| {});
+        assert_eq!(result.err().unwrap().message, format!("Z85 decode error: invalid character at offset {}", PROGRESS_SLICE_GROUPS * 5 + 7));
+
+        let result = base64_to_z85_with_progress_internal("not valid base64!", Base64Variant::Standard, &mut |_, _| {});
+        assert!(result.err().unwrap().message.contains("Base64 decode error"));
+    }
+}
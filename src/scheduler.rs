@@ -0,0 +1,286 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::{codec, now_ms, split_padding_suffix};
+
+/// Number of 4-byte groups processed between clock checks
+const SLICE_GROUPS: usize = 16 * 1024;
+
+/// Work remaining in a resumable conversion
+enum TaskState {
+    Encode {
+        input: Vec<u8>,
+        position: usize,
+        output: String,
+    },
+    Decode {
+        input: String,
+        padding: usize,
+        position: usize,
+        output: Vec<u8>,
+    },
+}
+
+/// Resumable conversion driven by the host's own scheduler.
+/// Each `run(budget_ms)` call processes as much as fits in the budget and then returns control.
+#[wasm_bindgen]
+pub struct ConversionTask {
+    state: TaskState,
+    done: bool,
+}
+
+impl ConversionTask {
+    // Create an encode task over raw bytes
+    fn new_encode(data: &[u8]) -> ConversionTask {
+        ConversionTask {
+            state: TaskState::Encode {
+                input: data.to_vec(),
+                position: 0,
+                output: String::with_capacity(data.len().div_ceil(4) * 5 + 2),
+            },
+            done: false,
+        }
+    }
+
+    // Create a decode task over `z85_data:padding`
+    fn new_decode(z85_data_with_padding: &str) -> Result<ConversionTask, Error> {
+        let (z85_data, padding) = split_padding_suffix(z85_data_with_padding)?;
+        if !z85_data.is_ascii() {
+            return Err(Error::new(ErrorCode::InvalidChar, "Z85 decode error: input contains non-ASCII characters"));
+        }
+        if !z85_data.len().is_multiple_of(5) {
+            return Err(Error::new(ErrorCode::InvalidLength, "Z85 decode error: input length must be a multiple of 5"));
+        }
+        Ok(ConversionTask {
+            state: TaskState::Decode {
+                input: z85_data.to_string(),
+                padding,
+                position: 0,
+                output: Vec::with_capacity(z85_data.len() / 5 * 4),
+            },
+            done: false,
+        })
+    }
+
+    // Create an encode task under the current limits, checking the projected output up front
+    fn encode_internal(data: &[u8]) -> Result<ConversionTask, Error> {
+        limits::enforce(data.len(), ConversionTask::projected_output_len, || Ok(ConversionTask::new_encode(data)))
+    }
+
+    // Create a decode task under the current limits, checking the projected output up front
+    fn decode_internal(z85_data_with_padding: &str) -> Result<ConversionTask, Error> {
+        limits::enforce(z85_data_with_padding.len(), ConversionTask::projected_output_len, || ConversionTask::new_decode(z85_data_with_padding))
+    }
+
+    // Size of the finished output, known before any work runs (the encode padding suffix aside)
+    fn projected_output_len(&self) -> usize {
+        match &self.state {
+            TaskState::Encode { input, .. } => input.len().div_ceil(4) * 5,
+            TaskState::Decode { input, padding, .. } => (input.len() / 5 * 4).saturating_sub(*padding),
+        }
+    }
+
+    // Process a single slice of at most SLICE_GROUPS groups
+    fn step(&mut self) -> Result<(), Error> {
+        if self.done {
+            return Ok(());
+        }
+        match &mut self.state {
+            TaskState::Encode { input, position, output } => {
+                let remaining = input.len() - *position;
+                let aligned = (remaining / 4).min(SLICE_GROUPS) * 4;
+                if aligned > 0 {
+                    codec::encode_append(&input[*position..*position + aligned], output);
+                    *position += aligned;
+                } else {
+                    // Final partial block (or empty input)
+                    let padding_needed = codec::encode_padded_tail(&input[*position..], output);
+                    output.push_str(&format!(":{}", padding_needed));
+                    limits::check_output(output.len())?;
+                    *position = input.len();
+                    self.done = true;
+                }
+            }
+            TaskState::Decode { input, padding, position, output } => {
+                let end = (*position + SLICE_GROUPS * 5).min(input.len());
+                let start = output.len();
+                output.resize(start + (end - *position) / 5 * 4, 0);
+                codec::decode_groups(&input.as_bytes()[*position..end], &mut output[start..], *position)?;
+                *position = end;
+                if *position == input.len() {
+                    // Remove padding
+                    let original_length = output.len().checked_sub(*padding)
+                        .ok_or(z85_core::Error::InvalidPadding)?;
+                    output.truncate(original_length);
+                    limits::check_output(output.len())?;
+                    self.done = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Run slices until done or the clock passes the budget; always makes progress
+    fn run_with_clock(&mut self, budget_ms: f64, now: impl Fn() -> f64) -> Result<bool, Error> {
+        let deadline = now() + budget_ms;
+        loop {
+            self.step()?;
+            if self.done || now() >= deadline {
+                return Ok(self.done);
+            }
+        }
+    }
+
+    // Fraction of the input processed so far
+    fn progress_internal(&self) -> f64 {
+        let (position, total) = match &self.state {
+            TaskState::Encode { input, position, .. } => (*position, input.len()),
+            TaskState::Decode { input, position, .. } => (*position, input.len()),
+        };
+        if self.done {
+            1.0
+        } else if total == 0 {
+            0.0
+        } else {
+            position as f64 / total as f64
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ConversionTask {
+    /// Create a resumable task encoding raw bytes to Z85 with padding info
+    pub fn encode(data: &[u8]) -> Result<ConversionTask, JsValue> {
+        ConversionTask::encode_internal(data)
+            .map_err(js_error)
+    }
+
+    /// Create a resumable task decoding Z85 data with padding info to raw bytes
+    pub fn decode(z85_data_with_padding: &str) -> Result<ConversionTask, JsValue> {
+        ConversionTask::decode_internal(z85_data_with_padding)
+            .map_err(js_error)
+    }
+
+    /// Process as much as fits in `budget_ms` milliseconds; returns `true` once finished
+    pub fn run(&mut self, budget_ms: f64) -> Result<bool, JsValue> {
+        self.run_with_clock(budget_ms, now_ms)
+            .map_err(js_error)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn done(&self) -> bool {
+        self.done
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn progress(&self) -> f64 {
+        self.progress_internal()
+    }
+
+    /// Take the finished result: a string for encode tasks, a `Uint8Array` for decode tasks
+    #[wasm_bindgen(unchecked_return_type = "string | Uint8Array")]
+    pub fn result(self) -> Result<JsValue, JsValue> {
+        if !self.done {
+            return Err(js_error(Error::new(ErrorCode::InvalidInput, "Conversion task has not finished")));
+        }
+        Ok(match self.state {
+            TaskState::Encode { output, .. } => JsValue::from_str(&output),
+            TaskState::Decode { output, .. } => js_sys::Uint8Array::from(output.as_slice()).into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_z85_internal, encode_z85_internal};
+    use std::cell::Cell;
+
+    // Clock that advances by one millisecond every time it is read
+    fn ticking_clock() -> impl Fn() -> f64 {
+        let ticks = Cell::new(0.0);
+        move || {
+            ticks.set(ticks.get() + 1.0);
+            ticks.get()
+        }
+    }
+
+    #[test]
+    fn test_encode_task_matches_one_shot() {
+        let data: Vec<u8> = (0..(SLICE_GROUPS * 4 * 3 + 3)).map(|i| i as u8).collect();
+        let mut task = ConversionTask::new_encode(&data);
+
+        let mut runs = 0;
+        while !task.run_with_clock(0.0, ticking_clock()).unwrap() {
+            runs += 1;
+            assert!(task.progress_internal() < 1.0);
+        }
+        assert!(runs >= 2);
+        assert_eq!(task.progress_internal(), 1.0);
+
+        let TaskState::Encode { output, .. } = task.state else { panic!("expected encode task") };
+        assert_eq!(output, encode_z85_internal(&data));
+    }
+
+    #[test]
+    fn test_decode_task_matches_one_shot() {
+        let data: Vec<u8> = (0..(SLICE_GROUPS * 4 * 2 + 1)).map(|i| (i * 7) as u8).collect();
+        let encoded = encode_z85_internal(&data);
+        let mut task = ConversionTask::new_decode(&encoded).unwrap();
+
+        // A generous budget finishes in a single run
+        assert!(task.run_with_clock(1_000_000.0, ticking_clock()).unwrap());
+
+        let TaskState::Decode { output, .. } = task.state else { panic!("expected decode task") };
+        assert_eq!(output, decode_z85_internal(&encoded).unwrap());
+    }
+
+    #[test]
+    fn test_empty_encode_task() {
+        let mut task = ConversionTask::new_encode(b"");
+        assert!(task.run_with_clock(0.0, ticking_clock()).unwrap());
+        let TaskState::Encode { output, .. } = task.state else { panic!("expected encode task") };
+        assert_eq!(output, ":0");
+    }
+
+    #[test]
+    fn test_task_output_limits() {
+        let encoded = encode_z85_internal(&[0u8; 16]);
+        limits::with_limits(None, Some(11), || {
+            // The projected output is checked before any work is scheduled
+            let error = ConversionTask::encode_internal(&[0u8; 16]).err().unwrap();
+            assert_eq!(error.message, "Output size limit exceeded: 20 bytes (max_output_bytes is 11)");
+            let error = ConversionTask::decode_internal(&encoded).err().unwrap();
+            assert_eq!(error.message, "Output size limit exceeded: 16 bytes (max_output_bytes is 11)");
+
+            // The real output, padding suffix included, is checked when the task finishes
+            let mut task = ConversionTask::encode_internal(&[0u8; 8]).unwrap();
+            let error = task.run_with_clock(1_000_000.0, ticking_clock()).err().unwrap();
+            assert_eq!(error.code, ErrorCode::LimitExceeded);
+            assert_eq!(error.message, "Output size limit exceeded: 12 bytes (max_output_bytes is 11)");
+        });
+    }
+
+    #[test]
+    fn test_decode_task_errors() {
+        let result = ConversionTask::new_decode("no_colon_here");
+        assert_eq!(result.err().unwrap().message, "Invalid format: expected 'z85_data:padding'");
+
+        let result = ConversionTask::new_decode("abcd:0");
+        assert!(result.err().unwrap().message.contains("multiple of 5"));
+
+        // Padding larger than the decoded data is rejected before any work is scheduled
+        let result = ConversionTask::new_decode("HelloWorld:9");
+        assert_eq!(result.err().unwrap().message, "Invalid padding number");
+
+        // Errors in a later slice report their offset in the whole input
+        let mut encoded = encode_z85_internal(&vec![0u8; SLICE_GROUPS * 4 * 2]);
+        encoded.replace_range(SLICE_GROUPS * 5 + 7..SLICE_GROUPS * 5 + 8, "~");
+        let mut task = ConversionTask::new_decode(&encoded).unwrap();
+        assert_eq!(
+            task.run_with_clock(1_000_000.0, ticking_clock()).err().unwrap().message,
+            format!("Z85 decode error: invalid character at offset {}", SLICE_GROUPS * 5 + 7)
+        );
+    }
+}
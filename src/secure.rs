@@ -0,0 +1,93 @@
+use wasm_bindgen::prelude::*;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::error::{js_error, Error};
+use crate::limits;
+use crate::{codec, split_padding_suffix};
+
+// Internal pure Rust function for encoding secret bytes to Z85, wiping the padded tail copy.
+// The scalar codec writes straight into the output, unlike the SIMD and multi-threaded kernels
+// behind `codec::encode_append`, which stage encoded data in buffers that are not wiped.
+fn encode_z85_secure_internal(data: &[u8]) -> Zeroizing<String> {
+    let aligned = data.len() / 4 * 4;
+    let mut z85_data = Zeroizing::new(String::with_capacity(data.len().div_ceil(4) * 5 + 2));
+    codec::encode_append_with(&codec::Z85, &data[..aligned], &mut z85_data);
+
+    // Pad the tail in a scratch word that is wiped after use.
+    // The output capacity is exact, so pushing never reallocates (leaving a stale copy behind).
+    let tail = &data[aligned..];
+    let padding_needed = if tail.is_empty() {
+        0
+    } else {
+        let mut word = Zeroizing::new([0u8; 4]);
+        word[..tail.len()].copy_from_slice(tail);
+        codec::encode_append_with(&codec::Z85, &word[..], &mut z85_data);
+        4 - tail.len()
+    };
+    z85_data.push(':');
+    z85_data.push_str(&padding_needed.to_string());
+    z85_data
+}
+
+/// Encode secret bytes to Z85 with padding info like `encode_z85`, zeroizing every Rust-side
+/// buffer (the copy of the input, the padded tail and the output) before returning
+#[wasm_bindgen]
+pub fn encode_z85_secure(mut data: Vec<u8>) -> Result<js_sys::JsString, JsValue> {
+    let z85_data = limits::check_input(data.len())
+        .map(|()| encode_z85_secure_internal(&data));
+    data.zeroize();
+    z85_data
+        .map(|z85_data| js_sys::JsString::from(z85_data.as_str()))
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for decoding Z85 into a buffer that is wiped when dropped.
+// Like encoding, this uses the scalar codec only.
+fn decode_z85_secure_internal(z85_data_with_padding: &str) -> Result<Zeroizing<Vec<u8>>, Error> {
+    let (z85_data, padding) = split_padding_suffix(z85_data_with_padding)?;
+    let mut decoded_data = Zeroizing::new(vec![0u8; (z85_data.len() / 5 * 4).saturating_sub(padding)]);
+    z85_core::codec::decode_into_with(&codec::Z85, z85_data.as_bytes(), padding, &mut decoded_data)?;
+    Ok(decoded_data)
+}
+
+/// Decode Z85 data with padding info like `decode_z85`, zeroizing every Rust-side buffer
+/// (the copy of the input string and the decoded bytes) once the result has been copied to JS
+#[wasm_bindgen]
+pub fn decode_z85_secure(mut z85_data_with_padding: String) -> Result<js_sys::Uint8Array, JsValue> {
+    let result = limits::check_input(z85_data_with_padding.len())
+        .and_then(|()| decode_z85_secure_internal(&z85_data_with_padding))
+        .map(|decoded| js_sys::Uint8Array::from(decoded.as_slice()));
+    z85_data_with_padding.zeroize();
+    result.map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_z85_internal, encode_z85_internal};
+
+    #[test]
+    fn test_secure_matches_regular_codec() {
+        for len in 0..10 {
+            let data: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(29).wrapping_add(3)).collect();
+            let encoded = encode_z85_secure_internal(&data);
+            assert_eq!(encoded.as_str(), encode_z85_internal(&data));
+            assert_eq!(decode_z85_secure_internal(&encoded).unwrap().as_slice(), decode_z85_internal(&encoded).unwrap());
+        }
+    }
+
+    #[cfg(feature = "threads")]
+    #[test]
+    fn test_secure_large_input_matches_regular_codec() {
+        let data: Vec<u8> = (0..crate::parallel::PARALLEL_THRESHOLD as u32 + 6).map(|i| (i * 13 % 251) as u8).collect();
+        let encoded = encode_z85_secure_internal(&data);
+        assert_eq!(encoded.as_str(), encode_z85_internal(&data));
+        assert_eq!(decode_z85_secure_internal(&encoded).unwrap().as_slice(), data);
+    }
+
+    #[test]
+    fn test_decode_secure_errors() {
+        assert_eq!(decode_z85_secure_internal("Hel~o:0").err().unwrap().message, "Z85 decode error: invalid character at offset 3");
+        assert_eq!(decode_z85_secure_internal("Hello:5").err().unwrap().message, "Invalid padding number");
+    }
+}
@@ -0,0 +1,238 @@
+use wasm_bindgen::prelude::*;
+
+use z85_core::container::{block_count, index_len, pack_blocks, parse_index};
+
+use crate::codec;
+use crate::compression::{deflate_raw, with_decode_options, zstd_compress, Compression};
+use crate::container::{self, crc32, ContainerHeader, V2_PREFIX};
+use crate::error::{js_error, Error, ErrorCode};
+use crate::limits;
+use crate::split_padding_suffix;
+
+/// Uncompressed block size used by `encode_z85_seekable` when none is given
+const DEFAULT_BLOCK_SIZE: usize = 64 * 1024;
+
+// Compress `data` in independent blocks of `block_size` bytes, behind the block index.
+// Returns the payload and the id of the Zstandard dictionary used.
+fn compress_blocks(data: &[u8], block_size: usize, compression: Compression) -> (Vec<u8>, Option<u32>) {
+    let mut dictionary_id = None;
+    let blocks: Vec<Vec<u8>> = data.chunks(block_size)
+        .map(|block| match compression {
+            Compression::Deflate => deflate_raw(block),
+            Compression::Zstd => {
+                let (frame, id) = zstd_compress(block);
+                dictionary_id = id;
+                frame
+            }
+            Compression::None => block.to_vec(),
+        })
+        .collect();
+    (pack_blocks(&blocks), dictionary_id)
+}
+
+// Decompress one block and check it holds `block_size` bytes (the last block may hold fewer)
+fn decompress_block(block: &[u8], block_size: usize, is_last: bool, header: (z85_core::Compression, Option<u32>)) -> Result<Vec<u8>, Error> {
+    Ok(with_decode_options(|options| z85_core::container::decompress_block(block, block_size, is_last, header, options))?)
+}
+
+// Decode bytes `start..end` of the payload stored as `z85_data` (`payload_len` bytes, the
+// rest of the last group being padding), touching only the groups that cover them
+fn decode_payload_range(z85_data: &str, payload_len: usize, start: usize, end: usize) -> Result<Vec<u8>, Error> {
+    debug_assert!(start <= end && end <= payload_len);
+    if z85_data.len() / 5 * 4 < payload_len {
+        return Err(z85_core::Error::Truncated { expected: payload_len, actual: z85_data.len() / 5 * 4 }.into());
+    }
+    let (first_group, last_group) = (start / 4, end.div_ceil(4));
+    let mut decoded = vec![0u8; (last_group - first_group) * 4];
+    codec::decode_groups(&z85_data.as_bytes()[first_group * 5..last_group * 5], &mut decoded, first_group * 5)?;
+    Ok(decoded[start - first_group * 4..end - first_group * 4].to_vec())
+}
+
+// Internal pure Rust function for encoding bytes to a `z85v2:` container whose payload is
+// compressed in independent blocks behind a block index. Falls back to an uncompressed
+// container (seekable without an index) when compression does not make it smaller.
+fn encode_z85_seekable_internal(data: &[u8], compression: Compression, block_size: usize) -> Result<String, Error> {
+    if block_size == 0 || block_size > u32::MAX as usize {
+        return Err(Error::new(ErrorCode::InvalidOptions, format!("Invalid block size: {}", block_size)));
+    }
+    // A block larger than the data only inflates the header's claim on the decoder
+    let block_size = block_size.min(data.len().max(1));
+    let (payload, dictionary_id) = compress_blocks(data, block_size, compression);
+    let checksum = Some(crc32(data));
+    if compression == Compression::None || payload.len() >= data.len() {
+        let header = ContainerHeader { length: data.len(), checksum, ..ContainerHeader::default() };
+        return Ok(container::encode(&header, data));
+    }
+    let header = ContainerHeader { length: payload.len(), checksum, compression: compression.into(), dictionary_id, block_size: Some(block_size), ..ContainerHeader::default() };
+    Ok(container::encode(&header, &payload))
+}
+
+// Internal pure Rust function for decoding bytes `offset..offset + length` of Z85 with padding
+// info or of a `z85v2:` container. Uncompressed data is sliced by group arithmetic and
+// block-indexed containers decompress only the blocks covering the range; other compressed
+// containers are decoded in full. Checksums are not verified, as they cover the whole data.
+fn decode_range_internal(encoded: &str, offset: usize, length: usize) -> Result<Vec<u8>, Error> {
+    let encoded = encoded.trim();
+    let end = offset.checked_add(length)
+        .ok_or_else(|| Error::new(ErrorCode::InvalidOptions, format!("Range out of bounds: offset {} + length {} overflows", offset, length)))?;
+    let out_of_bounds = |size: usize| Error::new(ErrorCode::InvalidOptions, format!("Range out of bounds: {}..{} of {} bytes", offset, end, size));
+
+    if !encoded.starts_with(V2_PREFIX) {
+        let (z85_data, padding) = split_padding_suffix(encoded)?;
+        let size = (z85_data.len() / 5 * 4).saturating_sub(padding);
+        if end > size {
+            return Err(out_of_bounds(size));
+        }
+        return decode_payload_range(z85_data, size, offset, end);
+    }
+
+    let (header, z85_data) = container::parse_header(encoded)?;
+    if let Some(algorithm) = container::encryption(&header)? {
+        return Err(Error::new(ErrorCode::BadContainer, format!("Container is encrypted ({}): decode it with Pipeline.fromHeader and the key", algorithm.name())));
+    }
+    match (header.compression, header.block_size) {
+        (z85_core::Compression::None, _) => {
+            if end > header.length {
+                return Err(out_of_bounds(header.length));
+            }
+            decode_payload_range(z85_data, header.length, offset, end)
+        }
+        (_, None) => {
+            let data = container::decompress(&header, container::decode_payload(&header, z85_data)?)?;
+            data.get(offset..end).map(<[u8]>::to_vec).ok_or_else(|| out_of_bounds(data.len()))
+        }
+        (compression, Some(block_size)) => {
+            if header.length < 4 {
+                return Err(Error::new(ErrorCode::BadContainer, "Invalid z85v2 block index: truncated"));
+            }
+            let count = block_count(&decode_payload_range(z85_data, header.length, 0, 4)?, header.length)?;
+            let index_len = index_len(count)?;
+            let lengths = parse_index(&decode_payload_range(z85_data, header.length, 0, index_len)?, header.length)?;
+            let block_header = (compression, header.dictionary_id);
+            // `parse_index` checked that the lengths add up to `header.length`, so this cannot overflow
+            let block_start = |index: usize| index_len + lengths[..index].iter().sum::<usize>();
+            let decode_block = |index: usize| {
+                let block = decode_payload_range(z85_data, header.length, block_start(index), block_start(index + 1))?;
+                decompress_block(&block, block_size, index + 1 == count, block_header)
+            };
+
+            // Blocks holding the first and last byte of the range (for an empty range, the
+            // block it starts in)
+            let (first, last) = (offset / block_size, end.saturating_sub(1).max(offset) / block_size);
+            if last >= count {
+                // Size the data exactly from its last block for the error
+                let size = match count {
+                    0 => 0,
+                    _ => {
+                        let last_len = decode_block(count - 1)?.len();
+                        (count - 1).checked_mul(block_size).and_then(|full| full.checked_add(last_len))
+                            .ok_or_else(|| Error::new(ErrorCode::BadContainer, "Invalid z85v2 block index: data size overflows"))?
+                    }
+                };
+                return if length == 0 && offset == size { Ok(Vec::new()) } else { Err(out_of_bounds(size)) };
+            }
+
+            // Grown from the decoded blocks: `block_size` comes from the header, so it is not
+            // trusted to size an allocation
+            let mut data = Vec::new();
+            for index in first..=last {
+                data.extend_from_slice(&decode_block(index)?);
+            }
+            let range = offset - first * block_size..end - first * block_size;
+            data.get(range).map(<[u8]>::to_vec).ok_or_else(|| out_of_bounds(first * block_size + data.len()))
+        }
+    }
+}
+
+/// Encode raw bytes to a `z85v2:` container (with a CRC-32) whose payload is compressed in
+/// independent blocks of `block_size` bytes (default 64 KiB) behind a block index, so
+/// `decode_range` can decompress just the blocks it needs. The header records `block=<size>`.
+#[wasm_bindgen]
+pub fn encode_z85_seekable(data: &[u8], compression: Compression, block_size: Option<usize>) -> Result<String, JsValue> {
+    limits::enforce(data.len(), String::len, || encode_z85_seekable_internal(data, compression, block_size.unwrap_or(DEFAULT_BLOCK_SIZE)))
+        .map_err(js_error)
+}
+
+/// Decode only bytes `offset..offset + length` of Z85 with padding info or a `z85v2:`
+/// container, without processing the rest of the payload (compressed containers need
+/// `encode_z85_seekable`'s block index for this). The checksum is not verified.
+#[wasm_bindgen]
+pub fn decode_range(encoded: &str, offset: usize, length: usize) -> Result<Vec<u8>, JsValue> {
+    limits::enforce(encoded.len(), Vec::len, || decode_range_internal(encoded, offset, length))
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::container::decode_z85_v2_internal;
+    use crate::encode_z85_internal;
+
+    fn media() -> Vec<u8> {
+        (0..10_000u32).map(|i| (i / 7 % 13) as u8 + (i % 3) as u8).collect()
+    }
+
+    #[test]
+    fn test_seekable_roundtrip() {
+        let data = media();
+        for compression in [Compression::Deflate, Compression::Zstd] {
+            let encoded = encode_z85_seekable_internal(&data, compression, 1024).unwrap();
+            assert!(encoded.contains(&format!(";compression={};block=1024", compression.name())));
+            assert_eq!(decode_z85_v2_internal(&encoded).unwrap(), data);
+
+            for (offset, length) in [(0, 10), (1000, 100), (1023, 2), (5000, 3000), (9990, 10), (0, 10_000), (10_000, 0), (3, 0)] {
+                assert_eq!(decode_range_internal(&encoded, offset, length).unwrap(), &data[offset..offset + length], "{} {}", offset, length);
+            }
+            assert_eq!(decode_range_internal(&encoded, 9990, 11).err().unwrap().message, "Range out of bounds: 9990..10001 of 10000 bytes");
+            assert_eq!(decode_range_internal(&encoded, 20_000, 0).err().unwrap().message, "Range out of bounds: 20000..20000 of 10000 bytes");
+        }
+
+        // Incompressible data is stored without compression, which is seekable as is
+        let noise: Vec<u8> = (0..100u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 24) as u8).collect();
+        let encoded = encode_z85_seekable_internal(&noise, Compression::Deflate, 16).unwrap();
+        assert!(!encoded.contains("block="));
+        assert_eq!(decode_range_internal(&encoded, 17, 50).unwrap(), &noise[17..67]);
+        assert_eq!(encode_z85_seekable_internal(&noise, Compression::Deflate, 0).err().unwrap().message, "Invalid block size: 0");
+    }
+
+    #[test]
+    fn test_decode_range_hostile_block_size() {
+        let encoded = encode_z85_seekable_internal(&media(), Compression::Deflate, 1024).unwrap();
+        let huge = encoded.replace(";block=1024", &format!(";block={}", u32::MAX));
+        assert_eq!(
+            decode_range_internal(&huge, 0, 10).err().unwrap().message,
+            format!("Invalid z85v2 block: decompressed to 1024 bytes (block size is {})", u32::MAX)
+        );
+        let overflowing = encoded.replace(";block=1024", ";block=18446744073709551615");
+        assert_eq!(decode_range_internal(&overflowing, 0, 10).err().unwrap().message, "Invalid z85v2 block size: 18446744073709551615");
+
+        // Blocks larger than the data are written as the data length
+        let small = encode_z85_seekable_internal(&[7; 300], Compression::Deflate, 1 << 20).unwrap();
+        assert!(small.contains(";block=300:"));
+        assert_eq!(decode_range_internal(&small, 290, 10).unwrap(), [7; 10]);
+    }
+
+    #[test]
+    fn test_decode_range_plain() {
+        let data = b"Hello, World! Seekable Z85.";
+        let encoded = encode_z85_internal(data);
+        for offset in 0..data.len() {
+            for length in 0..=data.len() - offset {
+                assert_eq!(decode_range_internal(&encoded, offset, length).unwrap(), &data[offset..offset + length]);
+            }
+        }
+        assert_eq!(decode_range_internal(&encoded, 20, 8).err().unwrap().message, "Range out of bounds: 20..28 of 27 bytes");
+
+        let container = crate::container::encode_z85_v2_internal(data, true);
+        assert_eq!(decode_range_internal(&container, 7, 5).unwrap(), b"World");
+
+        // Compressed containers without a block index are decoded in full
+        let compressed = crate::container::encode_container_internal(&media(), true, Compression::Deflate);
+        assert_eq!(decode_range_internal(&compressed, 500, 4).unwrap(), &media()[500..504]);
+
+        // Only the groups covering the range are decoded: damage elsewhere goes unnoticed
+        let damaged = format!("~~~~~{}", &encoded[5..]);
+        assert_eq!(decode_range_internal(&damaged, 8, 4).unwrap(), b"orld");
+        assert!(decode_range_internal(&damaged, 0, 4).is_err());
+    }
+}
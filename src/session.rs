@@ -0,0 +1,157 @@
+use wasm_bindgen::prelude::*;
+
+use crate::compression::Compression;
+use crate::encoding::{transcode_internal, Base64Variant, Encoding};
+use crate::error::{js_error, Error};
+use crate::memory;
+use crate::{
+    base64_to_z85_into, container, decode_z85_internal, decode_z85_into_internal, decoded_size_hint_internal,
+    encode_z85_into, telemetry, z85_to_base64_into, ConversionOptions, DataType, PaddingFormat,
+};
+
+/// Converter that keeps its output buffers between calls, for steady streams of similar-sized
+/// messages. Z85 encode/decode and Z85 ⇄ base64 reuse the buffers; compression, containers
+/// and other encodings convert as the standalone functions do.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Z85Session {
+    text: String,
+    bytes: Vec<u8>,
+    /// Buffer capacity last reported to `memory_stats`
+    reported: usize,
+}
+
+impl Drop for Z85Session {
+    fn drop(&mut self) {
+        memory::adjust_session_bytes(self.reported, 0);
+    }
+}
+
+impl Z85Session {
+    // Report the current capacity of the buffers to `memory_stats`
+    fn report(&mut self) {
+        let held = self.text.capacity() + self.bytes.capacity();
+        memory::adjust_session_bytes(self.reported, held);
+        self.reported = held;
+    }
+
+    fn encode_internal(&mut self, data: &[u8], compression: Compression) -> Result<&str, Error> {
+        self.text.clear();
+        if compression == Compression::None {
+            encode_z85_into(data, PaddingFormat::Count, &mut self.text);
+        } else {
+            self.text.push_str(&container::encode_container_internal(data, true, compression));
+        }
+        Ok(&self.text)
+    }
+
+    fn decode_internal(&mut self, z85_data_with_padding: &str) -> Result<&[u8], Error> {
+        self.bytes.clear();
+        if z85_data_with_padding.starts_with(container::V2_PREFIX) {
+            self.bytes.extend_from_slice(&decode_z85_internal(z85_data_with_padding)?);
+        } else {
+            self.bytes.resize(decoded_size_hint_internal(z85_data_with_padding)?, 0);
+            let written = decode_z85_into_internal(z85_data_with_padding, &mut self.bytes)?;
+            self.bytes.truncate(written);
+        }
+        Ok(&self.bytes)
+    }
+
+    fn transcode_internal(&mut self, data: &str, from: Encoding, to: Encoding, options: &ConversionOptions) -> Result<&str, Error> {
+        self.text.clear();
+        let variant = |encoding| if encoding == Encoding::Base64 { Base64Variant::Standard } else { Base64Variant::UrlSafeNoPad };
+        match (from, to) {
+            (Encoding::Base64 | Encoding::Base64Url, Encoding::Z85) if options.compression == Compression::None => {
+                base64_to_z85_into(data, options.padding_format, variant(from), &mut self.text)?;
+            }
+            (Encoding::Z85, Encoding::Base64 | Encoding::Base64Url) => z85_to_base64_into(data, variant(to), &mut self.text)?,
+            _ => self.text.push_str(&transcode_internal(data, from, to, options)?),
+        }
+        Ok(&self.text)
+    }
+}
+
+#[wasm_bindgen]
+impl Z85Session {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Z85Session {
+        Z85Session::default()
+    }
+
+    /// Size the buffers for payloads of up to `capacity` bytes, so later calls don't reallocate
+    pub fn reserve(&mut self, capacity: usize) {
+        self.bytes.reserve(capacity.saturating_sub(self.bytes.len()));
+        // Base64 is the longest text the session writes into its buffer
+        let text_capacity = capacity.div_ceil(3) * 4 + 7;
+        self.text.reserve(text_capacity.saturating_sub(self.text.len()));
+        self.report();
+    }
+
+    /// Encode raw bytes to Z85 with padding info, as `encode_z85`
+    #[wasm_bindgen(unchecked_return_type = "string")]
+    pub fn encode(&mut self, data: &[u8], compression: Option<Compression>) -> Result<JsValue, JsValue> {
+        let compression = compression.unwrap_or(Compression::None);
+        let result = telemetry::instrument("Z85Session.encode", data.len(), |text: &&str| text.len(), || self.encode_internal(data, compression))
+            .map(JsValue::from_str)
+            .map_err(js_error);
+        self.report();
+        result
+    }
+
+    /// Decode Z85 data with padding info (or a `z85v2:` container), as `decode_z85`
+    pub fn decode(&mut self, z85_data_with_padding: &str) -> Result<js_sys::Uint8Array, JsValue> {
+        let result = telemetry::instrument("Z85Session.decode", z85_data_with_padding.len(), |bytes: &&[u8]| bytes.len(), || {
+            self.decode_internal(z85_data_with_padding)
+        })
+            .map(js_sys::Uint8Array::from)
+            .map_err(js_error);
+        self.report();
+        result
+    }
+
+    /// Convert `data` from one encoding to another, as `transcode`
+    #[wasm_bindgen(unchecked_return_type = "string")]
+    pub fn transcode(&mut self, data: &str, from: Encoding, to: Encoding, options: Option<ConversionOptions>) -> Result<JsValue, JsValue> {
+        let opts = options.unwrap_or(ConversionOptions::new(DataType::Raw, DataType::Raw));
+        let result = telemetry::instrument("Z85Session.transcode", data.len(), |text: &&str| text.len(), || {
+            self.transcode_internal(data, from, to, &opts)
+        })
+            .map(JsValue::from_str)
+            .map_err(js_error);
+        self.report();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode_z85_internal, encode_z85_with_options_internal};
+
+    #[test]
+    fn test_session_matches_standalone_functions() {
+        let options = ConversionOptions::new(DataType::Raw, DataType::Raw);
+        let mut session = Z85Session::new();
+        session.reserve(64 * 1024);
+        let (text_capacity, bytes_capacity) = (session.text.capacity(), session.bytes.capacity());
+
+        for size in [64 * 1024, 0, 1, 5, 1000, 64 * 1024 - 3] {
+            let data: Vec<u8> = (0..size).map(|i| (i * 31 + 7) as u8).collect();
+            let encoded = encode_z85_internal(&data);
+            assert_eq!(session.encode_internal(&data, Compression::None).unwrap(), encoded);
+            assert_eq!(session.decode_internal(&encoded).unwrap(), data);
+
+            let base64 = transcode_internal(&encoded, Encoding::Z85, Encoding::Base64, &options).unwrap();
+            assert_eq!(session.transcode_internal(&encoded, Encoding::Z85, Encoding::Base64, &options).unwrap(), base64);
+            assert_eq!(session.transcode_internal(&base64, Encoding::Base64, Encoding::Z85, &options).unwrap(), encoded);
+        }
+        // Nothing above needed more room than was reserved
+        assert_eq!((session.text.capacity(), session.bytes.capacity()), (text_capacity, bytes_capacity));
+
+        let container = encode_z85_with_options_internal(&[9; 500], PaddingFormat::Count, Compression::Deflate);
+        assert_eq!(session.encode_internal(&[9; 500], Compression::Deflate).unwrap(), container);
+        assert_eq!(session.decode_internal(&container).unwrap(), [9; 500]);
+        assert_eq!(session.transcode_internal("48656c6c6f", Encoding::Hex, Encoding::Base58, &options).unwrap(), "9Ajdvzr");
+        assert!(session.decode_internal("Hello~orld:0").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use wasm_bindgen::prelude::*;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey, SIGNATURE_LENGTH};
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::curve::{decode_curve_key_internal, encode_curve_key_internal};
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{decode_z85_internal, encode_z85_internal};
+
+/// Keypair returned by `generate_signing_keypair`, both keys Z85-encoded
+#[derive(Serialize, Tsify, Debug)]
+struct SigningKeypair {
+    public_key: String,
+    secret_key: String,
+}
+
+// Decode a 40-character Z85 Ed25519 key (same text form as CURVE keys)
+fn decode_ed25519_key(z85_key: &str) -> Result<[u8; 32], Error> {
+    if z85_key.len() != 40 {
+        return Err(Error::new(ErrorCode::InvalidKey, format!("Ed25519 key must be 40 Z85 characters, got {}", z85_key.len())));
+    }
+    decode_curve_key_internal(z85_key)
+}
+
+// Internal pure Rust function for signing bytes into a Z85 container.
+// Container layout (before Z85 encoding): payload || Ed25519 signature (64 bytes)
+fn sign_z85_internal(data: &[u8], secret_key: &str) -> Result<String, Error> {
+    let signing_key = SigningKey::from_bytes(&decode_ed25519_key(secret_key)?);
+    let signature = signing_key.sign(data);
+
+    let mut container = Vec::with_capacity(data.len() + SIGNATURE_LENGTH);
+    container.extend_from_slice(data);
+    container.extend_from_slice(&signature.to_bytes());
+    Ok(encode_z85_internal(&container))
+}
+
+/// Sign raw bytes with an Ed25519 secret key (40-character Z85) and encode the payload
+/// and signature together to Z85 with padding info
+#[wasm_bindgen]
+pub fn sign_z85(data: &[u8], secret_key: &str) -> Result<String, JsValue> {
+    sign_z85_internal(data, secret_key)
+        .map_err(js_error)
+}
+
+// Internal pure Rust function for verifying a signed Z85 container and extracting its payload
+fn verify_z85_internal(container: &str, public_key: &str) -> Result<Vec<u8>, Error> {
+    let verifying_key = VerifyingKey::from_bytes(&decode_ed25519_key(public_key)?)
+        .map_err(|_| Error::new(ErrorCode::InvalidKey, "Invalid Ed25519 public key"))?;
+    let mut payload = decode_z85_internal(container)?;
+    let data_len = payload.len().checked_sub(SIGNATURE_LENGTH)
+        .ok_or_else(|| Error::new(ErrorCode::CryptoFailed, "Signature verification failed: container too short"))?;
+
+    let signature = Signature::from_slice(&payload[data_len..])
+        .map_err(|_| Error::new(ErrorCode::CryptoFailed, "Signature verification failed: malformed signature"))?;
+    verifying_key.verify(&payload[..data_len], &signature)
+        .map_err(|_| Error::new(ErrorCode::CryptoFailed, "Signature verification failed"))?;
+
+    payload.truncate(data_len);
+    Ok(payload)
+}
+
+/// Verify a container produced by `sign_z85` against an Ed25519 public key (40-character Z85),
+/// returning the payload only if the signature is valid
+#[wasm_bindgen]
+pub fn verify_z85(container: &str, public_key: &str) -> Result<Vec<u8>, JsValue> {
+    verify_z85_internal(container, public_key)
+        .map_err(js_error)
+}
+
+// Derive the Z85 keypair for an Ed25519 secret key (seed)
+fn signing_keypair_from_secret(secret: [u8; 32]) -> Result<SigningKeypair, Error> {
+    let signing_key = SigningKey::from_bytes(&secret);
+    Ok(SigningKeypair {
+        public_key: encode_curve_key_internal(signing_key.verifying_key().as_bytes())?,
+        secret_key: encode_curve_key_internal(&secret)?,
+    })
+}
+
+/// Generate an Ed25519 keypair using the platform CSPRNG.
+/// Returns `{ public_key, secret_key }`, both 40-character Z85 strings.
+#[wasm_bindgen(unchecked_return_type = "SigningKeypair")]
+pub fn generate_signing_keypair() -> Result<JsValue, JsValue> {
+    let mut secret = [0u8; 32];
+    getrandom::getrandom(&mut secret)
+        .map_err(|e| js_error(Error::new(ErrorCode::CryptoFailed, format!("Random number generation failed: {}", e))))?;
+    let keypair = signing_keypair_from_secret(secret)
+        .map_err(js_error)?;
+    serde_wasm_bindgen::to_value(&keypair)
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, format!("Keypair serialization error: {}", e))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len() / 2).map(|i| u8::from_str_radix(&s[i * 2..i * 2 + 2], 16).unwrap()).collect()
+    }
+
+    fn test_keypair() -> SigningKeypair {
+        // RFC 8032 section 7.1, test 1
+        let secret = hex("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60");
+        signing_keypair_from_secret(secret.try_into().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_keypair_matches_rfc8032() {
+        let keypair = test_keypair();
+        let public = hex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a");
+        assert_eq!(keypair.public_key, z85::encode(public));
+    }
+
+    #[test]
+    fn test_sign_matches_rfc8032() {
+        let keypair = test_keypair();
+        let container = decode_z85_internal(&sign_z85_internal(b"", &keypair.secret_key).unwrap()).unwrap();
+        let signature = hex("e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b");
+        assert_eq!(container, signature);
+    }
+
+    #[test]
+    fn test_sign_verify_roundtrip() {
+        let keypair = test_keypair();
+        let signed = sign_z85_internal(b"Hello, World!", &keypair.secret_key).unwrap();
+        assert_eq!(verify_z85_internal(&signed, &keypair.public_key).unwrap(), b"Hello, World!");
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        let keypair = test_keypair();
+        let signed = sign_z85_internal(b"Hello, World!", &keypair.secret_key).unwrap();
+
+        let mut container = decode_z85_internal(&signed).unwrap();
+        container[0] ^= 1;
+        let result = verify_z85_internal(&encode_z85_internal(&container), &keypair.public_key);
+        assert_eq!(result.err().unwrap().message, "Signature verification failed");
+
+        let other = signing_keypair_from_secret([5u8; 32]).unwrap();
+        assert_eq!(verify_z85_internal(&signed, &other.public_key).err().unwrap().message, "Signature verification failed");
+
+        let result = verify_z85_internal(&encode_z85_internal(b"short"), &keypair.public_key);
+        assert_eq!(result.err().unwrap().message, "Signature verification failed: container too short");
+        assert!(sign_z85_internal(b"data", "too short").err().unwrap().message.starts_with("Ed25519 key must be 40 Z85 characters"));
+    }
+}
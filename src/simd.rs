@@ -0,0 +1,129 @@
+//! WASM SIMD128 kernels for the Z85 divide/multiply chains.
+//!
+//! Compiled only with the `simd` feature on `wasm32` with `target-feature=+simd128`.
+//! Each kernel processes 4 words (16 bytes / 20 chars) per call; callers handle
+//! the remaining words, and any block with invalid input, with the scalar code.
+
+use core::arch::wasm32::*;
+
+use crate::codec::{INVALID_DIGIT, Z85, Z85_ALPHABET};
+
+/// `x / 85 == (x * DIV85_MAGIC) >> DIV85_SHIFT` for every u32 `x`
+const DIV85_MAGIC: u32 = 0xC0C0_C0C1;
+const DIV85_SHIFT: u32 = 38;
+
+// Reverse the byte order within each 32-bit lane (big-endian words <-> little-endian lanes)
+#[inline]
+fn bswap32x4(v: v128) -> v128 {
+    u8x16_shuffle::<3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8, 15, 14, 13, 12>(v, v)
+}
+
+// Divide each u32 lane by 85 using a widening multiply by the magic reciprocal
+#[inline]
+fn div85(v: v128) -> v128 {
+    let magic = u32x4_splat(DIV85_MAGIC);
+    let low = u64x2_shr(u64x2_extmul_low_u32x4(v, magic), DIV85_SHIFT);
+    let high = u64x2_shr(u64x2_extmul_high_u32x4(v, magic), DIV85_SHIFT);
+    i32x4_shuffle::<0, 2, 4, 6>(low, high)
+}
+
+// Store the four u32 lanes of a vector
+#[inline]
+fn lanes(v: v128) -> [u32; 4] {
+    [
+        u32x4_extract_lane::<0>(v),
+        u32x4_extract_lane::<1>(v),
+        u32x4_extract_lane::<2>(v),
+        u32x4_extract_lane::<3>(v),
+    ]
+}
+
+// Encode 16 bytes (4 words) into 20 Z85 characters
+pub(crate) fn encode_block(input: &[u8; 16], out: &mut [u8; 20]) {
+    // SAFETY: `input` is exactly 16 readable bytes; v128_load has no alignment requirement
+    let mut value = bswap32x4(unsafe { v128_load(input.as_ptr() as *const v128) });
+    let eighty_five = u32x4_splat(85);
+
+    // Peel digits least significant first
+    for digit in (0..5).rev() {
+        let quotient = div85(value);
+        let remainder = i32x4_sub(value, i32x4_mul(quotient, eighty_five));
+        for (lane, r) in lanes(remainder).into_iter().enumerate() {
+            out[lane * 5 + digit] = Z85_ALPHABET[r as usize];
+        }
+        value = quotient;
+    }
+}
+
+// Decode 20 Z85 characters into 16 bytes (4 words).
+// Returns `false` (leaving `out` unspecified) if the block contains an invalid character
+// or a group overflowing 32 bits, so the caller can report the exact error.
+pub(crate) fn decode_block(input: &[u8; 20], out: &mut [u8; 16]) -> bool {
+    let mut digits = [[0u32; 4]; 5];
+    for (lane, group) in input.chunks_exact(5).enumerate() {
+        for (position, &c) in group.iter().enumerate() {
+            let digit = Z85.digits[c as usize];
+            if digit == INVALID_DIGIT {
+                return false;
+            }
+            digits[position][lane] = digit as u32;
+        }
+    }
+
+    // Accumulate in 64-bit lanes so overflow can be detected
+    let eighty_five = u64x2_splat(85);
+    let mut low = u64x2_splat(0);
+    let mut high = u64x2_splat(0);
+    for row in digits {
+        let row = u32x4(row[0], row[1], row[2], row[3]);
+        low = i64x2_add(i64x2_mul(low, eighty_five), u64x2_extend_low_u32x4(row));
+        high = i64x2_add(i64x2_mul(high, eighty_five), u64x2_extend_high_u32x4(row));
+    }
+    if v128_any_true(v128_or(u64x2_shr(low, 32), u64x2_shr(high, 32))) {
+        return false;
+    }
+
+    let words = bswap32x4(i32x4_shuffle::<0, 2, 4, 6>(low, high));
+    // SAFETY: `out` is exactly 16 writable bytes; v128_store has no alignment requirement
+    unsafe { v128_store(out.as_mut_ptr() as *mut v128, words) };
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    #[wasm_bindgen_test]
+    fn simd_encode_block_matches_z85_crate() {
+        let input: [u8; 16] = [0, 1, 2, 3, 0xff, 0xff, 0xff, 0xff, 0x86, 0x4f, 0xd2, 0x6f, 0xb5, 0x59, 0xf7, 0x5b];
+        let mut out = [0u8; 20];
+        encode_block(&input, &mut out);
+        assert_eq!(&out, z85::encode(input).as_bytes());
+    }
+
+    #[wasm_bindgen_test]
+    fn simd_decode_block_roundtrip() {
+        let input: [u8; 16] = core::array::from_fn(|i| (i * 37 + 11) as u8);
+        let encoded: [u8; 20] = z85::encode(input).as_bytes().try_into().unwrap();
+        let mut out = [0u8; 16];
+        assert!(decode_block(&encoded, &mut out));
+        assert_eq!(out, input);
+    }
+
+    #[wasm_bindgen_test]
+    fn simd_decode_block_rejects_invalid_input() {
+        let mut out = [0u8; 16];
+        assert!(!decode_block(b"HelloWorldHelloWor~d", &mut out));
+        // "#####" overflows 32 bits
+        assert!(!decode_block(b"#####WorldHelloWorld", &mut out));
+    }
+
+    #[wasm_bindgen_test]
+    fn simd_div85_is_exact() {
+        for x in [0u32, 84, 85, 86, 7225, 614_124, 52_200_624, 52_200_625, u32::MAX - 1, u32::MAX] {
+            let q = u32x4_extract_lane::<0>(div85(u32x4_splat(x)));
+            assert_eq!(q, x / 85, "x = {}", x);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Streaming encode/decode between `Read` and `Write`, for the `z85-wasm` command built for
+//! WASI (`wasm32-wasip1`) and native targets.
+//!
+//! Input is read in fixed-size chunks through `Z85Encoder` and `Z85Decoder`, so memory use does
+//! not grow with the stream and the output is byte-for-byte what `encode_z85` produces and
+//! `decode_z85` accepts.
+
+use std::io::{self, Read, Write};
+
+use crate::error::Error;
+use crate::stream::{Z85Decoder, Z85Encoder};
+
+/// Bytes read from the input per chunk
+const CHUNK_BYTES: usize = 64 * 1024;
+
+// Feed every chunk of `input` to `step`, until end of input
+fn for_each_chunk(mut input: impl Read, mut step: impl FnMut(&[u8]) -> io::Result<()>) -> io::Result<()> {
+    let mut buffer = vec![0u8; CHUNK_BYTES];
+    loop {
+        match input.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(read) => step(&buffer[..read])?,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Encode `input` to Z85 with a `:padding` suffix, written to `output` as it is produced
+pub fn encode_stream(input: impl Read, mut output: impl Write) -> io::Result<()> {
+    let mut encoder = Z85Encoder::new();
+    for_each_chunk(input, |chunk| output.write_all(encoder.update(chunk).as_bytes()))?;
+    output.write_all(encoder.finalize().as_bytes())?;
+    output.flush()
+}
+
+/// Decode Z85 with a `:padding` or `#length` suffix from `input`, written to `output` as it is produced.
+/// ASCII whitespace (line breaks, a final newline) is skipped; as with `Z85Decoder`, `z85v2:`
+/// containers and `#length` suffixes are not supported. Decode errors are `InvalidData`.
+pub fn decode_stream(input: impl Read, mut output: impl Write) -> io::Result<()> {
+    let invalid = |error: Error| io::Error::new(io::ErrorKind::InvalidData, error.message);
+    let mut decoder = Z85Decoder::new();
+    for_each_chunk(input, |chunk| {
+        let text: String = String::from_utf8_lossy(chunk).chars().filter(|c| !c.is_ascii_whitespace()).collect();
+        output.write_all(&decoder.update_internal(&text).map_err(invalid)?)
+    })?;
+    output.write_all(&decoder.finalize_internal(None).map_err(invalid)?)?;
+    output.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{decode_z85_internal, encode_z85_internal};
+
+    #[test]
+    fn test_stream_roundtrip() {
+        let data: Vec<u8> = (0..CHUNK_BYTES as u32 * 2 + 7).map(|i| (i % 251) as u8).collect();
+        let mut encoded = Vec::new();
+        encode_stream(&data[..], &mut encoded).unwrap();
+        let encoded = String::from_utf8(encoded).unwrap();
+        assert_eq!(encoded, encode_z85_internal(&data));
+
+        let mut decoded = Vec::new();
+        decode_stream(format!("{}\n", encoded).as_bytes(), &mut decoded).unwrap();
+        assert_eq!(decoded, data);
+        assert_eq!(decode_z85_internal(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn test_stream_small_and_wrapped() {
+        let mut encoded = Vec::new();
+        encode_stream(&b""[..], &mut encoded).unwrap();
+        assert_eq!(encoded, b":0");
+
+        let mut decoded = Vec::new();
+        decode_stream(&b"nm=QN\r\nzY&b1\r\nA+]m^:1\r\n"[..], &mut decoded).unwrap();
+        assert_eq!(decoded, b"Hello World");
+    }
+
+    #[test]
+    fn test_decode_stream_errors() {
+        let error = decode_stream(&b"nm=QNzY&b1A+]m^:x"[..], io::sink()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "Invalid padding number");
+        let error = decode_stream("nm=QN\u{e9}".as_bytes(), io::sink()).unwrap_err();
+        assert_eq!(error.to_string(), "Z85 decode error: input contains non-ASCII characters");
+    }
+}
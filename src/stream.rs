@@ -0,0 +1,570 @@
+use wasm_bindgen::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use sha2::{Digest, Sha256};
+use web_sys::{AbortSignal, ReadableStream, ReadableWritablePair, TransformStream, TransformStreamDefaultController, Transformer, WritableStream};
+
+use crate::async_ops::{abort_reason, check_aborted};
+use crate::codec;
+use crate::digest::digest_to_z85;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::split_padding_suffix;
+use z85_core::padding::{self, MAX_SUFFIX_LEN};
+
+/// Streaming Z85 encoder.
+/// Feed chunks of any size to `update()`; the concatenation of every `update()` result
+/// and the final `finalize()` result equals `encode_z85` of the whole input.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Z85Encoder {
+    /// Trailing bytes (< 4) not yet forming a full Z85 group
+    remainder: Vec<u8>,
+    /// Running SHA-256 of the current stream, when enabled with `with_sha256()`
+    hasher: Option<Sha256>,
+    /// Z85 SHA-256 digest of the stream completed by the last `finalize()`
+    last_digest: Option<String>,
+}
+
+impl Z85Encoder {
+    // The final partial group and `:padding` suffix for `bytes` (fewer than 4)
+    fn tail(bytes: &[u8]) -> String {
+        let mut output = String::with_capacity(7);
+        let padding_needed = codec::encode_padded_tail(bytes, &mut output);
+        output.push_str(&format!(":{}", padding_needed));
+        output
+    }
+
+    fn export_state_internal(&self) -> Result<String, Error> {
+        if self.hasher.is_some() {
+            return Err(Error::new(ErrorCode::Unsupported, "Z85 stream error: the SHA-256 state of a with_sha256() encoder cannot be exported"));
+        }
+        Ok(Z85Encoder::tail(&self.remainder))
+    }
+
+    // Number of characters at the end of `encoded` holding the partial final group and the
+    // padding suffix: the part an appending encoder rewrites
+    fn tail_length_internal(encoded: &str) -> Result<usize, Error> {
+        let (z85_data, padding) = split_padding_suffix(encoded)?;
+        let suffix_len = encoded.len() - z85_data.len();
+        match padding {
+            0 => Ok(suffix_len),
+            1..=3 if z85_data.len() >= 5 => Ok(suffix_len + 5),
+            _ => Err(Error::new(ErrorCode::InvalidPadding, format!("Invalid padding number: {} does not leave a partial group", padding))),
+        }
+    }
+
+    fn resume_internal(state: &str) -> Result<Z85Encoder, Error> {
+        let tail_length = Z85Encoder::tail_length_internal(state)?;
+        let tail = &state[state.len() - tail_length..];
+        let (z85_data, padding) = split_padding_suffix(tail)?;
+        let mut remainder = vec![0u8; 4];
+        let written = codec::decode_into(z85_data.as_bytes(), padding, &mut remainder)?;
+        remainder.truncate(written);
+        Ok(Z85Encoder { remainder, ..Z85Encoder::default() })
+    }
+}
+
+#[wasm_bindgen]
+impl Z85Encoder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Z85Encoder {
+        Z85Encoder::default()
+    }
+
+    /// Create an encoder that also computes the SHA-256 digest of the stream in the same pass
+    pub fn with_sha256() -> Z85Encoder {
+        Z85Encoder { hasher: Some(Sha256::new()), ..Z85Encoder::default() }
+    }
+
+    /// SHA-256 digest (40-character Z85) of the stream completed by the last `finalize()`,
+    /// or `undefined` if the encoder was not created with `with_sha256()`
+    #[wasm_bindgen(getter)]
+    pub fn digest(&self) -> Option<String> {
+        self.last_digest.clone()
+    }
+
+    /// Encode the next chunk, returning every complete 5-char group available so far
+    pub fn update(&mut self, chunk: &[u8]) -> String {
+        if let Some(hasher) = &mut self.hasher {
+            hasher.update(chunk);
+        }
+        let mut output = String::with_capacity((self.remainder.len() + chunk.len()) / 4 * 5);
+        let mut chunk = chunk;
+
+        // Complete the group carried over from the previous call
+        if !self.remainder.is_empty() {
+            let needed = (4 - self.remainder.len()).min(chunk.len());
+            self.remainder.extend_from_slice(&chunk[..needed]);
+            chunk = &chunk[needed..];
+            if self.remainder.len() < 4 {
+                return output;
+            }
+            codec::encode_append(&self.remainder, &mut output);
+            self.remainder.clear();
+        }
+
+        // Encode the aligned part of the chunk directly and carry the rest
+        let aligned = chunk.len() / 4 * 4;
+        codec::encode_append(&chunk[..aligned], &mut output);
+        self.remainder.extend_from_slice(&chunk[aligned..]);
+        output
+    }
+
+    /// Flush the final partial group and return it followed by the `:padding` suffix.
+    /// The encoder is reset and can be reused for a new stream.
+    pub fn finalize(&mut self) -> String {
+        let tail = std::mem::take(&mut self.remainder);
+        if let Some(hasher) = &mut self.hasher {
+            self.last_digest = Some(digest_to_z85(&hasher.finalize_reset()));
+        }
+        Z85Encoder::tail(&tail)
+    }
+
+    /// The bytes carried over to the next group, as the text `finalize()` would return now
+    /// (the final partial group and `:padding` suffix), without ending the stream. Everything
+    /// `update()` returned so far followed by this state is always a complete Z85 encoding.
+    /// Not available for `with_sha256()` encoders, whose hash state cannot be saved.
+    pub fn export_state(&self) -> Result<String, JsValue> {
+        self.export_state_internal()
+            .map_err(js_error)
+    }
+
+    /// Continue a stream from `export_state()`, or from the end of a finalized stream: only
+    /// its last `tail_length(state)` characters are read. Append the `update()` and
+    /// `finalize()` output after dropping those characters from the stored text.
+    pub fn resume(state: &str) -> Result<Z85Encoder, JsValue> {
+        Z85Encoder::resume_internal(state)
+            .map_err(js_error)
+    }
+
+    /// Number of characters at the end of a finalized stream that `resume` reads and the
+    /// appended output replaces: the partial final group, if any, and the padding suffix
+    pub fn tail_length(encoded: &str) -> Result<usize, JsValue> {
+        Z85Encoder::tail_length_internal(encoded)
+            .map_err(js_error)
+    }
+}
+
+/// Streaming Z85 decoder.
+/// Feed Z85 text in chunks of any size to `update()`; decoded bytes are returned as soon as
+/// they are known not to belong to the final (possibly padded) group.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct Z85Decoder {
+    /// Z85 characters not yet decoded (the withheld last group and room for a suffix)
+    pending: String,
+    /// Z85 characters already decoded: the offset of `pending` in the whole input
+    consumed: usize,
+}
+
+impl Z85Decoder {
+    // Accept the next chunk of Z85 text and decode every group that is safe to emit
+    pub(crate) fn update_internal(&mut self, chunk: &str) -> Result<Vec<u8>, Error> {
+        if !chunk.is_ascii() {
+            return Err(Error::new(ErrorCode::InvalidChar, "Z85 decode error: input contains non-ASCII characters"));
+        }
+        self.pending.push_str(chunk);
+
+        // Always withhold the last complete group: it may carry padding
+        // (and the longest suffix after it may be the `:padding` or `#length` suffix)
+        let ready = self.pending.len().saturating_sub(5 + MAX_SUFFIX_LEN) / 5 * 5;
+        if ready == 0 {
+            return Ok(Vec::new());
+        }
+        let mut decoded = vec![0u8; ready / 5 * 4];
+        codec::decode_groups(&self.pending.as_bytes()[..ready], &mut decoded, self.consumed)?;
+        self.pending.drain(..ready);
+        self.consumed += ready;
+        Ok(decoded)
+    }
+
+    // Decode the withheld group and trim padding
+    pub(crate) fn finalize_internal(&mut self, padding: Option<usize>) -> Result<Vec<u8>, Error> {
+        let pending = std::mem::take(&mut self.pending);
+        let base_offset = std::mem::take(&mut self.consumed);
+
+        // ':' and '#' are part of the Z85 alphabet: a suffix is read only if the text before it
+        // is whole groups, and text of whole groups without one has no padding
+        let whole_groups = pending.len().is_multiple_of(5);
+        let (z85_data, padding) = match padding {
+            Some(padding) if whole_groups => (pending.as_str(), padding),
+            None => match padding::split_padding_suffix_after(&pending, base_offset / 5 * 4, false) {
+                Ok((z85_data, padding)) if z85_data.len().is_multiple_of(5) => (z85_data, padding),
+                _ if whole_groups => (pending.as_str(), 0),
+                Err(error) if pending.contains([':', '#']) => return Err(error.into()),
+                _ => return Err(Error::new(ErrorCode::InvalidLength, "Z85 decode error: input length must be a multiple of 5")),
+            },
+            Some(_) => {
+                return Err(Error::new(ErrorCode::InvalidLength, "Z85 decode error: input length must be a multiple of 5"));
+            }
+        };
+
+        let mut decoded = vec![0u8; z85_data.len() / 5 * 4];
+        codec::decode_groups(z85_data.as_bytes(), &mut decoded, base_offset)?;
+
+        // Remove padding
+        if padding > 3 || padding > decoded.len() {
+            return Err(Error::new(ErrorCode::InvalidPadding, "Invalid padding number"));
+        }
+        decoded.truncate(decoded.len() - padding);
+        Ok(decoded)
+    }
+}
+
+#[wasm_bindgen]
+impl Z85Decoder {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Z85Decoder {
+        Z85Decoder::default()
+    }
+
+    /// Decode the next chunk of Z85 text, returning the bytes that are ready so far
+    pub fn update(&mut self, chunk: &str) -> Result<Vec<u8>, JsValue> {
+        self.update_internal(chunk)
+            .map_err(js_error)
+    }
+
+    /// Flush the remaining bytes, trimming `padding` bytes from the end.
+    /// When `padding` is omitted, the `:padding` or `#length` suffix from the stream is used (or 0 if there was none).
+    /// The decoder is reset and can be reused for a new stream.
+    pub fn finalize(&mut self, padding: Option<usize>) -> Result<Vec<u8>, JsValue> {
+        self.finalize_internal(padding)
+            .map_err(js_error)
+    }
+}
+
+// Build a TransformStream from transform/flush callbacks. When `signal` aborts, `on_abort`
+// releases the buffered state and the stream errors with the abort reason right away, even
+// while no chunk is in flight.
+pub(crate) fn build_transform_stream(
+    transform: impl FnMut(JsValue, TransformStreamDefaultController) -> Result<(), JsValue> + 'static,
+    flush: impl FnMut(TransformStreamDefaultController) -> Result<(), JsValue> + 'static,
+    signal: Option<AbortSignal>,
+    on_abort: impl FnOnce() + 'static,
+) -> Result<TransformStream, JsValue> {
+    let transformer = Transformer::new();
+    let transform = Closure::<dyn FnMut(JsValue, TransformStreamDefaultController) -> Result<(), JsValue>>::new(transform);
+    let flush = Closure::<dyn FnMut(TransformStreamDefaultController) -> Result<(), JsValue>>::new(flush);
+    transformer.set_transform(transform.into_js_value().unchecked_ref());
+    transformer.set_flush(flush.into_js_value().unchecked_ref());
+    if let Some(signal) = signal {
+        check_aborted(Some(&signal))?;
+        let start = Closure::once_into_js(move |controller: TransformStreamDefaultController| {
+            let listener_signal = signal.clone();
+            let listener = Closure::once_into_js(move || {
+                on_abort();
+                controller.error_with_reason(&abort_reason(&listener_signal));
+            });
+            signal.add_event_listener_with_callback("abort", listener.unchecked_ref())
+        });
+        transformer.set_start(start.unchecked_ref());
+    }
+    TransformStream::new_with_transformer(&transformer)
+}
+
+// Drop the state of an aborted stream, unless a callback is using it
+pub(crate) fn reset<T>(state: &RefCell<T>, new: impl FnOnce() -> T) {
+    if let Ok(mut state) = state.try_borrow_mut() {
+        *state = new();
+    }
+}
+
+// Bytes of a stream chunk: an `ArrayBuffer` or any view of one (`Uint8Array`, `DataView`, ...)
+fn byte_chunk(chunk: &JsValue) -> Option<Vec<u8>> {
+    if let Some(bytes) = chunk.dyn_ref::<js_sys::Uint8Array>() {
+        return Some(bytes.to_vec());
+    }
+    if chunk.is_instance_of::<js_sys::ArrayBuffer>() {
+        return Some(js_sys::Uint8Array::new(chunk).to_vec());
+    }
+    if !js_sys::ArrayBuffer::is_view(chunk) {
+        return None;
+    }
+    let field = |name: &str| js_sys::Reflect::get(chunk, &JsValue::from_str(name)).ok();
+    let buffer = field("buffer")?;
+    let byte_offset = field("byteOffset")?.as_f64()? as u32;
+    let byte_length = field("byteLength")?.as_f64()? as u32;
+    Some(js_sys::Uint8Array::new_with_byte_offset_and_length(&buffer, byte_offset, byte_length).to_vec())
+}
+
+/// Create a `TransformStream` turning byte chunks (`Uint8Array`, any other `ArrayBuffer` view,
+/// or `ArrayBuffer`) into Z85 text chunks (ending with the `:padding` suffix). Any other chunk
+/// errors the stream with `INVALID_INPUT`.
+/// Usage: `blob.stream().pipeThrough(create_z85_encode_stream())`. If `signal` aborts, the
+/// stream errors with `signal.reason` and frees its buffers.
+#[wasm_bindgen]
+pub fn create_z85_encode_stream(signal: Option<AbortSignal>) -> Result<TransformStream, JsValue> {
+    let encoder = Rc::new(RefCell::new(Z85Encoder::new()));
+    let flush_encoder = encoder.clone();
+    let abort_encoder = encoder.clone();
+    build_transform_stream(
+        move |chunk, controller| {
+            let Some(bytes) = byte_chunk(&chunk) else {
+                let error = js_error(Error::new(ErrorCode::InvalidInput, "Expected a Uint8Array, ArrayBuffer view or ArrayBuffer chunk"));
+                controller.error_with_reason(&error);
+                return Err(error);
+            };
+            let encoded = encoder.borrow_mut().update(&bytes);
+            if !encoded.is_empty() {
+                controller.enqueue_with_chunk(&JsValue::from_str(&encoded))?;
+            }
+            Ok(())
+        },
+        move |controller| {
+            let encoded = flush_encoder.borrow_mut().finalize();
+            controller.enqueue_with_chunk(&JsValue::from_str(&encoded))
+        },
+        signal,
+        move || reset(&abort_encoder, Z85Encoder::new),
+    )
+}
+
+// Text of a stream chunk: a string, or the ASCII bytes of a `Uint8Array` (e.g. a fetch body)
+fn text_chunk(chunk: JsValue) -> Result<String, JsValue> {
+    if let Some(text) = chunk.as_string() {
+        return Ok(text);
+    }
+    let bytes = chunk.dyn_into::<js_sys::Uint8Array>()
+        .map_err(|_| js_error(Error::new(ErrorCode::InvalidInput, "Expected a string or Uint8Array chunk")))?
+        .to_vec();
+    String::from_utf8(bytes)
+        .map_err(|_| js_error(Error::new(ErrorCode::InvalidChar, "Z85 decode error: input contains non-ASCII characters")))
+}
+
+/// Create a `TransformStream` turning Z85 text chunks (with a trailing `:padding` or `#length` suffix) into `Uint8Array` chunks.
+/// Chunks may be strings or `Uint8Array`s of the text's bytes. `signal` cancels it as for
+/// `create_z85_encode_stream`.
+#[wasm_bindgen]
+pub fn create_z85_decode_stream(signal: Option<AbortSignal>) -> Result<TransformStream, JsValue> {
+    let decoder = Rc::new(RefCell::new(Z85Decoder::new()));
+    let flush_decoder = decoder.clone();
+    let abort_decoder = decoder.clone();
+    build_transform_stream(
+        move |chunk, controller| {
+            let chunk = text_chunk(chunk)?;
+            let decoded = decoder.borrow_mut().update_internal(&chunk)
+                .map_err(js_error)?;
+            if !decoded.is_empty() {
+                controller.enqueue_with_chunk(&js_sys::Uint8Array::from(decoded.as_slice()))?;
+            }
+            Ok(())
+        },
+        move |controller| {
+            let decoded = flush_decoder.borrow_mut().finalize_internal(None)
+                .map_err(js_error)?;
+            controller.enqueue_with_chunk(&js_sys::Uint8Array::from(decoded.as_slice()))
+        },
+        signal,
+        move || reset(&abort_decoder, Z85Decoder::new),
+    )
+}
+
+/// Wrap a `ReadableStream` of Z85 text (strings or bytes, such as `response.body`) as a
+/// `ReadableStream` of the decoded bytes. Cancelling the result cancels `source`.
+#[wasm_bindgen]
+pub fn z85_decode_readable(source: &ReadableStream) -> Result<ReadableStream, JsValue> {
+    let decode = create_z85_decode_stream(None)?;
+    Ok(source.pipe_through(&ReadableWritablePair::new(&decode.readable(), &decode.writable())))
+}
+
+/// Wrap a `WritableStream` (e.g. a `FileSystemWritableFileStream`) as a `WritableStream` that
+/// accepts raw `Uint8Array` chunks and writes their Z85 encoding to `sink` as text chunks,
+/// ending with the `:padding` suffix when closed, after which `sink` is closed too. Errors in
+/// `sink` error the result.
+#[wasm_bindgen]
+pub fn z85_encode_writable(sink: &WritableStream) -> Result<WritableStream, JsValue> {
+    let encode = create_z85_encode_stream(None)?;
+    // Failures reach the writer through the returned stream; the pipe's own promise is not awaited
+    let ignore = Closure::once(|_: JsValue| {});
+    let _ = encode.readable().pipe_to(sink).catch(&ignore);
+    ignore.forget();
+    Ok(encode.writable())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compression::Compression;
+    use crate::{decode_z85_internal, encode_z85_internal, encode_z85_with_options_internal, PaddingFormat};
+
+    fn encode_in_chunks(data: &[u8], chunk_size: usize) -> String {
+        let mut encoder = Z85Encoder::new();
+        let mut output = String::new();
+        for chunk in data.chunks(chunk_size) {
+            output.push_str(&encoder.update(chunk));
+        }
+        output.push_str(&encoder.finalize());
+        output
+    }
+
+    #[test]
+    fn test_encoder_matches_one_shot_for_all_chunk_sizes() {
+        let data: Vec<u8> = (0..103u32).map(|i| (i * 31) as u8).collect();
+        let expected = encode_z85_internal(&data);
+        for chunk_size in 1..=17 {
+            assert_eq!(encode_in_chunks(&data, chunk_size), expected, "chunk size {}", chunk_size);
+        }
+    }
+
+    #[test]
+    fn test_encoder_emits_incrementally() {
+        let mut encoder = Z85Encoder::new();
+        assert_eq!(encoder.update(b"Hel"), "");
+        assert_eq!(encoder.update(b"lo, W").len(), 10);
+        assert_eq!(encoder.finalize(), ":0");
+    }
+
+    #[test]
+    fn test_encoder_empty_and_reuse() {
+        let mut encoder = Z85Encoder::new();
+        assert_eq!(encoder.finalize(), encode_z85_internal(b""));
+
+        // Reuse after finalize starts a fresh stream
+        encoder.update(b"abc");
+        assert_eq!(encoder.finalize(), encode_z85_internal(b"abc"));
+        encoder.update(b"Hello");
+        assert_eq!(encoder.finalize(), encode_z85_internal(b"o"));
+    }
+
+    #[test]
+    fn test_encoder_computes_sha256_in_same_pass() {
+        use crate::digest::sha256_z85_internal;
+
+        let mut encoder = Z85Encoder::with_sha256();
+        assert_eq!(encoder.digest(), None);
+        encoder.update(b"Hello, ");
+        encoder.update(b"World");
+        encoder.finalize();
+        assert_eq!(encoder.digest().unwrap(), sha256_z85_internal(b"Hello, World"));
+
+        // The digest restarts with each stream
+        encoder.update(b"abc");
+        encoder.finalize();
+        assert_eq!(encoder.digest().unwrap(), sha256_z85_internal(b"abc"));
+
+        assert_eq!(Z85Encoder::new().digest(), None);
+    }
+
+    #[test]
+    fn test_encoder_export_and_resume() {
+        let data: Vec<u8> = (0..50u32).map(|i| (i * 13) as u8).collect();
+        let expected = encode_z85_internal(&data);
+        for split in 0..data.len() {
+            // Suspend after `split` bytes: the stored text plus the state is a valid stream
+            let mut encoder = Z85Encoder::new();
+            let mut stored = encoder.update(&data[..split]);
+            let state = encoder.export_state_internal().unwrap();
+            assert_eq!(decode_z85_internal(&format!("{}{}", stored, state)).unwrap(), &data[..split]);
+
+            let mut resumed = Z85Encoder::resume_internal(&state).unwrap();
+            stored.push_str(&resumed.update(&data[split..]));
+            stored.push_str(&resumed.finalize());
+            assert_eq!(stored, expected, "split {}", split);
+
+            // Appending to a finalized stream: drop its tail and resume from it
+            let finalized = encode_z85_internal(&data[..split]);
+            let cut = finalized.len() - Z85Encoder::tail_length_internal(&finalized).unwrap();
+            let mut resumed = Z85Encoder::resume_internal(&finalized).unwrap();
+            let appended = format!("{}{}{}", &finalized[..cut], resumed.update(&data[split..]), resumed.finalize());
+            assert_eq!(appended, expected, "split {}", split);
+        }
+
+        let mut hashing = Z85Encoder::with_sha256();
+        hashing.update(b"abc");
+        assert!(hashing.export_state_internal().is_err());
+        assert_eq!(Z85Encoder::resume_internal("abc").err().unwrap().message, "Invalid format: expected 'z85_data:padding'");
+    }
+
+    fn decode_in_chunks(encoded: &str, chunk_size: usize, padding: Option<usize>) -> Result<Vec<u8>, Error> {
+        let mut decoder = Z85Decoder::new();
+        let mut output = Vec::new();
+        for chunk in encoded.as_bytes().chunks(chunk_size) {
+            output.extend(decoder.update_internal(std::str::from_utf8(chunk).unwrap())?);
+        }
+        output.extend(decoder.finalize_internal(padding)?);
+        Ok(output)
+    }
+
+    #[test]
+    fn test_decoder_matches_one_shot_for_all_chunk_sizes() {
+        let data: Vec<u8> = (0..103u32).map(|i| (i * 17) as u8).collect();
+        let encoded = encode_z85_internal(&data);
+        for chunk_size in 1..=17 {
+            assert_eq!(decode_in_chunks(&encoded, chunk_size, None).unwrap(), data, "chunk size {}", chunk_size);
+        }
+    }
+
+    #[test]
+    fn test_decoder_explicit_padding() {
+        let encoded = encode_z85_internal(b"Hello");
+        let (z85_data, _) = encoded.rsplit_once(':').unwrap();
+        assert_eq!(decode_in_chunks(z85_data, 3, Some(3)).unwrap(), b"Hello");
+        assert_eq!(decode_in_chunks(z85_data, 3, Some(3)).unwrap(), decode_z85_internal(&encoded).unwrap());
+    }
+
+    #[test]
+    fn test_decoder_colon_in_data() {
+        // ':' is a valid Z85 character, only the trailing suffix is padding info
+        let encoded = "nm=QNzY&b1A+]m^:1";
+        let (z85_data, _) = encoded.rsplit_once(':').unwrap();
+        let with_colon = format!("{}a:b:c:0", z85_data);
+        assert_eq!(decode_in_chunks(&with_colon, 4, None).unwrap(), decode_z85_internal(&with_colon).unwrap());
+    }
+
+    #[test]
+    fn test_decoder_length_suffix() {
+        let data: Vec<u8> = (0..2003u32).map(|i| (i * 13) as u8).collect();
+        let encoded = encode_z85_with_options_internal(&data, PaddingFormat::Length, Compression::None);
+        assert!(encoded.ends_with("#2003"));
+        for chunk_size in [1, 3, 7, 64, encoded.len()] {
+            assert_eq!(decode_in_chunks(&encoded, chunk_size, None).unwrap(), data, "chunk size {}", chunk_size);
+        }
+
+        let (z85_data, _) = encoded.rsplit_once('#').unwrap();
+        let truncated = format!("{}#20030", z85_data);
+        assert_eq!(decode_in_chunks(&truncated, 64, None).unwrap_err().code, ErrorCode::InvalidPadding);
+    }
+
+    #[test]
+    fn test_decoder_withholds_last_group_and_suffix() {
+        let mut decoder = Z85Decoder::new();
+        assert!(decoder.update_internal(&"HelloWorld".repeat(3)).unwrap().is_empty());
+        assert_eq!(decoder.update_internal("HelloWorld").unwrap().len(), 8);
+        assert!(decoder.update_internal("").unwrap().is_empty());
+        assert_eq!(decoder.finalize_internal(Some(0)).unwrap().len(), 24);
+    }
+
+    #[test]
+    fn test_decoder_errors() {
+        let mut decoder = Z85Decoder::new();
+        assert!(decoder.update_internal("Hell").unwrap().is_empty());
+        assert!(decoder.finalize_internal(None).err().unwrap().message.contains("multiple of 5"));
+
+        let mut decoder = Z85Decoder::new();
+        decoder.update_internal("Hello:x").unwrap();
+        assert_eq!(decoder.finalize_internal(None).err().unwrap().message, "Invalid padding number");
+
+        let mut decoder = Z85Decoder::new();
+        decoder.update_internal("Hello:1").unwrap();
+        assert!(decoder.finalize_internal(Some(1)).err().unwrap().message.contains("multiple of 5"));
+
+        let mut decoder = Z85Decoder::new();
+        decoder.update_internal("Hello").unwrap();
+        assert_eq!(decoder.finalize_internal(Some(4)).err().unwrap().message, "Invalid padding number");
+
+        let mut decoder = Z85Decoder::new();
+        assert!(decoder.update_internal("Hé").err().unwrap().message.contains("non-ASCII"));
+
+        // Offsets count from the start of the stream, not the current chunk
+        let mut decoder = Z85Decoder::new();
+        decoder.update_internal(&"HelloWorld".repeat(3)).unwrap();
+        assert_eq!(
+            decoder.update_internal(&format!("Hel~o{}", "World".repeat(7))).err().unwrap().message,
+            "Z85 decode error: invalid character at offset 33"
+        );
+        let mut decoder = Z85Decoder::new();
+        decoder.update_internal("HelloWorldHello").unwrap();
+        decoder.update_internal("Wor~d").unwrap();
+        assert_eq!(decoder.finalize_internal(Some(0)).err().unwrap().message, "Z85 decode error: invalid character at offset 18");
+    }
+}
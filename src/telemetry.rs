@@ -0,0 +1,179 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::rc::Rc;
+use tsify::Tsify;
+
+use crate::error::Error;
+use crate::{limits, now_ms};
+
+/// Structured telemetry event delivered to the registered hook
+#[derive(Serialize, Tsify, Clone, Debug, PartialEq)]
+pub(crate) struct TelemetryEvent {
+    /// `conversion_started`, `conversion_finished` or `conversion_failed`
+    #[tsify(type = "\"conversion_started\" | \"conversion_finished\" | \"conversion_failed\"")]
+    pub kind: &'static str,
+    pub operation: &'static str,
+    pub bytes_in: usize,
+    #[tsify(optional)]
+    pub bytes_out: Option<usize>,
+    #[tsify(optional)]
+    pub duration_ms: Option<f64>,
+    #[tsify(optional)]
+    pub error: Option<String>,
+}
+
+type TelemetrySink = Rc<dyn Fn(&TelemetryEvent)>;
+
+#[wasm_bindgen]
+extern "C" {
+    // A `set_telemetry_hook` callback
+    #[wasm_bindgen(typescript_type = "((event: TelemetryEvent) => void)")]
+    pub type TelemetryCallback;
+}
+
+thread_local! {
+    static TELEMETRY_SINK: RefCell<Option<TelemetrySink>> = RefCell::new(None);
+}
+
+// Replace the current telemetry sink
+fn set_sink(sink: Option<TelemetrySink>) {
+    TELEMETRY_SINK.with(|cell| *cell.borrow_mut() = sink);
+}
+
+fn is_enabled() -> bool {
+    TELEMETRY_SINK.with(|cell| cell.borrow().is_some())
+}
+
+// Call the sink outside the borrow, so a hook may replace or remove itself
+fn emit(event: TelemetryEvent) {
+    let sink = TELEMETRY_SINK.with(|cell| cell.borrow().clone());
+    if let Some(sink) = sink {
+        sink(&event);
+    }
+}
+
+// Run a conversion under the size limits, reporting started/finished/failed events when a hook is registered
+pub(crate) fn instrument<T>(
+    operation: &'static str,
+    bytes_in: usize,
+    output_len: impl Fn(&T) -> usize,
+    f: impl FnOnce() -> Result<T, Error>,
+) -> Result<T, Error> {
+    if !is_enabled() {
+        return limits::enforce(bytes_in, &output_len, f);
+    }
+
+    emit(TelemetryEvent {
+        kind: "conversion_started",
+        operation,
+        bytes_in,
+        bytes_out: None,
+        duration_ms: None,
+        error: None,
+    });
+
+    let started = now_ms();
+    let result = limits::enforce(bytes_in, &output_len, f);
+    let duration_ms = Some(now_ms() - started);
+
+    emit(match &result {
+        Ok(value) => TelemetryEvent {
+            kind: "conversion_finished",
+            operation,
+            bytes_in,
+            bytes_out: Some(output_len(value)),
+            duration_ms,
+            error: None,
+        },
+        Err(e) => TelemetryEvent {
+            kind: "conversion_failed",
+            operation,
+            bytes_in,
+            bytes_out: None,
+            duration_ms,
+            error: Some(e.message.clone()),
+        },
+    });
+
+    result
+}
+
+/// Register a telemetry callback receiving structured conversion events, or pass `undefined` to remove it.
+/// Exceptions thrown by the callback are ignored so monitoring can never break a conversion.
+#[wasm_bindgen]
+pub fn set_telemetry_hook(callback: Option<TelemetryCallback>) {
+    set_sink(callback.map(|callback| -> TelemetrySink {
+        let callback: js_sys::Function = callback.unchecked_into();
+        Rc::new(move |event| {
+            if let Ok(value) = serde_wasm_bindgen::to_value(event) {
+                let _ = callback.call1(&JsValue::NULL, &value);
+            }
+        })
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    fn collect_events() -> Rc<RefCell<Vec<TelemetryEvent>>> {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let sink_events = events.clone();
+        set_sink(Some(Rc::new(move |event| sink_events.borrow_mut().push(event.clone()))));
+        events
+    }
+
+    #[test]
+    fn test_instrument_without_hook() {
+        set_sink(None);
+        let result = instrument("encode_z85", 4, String::len, || Ok("HelloWorld".to_string()));
+        assert_eq!(result.unwrap(), "HelloWorld");
+    }
+
+    #[test]
+    fn test_instrument_success_events() {
+        let events = collect_events();
+        let result = instrument("encode_z85", 4, String::len, || Ok("12345:0".to_string()));
+        set_sink(None);
+
+        assert!(result.is_ok());
+        let events = events.borrow();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, "conversion_started");
+        assert_eq!(events[0].bytes_in, 4);
+        assert_eq!(events[1].kind, "conversion_finished");
+        assert_eq!(events[1].operation, "encode_z85");
+        assert_eq!(events[1].bytes_out, Some(7));
+        assert!(events[1].duration_ms.unwrap() >= 0.0);
+    }
+
+    #[test]
+    fn test_instrument_failure_events() {
+        let events = collect_events();
+        let result: Result<String, Error> = instrument("decode_z85", 3, String::len, || Err(Error::new(ErrorCode::InvalidInput, "boom")));
+        set_sink(None);
+
+        assert_eq!(result.err().unwrap().message, "boom");
+        let events = events.borrow();
+        assert_eq!(events[1].kind, "conversion_failed");
+        assert_eq!(events[1].error.as_deref(), Some("boom"));
+        assert_eq!(events[1].bytes_out, None);
+    }
+
+    #[test]
+    fn test_hook_can_remove_itself() {
+        let calls = Rc::new(RefCell::new(0));
+        let sink_calls = calls.clone();
+        set_sink(Some(Rc::new(move |_| {
+            *sink_calls.borrow_mut() += 1;
+            set_sink(None);
+        })));
+        let result = instrument("encode_z85", 4, String::len, || Ok("12345:0".to_string()));
+
+        assert!(result.is_ok());
+        assert!(!is_enabled());
+        assert_eq!(*calls.borrow(), 1);
+    }
+}
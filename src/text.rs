@@ -0,0 +1,57 @@
+use wasm_bindgen::prelude::*;
+
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{decode_z85_internal, encode_z85_internal, telemetry};
+
+// Internal pure Rust function for decoding Z85 to a string, replacing invalid UTF-8 with U+FFFD
+// when `lossy` is set
+pub(crate) fn decode_z85_to_text_internal(z85_data_with_padding: &str, lossy: bool) -> Result<String, Error> {
+    let decoded_data = decode_z85_internal(z85_data_with_padding)?;
+    match String::from_utf8(decoded_data) {
+        Ok(text) => Ok(text),
+        Err(e) if lossy => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+        Err(e) => Err(Error::new(ErrorCode::InvalidInput, format!("Decoded data is not valid UTF-8: {}", e.utf8_error()))),
+    }
+}
+
+/// Encode the UTF-8 bytes of a string to Z85 with padding info, as
+/// `encode_z85(new TextEncoder().encode(text))` would, without the intermediate `Uint8Array`
+#[wasm_bindgen]
+pub fn encode_text_z85(text: &str) -> Result<String, JsValue> {
+    telemetry::instrument("encode_text_z85", text.len(), String::len, || Ok(encode_z85_internal(text.as_bytes())))
+        .map_err(js_error)
+}
+
+/// Decode Z85 data with padding info (or a `z85v2:` container) to a string, as
+/// `new TextDecoder("utf-8", { fatal: !lossy }).decode(decode_z85(...))` would. Throws for
+/// invalid UTF-8 unless `lossy` is set, which replaces it with U+FFFD.
+#[wasm_bindgen]
+pub fn decode_z85_to_text(z85_data_with_padding: &str, lossy: Option<bool>) -> Result<String, JsValue> {
+    telemetry::instrument("decode_z85_to_text", z85_data_with_padding.len(), String::len, || {
+        decode_z85_to_text_internal(z85_data_with_padding, lossy.unwrap_or(false))
+    })
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_roundtrip() {
+        let text = "Hello, 世界 👋";
+        let encoded = encode_z85_internal(text.as_bytes());
+        assert_eq!(decode_z85_to_text_internal(&encoded, false).unwrap(), text);
+        assert_eq!(decode_z85_to_text_internal("nm=QNzY&b1A+]m^:1", false).unwrap(), "Hello World");
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let encoded = encode_z85_internal(&[b'H', b'i', 0xff, b'!']);
+        assert_eq!(
+            decode_z85_to_text_internal(&encoded, false).unwrap_err().message,
+            "Decoded data is not valid UTF-8: invalid utf-8 sequence of 1 bytes from index 2"
+        );
+        assert_eq!(decode_z85_to_text_internal(&encoded, true).unwrap(), "Hi\u{FFFD}!");
+    }
+}
@@ -0,0 +1,114 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+
+use crate::compression::Compression;
+use crate::encoding::{transcode_internal, Base64Variant, Encoding};
+use crate::error::{Error, ErrorInfo};
+use crate::{base64_to_z85_internal, container, decode_z85_internal, encode_z85_internal, telemetry, z85_to_base64_with_variant_internal, ConversionOptions, DataType};
+
+// `TryResult` as a union on `ok`, which a derived interface cannot express
+#[wasm_bindgen(typescript_custom_section)]
+const TRY_RESULT_TS: &str = r#"
+export type TryResult<T> =
+    | { ok: true; value: T }
+    | { ok: false; error: ErrorInfo };
+"#;
+
+/// Outcome of a `try_*` function: `{ ok: true, value }` or `{ ok: false, error: { code, message, offset } }`
+#[derive(Serialize, Debug, PartialEq)]
+struct TryResult<T> {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorInfo>,
+}
+
+impl<T> From<Result<T, Error>> for TryResult<T> {
+    fn from(result: Result<T, Error>) -> TryResult<T> {
+        match result {
+            Ok(value) => TryResult { ok: true, value: Some(value), error: None },
+            Err(message) => TryResult { ok: false, value: None, error: Some(ErrorInfo::from(message)) },
+        }
+    }
+}
+
+// Convert to a plain JS object. Strings, bytes and error details always serialize.
+fn to_js<T: Serialize>(result: TryResult<T>) -> JsValue {
+    serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::UNDEFINED)
+}
+
+/// Like `encode_z85` (without progress reporting), but returns `{ ok, value, error }` instead of throwing
+#[wasm_bindgen(unchecked_return_type = "TryResult<string>")]
+pub fn try_encode_z85(data: &[u8], compression: Option<Compression>) -> JsValue {
+    let result = telemetry::instrument("try_encode_z85", data.len(), String::len, || {
+        Ok(match compression.unwrap_or(Compression::None) {
+            Compression::None => encode_z85_internal(data),
+            compression => container::encode_container_internal(data, true, compression),
+        })
+    });
+    to_js(TryResult::from(result))
+}
+
+/// Like `decode_z85` (without progress reporting), but returns `{ ok, value, error }` instead of throwing.
+/// `value` is a `Uint8Array`.
+#[wasm_bindgen(unchecked_return_type = "TryResult<Uint8Array>")]
+pub fn try_decode_z85(z85_data_with_padding: &str) -> JsValue {
+    let result = telemetry::instrument("try_decode_z85", z85_data_with_padding.len(), Vec::len, || {
+        decode_z85_internal(z85_data_with_padding)
+    });
+    to_js(TryResult::from(result.map(serde_bytes::ByteBuf::from)))
+}
+
+/// Like `z85_to_base64` (without progress reporting), but returns `{ ok, value, error }` instead of throwing
+#[wasm_bindgen(unchecked_return_type = "TryResult<string>")]
+pub fn try_z85_to_base64(z85_data_with_padding: &str, variant: Option<Base64Variant>) -> JsValue {
+    let result = telemetry::instrument("try_z85_to_base64", z85_data_with_padding.len(), String::len, || {
+        z85_to_base64_with_variant_internal(z85_data_with_padding, variant.unwrap_or(Base64Variant::Standard))
+    });
+    to_js(TryResult::from(result))
+}
+
+/// Like `base64_to_z85` (without progress reporting), but returns `{ ok, value, error }` instead of throwing
+#[wasm_bindgen(unchecked_return_type = "TryResult<string>")]
+pub fn try_base64_to_z85(base64_data: &str, variant: Option<Base64Variant>) -> JsValue {
+    let result = telemetry::instrument("try_base64_to_z85", base64_data.len(), String::len, || {
+        base64_to_z85_internal(base64_data, variant.unwrap_or(Base64Variant::Standard))
+    });
+    to_js(TryResult::from(result))
+}
+
+/// Like `transcode`, but returns `{ ok, value, error }` instead of throwing
+#[wasm_bindgen(unchecked_return_type = "TryResult<string>")]
+pub fn try_transcode(data: &str, from: Encoding, to: Encoding, options: Option<ConversionOptions>) -> JsValue {
+    let opts = options.unwrap_or(ConversionOptions::new(DataType::Raw, DataType::Raw));
+    let result = telemetry::instrument("try_transcode", data.len(), String::len, || {
+        transcode_internal(data, from, to, &opts)
+    });
+    to_js(TryResult::from(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    #[test]
+    fn test_try_result() {
+        assert_eq!(
+            TryResult::from(decode_z85_internal("HelloWorld:0")),
+            TryResult { ok: true, value: Some(vec![0x86, 0x4f, 0xd2, 0x6f, 0xb5, 0x59, 0xf7, 0x5b]), error: None }
+        );
+
+        let failed = TryResult::from(decode_z85_internal("Hello Wor:0"));
+        assert!(!failed.ok && failed.value.is_none());
+        assert_eq!(failed.error, Some(ErrorInfo::from(Error::new(ErrorCode::InvalidLength, "Z85 decode error: data length (9) is not a multiple of 5"))));
+
+        let failed = TryResult::from(decode_z85_internal("Hello~orld:0"));
+        let serialized = serde_json::to_value(&failed).unwrap();
+        assert_eq!(serialized, serde_json::json!({
+            "ok": false,
+            "error": { "code": "INVALID_CHAR", "message": "Z85 decode error: invalid character at offset 5", "offset": 5 },
+        }));
+    }
+}
@@ -0,0 +1,103 @@
+use wasm_bindgen::prelude::*;
+
+use crate::data_url::hex_value;
+use crate::encoding::hex_encode;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{decode_z85_strict_internal, encode_z85_strict_internal};
+
+/// Offsets of the hyphens in the 8-4-4-4-12 form
+const HYPHEN_OFFSETS: [usize; 4] = [8, 13, 18, 23];
+
+/// Length of the Z85 form: 16 bytes in four 5-character groups
+const Z85_UUID_LEN: usize = 20;
+
+// Parse a UUID in the hyphenated 8-4-4-4-12 form or as 32 bare hex digits, in either case
+fn parse_uuid(uuid: &str) -> Result<[u8; 16], Error> {
+    let hyphenated = match uuid.len() {
+        36 => true,
+        32 => false,
+        len => return Err(Error::new(ErrorCode::InvalidLength, format!("UUID parse error: length must be 36 (hyphenated) or 32 (hex digits only), got {}", len))),
+    };
+    let mut bytes = [0u8; 16];
+    let mut digits = 0;
+    for (offset, c) in uuid.bytes().enumerate() {
+        if hyphenated && HYPHEN_OFFSETS.contains(&offset) {
+            if c != b'-' {
+                return Err(Error::new(ErrorCode::InvalidChar, format!("UUID parse error: invalid character at offset {} (expected '-')", offset)).at(offset));
+            }
+            continue;
+        }
+        let value = hex_value(c)
+            .ok_or_else(|| Error::new(ErrorCode::InvalidChar, format!("UUID parse error: invalid character at offset {}", offset)).at(offset))?;
+        bytes[digits / 2] |= value << if digits % 2 == 0 { 4 } else { 0 };
+        digits += 1;
+    }
+    Ok(bytes)
+}
+
+// Format 16 bytes in the lowercase hyphenated 8-4-4-4-12 form
+fn format_uuid(bytes: &[u8]) -> String {
+    let hex = hex_encode(bytes, false);
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+// Internal pure Rust function for converting a UUID to its 20-character Z85 form
+fn uuid_to_z85_internal(uuid: &str) -> Result<String, Error> {
+    encode_z85_strict_internal(&parse_uuid(uuid)?)
+}
+
+// Internal pure Rust function for converting the Z85 form back to a hyphenated UUID
+fn z85_to_uuid_internal(z85_data: &str) -> Result<String, Error> {
+    if z85_data.len() != Z85_UUID_LEN {
+        return Err(Error::new(ErrorCode::InvalidLength, format!("UUID Z85 error: length must be {}, got {}", Z85_UUID_LEN, z85_data.len())));
+    }
+    Ok(format_uuid(&decode_z85_strict_internal(z85_data)?))
+}
+
+/// Convert a UUID (hyphenated, or 32 hex digits, in either case) to the 20-character Z85
+/// encoding of its 16 bytes. The length is fixed, so no padding suffix is appended.
+#[wasm_bindgen]
+pub fn uuid_to_z85(uuid: &str) -> Result<String, JsValue> {
+    uuid_to_z85_internal(uuid).map_err(js_error)
+}
+
+/// Convert the 20-character Z85 form from `uuid_to_z85` back to a lowercase hyphenated UUID
+#[wasm_bindgen]
+pub fn z85_to_uuid(z85_data: &str) -> Result<String, JsValue> {
+    z85_to_uuid_internal(z85_data).map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UUID: &str = "f81d4fae-7dec-11d0-a765-00a0c91e6bf6";
+
+    #[test]
+    fn test_uuid_roundtrip() {
+        let z85 = uuid_to_z85_internal(UUID).unwrap();
+        assert_eq!(z85.len(), 20);
+        assert_eq!(z85_to_uuid_internal(&z85).unwrap(), UUID);
+        assert_eq!(uuid_to_z85_internal(&UUID.to_uppercase()).unwrap(), z85);
+        assert_eq!(uuid_to_z85_internal(&UUID.replace('-', "")).unwrap(), z85);
+        assert_eq!(uuid_to_z85_internal("00000000-0000-0000-0000-000000000000").unwrap(), "00000000000000000000");
+    }
+
+    #[test]
+    fn test_uuid_errors() {
+        assert_eq!(
+            uuid_to_z85_internal("f81d4fae-7dec-11d0-a765").unwrap_err().message,
+            "UUID parse error: length must be 36 (hyphenated) or 32 (hex digits only), got 23"
+        );
+        assert_eq!(
+            uuid_to_z85_internal("f81d4fae7-dec-11d0-a765-00a0c91e6bf6").unwrap_err().message,
+            "UUID parse error: invalid character at offset 8 (expected '-')"
+        );
+        assert_eq!(
+            uuid_to_z85_internal("f81d4fae-7dec-11d0-a765-00a0c91e6bg6").unwrap_err().message,
+            "UUID parse error: invalid character at offset 34"
+        );
+        assert_eq!(z85_to_uuid_internal("nm=QN").unwrap_err().message, "UUID Z85 error: length must be 20, got 5");
+        assert!(z85_to_uuid_internal("%%%%%000000000000000").unwrap_err().message.contains("invalid group"));
+    }
+}
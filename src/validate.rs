@@ -0,0 +1,172 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::codec::{self, INVALID_DIGIT};
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{container, decode_z85_internal};
+
+/// Result of `validate_z85` (returned to JS as a plain object)
+#[derive(Serialize, Tsify, Debug, Default, PartialEq)]
+struct ValidationReport {
+    /// Whether the input is well-formed (every check below passes)
+    valid: bool,
+    /// Every problem found, separated by `; `
+    #[tsify(optional)]
+    error: Option<String>,
+    /// JS string index and text of the first character outside the Z85 alphabet
+    #[tsify(optional)]
+    invalid_index: Option<usize>,
+    #[tsify(optional)]
+    invalid_char: Option<String>,
+    /// JS string index of the first 5-character group whose value exceeds 32 bits
+    #[tsify(optional)]
+    invalid_group_index: Option<usize>,
+    /// Number of Z85 characters before the suffix, and that number modulo 5 (must be 0)
+    data_length: usize,
+    length_remainder: usize,
+    /// `"padding"` for `:padding`, `"length"` for `#length`, `"container"` for `z85v2:`
+    #[tsify(optional, type = "\"padding\" | \"length\" | \"container\"")]
+    suffix: Option<&'static str>,
+    /// Padding bytes implied by the suffix
+    #[tsify(optional)]
+    padding: Option<usize>,
+    /// Whether the suffix agrees with the data: at most 3 padding bytes, no more than the data holds
+    padding_consistent: bool,
+    /// Number of bytes the input decodes to, when valid
+    #[tsify(optional)]
+    decoded_length: Option<usize>,
+}
+
+// Internal pure Rust function for diagnosing Z85 text with a `:padding` or `#length` suffix,
+// bare Z85 (no suffix) or a `z85v2:` container. A trailing `:` or `#` followed by digits is
+// read as the suffix; otherwise those characters are Z85 data.
+fn validate_z85_internal(input: &str) -> ValidationReport {
+    if input.starts_with(container::V2_PREFIX) {
+        let decoded = decode_z85_internal(input);
+        return ValidationReport {
+            valid: decoded.is_ok(),
+            decoded_length: decoded.as_ref().ok().map(Vec::len),
+            error: decoded.err().map(|e| e.message),
+            suffix: Some("container"),
+            padding_consistent: true,
+            ..ValidationReport::default()
+        };
+    }
+
+    let suffix_start = input.rfind([':', '#'])
+        .filter(|&separator| {
+            let digits = &input[separator + 1..];
+            !digits.is_empty() && digits.bytes().all(|c| c.is_ascii_digit())
+        });
+    let data = &input[..suffix_start.unwrap_or(input.len())];
+    let mut report = ValidationReport {
+        data_length: data.chars().count(),
+        length_remainder: data.chars().count() % 5,
+        padding_consistent: true,
+        ..ValidationReport::default()
+    };
+    let mut errors = Vec::new();
+
+    // Everything before the first invalid character is ASCII, so byte offsets are JS indices
+    if let Some((index, c)) = data.char_indices().find(|&(_, c)| !c.is_ascii() || codec::Z85.digits[c as usize] == INVALID_DIGIT) {
+        report.invalid_index = Some(index);
+        report.invalid_char = Some(c.to_string());
+        errors.push(format!("invalid character '{}' at index {}", c, index));
+    }
+    if report.length_remainder != 0 {
+        errors.push(format!("data length ({}) is not a multiple of 5", report.data_length));
+    }
+    if report.invalid_index.is_none() && report.length_remainder == 0 {
+        let overflow = data.as_bytes().chunks_exact(5).position(|group| {
+            group.iter().fold(0u64, |value, &c| value * 85 + codec::Z85.digits[c as usize] as u64) > u32::MAX as u64
+        });
+        if let Some(group) = overflow {
+            report.invalid_group_index = Some(group * 5);
+            errors.push(format!("group at index {} exceeds 32 bits", group * 5));
+        }
+    }
+
+    // Bytes held by whole groups; the suffix may drop up to 3 of them
+    let available = report.data_length / 5 * 4;
+    if let Some(separator) = suffix_start {
+        // The suffix is all digits, so only overflow fails to parse
+        let number: usize = input[separator + 1..].parse().unwrap_or(usize::MAX);
+        let padding = if input.as_bytes()[separator] == b':' {
+            report.suffix = Some("padding");
+            Some(number)
+        } else {
+            report.suffix = Some("length");
+            available.checked_sub(number)
+        };
+        report.padding = padding;
+        report.padding_consistent = padding.is_some_and(|padding| padding <= 3 && padding <= available);
+        if !report.padding_consistent {
+            errors.push(format!("suffix '{}' does not fit {} bytes of data", &input[separator..], available));
+        }
+    }
+
+    report.valid = errors.is_empty();
+    if report.valid {
+        report.decoded_length = Some(available - report.padding.unwrap_or(0));
+    }
+    report.error = (!errors.is_empty()).then(|| errors.join("; "));
+    report
+}
+
+/// Diagnose Z85 text with or without a `:padding` / `#length` suffix (or a `z85v2:` container)
+/// without decoding it. Returns `{ valid, error, invalid_index, invalid_char, invalid_group_index,
+/// data_length, length_remainder, suffix, padding, padding_consistent, decoded_length }`; indices
+/// are JS string indices.
+#[wasm_bindgen(unchecked_return_type = "ValidationReport")]
+pub fn validate_z85(input: &str) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&validate_z85_internal(input))
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_well_formed() {
+        let report = validate_z85_internal("nm=QNzVx+q:3");
+        assert!(report.valid && report.padding_consistent);
+        assert_eq!((report.suffix, report.padding, report.decoded_length), (Some("padding"), Some(3), Some(5)));
+        assert_eq!(validate_z85_internal("nm=QNzVx+q#5").decoded_length, Some(5));
+
+        // Bare RFC 32 text, including ':' and '#' used as data
+        let report = validate_z85_internal("HelloWorld");
+        assert_eq!((report.valid, report.suffix, report.decoded_length), (true, None, Some(8)));
+        assert!(validate_z85_internal("0000#0000:").valid);
+
+        let container = crate::encode_z85_with_options_internal(&[1; 100], crate::PaddingFormat::Count, crate::compression::Compression::Deflate);
+        assert_eq!(validate_z85_internal(&container).decoded_length, Some(100));
+    }
+
+    #[test]
+    fn test_validate_diagnostics() {
+        let report = validate_z85_internal("nm=QN zVx+q:3");
+        assert!(!report.valid);
+        assert_eq!((report.invalid_index, report.invalid_char.as_deref()), (Some(5), Some(" ")));
+        assert_eq!((report.data_length, report.length_remainder), (11, 1));
+        assert_eq!(report.error.unwrap(), "invalid character ' ' at index 5; data length (11) is not a multiple of 5");
+
+        let report = validate_z85_internal("nm=€QN");
+        assert_eq!((report.invalid_index, report.invalid_char.as_deref()), (Some(3), Some("€")));
+
+        let report = validate_z85_internal("HelloWorld%%%%%:0");
+        assert_eq!((report.valid, report.invalid_group_index), (false, Some(10)));
+    }
+
+    #[test]
+    fn test_validate_padding() {
+        let report = validate_z85_internal("nm=QNzVx+q:4");
+        assert!(!report.valid && !report.padding_consistent);
+        assert_eq!(report.error.unwrap(), "suffix ':4' does not fit 8 bytes of data");
+        assert!(!validate_z85_internal("nm=QN:5").padding_consistent);
+        assert!(!validate_z85_internal("nm=QNzVx+q#9").padding_consistent);
+        assert!(!validate_z85_internal("nm=QNzVx+q#4").padding_consistent);
+        assert!(validate_z85_internal(":0").valid);
+    }
+}
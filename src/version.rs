@@ -0,0 +1,73 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use tsify::Tsify;
+
+use crate::error::{js_error, Error, ErrorCode};
+
+/// Versions of the encoding crates: the z85-core workspace member and the locked base64
+#[derive(Serialize, Tsify, Debug)]
+struct DependencyVersions {
+    z85_core: &'static str,
+    base64: &'static str,
+}
+
+/// Build provenance returned by `version_info`
+#[derive(Serialize, Tsify, Debug)]
+struct VersionInfo {
+    /// Crate (and npm package) version
+    version: &'static str,
+    /// Abbreviated commit hash, or `"unknown"` for builds outside a git checkout
+    git_hash: &'static str,
+    /// Optional cargo features compiled in
+    features: Vec<&'static str>,
+    /// `"release"` or `"debug"`
+    #[tsify(type = "\"release\" | \"debug\"")]
+    profile: &'static str,
+    dependencies: DependencyVersions,
+}
+
+// Internal pure Rust function describing this build
+fn version_info_internal() -> VersionInfo {
+    let features = [
+        ("console", cfg!(feature = "console")),
+        ("simd", cfg!(feature = "simd")),
+        ("threads", cfg!(feature = "threads")),
+        ("node", cfg!(feature = "node")),
+    ]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect();
+    VersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_hash: env!("Z85_WASM_GIT_HASH"),
+        features,
+        profile: if cfg!(debug_assertions) { "debug" } else { "release" },
+        dependencies: DependencyVersions {
+            z85_core: z85_core::VERSION,
+            base64: env!("Z85_WASM_BASE64_VERSION"),
+        },
+    }
+}
+
+/// Describe this build of the module, for support tooling:
+/// `{ version, git_hash, features, profile, dependencies: { z85_core, base64 } }`
+#[wasm_bindgen(unchecked_return_type = "VersionInfo")]
+pub fn version_info() -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&version_info_internal())
+        .map_err(|e| js_error(Error::new(ErrorCode::InvalidInput, e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_info() {
+        let info = version_info_internal();
+        assert!(!info.version.is_empty() && !info.git_hash.is_empty());
+        assert!(info.dependencies.z85_core.starts_with("0.1."));
+        assert!(info.dependencies.base64.starts_with("0.22."));
+        assert_eq!(info.features.contains(&"console"), cfg!(feature = "console"));
+        assert_eq!(info.features.contains(&"node"), cfg!(feature = "node"));
+    }
+}
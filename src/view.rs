@@ -0,0 +1,136 @@
+//! Encoded output left in WASM linear memory for JS to read in place.
+//!
+//! The bytes of each `EncodedView` live in a module-level arena until the view is freed, so JS
+//! can wrap them in a `Uint8Array` without a copy. Such a view is only valid while the bytes are
+//! in the arena and the memory has not grown: `free()` or `release_encoded_views()` invalidate
+//! it, and so can any later call into the module that allocates.
+
+use wasm_bindgen::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::compression::Compression;
+use crate::error::{js_error, Error, ErrorCode};
+use crate::{encode_z85_with_options_internal, telemetry, PaddingFormat};
+
+/// Buffers of the live `EncodedView`s, by view id
+#[derive(Default)]
+struct Arena {
+    next_id: u32,
+    buffers: HashMap<u32, Vec<u8>>,
+}
+
+thread_local! {
+    static ARENA: RefCell<Arena> = RefCell::new(Arena::default());
+}
+
+// Move `bytes` into the arena and return their id
+fn store(bytes: Vec<u8>) -> u32 {
+    ARENA.with_borrow_mut(|arena| {
+        let id = arena.next_id;
+        arena.next_id = arena.next_id.wrapping_add(1);
+        arena.buffers.insert(id, bytes);
+        id
+    })
+}
+
+// Run `f` on the bytes stored under `id`
+fn with_buffer<T>(id: u32, f: impl FnOnce(&[u8]) -> T) -> Result<T, Error> {
+    ARENA.with_borrow(|arena| {
+        arena.buffers.get(&id)
+            .map(|bytes| f(bytes))
+            .ok_or_else(|| Error::new(ErrorCode::InvalidInput, "EncodedView has been released"))
+    })
+}
+
+// Free every buffer in the arena, returning how many there were
+fn release_all() -> usize {
+    ARENA.with_borrow_mut(|arena| arena.buffers.drain().count())
+}
+
+// Bytes held by the arena
+pub(crate) fn arena_bytes() -> usize {
+    ARENA.with_borrow(|arena| arena.buffers.values().map(Vec::capacity).sum())
+}
+
+/// Z85 text (as ASCII bytes) held in WASM memory; see `encode_z85_view`. Call `free()` when done.
+#[wasm_bindgen]
+pub struct EncodedView {
+    id: u32,
+}
+
+impl Drop for EncodedView {
+    fn drop(&mut self) {
+        // The arena may already be gone when the module is torn down
+        let _ = ARENA.try_with(|arena| arena.borrow_mut().buffers.remove(&self.id));
+    }
+}
+
+#[wasm_bindgen]
+impl EncodedView {
+    /// Byte offset of the text in the module's memory (see `wasm_memory`)
+    #[wasm_bindgen(getter)]
+    pub fn ptr(&self) -> Result<usize, JsValue> {
+        with_buffer(self.id, |bytes| bytes.as_ptr() as usize)
+            .map_err(js_error)
+    }
+
+    /// Length of the text in bytes
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> Result<usize, JsValue> {
+        with_buffer(self.id, <[u8]>::len)
+            .map_err(js_error)
+    }
+
+    /// A `Uint8Array` over the text in WASM memory, without copying. Read or copy it right
+    /// away: it becomes invalid when the view is freed or any other call grows the memory.
+    pub fn view(&self) -> Result<js_sys::Uint8Array, JsValue> {
+        // SAFETY: the caller is told not to use the array past the next call into the module
+        with_buffer(self.id, |bytes| unsafe { js_sys::Uint8Array::view(bytes) })
+            .map_err(js_error)
+    }
+}
+
+/// Encode raw bytes to Z85 (as `encode_z85_bytes` would) and keep the result in WASM memory,
+/// so JS can read it through `view()` without a copy
+#[wasm_bindgen]
+pub fn encode_z85_view(data: &[u8], compression: Option<Compression>) -> Result<EncodedView, JsValue> {
+    let compression = compression.unwrap_or(Compression::None);
+    let encoded = telemetry::instrument("encode_z85_view", data.len(), Vec::len, || {
+        Ok(encode_z85_with_options_internal(data, PaddingFormat::Count, compression).into_bytes())
+    })
+        .map_err(js_error)?;
+    Ok(EncodedView { id: store(encoded) })
+}
+
+/// Free the buffers of every `EncodedView` at once (e.g. at the end of a frame), returning
+/// how many were live. The views themselves then throw when read.
+#[wasm_bindgen]
+pub fn release_encoded_views() -> usize {
+    release_all()
+}
+
+/// The module's `WebAssembly.Memory`, for reading `EncodedView`s by `ptr` and `length`
+#[wasm_bindgen(unchecked_return_type = "WebAssembly.Memory")]
+pub fn wasm_memory() -> JsValue {
+    wasm_bindgen::memory()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arena_lifecycle() {
+        let first = EncodedView { id: store(b"nm=QNzVx+q:3".to_vec()) };
+        let second = EncodedView { id: store(b"HelloWorld:0".to_vec()) };
+        assert_eq!(with_buffer(first.id, |bytes| bytes.to_vec()).unwrap(), b"nm=QNzVx+q:3");
+
+        drop(first);
+        assert_eq!(ARENA.with_borrow(|arena| arena.buffers.len()), 1);
+        assert_eq!(with_buffer(second.id, <[u8]>::len).unwrap(), 12);
+
+        assert_eq!(release_all(), 1);
+        assert_eq!(with_buffer(second.id, <[u8]>::len).err().unwrap().message, "EncodedView has been released");
+    }
+}
@@ -28,6 +28,151 @@ describe('Z85 WASM E2E Tests', () => {
     });
   });
 
+  describe('Z85Session', () => {
+    it('should convert like the standalone functions across calls', () => {
+      const session = new wasm.Z85Session();
+      session.reserve(4096);
+      const data = new Uint8Array(4096).map((_, i) => (i * 31) % 251);
+      const encoded = session.encode(data);
+      expect(encoded).toBe(wasm.encode_z85(data));
+      const decoded = session.decode(encoded);
+      session.decode(wasm.encode_z85(new Uint8Array(8)));
+      expect(decoded).toEqual(data);
+      const base64 = session.transcode(encoded, wasm.Encoding.Z85, wasm.Encoding.Base64);
+      expect(base64).toBe(Buffer.from(data).toString('base64'));
+      session.free();
+    });
+
+    it('should report session buffers and peak heap in memory_stats', () => {
+      const before = wasm.memory_stats();
+      wasm.reset_peak();
+      const session = new wasm.Z85Session();
+      session.reserve(1 << 20);
+      const stats = wasm.memory_stats();
+      expect(stats.session_bytes).toBeGreaterThanOrEqual(before.session_bytes + (1 << 20));
+      expect(stats.peak_heap_bytes).toBeGreaterThanOrEqual(1 << 20);
+      expect(stats.memory_bytes).toBeGreaterThan(stats.heap_bytes);
+      session.free();
+      expect(wasm.memory_stats().session_bytes).toBe(before.session_bytes);
+    });
+
+    it('should report throughput from run_benchmark', () => {
+      const report = wasm.run_benchmark(64 * 1024, 5);
+      expect(report.payload_bytes).toBe(64 * 1024);
+      expect(report.encode_mb_per_s).toBeGreaterThan(0);
+      expect(report.decode_mb_per_s).toBeGreaterThan(0);
+      expect(report.transcode_mb_per_s).toBeGreaterThan(0);
+      expect(() => wasm.run_benchmark(0, 5)).toThrow('non-zero');
+    });
+  });
+
+  describe('Encoding efficiency', () => {
+    it('should return a typed report', () => {
+      const report = wasm.get_encoding_efficiency(1000);
+      expect(report).toBeInstanceOf(wasm.EfficiencyReport);
+      expect([report.base64_size, report.z85_size]).toEqual([1336, 1250]);
+      expect(JSON.parse(JSON.stringify(report))).toEqual({
+        original_size: 1000,
+        base64_size: 1336,
+        z85_size: 1250,
+        efficiency_ratio: report.efficiency_ratio,
+        bandwidth_saving: report.bandwidth_saving,
+      });
+      report.free();
+    });
+
+    it('should measure actual sizes in context', () => {
+      const data = new TextEncoder().encode('Hello, World!');
+      const raw = wasm.get_encoding_efficiency_for(data, wasm.EfficiencyContext.Raw);
+      expect(raw.z85_size).toBe(wasm.encode_z85(data).length);
+      const url = wasm.get_encoding_efficiency_for(data, wasm.EfficiencyContext.Url);
+      expect(url.z85_size).toBe(encodeURIComponent(wasm.encode_z85(data)).length);
+      expect(url.base64_size).toBe(Buffer.from(data).toString('base64url').length);
+    });
+
+    it('should compare encodings side by side', () => {
+      const data = new TextEncoder().encode('Hello, World!');
+      const matrix = wasm.compare_encodings(data, undefined, true);
+      expect(matrix.original_size).toBe(13);
+      expect(matrix.encodings.map((row) => row.encoding)).toEqual(['z85', 'base64', 'base64url', 'base32', 'hex', 'base45', 'base91']);
+      expect(matrix.encodings[1].size).toBe(Buffer.from(data).toString('base64').length);
+      expect(matrix.encodings[4].ratio).toBe(2);
+      expect(matrix.encodings.every((row) => row.gzip_size! > 0)).toBe(true);
+      expect(matrix.smallest).toBe('base91');
+      expect(() => wasm.compare_encodings(data, wasm.EfficiencyContext.DataUrl)).toThrow('Data URLs');
+    });
+
+    it('should estimate compressed sizes', () => {
+      const text = new TextEncoder().encode('{"id":1,"name":"example"},'.repeat(200));
+      const estimate = wasm.estimate_compressed_size(text);
+      expect(estimate.exact).toBe(true);
+      expect(estimate.should_compress).toBe(true);
+      expect(estimate.deflate_z85_size).toBe(wasm.encode_z85(text, undefined, wasm.Compression.Deflate).length);
+      expect(estimate.gzip_base64_size).toBeLessThan(estimate.z85_size);
+      expect(wasm.estimate_compressed_size(text, 512).sampled_bytes).toBe(512);
+    });
+  });
+
+  describe('ASCII bytes', () => {
+    it('should encode to and decode from ASCII bytes', () => {
+      const data = new Uint8Array(1001).map((_, i) => i % 251);
+      const ascii = wasm.encode_z85_bytes(data);
+      expect(new TextDecoder().decode(ascii)).toBe(wasm.encode_z85(data));
+      expect(wasm.decode_z85_from_bytes(ascii)).toEqual(data);
+      expect(() => wasm.decode_z85_from_bytes(new Uint8Array([0x48, 0xff]))).toThrow('offset 1');
+    });
+
+    it('should expose encoded views over WASM memory', () => {
+      const data = new Uint8Array(1001).map((_, i) => i % 251);
+      const encoded = wasm.encode_z85_view(data);
+      const view = encoded.view();
+      expect(view.buffer).toBe(wasm.wasm_memory().buffer);
+      expect(new TextDecoder().decode(view)).toBe(wasm.encode_z85(data));
+      expect(wasm.release_encoded_views()).toBe(1);
+      expect(() => encoded.view()).toThrow('EncodedView has been released');
+      encoded.free();
+    });
+  });
+
+  describe('UTF-8 text', () => {
+    it('should encode and decode text like TextEncoder and TextDecoder', () => {
+      const text = 'Hello, 世界 👋';
+      const encoded = wasm.encode_text_z85(text);
+      expect(encoded).toBe(wasm.encode_z85(new TextEncoder().encode(text)));
+      expect(wasm.decode_z85_to_text(encoded)).toBe(text);
+
+      const invalid = wasm.encode_z85(new Uint8Array([0x48, 0x69, 0xff, 0x21]));
+      expect(() => wasm.decode_z85_to_text(invalid)).toThrow('not valid UTF-8');
+      expect(wasm.decode_z85_to_text(invalid, true)).toBe(new TextDecoder().decode(new Uint8Array([0x48, 0x69, 0xff, 0x21])));
+    });
+
+    it('should round-trip JSON values', () => {
+      const state = { tab: 'settings', zoom: 1.5, tags: ['a', 'ü'], open: null };
+      expect(wasm.decode_z85_to_json(wasm.encode_json_z85(state))).toEqual(state);
+      expect(wasm.decode_z85_to_json(wasm.encode_json_z85(state, wasm.Compression.Deflate))).toEqual(state);
+      expect(() => wasm.encode_json_z85(10n)).toThrow('JSON encode error');
+      expect(() => wasm.decode_z85_to_json(wasm.encode_text_z85('{'))).toThrow('JSON decode error');
+    });
+
+    it('should round-trip CBOR values deterministically', () => {
+      const reading = { device: 't-17', temp: 21.5, seq: 1042, raw: new Uint8Array([1, 2]), ok: true, note: null };
+      const z85 = wasm.cbor_to_z85(reading);
+      expect(wasm.cbor_to_z85({ note: null, ok: true, raw: new Uint8Array([1, 2]), seq: 1042, temp: 21.5, device: 't-17' })).toBe(z85);
+      expect(wasm.z85_to_cbor(z85)).toEqual(reading);
+      expect(wasm.z85_to_cbor(wasm.cbor_to_z85(new Map([[1, 'x'], [-1, 'y']])))).toEqual(new Map([[1, 'x'], [-1, 'y']]));
+      expect(wasm.z85_to_cbor(wasm.cbor_to_z85(2n ** 60n))).toBe(2n ** 60n);
+    });
+
+    it('should shorten UUIDs to 20 Z85 characters', () => {
+      const uuid = 'f81d4fae-7dec-11d0-a765-00a0c91e6bf6';
+      const z85 = wasm.uuid_to_z85(uuid);
+      expect(z85).toHaveLength(20);
+      expect(wasm.z85_to_uuid(z85)).toBe(uuid);
+      expect(wasm.uuid_to_z85(uuid.toUpperCase().replace(/-/g, ''))).toBe(z85);
+      expect(() => wasm.uuid_to_z85('f81d4fae-7dec-11d0-a765-00a0c91e6bg6')).toThrow('offset 34');
+    });
+  });
+
   describe('DataURL conversions', () => {
     it('should convert Z85 DataURL to base64 DataURL', () => {
       const input = 'data:image/png;z85,nm=QNzY&b1A+]m^:1';
@@ -60,6 +205,30 @@ describe('Z85 WASM E2E Tests', () => {
         expect(result).toMatch(new RegExp(`^data:${mime.replace(/[.*+?^${}()|[\]\\]/g, '\\$&')};z85,`));
       }
     });
+
+    it('should return statistics with with_stats', () => {
+      const options = new wasm.ConversionOptions(wasm.DataType.DataURL, wasm.DataType.Raw);
+      options.with_stats = true;
+      const result = wasm.z85_to_base64_with_options('data:image/png;z85,nm=QNzY&b1A+]m^:1', options);
+      expect(result).toBeInstanceOf(wasm.ConversionResult);
+      expect(result.output).toBe('SGVsbG8gV29ybGQ=');
+      expect(result.input_size).toBe(36);
+      expect(result.output_size).toBe(16);
+      expect(result.ratio).toBeCloseTo(16 / 36);
+      expect(result.padding).toBe(1);
+      expect(result.mime_type).toBe('image/png');
+      expect(result.elapsed_ms).toBeGreaterThanOrEqual(0);
+    });
+
+    it('should build options fluently and reject impossible combinations', () => {
+      const options = wasm.ConversionOptions.builder()
+        .output(wasm.DataType.DataURL)
+        .mime('text/plain')
+        .build();
+      expect(wasm.z85_to_base64_with_options('nm=QNzY&b1A+]m^:1', options)).toBe('data:text/plain;base64,SGVsbG8gV29ybGQ=');
+
+      expect(() => wasm.ConversionOptions.builder().mime('image/png').build()).toThrow('ConversionOptions.mime_type');
+    });
   });
 
   describe('Error handling', () => {
@@ -81,6 +250,98 @@ describe('Z85 WASM E2E Tests', () => {
         wasm.base64_to_z85('not valid base64!');
       }).toThrow('Base64 decode error');
     });
+
+    it('should report typed error details', () => {
+      const result: WasmModule.TryResult<Uint8Array> = wasm.try_decode_z85('nm=Q~zY&b1A+]m^:1');
+      expect(result.ok).toBe(false);
+      if (!result.ok) {
+        const code: WasmModule.Z85ErrorCode = result.error.code;
+        expect(code).toBe('INVALID_CHAR');
+        expect(result.error.offset).toBe(4);
+      }
+
+      const report: WasmModule.ValidationReport = wasm.validate_z85('nm=Q~zY&b1A+]m^:1');
+      expect(report.valid).toBe(false);
+      expect(report.invalid_index).toBe(4);
+    });
+  });
+
+  describe('Version info', () => {
+    it('should describe the build', () => {
+      const info = wasm.version_info();
+      expect(info.version).toBe(require('../package.json').version);
+      expect(info.git_hash).toMatch(/^([0-9a-f]{12}|unknown)$/);
+      expect(info.features).toContain('node');
+      expect(info.dependencies.z85_core).toMatch(/^\d+\.\d+\.\d+$/);
+    });
+  });
+
+  describe('Logging', () => {
+    it('should route enabled levels to the logger', () => {
+      const messages: string[] = [];
+      wasm.set_logger((level: string, message: string) => messages.push(`${level}: ${message}`));
+      const lenient = () => {
+        const options = new wasm.ConversionOptions(wasm.DataType.Raw, wasm.DataType.Raw);
+        options.lenient = true;
+        return options;
+      };
+      wasm.base64_to_z85_with_options('SGVs\r\nbG8', lenient());
+      expect(messages).toEqual([]);
+
+      wasm.set_log_level(wasm.LogLevel.Debug);
+      wasm.base64_to_z85_with_options('SGVs\r\nbG8', lenient());
+      wasm.set_log_level(wasm.LogLevel.Warn);
+      wasm.set_logger(undefined);
+      expect(messages).toEqual(['debug: Lenient base64: removed 2 whitespace characters, restored 1 padding characters']);
+    });
+  });
+
+  describe('Progress callbacks', () => {
+    it('should report progress while encoding and decoding', () => {
+      const data = new Uint8Array(200000).fill(7);
+      const reports: Array<[number, number]> = [];
+      const encoded = wasm.encode_z85(data, (done: number, total: number) => reports.push([done, total]));
+      expect(reports.length).toBeGreaterThan(1);
+      expect(reports[reports.length - 1]).toEqual([data.length, data.length]);
+
+      let lastDecodeReport = [0, 0];
+      const decoded = wasm.decode_z85(encoded, (done: number, total: number) => { lastDecodeReport = [done, total]; });
+      expect(decoded).toEqual(data);
+      expect(lastDecodeReport).toEqual([encoded.length, encoded.length]);
+    });
+  });
+
+  describe('Zero-copy decode', () => {
+    it('should decode into a caller-provided buffer', () => {
+      const encoded = wasm.encode_z85(new TextEncoder().encode('Hello, World!'));
+      const out = new Uint8Array(wasm.decoded_size_hint(encoded));
+      const written = wasm.decode_z85_into(encoded, out);
+      expect(written).toBe(13);
+      expect(new TextDecoder().decode(out)).toBe('Hello, World!');
+    });
+  });
+
+  describe('Batch', () => {
+    it('should return batch items that outlive later calls', () => {
+      const items = [new Uint8Array(100).fill(1), new Uint8Array(50).fill(2)];
+      const decoded = wasm.decode_z85_batch(items.map((item) => wasm.encode_z85(item)));
+      // Grow and reuse WASM memory
+      wasm.encode_z85(new Uint8Array(10_000_000).fill(7));
+      expect(decoded).toEqual(items);
+    });
+  });
+
+  describe('Node.js Buffer', () => {
+    it('should decode to Node.js Buffers', () => {
+      const data = Buffer.from('Hello, World!');
+      const single = wasm.decode_z85_buffer(wasm.encode_z85(data));
+      expect(Buffer.isBuffer(single)).toBe(true);
+      expect(single.equals(data)).toBe(true);
+
+      const batch = wasm.decode_z85_batch_buffers([wasm.encode_z85(data), wasm.encode_z85(Buffer.from('hi'))]);
+      expect(batch.every((item: Uint8Array) => Buffer.isBuffer(item))).toBe(true);
+      expect(batch.map((item: Buffer) => item.toString())).toEqual(['Hello, World!', 'hi']);
+    });
   });
 
   describe('Edge cases', () => {
@@ -121,4 +382,162 @@ describe('Z85 WASM E2E Tests', () => {
       }
     });
   });
-});
\ No newline at end of file
+
+  describe('Blob and File', () => {
+    it('should round-trip a File with its name and type', async () => {
+      const file = new File(['Hello, File!'], 'greeting.txt', { type: 'text/plain', lastModified: 1700000000000 });
+      const encoded = await wasm.encode_blob_z85(file);
+      expect(encoded).toContain(';name=greeting.txt;mime=text/plain;mtime=1700000000000;');
+      const decoded = wasm.decode_z85_to_blob(encoded) as File;
+      expect(decoded).toBeInstanceOf(File);
+      expect([decoded.name, decoded.type, decoded.lastModified]).toEqual(['greeting.txt', 'text/plain', 1700000000000]);
+      expect(await decoded.text()).toBe('Hello, File!');
+    });
+
+    it('should encode a plain Blob as encode_z85 does', async () => {
+      const data = new Uint8Array([1, 2, 3]);
+      const encoded = await wasm.encode_blob_z85(new Blob([data]));
+      expect(encoded).toBe(wasm.encode_z85(data));
+      const decoded = wasm.decode_z85_to_blob(encoded, 'image/png');
+      expect(decoded.type).toBe('image/png');
+      expect(new Uint8Array(await decoded.arrayBuffer())).toEqual(data);
+    });
+
+    it('should create and revoke object URLs for decoded content', async () => {
+      const { resolveObjectURL } = await import('buffer');
+      const url = wasm.decode_z85_to_object_url(wasm.encode_z85(new Uint8Array([1, 2, 3])), 'image/png');
+      expect(url.startsWith('blob:')).toBe(true);
+      const blob = resolveObjectURL(url);
+      expect([blob?.type, blob?.size]).toEqual(['image/png', 3]);
+      wasm.revoke_object_url(url);
+      expect(resolveObjectURL(url)).toBeUndefined();
+    });
+  });
+
+  describe('Fetch', () => {
+    it('should fetch and decode a Z85 resource', async () => {
+      const url = 'data:image/png,' + encodeURIComponent(wasm.encode_z85(new Uint8Array([1, 2, 3])) + '\n');
+      expect(Array.from(await wasm.fetch_z85(url))).toEqual([1, 2, 3]);
+      const blob = await wasm.fetch_z85_blob(url);
+      expect(blob.type).toBe('image/png');
+      expect(blob.size).toBe(3);
+    });
+
+    it('should decode a Z85 Response with corrected headers', async () => {
+      const data = new Uint8Array(10_000).map((_, i) => i % 251);
+      data.set([0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a]);
+      const text = new TextEncoder().encode(wasm.encode_z85(data));
+      const body = new ReadableStream({
+        start(controller) {
+          for (let i = 0; i < text.length; i += 1000) controller.enqueue(text.subarray(i, i + 1000));
+          controller.close();
+        },
+      });
+      const headers = { 'content-type': 'text/plain', 'content-length': String(text.length) };
+      const response = await wasm.decode_z85_response(new Response(body, { headers }) as any) as unknown as Response;
+      expect(response.headers.get('content-type')).toBe('image/png');
+      expect(response.headers.get('content-length')).toBeNull();
+      expect(new Uint8Array(await response.arrayBuffer())).toEqual(data);
+
+      const missing = new Response('not found', { status: 404 });
+      expect(await wasm.decode_z85_response(missing as any)).toBe(missing);
+    });
+  });
+
+  describe('Streaming', () => {
+    it('should encode asynchronously in slices', async () => {
+      const data = new Uint8Array(10001).map((_, i) => i % 251);
+      const encoded = await wasm.encode_z85_async(data, 1000);
+      expect(encoded).toBe(wasm.encode_z85(data));
+    });
+
+    it('should encode chunks incrementally with Z85Encoder', () => {
+      const data = new TextEncoder().encode('Hello, World!');
+      const encoder = new wasm.Z85Encoder();
+      let encoded = '';
+      for (let i = 0; i < data.length; i += 3) {
+        encoded += encoder.update(data.subarray(i, i + 3));
+      }
+      encoded += encoder.finalize();
+      expect(encoded).toBe(wasm.encode_z85(data));
+    });
+
+    it('should decode chunks incrementally with Z85Decoder', () => {
+      const encoded = wasm.encode_z85(new TextEncoder().encode('Hello, World!'));
+      const decoder = new wasm.Z85Decoder();
+      const parts: Uint8Array[] = [];
+      for (let i = 0; i < encoded.length; i += 4) {
+        parts.push(decoder.update(encoded.slice(i, i + 4)));
+      }
+      parts.push(decoder.finalize());
+      const decoded = Buffer.concat(parts);
+      expect(new TextDecoder().decode(decoded)).toBe('Hello, World!');
+    });
+
+    it('should pipe through encode and decode TransformStreams', async () => {
+      const data = new TextEncoder().encode('Hello, World!');
+      const source = new Blob([data]).stream();
+      const decoded = await new Response(
+        source
+          .pipeThrough(wasm.create_z85_encode_stream() as unknown as TransformStream)
+          .pipeThrough(wasm.create_z85_decode_stream() as unknown as TransformStream)
+      ).arrayBuffer();
+      expect(new TextDecoder().decode(decoded)).toBe('Hello, World!');
+    });
+
+    it('should encode any byte chunk and error the encode stream on other chunks', async () => {
+      const data = new Uint8Array([1, 2, 3, 4, 5, 6, 7, 8, 9]);
+      const chunks = [data.subarray(0, 2), new DataView(data.buffer, 2, 3), data.buffer.slice(5)];
+      const source = new ReadableStream({
+        start(controller) {
+          chunks.forEach((chunk) => controller.enqueue(chunk));
+          controller.close();
+        },
+      });
+      const encoded = await new Response(
+        source
+          .pipeThrough(wasm.create_z85_encode_stream() as unknown as TransformStream)
+          .pipeThrough(new TextEncoderStream())
+      ).text();
+      expect(encoded).toBe(wasm.encode_z85(data));
+
+      const stream = wasm.create_z85_encode_stream() as unknown as TransformStream;
+      const read = stream.readable.getReader().read();
+      await expect(stream.writable.getWriter().write('not bytes')).rejects.toMatchObject({ name: 'Z85Error', code: 'INVALID_INPUT' });
+      await expect(read).rejects.toMatchObject({ code: 'INVALID_INPUT' });
+    });
+
+    it('should adapt ReadableStream sources and WritableStream sinks', async () => {
+      const data = new Uint8Array(100_003).map((_, i) => i % 251);
+      const body = new Response(wasm.encode_z85(data)).body!;
+      const decoded = await new Response(wasm.z85_decode_readable(body as any) as unknown as ReadableStream).arrayBuffer();
+      expect(new Uint8Array(decoded)).toEqual(data);
+
+      let text = '';
+      let closed = false;
+      const sink = new WritableStream<string>({ write: (chunk) => { text += chunk; }, close: () => { closed = true; } });
+      await new Blob([data]).stream().pipeTo(wasm.z85_encode_writable(sink as any) as unknown as WritableStream);
+      await new Promise((resolve) => setTimeout(resolve, 0));
+      expect(closed).toBe(true);
+      expect(text).toBe(wasm.encode_z85(data));
+    });
+
+    it('should reject encode_z85_async when its signal aborts', async () => {
+      const controller = new AbortController();
+      const pending = wasm.encode_z85_async(new Uint8Array(1_000_000), 1000, controller.signal);
+      controller.abort();
+      await expect(pending).rejects.toMatchObject({ name: 'AbortError' });
+      await expect(wasm.encode_z85_async(new Uint8Array(4), undefined, AbortSignal.abort('stop'))).rejects.toBe('stop');
+    });
+
+    it('should error a TransformStream when its signal aborts', async () => {
+      const controller = new AbortController();
+      const stream = wasm.create_z85_encode_stream(controller.signal) as unknown as TransformStream;
+      const reader = stream.readable.getReader();
+      stream.writable.getWriter().write(new Uint8Array([1, 2, 3, 4, 5]));
+      expect((await reader.read()).value).toBe(wasm.encode_z85(new Uint8Array([1, 2, 3, 4])).slice(0, 5));
+      controller.abort();
+      await expect(reader.read()).rejects.toMatchObject({ name: 'AbortError' });
+    });
+  });
+});
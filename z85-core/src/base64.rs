@@ -0,0 +1,40 @@
+//! Conversions between Z85 with padding info and standard base64 (RFC 4648, padded)
+
+use ::base64::engine::general_purpose::STANDARD;
+use ::base64::Engine;
+use alloc::string::String;
+
+use crate::error::{Error, Result};
+use crate::PaddingFormat;
+
+/// Convert Z85 with a padding suffix, or a `z85v2:` container, to base64
+pub fn z85_to_base64(z85_data_with_padding: &str) -> Result<String> {
+    Ok(STANDARD.encode(crate::decode(z85_data_with_padding)?))
+}
+
+/// Convert base64 to Z85 with the given padding suffix
+pub fn base64_to_z85(base64_data: &str, padding_format: PaddingFormat) -> Result<String> {
+    let data = STANDARD.decode(base64_data).map_err(Error::Base64)?;
+    Ok(crate::encode_with(&data, padding_format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_base64_roundtrip() {
+        assert_eq!(base64_to_z85("SGVsbG8gV29ybGQ=", PaddingFormat::Count).unwrap(), "nm=QNzY&b1A+]m^:1");
+        assert_eq!(base64_to_z85("SGVsbG8gV29ybGQ=", PaddingFormat::Length).unwrap(), "nm=QNzY&b1A+]m^#11");
+        assert_eq!(z85_to_base64("nm=QNzY&b1A+]m^:1").unwrap(), "SGVsbG8gV29ybGQ=");
+        let container = crate::encode_container(b"Hello World", true, crate::Compression::Deflate);
+        assert_eq!(z85_to_base64(&container).unwrap(), "SGVsbG8gV29ybGQ=");
+    }
+
+    #[test]
+    fn test_base64_errors() {
+        let error = base64_to_z85("SGVs!G8=", PaddingFormat::Count).unwrap_err();
+        assert_eq!(error.to_string(), "Base64 decode error: Invalid symbol 33, offset 4.");
+    }
+}
@@ -0,0 +1,250 @@
+//! `z85` command: encodes and decodes files or stdin/stdout with the formats of z85-wasm
+//!
+//! ```text
+//! z85 encode file.bin -o file.z85 --compression zstd
+//! z85 encode logo.png --data-url --mime image/png
+//! z85 --decode file.z85 -o file.bin
+//! ```
+
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::ExitCode;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use z85_core::{Compression, DecodeOptions, Error, PaddingFormat};
+
+const USAGE: &str = "\
+Usage: z85 [encode] [FILE] [OPTIONS]
+       z85 decode [FILE] [OPTIONS]
+       z85 --decode [FILE] [OPTIONS]
+
+Reads FILE, or stdin when FILE is missing or `-`, and writes to stdout unless -o is given.
+
+Options:
+  -d, --decode               Decode Z85, a z85v2: container or a data URL
+  -o, --output FILE          Write to FILE instead of stdout
+  -c, --compression NAME     Encode to a z85v2: container compressed with none, deflate or zstd
+      --container            Encode to a z85v2: container with a CRC-32
+      --length               Encode with a #length suffix instead of :padding
+      --data-url             Encode to a data:<mime>;z85, URL
+      --mime TYPE            Media type of the data URL (default application/octet-stream)
+      --dictionary FILE      Zstandard dictionary for decoding containers compressed with one
+  -h, --help                 Print this help
+  -V, --version              Print the version";
+
+/// Media type of `--data-url` output when `--mime` is not given
+const DEFAULT_MIME_TYPE: &str = "application/octet-stream";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Mode {
+    #[default]
+    Encode,
+    Decode,
+}
+
+/// Parsed command line
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Options {
+    mode: Mode,
+    input: Option<String>,
+    output: Option<String>,
+    /// `Some` to encode to a `z85v2:` container
+    compression: Option<Compression>,
+    padding_format: PaddingFormat,
+    data_url: bool,
+    mime_type: Option<String>,
+    dictionary: Option<String>,
+    help: bool,
+    version: bool,
+}
+
+// Parse the arguments after the program name
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    let mut first = true;
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("Missing value for {}", name));
+        match arg.as_str() {
+            "encode" if first => options.mode = Mode::Encode,
+            "decode" if first => options.mode = Mode::Decode,
+            "-d" | "--decode" => options.mode = Mode::Decode,
+            "-o" | "--output" => options.output = Some(value(&arg)?),
+            "-c" | "--compression" => options.compression = Some(Compression::from_name(&value(&arg)?)?),
+            "--container" => options.compression = options.compression.or(Some(Compression::None)),
+            "--length" => options.padding_format = PaddingFormat::Length,
+            "--data-url" => options.data_url = true,
+            "--mime" => options.mime_type = Some(value(&arg)?),
+            "--dictionary" => options.dictionary = Some(value(&arg)?),
+            "-h" | "--help" => options.help = true,
+            "-V" | "--version" => options.version = true,
+            "-" => set_input(&mut options, arg)?,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option: {}", arg)),
+            _ => set_input(&mut options, arg)?,
+        }
+        first = false;
+    }
+    if options.mode == Mode::Decode && (options.compression.is_some() || options.data_url || options.mime_type.is_some()) {
+        return Err("Encoding options cannot be combined with --decode".to_string());
+    }
+    if options.mime_type.is_some() && !options.data_url {
+        return Err("--mime requires --data-url".to_string());
+    }
+    Ok(options)
+}
+
+fn set_input(options: &mut Options, path: String) -> Result<(), String> {
+    if options.input.is_some() {
+        return Err(format!("Unexpected argument: {}", path));
+    }
+    options.input = Some(path);
+    Ok(())
+}
+
+// Encode `data` as text, exactly as z85-wasm would
+fn encode(data: &[u8], options: &Options) -> Result<String, String> {
+    let encoded = match options.compression {
+        Some(compression) => z85_core::encode_container(data, true, compression),
+        None => z85_core::encode_with(data, options.padding_format),
+    };
+    if !options.data_url {
+        return Ok(encoded);
+    }
+    let mime_type = options.mime_type.as_deref().unwrap_or(DEFAULT_MIME_TYPE);
+    if mime_type.is_empty() || mime_type.contains([',', ' ']) {
+        return Err(format!("Invalid MIME type: {}", mime_type));
+    }
+    Ok(format!("data:{};z85,{}", mime_type, encoded))
+}
+
+// Decode Z85 with padding info, a `z85v2:` container, or a `;z85` / `;base64` data URL
+fn decode(text: &str, options: &DecodeOptions) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    let Some(url) = text.get(..5).filter(|scheme| scheme.eq_ignore_ascii_case("data:")).map(|_| &text[5..]) else {
+        return Ok(z85_core::decode_with(text, options)?);
+    };
+    let (header, payload) = url.split_once(',')
+        .ok_or_else(|| "Invalid data URL: missing ',' before the payload".to_string())?;
+    match header.rsplit(';').next().map(str::trim) {
+        Some(marker) if marker.eq_ignore_ascii_case("z85") => Ok(z85_core::decode_with(payload, options)?),
+        Some(marker) if marker.eq_ignore_ascii_case("base64") => Ok(STANDARD.decode(payload).map_err(Error::Base64)?),
+        _ => Err("Unsupported data URL: expected a ;z85 or ;base64 payload".to_string()),
+    }
+}
+
+fn read_input(path: Option<&str>) -> Result<Vec<u8>, String> {
+    match path {
+        None | Some("-") => {
+            let mut data = Vec::new();
+            io::stdin().read_to_end(&mut data).map_err(|e| format!("Cannot read stdin: {}", e))?;
+            Ok(data)
+        }
+        Some(path) => fs::read(path).map_err(|e| format!("Cannot read {}: {}", path, e)),
+    }
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let input = read_input(options.input.as_deref())?;
+    let output = match options.mode {
+        Mode::Encode => {
+            let mut text = encode(&input, options)?;
+            // Only a terminal gets a trailing newline: the WASM decoders expect the exact text
+            if options.output.is_none() && io::stdout().is_terminal() {
+                text.push('\n');
+            }
+            text.into_bytes()
+        }
+        Mode::Decode => {
+            let text = String::from_utf8(input).map_err(|_| "Input is not valid Z85 text".to_string())?;
+            let dictionary = options.dictionary.as_deref()
+                .map(|path| fs::read(path).map_err(|e| format!("Cannot read {}: {}", path, e)))
+                .transpose()?;
+            decode(&text, &DecodeOptions { dictionary: dictionary.as_deref(), max_output_bytes: None })?
+        }
+    };
+    match options.output.as_deref() {
+        None | Some("-") => io::stdout().write_all(&output).map_err(|e| format!("Cannot write stdout: {}", e)),
+        Some(path) => fs::write(path, &output).map_err(|e| format!("Cannot write {}: {}", path, e)),
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("z85: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    if options.version {
+        println!("z85 {}", env!("CARGO_PKG_VERSION"));
+        return ExitCode::SUCCESS;
+    }
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("z85: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse(&["encode", "file.bin", "-o", "file.z85", "--compression", "zstd"]).unwrap();
+        assert_eq!(options.mode, Mode::Encode);
+        assert_eq!(options.input.as_deref(), Some("file.bin"));
+        assert_eq!(options.output.as_deref(), Some("file.z85"));
+        assert_eq!(options.compression, Some(Compression::Zstd));
+
+        assert_eq!(parse(&["--decode", "-"]).unwrap().mode, Mode::Decode);
+        assert_eq!(parse(&["decode"]).unwrap().mode, Mode::Decode);
+        // Subcommands are only recognised first
+        assert_eq!(parse(&["decode", "encode"]).unwrap().input.as_deref(), Some("encode"));
+
+        assert_eq!(parse(&["-c", "brotli"]).unwrap_err(), "Unsupported compression: brotli");
+        assert_eq!(parse(&["-o"]).unwrap_err(), "Missing value for -o");
+        assert_eq!(parse(&["--frobnicate"]).unwrap_err(), "Unknown option: --frobnicate");
+        assert_eq!(parse(&["a", "b"]).unwrap_err(), "Unexpected argument: b");
+        assert_eq!(parse(&["--mime", "image/png"]).unwrap_err(), "--mime requires --data-url");
+        assert_eq!(parse(&["-d", "--data-url"]).unwrap_err(), "Encoding options cannot be combined with --decode");
+    }
+
+    #[test]
+    fn test_encode_decode() {
+        let data = b"Hello World".repeat(20);
+        for args in [&[][..], &["--length"], &["--container"], &["-c", "deflate"], &["-c", "zstd"], &["--data-url", "--mime", "image/png"]] {
+            let encoded = encode(&data, &parse(args).unwrap()).unwrap();
+            assert_eq!(decode(&format!("{}\n", encoded), &DecodeOptions::default()).unwrap(), data, "{:?}", args);
+        }
+
+        let options = parse(&["--data-url", "--mime", "image/png"]).unwrap();
+        assert_eq!(encode(b"Hello World", &options).unwrap(), "data:image/png;z85,nm=QNzY&b1A+]m^:1");
+        let options = parse(&["--data-url"]).unwrap();
+        assert_eq!(encode(b"", &options).unwrap(), "data:application/octet-stream;z85,:0");
+        let options = parse(&["--data-url", "--mime", "a,b"]).unwrap();
+        assert_eq!(encode(b"", &options).unwrap_err(), "Invalid MIME type: a,b");
+    }
+
+    #[test]
+    fn test_decode_data_url() {
+        let options = DecodeOptions::default();
+        assert_eq!(decode("DATA:text/plain;charset=utf-8;Z85,nm=QNzY&b1A+]m^:1", &options).unwrap(), b"Hello World");
+        assert_eq!(decode("data:text/plain;base64,SGVsbG8gV29ybGQ=", &options).unwrap(), b"Hello World");
+        assert_eq!(decode("data:text/plain,Hello", &options).unwrap_err(), "Unsupported data URL: expected a ;z85 or ;base64 payload");
+        assert_eq!(decode("data:;z85", &options).unwrap_err(), "Invalid data URL: missing ',' before the payload");
+    }
+}
@@ -0,0 +1,201 @@
+//! Scalar base85 group codec shared by every Z85 format
+
+use alloc::string::String;
+
+use crate::error::{Error, Result};
+
+/// Z85 alphabet (ZeroMQ RFC 32)
+pub const Z85_ALPHABET: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+/// RFC 1924 base85 alphabet (also used by git binary patches)
+pub const RFC1924_ALPHABET: &[u8; 85] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+/// Marker for bytes outside the alphabet in a decode table
+pub const INVALID_DIGIT: u8 = 0xff;
+
+/// Encode and decode tables of an 85-character alphabet
+#[derive(Clone, Debug, PartialEq)]
+pub struct Base85Alphabet {
+    /// Prefix of decode error messages, e.g. `Z85`
+    pub name: &'static str,
+    /// Digit -> character
+    pub symbols: [u8; 85],
+    /// Byte -> digit, or `INVALID_DIGIT`
+    pub digits: [u8; 256],
+}
+
+impl Base85Alphabet {
+    /// Build the reverse lookup table for `symbols`, which must be 85 distinct ASCII characters
+    pub const fn new(name: &'static str, symbols: &[u8; 85]) -> Base85Alphabet {
+        let mut digits = [INVALID_DIGIT; 256];
+        let mut i = 0;
+        while i < symbols.len() {
+            digits[symbols[i] as usize] = i as u8;
+            i += 1;
+        }
+        Base85Alphabet { name, symbols: *symbols, digits }
+    }
+}
+
+/// The Z85 alphabet and its tables
+pub const Z85: Base85Alphabet = Base85Alphabet::new("Z85", Z85_ALPHABET);
+
+/// The RFC 1924 alphabet and its tables
+pub const RFC1924: Base85Alphabet = Base85Alphabet::new("RFC 1924", RFC1924_ALPHABET);
+
+/// Encode 4-byte aligned data in any base85 alphabet, appending to `out`
+pub fn encode_append_with(alphabet: &Base85Alphabet, data: &[u8], out: &mut String) {
+    debug_assert!(data.len().is_multiple_of(4));
+    for word in data.chunks_exact(4) {
+        let mut value = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        let mut group = [0u8; 5];
+        for slot in group.iter_mut().rev() {
+            *slot = alphabet.symbols[(value % 85) as usize];
+            value /= 85;
+        }
+        // The alphabet is ASCII
+        out.extend(group.iter().map(|&c| c as char));
+    }
+}
+
+/// Encode a final partial word (< 4 bytes) zero-padded to a full group, appending to `out`.
+/// Returns the number of padding bytes added.
+pub fn encode_padded_tail_with(alphabet: &Base85Alphabet, tail: &[u8], out: &mut String) -> usize {
+    debug_assert!(tail.len() < 4);
+    if tail.is_empty() {
+        return 0;
+    }
+    let mut word = [0u8; 4];
+    word[..tail.len()].copy_from_slice(tail);
+    encode_append_with(alphabet, &word, out);
+    4 - tail.len()
+}
+
+// Look up the digit for the char at `offset`
+#[inline(always)]
+fn digit_at(alphabet: &Base85Alphabet, c: u8, offset: usize) -> Result<u64> {
+    match alphabet.digits[c as usize] {
+        INVALID_DIGIT => Err(Error::InvalidCharacter { alphabet: alphabet.name, offset }),
+        digit => Ok(digit as u64),
+    }
+}
+
+/// Decode one 5-char group into a big-endian 4-byte word. `group_offset` is the position of
+/// the group in the full input, used for error offsets.
+pub fn decode_group(alphabet: &Base85Alphabet, group: &[u8], group_offset: usize) -> Result<[u8; 4]> {
+    let value = digit_at(alphabet, group[0], group_offset)? * 85 * 85 * 85 * 85
+        + digit_at(alphabet, group[1], group_offset + 1)? * 85 * 85 * 85
+        + digit_at(alphabet, group[2], group_offset + 2)? * 85 * 85
+        + digit_at(alphabet, group[3], group_offset + 3)? * 85
+        + digit_at(alphabet, group[4], group_offset + 4)?;
+    let value = u32::try_from(value)
+        .map_err(|_| Error::InvalidGroup { alphabet: alphabet.name, offset: group_offset })?;
+    Ok(value.to_be_bytes())
+}
+
+/// Decode whole 5-char groups into `out` (exactly `data.len() / 5 * 4` bytes).
+/// `base_offset` is the position of `data` in the full input, used for error offsets.
+pub fn decode_groups_with(alphabet: &Base85Alphabet, data: &[u8], out: &mut [u8], base_offset: usize) -> Result<()> {
+    debug_assert_eq!(out.len(), data.len() / 5 * 4);
+    for (index, (group, word)) in data.chunks_exact(5).zip(out.chunks_exact_mut(4)).enumerate() {
+        word.copy_from_slice(&decode_group(alphabet, group, base_offset + index * 5)?);
+    }
+    Ok(())
+}
+
+/// Decode base85 text (length a multiple of 5) into `out`, which must hold at least
+/// `len / 5 * 4 - padding` bytes; the last `padding` bytes of the final group are dropped.
+/// Returns the number of bytes written.
+pub fn decode_into_with(alphabet: &Base85Alphabet, data: &[u8], padding: usize, out: &mut [u8]) -> Result<usize> {
+    decode_into_using(alphabet, data, padding, out, |groups, words| decode_groups_with(alphabet, groups, words, 0))
+}
+
+/// `decode_into_with`, decoding the whole groups at the start of `data` with `decode_groups`
+/// (e.g. an accelerated kernel for the same alphabet)
+pub fn decode_into_using(
+    alphabet: &Base85Alphabet,
+    data: &[u8],
+    padding: usize,
+    out: &mut [u8],
+    decode_groups: impl FnOnce(&[u8], &mut [u8]) -> Result<()>,
+) -> Result<usize> {
+    if !data.len().is_multiple_of(5) {
+        return Err(Error::InvalidDataLength { alphabet: alphabet.name, length: data.len() });
+    }
+    let decoded_len = (data.len() / 5 * 4).checked_sub(padding)
+        .ok_or(Error::InvalidPadding)?;
+    if out.len() < decoded_len {
+        return Err(Error::OutputTooSmall { needed: decoded_len, actual: out.len() });
+    }
+
+    // Full words go straight into the output, the padded tail through a scratch word
+    let full_groups = decoded_len / 4;
+    decode_groups(&data[..full_groups * 5], &mut out[..full_groups * 4])?;
+    let tail_len = decoded_len - full_groups * 4;
+    if tail_len > 0 {
+        let word = decode_group(alphabet, &data[full_groups * 5..full_groups * 5 + 5], full_groups * 5)?;
+        out[full_groups * 4..decoded_len].copy_from_slice(&word[..tail_len]);
+    }
+    // Groups dropped entirely by padding must still be valid
+    for (index, group) in data.chunks_exact(5).enumerate().skip(decoded_len.div_ceil(4)) {
+        decode_group(alphabet, group, index * 5)?;
+    }
+
+    Ok(decoded_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_decode_table_covers_alphabet() {
+        for (digit, &c) in Z85_ALPHABET.iter().enumerate() {
+            assert_eq!(Z85.digits[c as usize] as usize, digit);
+        }
+        let valid = Z85.digits.iter().filter(|&&d| d != INVALID_DIGIT).count();
+        assert_eq!(valid, 85);
+        assert_eq!(Z85.digits[b'~' as usize], INVALID_DIGIT);
+        assert_eq!(Z85.digits[0xc3], INVALID_DIGIT);
+    }
+
+    #[test]
+    fn test_encode_padded_tail() {
+        let mut out = String::new();
+        assert_eq!(encode_padded_tail_with(&Z85, b"", &mut out), 0);
+        assert!(out.is_empty());
+        assert_eq!(encode_padded_tail_with(&Z85, b"H", &mut out), 3);
+        let mut decoded = [0u8; 4];
+        decode_groups_with(&Z85, out.as_bytes(), &mut decoded, 0).unwrap();
+        assert_eq!(&decoded, b"H\0\0\0");
+    }
+
+    #[test]
+    fn test_decode_groups_reports_absolute_offset() {
+        let mut out = [0u8; 8];
+        let result = decode_groups_with(&Z85, b"HelloWor~d", &mut out, 100);
+        assert_eq!(result, Err(Error::InvalidCharacter { alphabet: "Z85", offset: 108 }));
+    }
+
+    #[test]
+    fn test_decode_into_trims_padding() {
+        let mut encoded = String::new();
+        encode_append_with(&Z85, b"Hello\0\0\0", &mut encoded);
+        let mut out = [0xaau8; 6];
+        assert_eq!(decode_into_with(&Z85, encoded.as_bytes(), 3, &mut out), Ok(5));
+        assert_eq!(&out, b"Hello\xaa");
+    }
+
+    #[test]
+    fn test_decode_into_errors() {
+        let mut out = [0u8; 8];
+        assert_eq!(decode_into_with(&Z85, b"Hell", 0, &mut out), Err(Error::InvalidDataLength { alphabet: "Z85", length: 4 }));
+        assert_eq!(decode_into_with(&Z85, b"Hello", 5, &mut out), Err(Error::InvalidPadding));
+        assert_eq!(decode_into_with(&Z85, b"HelloWorld", 0, &mut out[..3]), Err(Error::OutputTooSmall { needed: 8, actual: 3 }));
+        assert_eq!(decode_into_with(&Z85, b"#####", 0, &mut out).unwrap_err().to_string(), "Z85 decode error: invalid group at offset 0");
+        assert_eq!(decode_into_with(&RFC1924, b"NM&q.", 0, &mut out).unwrap_err().to_string(), "RFC 1924 decode error: invalid character at offset 4");
+    }
+}
@@ -0,0 +1,199 @@
+//! Payload compression recorded in `z85v2:` container headers
+
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::{decompress_to_vec_with_limit, TINFLStatus};
+use ruzstd::decoding::StreamingDecoder;
+use ruzstd::encoding::{CompressionLevel, FrameCompressor};
+use ruzstd::io::Read;
+
+use crate::container::crc32;
+use crate::error::{Error, Result};
+use crate::DecodeOptions;
+
+/// Compression applied to the payload before Z85 encoding
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum Compression {
+    /// Store the payload as is
+    #[default]
+    None,
+    /// Raw deflate (RFC 1951)
+    Deflate,
+    /// Zstandard (RFC 8878)
+    Zstd,
+}
+
+impl Compression {
+    /// Name used in `z85v2:` container headers
+    pub fn name(self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Deflate => "deflate",
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    /// Parse a name written by `name`
+    pub fn from_name(name: &str) -> Result<Compression> {
+        match name {
+            "none" => Ok(Compression::None),
+            "deflate" => Ok(Compression::Deflate),
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(Error::UnsupportedCompression(name.to_string())),
+        }
+    }
+}
+
+/// Default deflate compression level (0-10)
+const DEFLATE_LEVEL: u8 = 6;
+
+/// Zstandard block size limit
+pub const ZSTD_MAX_BLOCK: usize = 128 * 1024;
+
+/// Raw-deflate (RFC 1951) the given bytes
+pub fn deflate_raw(data: &[u8]) -> Vec<u8> {
+    compress_to_vec(data, DEFLATE_LEVEL)
+}
+
+/// Inflate raw-deflate (RFC 1951) data, stopping at `max_output_bytes`
+pub fn inflate_raw(data: &[u8], max_output_bytes: Option<usize>) -> Result<Vec<u8>> {
+    let max = max_output_bytes.unwrap_or(usize::MAX);
+    decompress_to_vec_with_limit(data, max)
+        .map_err(|e| match e.status {
+            TINFLStatus::HasMoreOutput => Error::DecompressedTooLarge { limit: max },
+            status => Error::Decompression(format!("Deflate decompression error: {:?}", status)),
+        })
+}
+
+/// Zstandard-compress the given bytes without a dictionary
+pub fn zstd_compress(data: &[u8]) -> Vec<u8> {
+    let mut output = Vec::new();
+    let mut compressor = FrameCompressor::new(CompressionLevel::Fastest);
+    compressor.set_source(data);
+    compressor.set_drain(&mut output);
+    compressor.compress();
+    output
+}
+
+/// Prepend the dictionary to a frame as raw blocks, so a decoder without dictionary support
+/// sees it as already-decoded history. The content checksum (which only covers the payload) is dropped.
+pub fn prepend_dictionary(frame: &[u8], dictionary: &[u8]) -> Result<Vec<u8>> {
+    let malformed = || Error::Decompression("Zstd decompression error: malformed frame header".to_string());
+    let descriptor = *frame.get(4).ok_or_else(malformed)?;
+    let single_segment = descriptor & 0x20 != 0;
+    let has_checksum = descriptor & 0x04 != 0;
+    let dictionary_id_bytes = [0, 1, 2, 4][(descriptor & 0x03) as usize];
+    let content_size_bytes = match descriptor >> 6 {
+        0 if single_segment => 1,
+        0 => 0,
+        1 => 2,
+        2 => 4,
+        _ => 8,
+    };
+    if single_segment || dictionary_id_bytes != 0 {
+        return Err(malformed());
+    }
+    let header_len = 4 + 1 + 1 + content_size_bytes;
+    let body_end = frame.len().checked_sub(if has_checksum { 4 } else { 0 }).ok_or_else(malformed)?;
+    if body_end < header_len {
+        return Err(malformed());
+    }
+
+    let mut spliced = Vec::with_capacity(frame.len() + dictionary.len() + dictionary.len() / ZSTD_MAX_BLOCK * 3 + 3);
+    spliced.extend_from_slice(&frame[..4]);
+    spliced.push(descriptor & !0x04);
+    spliced.extend_from_slice(&frame[5..header_len]);
+    for chunk in dictionary.chunks(ZSTD_MAX_BLOCK) {
+        // Raw block header: last_block = 0, block_type = 0 (raw), block_size
+        let header = (chunk.len() as u32) << 3;
+        spliced.extend_from_slice(&header.to_le_bytes()[..3]);
+        spliced.extend_from_slice(chunk);
+    }
+    spliced.extend_from_slice(&frame[header_len..body_end]);
+    Ok(spliced)
+}
+
+/// Decompress a Zstandard frame compressed with the given raw-content dictionary, if any,
+/// stopping past `max_output_bytes`
+pub fn zstd_decompress(frame: &[u8], dictionary: Option<&[u8]>, max_output_bytes: Option<usize>) -> Result<Vec<u8>> {
+    let (input, skip) = match dictionary {
+        None => (frame.to_vec(), 0),
+        Some(dictionary) => (prepend_dictionary(frame, dictionary)?, dictionary.len()),
+    };
+    let zstd_error = |e: &dyn fmt::Display| Error::Decompression(format!("Zstd decompression error: {}", e));
+    let decoder = StreamingDecoder::new(input.as_slice())
+        .map_err(|e| zstd_error(&e))?;
+    // Read at most one byte past `max_output_bytes` to detect oversized frames
+    let read_limit = max_output_bytes.map_or(u64::MAX, |max| (max as u64).saturating_add(skip as u64 + 1));
+    let mut output = Vec::new();
+    decoder.take(read_limit).read_to_end(&mut output)
+        .map_err(|e| zstd_error(&e))?;
+    if let Some(max) = max_output_bytes.filter(|&max| output.len() - skip.min(output.len()) > max) {
+        return Err(Error::DecompressedTooLarge { limit: max });
+    }
+    output.drain(..skip.min(output.len()));
+    Ok(output)
+}
+
+/// Undo `compression` on a payload. `dictionary_id` is the id (CRC-32) of the Zstandard
+/// dictionary recorded with the payload, which must match `options.dictionary`.
+pub fn decompress(payload: Vec<u8>, compression: Compression, dictionary_id: Option<u32>, options: &DecodeOptions) -> Result<Vec<u8>> {
+    match compression {
+        Compression::Deflate => inflate_raw(&payload, options.max_output_bytes),
+        Compression::Zstd => {
+            let dictionary = match (dictionary_id, options.dictionary) {
+                (None, _) => None,
+                (Some(id), None) => return Err(Error::DictionaryRequired { id }),
+                (Some(id), Some(dictionary)) => match crc32(dictionary) {
+                    actual if actual == id => Some(dictionary),
+                    actual => return Err(Error::DictionaryMismatch { expected: id, actual }),
+                },
+            };
+            zstd_decompress(&payload, dictionary, options.max_output_bytes)
+        }
+        Compression::None => Ok(payload),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_deflate_roundtrip() {
+        let data = b"Hello, World! Hello, World! Hello, World!".repeat(10);
+        let compressed = deflate_raw(&data);
+        assert!(compressed.len() < data.len());
+        assert_eq!(inflate_raw(&compressed, None).unwrap(), data);
+        assert_eq!(inflate_raw(&compressed, Some(100)), Err(Error::DecompressedTooLarge { limit: 100 }));
+        assert!(inflate_raw(&[0xff, 0xff, 0xff], None).unwrap_err().to_string().contains("Deflate decompression error"));
+    }
+
+    #[test]
+    fn test_zstd_roundtrip() {
+        for data in [Vec::new(), b"a".to_vec(), b"Hello, World! ".repeat(200)] {
+            let compressed = zstd_compress(&data);
+            assert_eq!(zstd_decompress(&compressed, None, None).unwrap(), data);
+        }
+        let compressed = zstd_compress(&b"Hello, World! ".repeat(200));
+        assert_eq!(zstd_decompress(&compressed, None, Some(100)), Err(Error::DecompressedTooLarge { limit: 100 }));
+    }
+
+    #[test]
+    fn test_decompress_checks_dictionary() {
+        let payload = zstd_compress(b"abc");
+        let options = DecodeOptions { dictionary: Some(b"dictionary"), ..DecodeOptions::default() };
+        assert_eq!(decompress(payload.clone(), Compression::Zstd, Some(7), &DecodeOptions::default()), Err(Error::DictionaryRequired { id: 7 }));
+        assert_eq!(
+            decompress(payload.clone(), Compression::Zstd, Some(7), &options),
+            Err(Error::DictionaryMismatch { expected: 7, actual: crc32(b"dictionary") })
+        );
+        assert_eq!(decompress(payload, Compression::Zstd, None, &options).unwrap(), b"abc");
+        assert_eq!(Compression::from_name("brotli").unwrap_err().to_string(), "Unsupported compression: brotli");
+    }
+}
@@ -0,0 +1,471 @@
+//! The self-describing `z85v2:` container format
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::codec::{self, Z85};
+use crate::compression::{self, Compression};
+use crate::error::{Error, Result};
+use crate::padding::padding_for_length;
+use crate::DecodeOptions;
+
+/// Prefix identifying the versioned container format
+pub const V2_PREFIX: &str = "z85v2:";
+
+/// CRC-32 (IEEE 802.3, as used by zlib and PNG)
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// File metadata stored in the `name`, `mime`, `mtime` and `size` header parameters
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct FileMetadata {
+    pub name: Option<String>,
+    pub mime: Option<String>,
+    /// Modification time in milliseconds since the Unix epoch
+    pub modified: Option<i64>,
+    /// Length of the original data in bytes
+    pub size: Option<usize>,
+}
+
+// Percent-escape a header value: everything but printable ASCII, plus `%` and the header
+// delimiters `;`, `:` and `=`
+fn escape_param(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_graphic() && !b"%;:=".contains(&byte) {
+            escaped.push(byte as char);
+        } else {
+            escaped.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    escaped
+}
+
+// Undo `escape_param`. Malformed escapes are kept literally.
+fn unescape_param(key: &str, value: &str) -> Result<String> {
+    let bytes = value.as_bytes();
+    let hex = |byte: u8| (byte as char).to_digit(16).map(|digit| digit as u8);
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes.get(index..index + 3) {
+            Some(&[b'%', high, low]) if hex(high).is_some() && hex(low).is_some() => {
+                decoded.push(hex(high).unwrap() << 4 | hex(low).unwrap());
+                index += 3;
+            }
+            _ => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded)
+        .map_err(|_| Error::InvalidContainer(format!("Invalid z85v2 {}: not UTF-8 after unescaping", key)))
+}
+
+/// Parameters of a `z85v2:` container header.
+/// Layout: `z85v2:len=<length>[;crc32=<hex>][;compression=deflate|zstd[;dict=<hex id>][;block=<size>]][;encryption=<algorithm>]`
+/// `[;name=<escaped>][;mime=<escaped>][;mtime=<ms>][;size=<length>]:<z85 data>`,
+/// where the parameters are `;`-separated (`;` is not a Z85 character), `len` is the length of the stored
+/// payload, the checksum covers the original data, and the data is zero-padded to whole groups.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(default))]
+pub struct ContainerHeader {
+    pub length: usize,
+    pub checksum: Option<u32>,
+    pub compression: Compression,
+    /// Id (CRC-32) of the Zstandard dictionary the payload was compressed with
+    pub dictionary_id: Option<u32>,
+    /// Name of the encryption algorithm, as written by z85-wasm's `Pipeline`
+    pub encryption: Option<String>,
+    /// Uncompressed block size when the payload is compressed in independent blocks behind a
+    /// block index
+    pub block_size: Option<usize>,
+    /// Present when any file metadata parameter is
+    pub file: Option<FileMetadata>,
+}
+
+impl ContainerHeader {
+    /// Parse the header of a `z85v2:` container, returning it with the Z85 data that follows.
+    /// Unknown parameters are ignored so later versions can add fields.
+    pub fn parse(payload: &str) -> Result<(ContainerHeader, &str)> {
+        let invalid = |message: String| Error::InvalidContainer(message);
+        let rest = payload.strip_prefix(V2_PREFIX)
+            .ok_or_else(|| invalid("Invalid format: expected 'z85v2:' prefix".to_string()))?;
+        let (header, z85_data) = rest.split_once(':')
+            .ok_or_else(|| invalid("Invalid z85v2 header: missing ':' before data".to_string()))?;
+
+        let mut length = None;
+        let mut parsed = ContainerHeader::default();
+        let mut file = FileMetadata::default();
+        for param in header.split(';') {
+            let (key, value) = param.split_once('=')
+                .ok_or_else(|| invalid(format!("Invalid z85v2 header parameter: {}", param)))?;
+            match key {
+                "len" => length = Some(value.parse::<usize>()
+                    .map_err(|_| invalid(format!("Invalid z85v2 length: {}", value)))?),
+                "crc32" => parsed.checksum = Some(u32::from_str_radix(value, 16)
+                    .map_err(|_| invalid(format!("Invalid z85v2 checksum: {}", value)))?),
+                "compression" => parsed.compression = Compression::from_name(value)?,
+                "dict" => parsed.dictionary_id = Some(u32::from_str_radix(value, 16)
+                    .map_err(|_| invalid(format!("Invalid z85v2 dictionary id: {}", value)))?),
+                // Block sizes are written as u32 lengths, like the block index entries
+                "block" => parsed.block_size = Some(value.parse::<usize>().ok().filter(|&size| size > 0 && size <= u32::MAX as usize)
+                    .ok_or_else(|| invalid(format!("Invalid z85v2 block size: {}", value)))?),
+                "encryption" => parsed.encryption = Some(value.to_string()),
+                "name" => file.name = Some(unescape_param(key, value)?),
+                "mime" => file.mime = Some(unescape_param(key, value)?),
+                "mtime" => file.modified = Some(value.parse::<i64>()
+                    .map_err(|_| invalid(format!("Invalid z85v2 mtime: {}", value)))?),
+                "size" => file.size = Some(value.parse::<usize>()
+                    .map_err(|_| invalid(format!("Invalid z85v2 size: {}", value)))?),
+                _ => {}
+            }
+        }
+        parsed.length = length.ok_or_else(|| invalid("Invalid z85v2 header: missing len".to_string()))?;
+        parsed.file = (file != FileMetadata::default()).then_some(file);
+        Ok((parsed, z85_data))
+    }
+
+    /// Serialize the header, up to but excluding the `:` before the Z85 data
+    pub fn to_header_string(&self) -> String {
+        let mut header = format!("{}len={}", V2_PREFIX, self.length);
+        if let Some(checksum) = self.checksum {
+            header.push_str(&format!(";crc32={:08x}", checksum));
+        }
+        if self.compression != Compression::None {
+            header.push_str(&format!(";compression={}", self.compression.name()));
+            if let Some(id) = self.dictionary_id {
+                header.push_str(&format!(";dict={:08x}", id));
+            }
+            if let Some(size) = self.block_size {
+                header.push_str(&format!(";block={}", size));
+            }
+        }
+        if let Some(algorithm) = &self.encryption {
+            header.push_str(&format!(";encryption={}", algorithm));
+        }
+        if let Some(file) = &self.file {
+            if let Some(name) = &file.name {
+                header.push_str(&format!(";name={}", escape_param(name)));
+            }
+            if let Some(mime) = &file.mime {
+                header.push_str(&format!(";mime={}", escape_param(mime)));
+            }
+            if let Some(modified) = file.modified {
+                header.push_str(&format!(";mtime={}", modified));
+            }
+            if let Some(size) = file.size {
+                header.push_str(&format!(";size={}", size));
+            }
+        }
+        header
+    }
+
+    /// Serialize the header and Z85-encode `payload` (which must be `length` bytes) after it
+    pub fn encode(&self, payload: &[u8]) -> String {
+        self.encode_using(payload, |words, output| codec::encode_append_with(&Z85, words, output))
+    }
+
+    /// `encode`, encoding the whole words of `payload` with `encode_words` (e.g. an accelerated
+    /// kernel for the Z85 alphabet)
+    pub fn encode_using(&self, payload: &[u8], encode_words: impl FnOnce(&[u8], &mut String)) -> String {
+        debug_assert_eq!(payload.len(), self.length);
+        let header = self.to_header_string();
+        let aligned = payload.len() / 4 * 4;
+        let mut output = String::with_capacity(header.len() + 1 + payload.len().div_ceil(4) * 5);
+        output.push_str(&header);
+        output.push(':');
+        encode_words(&payload[..aligned], &mut output);
+        codec::encode_padded_tail_with(&Z85, &payload[aligned..], &mut output);
+        output
+    }
+
+    /// Padding of the final group of the Z85 data following the header
+    pub fn padding(&self, z85_data: &str) -> Result<usize> {
+        // Length must account for the data up to the zero padding of the last group
+        padding_for_length(z85_data.len() / 5 * 4, self.length)
+    }
+
+    /// Decode the Z85 data following the header into the stored payload
+    pub fn decode_payload(&self, z85_data: &str) -> Result<Vec<u8>> {
+        self.decode_payload_using(z85_data, |groups, words| codec::decode_groups_with(&Z85, groups, words, 0))
+    }
+
+    /// `decode_payload`, decoding whole groups with `decode_groups` (see `codec::decode_into_using`)
+    pub fn decode_payload_using(&self, z85_data: &str, decode_groups: impl FnOnce(&[u8], &mut [u8]) -> Result<()>) -> Result<Vec<u8>> {
+        let padding = self.padding(z85_data)?;
+        let mut decoded = vec![0u8; self.length];
+        codec::decode_into_using(&Z85, z85_data.as_bytes(), padding, &mut decoded, decode_groups)?;
+        Ok(decoded)
+    }
+
+    /// Undo the compression recorded in the header
+    pub fn decompress(&self, payload: Vec<u8>, options: &DecodeOptions) -> Result<Vec<u8>> {
+        match self.block_size {
+            Some(block_size) => decompress_blocks(&payload, block_size, self.compression, self.dictionary_id, options),
+            None => compression::decompress(payload, self.compression, self.dictionary_id, options),
+        }
+    }
+
+    /// Verify the checksum and file size recorded in the header, if any, against the original data
+    pub fn verify_checksum(&self, data: &[u8]) -> Result<()> {
+        if let Some(expected) = self.checksum {
+            let actual = crc32(data);
+            if actual != expected {
+                return Err(Error::ChecksumMismatch { expected, actual });
+            }
+        }
+        if let Some(size) = self.file.as_ref().and_then(|file| file.size) {
+            if size != data.len() {
+                return Err(Error::SizeMismatch { expected: size, actual: data.len() });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bytes taken by the block index of `count` blocks: the count, then each compressed length
+/// (big-endian u32s). Fails if that does not fit in `usize` (a count read from hostile input
+/// on 32-bit targets).
+pub fn index_len(count: usize) -> Result<usize> {
+    (count as u64).checked_mul(4).and_then(|len| len.checked_add(4))
+        .and_then(|len| usize::try_from(len).ok())
+        .ok_or_else(truncated_index)
+}
+
+/// Join independently compressed blocks behind their block index
+pub fn pack_blocks(blocks: &[Vec<u8>]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(4 + 4 * blocks.len() + blocks.iter().map(Vec::len).sum::<usize>());
+    payload.extend_from_slice(&(blocks.len() as u32).to_be_bytes());
+    for block in blocks {
+        payload.extend_from_slice(&(block.len() as u32).to_be_bytes());
+    }
+    for block in blocks {
+        payload.extend_from_slice(block);
+    }
+    payload
+}
+
+/// Compressed lengths from a block index (the count, then one length per block), checked
+/// against the stored payload length. The sum is taken in u64 so lengths cannot wrap around to
+/// match; once this succeeds, offsets computed from the lengths fit in `usize`.
+pub fn parse_index(index: &[u8], payload_len: usize) -> Result<Vec<usize>> {
+    let count = block_count(index.get(..4).ok_or_else(truncated_index)?, payload_len)?;
+    let lengths: Vec<usize> = index[4..].chunks_exact(4)
+        .map(|length| u32::from_be_bytes(length.try_into().unwrap()) as usize)
+        .collect();
+    if lengths.len() != count || index.len() != index_len(count)? {
+        return Err(Error::InvalidContainer(format!("Invalid z85v2 block index: expected {} block lengths, got {}", count, lengths.len())));
+    }
+    let total = lengths.iter().try_fold(index.len() as u64, |total, &length| total.checked_add(length as u64));
+    if total != Some(payload_len as u64) {
+        return Err(Error::InvalidContainer("Invalid z85v2 block index: block lengths do not add up to len".to_string()));
+    }
+    Ok(lengths)
+}
+
+/// Number of blocks named by the first 4 bytes of a block-indexed payload
+pub fn block_count(count: &[u8], payload_len: usize) -> Result<usize> {
+    let count = u32::from_be_bytes(count.try_into().unwrap()) as usize;
+    if index_len(count)? > payload_len {
+        return Err(truncated_index());
+    }
+    Ok(count)
+}
+
+// Error for a block index cut short
+fn truncated_index() -> Error {
+    Error::InvalidContainer("Invalid z85v2 block index: truncated".to_string())
+}
+
+/// Decompress one block and check it holds `block_size` bytes (the last block may hold fewer)
+pub fn decompress_block(
+    block: &[u8],
+    block_size: usize,
+    is_last: bool,
+    (compression, dictionary_id): (Compression, Option<u32>),
+    options: &DecodeOptions,
+) -> Result<Vec<u8>> {
+    let data = compression::decompress(block.to_vec(), compression, dictionary_id, options)?;
+    if data.len() > block_size || (!is_last && data.len() != block_size) || (is_last && data.is_empty()) {
+        return Err(Error::InvalidContainer(format!("Invalid z85v2 block: decompressed to {} bytes (block size is {})", data.len(), block_size)));
+    }
+    Ok(data)
+}
+
+/// Decompress a whole block-indexed payload
+pub fn decompress_blocks(payload: &[u8], block_size: usize, compression: Compression, dictionary_id: Option<u32>, options: &DecodeOptions) -> Result<Vec<u8>> {
+    let count = block_count(payload.get(..4).ok_or_else(truncated_index)?, payload.len())?;
+    let lengths = parse_index(&payload[..index_len(count)?], payload.len())?;
+    let mut data = Vec::new();
+    let mut start = index_len(count)?;
+    for (index, length) in lengths.iter().enumerate() {
+        let block = decompress_block(&payload[start..start + length], block_size, index + 1 == count, (compression, dictionary_id), options)?;
+        data.extend_from_slice(&block);
+        start += length;
+    }
+    Ok(data)
+}
+
+/// Compress `data`, keeping the result only when it is actually smaller.
+/// Returns the payload and the compression applied.
+pub fn compress_payload(data: Vec<u8>, compression: Compression) -> (Vec<u8>, Compression) {
+    let (payload, compression, _) = compress_payload_using(data, compression, |data| (compression::zstd_compress(data), None));
+    (payload, compression)
+}
+
+/// `compress_payload`, Zstandard-compressing with `zstd_compress`, which returns the frame and
+/// the id of the dictionary it used. Returns the payload, the compression applied and that id.
+pub fn compress_payload_using(
+    data: Vec<u8>,
+    compression: Compression,
+    zstd_compress: impl FnOnce(&[u8]) -> (Vec<u8>, Option<u32>),
+) -> (Vec<u8>, Compression, Option<u32>) {
+    let (compressed, dictionary_id) = match compression {
+        Compression::Deflate => (compression::deflate_raw(&data), None),
+        Compression::Zstd => zstd_compress(&data),
+        Compression::None => return (data, Compression::None, None),
+    };
+    if compressed.len() < data.len() {
+        (compressed, compression, dictionary_id)
+    } else {
+        (data, Compression::None, None)
+    }
+}
+
+/// Encode bytes to the `z85v2:` container format, recording a CRC-32 of the data when
+/// `with_checksum` is set and compressing the payload when that makes it smaller
+pub fn encode_container(data: &[u8], with_checksum: bool, compression: Compression) -> String {
+    let checksum = with_checksum.then(|| crc32(data));
+    if compression == Compression::None {
+        return ContainerHeader { length: data.len(), checksum, ..ContainerHeader::default() }.encode(data);
+    }
+    let (payload, compression) = compress_payload(data.to_vec(), compression);
+    ContainerHeader { length: payload.len(), checksum, compression, ..ContainerHeader::default() }.encode(&payload)
+}
+
+/// Decode a `z85v2:` container, verifying its length and checksum
+pub fn decode_container(payload: &str) -> Result<Vec<u8>> {
+    decode_container_with(payload, &DecodeOptions::default())
+}
+
+/// `decode_container` with a Zstandard dictionary and output limit
+pub fn decode_container_with(payload: &str, options: &DecodeOptions) -> Result<Vec<u8>> {
+    let (header, z85_data) = ContainerHeader::parse(payload)?;
+    if let Some(algorithm) = header.encryption {
+        return Err(Error::Encrypted { algorithm });
+    }
+    let decoded = header.decode_payload(z85_data)?;
+    let decoded = header.decompress(decoded, options)?;
+    header.verify_checksum(&decoded)?;
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_container_format() {
+        assert_eq!(encode_container(b"Hello World", false, Compression::None), "z85v2:len=11:nm=QNzY&b1A+]m^");
+        let encoded = encode_container(b"Hello World", true, Compression::None);
+        assert_eq!(encoded, format!("z85v2:len=11;crc32={:08x}:nm=QNzY&b1A+]m^", crc32(b"Hello World")));
+        assert_eq!(decode_container(&encoded).unwrap(), b"Hello World");
+        // Unknown header parameters are ignored
+        assert_eq!(decode_container("z85v2:len=11;future=1:nm=QNzY&b1A+]m^").unwrap(), b"Hello World");
+    }
+
+    #[test]
+    fn test_container_compression() {
+        let data = b"Hello, World! ".repeat(50);
+        for compression in [Compression::Deflate, Compression::Zstd] {
+            let encoded = encode_container(&data, true, compression);
+            assert!(encoded.contains(&format!(";compression={}:", compression.name())));
+            assert_eq!(decode_container(&encoded).unwrap(), data);
+        }
+        // Incompressible payloads are stored as is
+        assert!(!encode_container(b"abc", true, Compression::Deflate).contains("compression"));
+
+        let limited = DecodeOptions { max_output_bytes: Some(100), ..DecodeOptions::default() };
+        let encoded = encode_container(&data, true, Compression::Deflate);
+        assert_eq!(decode_container_with(&encoded, &limited), Err(Error::DecompressedTooLarge { limit: 100 }));
+    }
+
+    #[test]
+    fn test_container_errors() {
+        assert_eq!(decode_container("z85v2:len=11").unwrap_err().to_string(), "Invalid z85v2 header: missing ':' before data");
+        assert_eq!(decode_container("z85v2:len=11:nm=QNzY&b1"), Err(Error::Truncated { expected: 11, actual: 8 }));
+        assert_eq!(decode_container("z85v2:len=0;compression=brotli:"), Err(Error::UnsupportedCompression("brotli".to_string())));
+        assert_eq!(decode_container("z85v2:len=0;encryption=aes-256-gcm:"), Err(Error::Encrypted { algorithm: "aes-256-gcm".to_string() }));
+        assert_eq!(
+            decode_container("z85v2:len=0;compression=deflate;block=18446744073709551615:").unwrap_err().to_string(),
+            "Invalid z85v2 block size: 18446744073709551615"
+        );
+
+        let tampered = encode_container(b"Hello World", true, Compression::None).replace("nm=QN", "nm=QM");
+        assert!(matches!(decode_container(&tampered), Err(Error::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    fn test_file_metadata_roundtrip() {
+        let file = FileMetadata { name: Some("Q3 report; grüße.pdf".into()), mime: Some("application/pdf".into()), modified: Some(1_700_000_000_000), size: Some(3) };
+        let header = ContainerHeader { length: 3, file: Some(file.clone()), ..ContainerHeader::default() };
+        let encoded = header.encode(b"abc");
+        assert!(encoded.contains(";name=Q3%20report%3B%20gr%C3%BC%C3%9Fe.pdf;mime=application/pdf;mtime=1700000000000;size=3:"));
+        assert_eq!(ContainerHeader::parse(&encoded).unwrap().0.file, Some(file));
+        assert_eq!(decode_container(&encoded.replace(";size=3", ";size=4")), Err(Error::SizeMismatch { expected: 4, actual: 3 }));
+    }
+
+    #[test]
+    fn test_block_index() {
+        let blocks = [compression::deflate_raw(&[1; 100]), compression::deflate_raw(&[2; 40])];
+        let payload = pack_blocks(&blocks);
+        let data = decompress_blocks(&payload, 100, Compression::Deflate, None, &DecodeOptions::default()).unwrap();
+        assert_eq!(data, [[1; 100].as_slice(), &[2; 40]].concat());
+        assert_eq!(
+            decompress_blocks(&payload, 50, Compression::Deflate, None, &DecodeOptions::default()).unwrap_err().to_string(),
+            "Invalid z85v2 block: decompressed to 100 bytes (block size is 50)"
+        );
+        assert_eq!(decompress_blocks(&payload[..2], 100, Compression::Deflate, None, &DecodeOptions::default()), Err(truncated_index()));
+    }
+
+    #[test]
+    fn test_block_index_rejects_wrapping_lengths() {
+        // Two lengths whose u32 sum wraps to the payload length
+        let mut payload = 2u32.to_be_bytes().to_vec();
+        payload.extend_from_slice(&u32::MAX.to_be_bytes());
+        payload.extend_from_slice(&6u32.to_be_bytes());
+        payload.extend_from_slice(&[0; 5]);
+        assert_eq!(12u32.wrapping_add(u32::MAX).wrapping_add(6), payload.len() as u32);
+        assert_eq!(
+            parse_index(&payload[..12], payload.len()).unwrap_err().to_string(),
+            "Invalid z85v2 block index: block lengths do not add up to len"
+        );
+        assert!(decompress_blocks(&payload, 100, Compression::Deflate, None, &DecodeOptions::default()).is_err());
+
+        // The index must hold exactly `count` lengths
+        assert_eq!(
+            parse_index(&payload[..8], payload.len()).unwrap_err().to_string(),
+            "Invalid z85v2 block index: expected 2 block lengths, got 1"
+        );
+        assert_eq!(index_len(usize::MAX), Err(truncated_index()));
+    }
+}
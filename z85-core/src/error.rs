@@ -0,0 +1,93 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// Errors of the core codec and container functions. The `Display` text is the message
+/// z85-wasm reports to JS, so native and WASM callers see the same wording.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A character outside the alphabet at `offset`
+    InvalidCharacter { alphabet: &'static str, offset: usize },
+    /// A 5-character group at `offset` that decodes to more than 32 bits
+    InvalidGroup { alphabet: &'static str, offset: usize },
+    /// Encoded data whose length is not a multiple of 5
+    InvalidDataLength { alphabet: &'static str, length: usize },
+    /// Input to the strict (suffix-free) codec whose length is not a multiple of `multiple`
+    StrictLength { length: usize, multiple: usize },
+    /// No `:padding` or `#length` suffix
+    MissingSuffix,
+    /// A `:padding` suffix that is not a number or exceeds the decoded data
+    InvalidPadding,
+    /// A `:padding` suffix above 3 while strict padding is on
+    StrictPadding { padding: usize },
+    /// A `#length` suffix that is not a number
+    InvalidLengthSuffix,
+    /// Encoded data decoding to fewer bytes than recorded
+    Truncated { expected: usize, actual: usize },
+    /// A recorded length more than 3 bytes short of the encoded data
+    LengthMismatch { length: usize, available: usize },
+    /// An output buffer smaller than the decoded data
+    OutputTooSmall { needed: usize, actual: usize },
+    /// A malformed `z85v2:` header or block index
+    InvalidContainer(String),
+    /// A compression algorithm this version does not know
+    UnsupportedCompression(String),
+    /// An encrypted container, which must be decrypted with its key first
+    Encrypted { algorithm: String },
+    /// CRC-32 of the decoded data differs from the one recorded in the header
+    ChecksumMismatch { expected: u32, actual: u32 },
+    /// Decoded data whose length differs from the `size` recorded in the header
+    SizeMismatch { expected: usize, actual: usize },
+    /// A corrupt compressed payload
+    Decompression(String),
+    /// Invalid base64 input
+    #[cfg(feature = "base64")]
+    Base64(base64::DecodeError),
+    /// A compressed payload expanding beyond the allowed output size
+    DecompressedTooLarge { limit: usize },
+    /// A payload compressed with a dictionary that was not supplied
+    DictionaryRequired { id: u32 },
+    /// A payload compressed with a different dictionary than the one supplied
+    DictionaryMismatch { expected: u32, actual: u32 },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidCharacter { alphabet, offset } => write!(f, "{} decode error: invalid character at offset {}", alphabet, offset),
+            Error::InvalidGroup { alphabet, offset } => write!(f, "{} decode error: invalid group at offset {}", alphabet, offset),
+            Error::InvalidDataLength { alphabet, length } => write!(f, "{} decode error: data length ({}) is not a multiple of 5", alphabet, length),
+            Error::StrictLength { length, multiple } => write!(f, "Z85 strict mode: input length ({}) is not a multiple of {}", length, multiple),
+            Error::MissingSuffix => f.write_str("Invalid format: expected 'z85_data:padding'"),
+            Error::InvalidPadding => f.write_str("Invalid padding number"),
+            Error::StrictPadding { padding } => write!(f, "Invalid padding number: {} is more than 3 (strict padding)", padding),
+            Error::InvalidLengthSuffix => f.write_str("Invalid length number"),
+            Error::Truncated { expected, actual } => write!(f, "Z85 data truncated: expected {} bytes, got {}", expected, actual),
+            Error::LengthMismatch { length, available } => write!(f, "Length suffix ({}) does not match Z85 data ({} bytes)", length, available),
+            Error::OutputTooSmall { needed, actual } => write!(f, "Output buffer too small: need {} bytes, got {}", needed, actual),
+            Error::InvalidContainer(message) | Error::Decompression(message) => f.write_str(message),
+            Error::UnsupportedCompression(name) => write!(f, "Unsupported compression: {}", name),
+            Error::Encrypted { algorithm } => write!(f, "Container is encrypted ({}): decrypt it with its key before decoding", algorithm),
+            Error::ChecksumMismatch { expected, actual } => write!(f, "Checksum mismatch: expected {:08x}, got {:08x}", expected, actual),
+            Error::SizeMismatch { expected, actual } => write!(f, "Container size mismatch: expected {} bytes, got {}", expected, actual),
+            #[cfg(feature = "base64")]
+            Error::Base64(error) => write!(f, "Base64 decode error: {}", error),
+            Error::DecompressedTooLarge { limit } => write!(f, "Output size limit exceeded: decompressed data is larger than {} bytes", limit),
+            Error::DictionaryRequired { id } => write!(f, "Payload requires compression dictionary {:08x}", id),
+            Error::DictionaryMismatch { expected, actual } => {
+                write!(f, "Compression dictionary mismatch: payload uses {:08x}, current is {:08x}", expected, actual)
+            }
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl From<Error> for String {
+    fn from(error: Error) -> String {
+        error.to_string()
+    }
+}
+
+/// Result of the core functions
+pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,144 @@
+//! The Z85 formats of z85-wasm as plain Rust: the `z85data:padding` / `z85data#length`
+//! suffixes, ZeroMQ RFC 32 strict Z85 and the self-describing `z85v2:` container with its
+//! compression and checksums. z85-wasm is built on this crate, so text produced by either
+//! decodes with the other.
+//!
+//! ```
+//! let encoded = z85_core::encode(b"Hello World");
+//! assert_eq!(encoded, "nm=QNzY&b1A+]m^:1");
+//! assert_eq!(z85_core::decode(&encoded).unwrap(), b"Hello World");
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+
+#[cfg(feature = "base64")]
+pub mod base64;
+pub mod codec;
+pub mod compression;
+pub mod container;
+mod error;
+pub mod padding;
+
+pub use compression::Compression;
+pub use container::{decode_container, decode_container_with, encode_container, ContainerHeader, FileMetadata};
+pub use error::{Error, Result};
+pub use padding::PaddingFormat;
+
+/// Version of this crate
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use codec::Z85;
+
+/// Options for decoding compressed containers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DecodeOptions<'a> {
+    /// Raw-content Zstandard dictionary, required by payloads compressed with one
+    pub dictionary: Option<&'a [u8]>,
+    /// Largest decompressed size accepted; larger payloads fail with `Error::DecompressedTooLarge`
+    pub max_output_bytes: Option<usize>,
+}
+
+/// Encode raw bytes to Z85 with a `:padding` suffix
+pub fn encode(data: &[u8]) -> String {
+    encode_with(data, PaddingFormat::Count)
+}
+
+/// Encode raw bytes to Z85 with the given padding suffix
+pub fn encode_with(data: &[u8], padding_format: PaddingFormat) -> String {
+    let aligned = data.len() / 4 * 4;
+    let mut z85_data = String::with_capacity(data.len().div_ceil(4) * 5 + 7);
+    codec::encode_append_with(&Z85, &data[..aligned], &mut z85_data);
+    let padding = codec::encode_padded_tail_with(&Z85, &data[aligned..], &mut z85_data);
+    padding::push_padding_suffix(&mut z85_data, padding, padding_format);
+    z85_data
+}
+
+/// Decode Z85 with a `:padding` or `#length` suffix, or a `z85v2:` container
+pub fn decode(z85_data_with_padding: &str) -> Result<Vec<u8>> {
+    decode_with(z85_data_with_padding, &DecodeOptions::default())
+}
+
+/// `decode` with a Zstandard dictionary and output limit for compressed containers
+pub fn decode_with(z85_data_with_padding: &str, options: &DecodeOptions) -> Result<Vec<u8>> {
+    if z85_data_with_padding.starts_with(container::V2_PREFIX) {
+        return decode_container_with(z85_data_with_padding, options);
+    }
+    let (z85_data, padding) = padding::split_padding_suffix(z85_data_with_padding, false)?;
+    let mut decoded = vec![0u8; decoded_len(z85_data_with_padding)?];
+    codec::decode_into_with(&Z85, z85_data.as_bytes(), padding, &mut decoded)?;
+    Ok(decoded)
+}
+
+/// Exact number of bytes `decode_into` writes for Z85 with a padding suffix
+pub fn decoded_len(z85_data_with_padding: &str) -> Result<usize> {
+    let (z85_data, padding) = padding::split_padding_suffix(z85_data_with_padding, false)?;
+    Ok(z85_data.len() / 5 * 4 - padding)
+}
+
+/// Decode Z85 with a padding suffix into `out`, returning the number of bytes written
+pub fn decode_into(z85_data_with_padding: &str, out: &mut [u8]) -> Result<usize> {
+    let (z85_data, padding) = padding::split_padding_suffix(z85_data_with_padding, false)?;
+    codec::decode_into_with(&Z85, z85_data.as_bytes(), padding, out)
+}
+
+/// Encode raw bytes exactly as specified by ZeroMQ RFC 32: the length must be a multiple of 4
+/// and no suffix is appended
+pub fn encode_strict(data: &[u8]) -> Result<String> {
+    if !data.len().is_multiple_of(4) {
+        return Err(Error::StrictLength { length: data.len(), multiple: 4 });
+    }
+    let mut z85_data = String::with_capacity(data.len() / 4 * 5);
+    codec::encode_append_with(&Z85, data, &mut z85_data);
+    Ok(z85_data)
+}
+
+/// Decode Z85 exactly as specified by ZeroMQ RFC 32: the length must be a multiple of 5
+pub fn decode_strict(z85_data: &str) -> Result<Vec<u8>> {
+    if !z85_data.len().is_multiple_of(5) {
+        return Err(Error::StrictLength { length: z85_data.len(), multiple: 5 });
+    }
+    let mut decoded = vec![0u8; z85_data.len() / 5 * 4];
+    codec::decode_groups_with(&Z85, z85_data.as_bytes(), &mut decoded, 0)?;
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_roundtrip() {
+        for len in 0..10 {
+            let data: Vec<u8> = (0..len as u8).map(|i| i.wrapping_mul(91)).collect();
+            for format in [PaddingFormat::Count, PaddingFormat::Length] {
+                let encoded = encode_with(&data, format);
+                assert_eq!(decode(&encoded).unwrap(), data);
+                assert_eq!(decoded_len(&encoded).unwrap(), data.len());
+            }
+            assert_eq!(decode(&encode_container(&data, true, Compression::Zstd)).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_padding_formats() {
+        assert_eq!(encode_with(b"Hello World", PaddingFormat::Length), "nm=QNzY&b1A+]m^#11");
+        let mut out = [0u8; 11];
+        assert_eq!(decode_into("nm=QNzY&b1A+]m^:1", &mut out), Ok(11));
+        assert_eq!(&out, b"Hello World");
+        assert_eq!(decode("nm=QNzY&b1A+]m^").unwrap_err().to_string(), "Invalid format: expected 'z85_data:padding'");
+    }
+
+    #[test]
+    fn test_strict() {
+        assert_eq!(encode_strict(&[0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B]).unwrap(), "HelloWorld");
+        assert_eq!(decode_strict("HelloWorld").unwrap(), [0x86, 0x4F, 0xD2, 0x6F, 0xB5, 0x59, 0xF7, 0x5B]);
+        assert_eq!(encode_strict(b"abc").unwrap_err().to_string(), "Z85 strict mode: input length (3) is not a multiple of 4");
+        assert_eq!(decode_strict("Hell"), Err(Error::StrictLength { length: 4, multiple: 5 }));
+    }
+}
@@ -0,0 +1,123 @@
+//! The `z85data:padding` and `z85data#length` suffixes recording the final partial group
+
+use alloc::format;
+use alloc::string::String;
+
+use crate::error::{Error, Result};
+
+/// How the Z85 output records the final partial group
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "lowercase"))]
+pub enum PaddingFormat {
+    /// Number of padding bytes (z85data:padding)
+    #[default]
+    Count,
+    /// Original byte length (z85data#length)
+    Length,
+}
+
+/// Split `z85_data:padding` or `z85_data#length` into the Z85 data and the padding count.
+/// The padding never exceeds the bytes the data decodes to, nor 3 when `strict` is set (the
+/// encoder never writes more; larger values drop whole groups).
+pub fn split_padding_suffix(z85_data_with_padding: &str, strict: bool) -> Result<(&str, usize)> {
+    split_padding_suffix_after(z85_data_with_padding, 0, strict)
+}
+
+/// Longest suffix: `#` followed by the digits of the largest length
+pub const MAX_SUFFIX_LEN: usize = 1 + 20;
+
+/// Split the end of a stream whose first `decoded_before` bytes were already decoded: a
+/// `#length` suffix counts those bytes too
+pub fn split_padding_suffix_after(z85_data_with_padding: &str, decoded_before: usize, strict: bool) -> Result<(&str, usize)> {
+    // Split by the LAST separator (both ':' and '#' are Z85 characters, but never appear in
+    // the numeric suffix)
+    let separator_pos = z85_data_with_padding.rfind([':', '#'])
+        .ok_or(Error::MissingSuffix)?;
+    let z85_data = &z85_data_with_padding[..separator_pos];
+    let suffix = &z85_data_with_padding[separator_pos + 1..];
+    let available = decoded_before + z85_data.len() / 5 * 4;
+
+    if z85_data_with_padding.as_bytes()[separator_pos] == b':' {
+        let padding: usize = suffix.parse()
+            .map_err(|_| Error::InvalidPadding)?;
+        if padding > available {
+            return Err(Error::InvalidPadding);
+        }
+        if padding > 3 && strict {
+            return Err(Error::StrictPadding { padding });
+        }
+        return Ok((z85_data, padding));
+    }
+
+    // Length suffix: the data must decode to between `length` and `length + 3` bytes
+    let length: usize = suffix.parse()
+        .map_err(|_| Error::InvalidLengthSuffix)?;
+    let padding = padding_for_length(available, length)?;
+    Ok((z85_data, padding))
+}
+
+/// Padding of Z85 data decoding to `available` bytes (whole groups) that holds `length` bytes
+pub fn padding_for_length(available: usize, length: usize) -> Result<usize> {
+    let padding = available.checked_sub(length)
+        .ok_or(Error::Truncated { expected: length, actual: available })?;
+    if padding > 3 {
+        return Err(Error::LengthMismatch { length, available });
+    }
+    Ok(padding)
+}
+
+/// Append the padding suffix for Z85 data decoding to `z85_data.len() / 5 * 4 - padding` bytes
+pub fn push_padding_suffix(z85_data: &mut String, padding: usize, format: PaddingFormat) {
+    let suffix = match format {
+        PaddingFormat::Count => format!(":{}", padding),
+        PaddingFormat::Length => format!("#{}", z85_data.len() / 5 * 4 - padding),
+    };
+    z85_data.push_str(&suffix);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_split_padding_suffix() {
+        assert_eq!(split_padding_suffix("HelloWorld:3", false), Ok(("HelloWorld", 3)));
+        assert_eq!(split_padding_suffix("HelloWorld#5", false), Ok(("HelloWorld", 3)));
+        // ':' is a Z85 character; only the last one separates the suffix
+        assert_eq!(split_padding_suffix("ab:de:0", false), Ok(("ab:de", 0)));
+        assert_eq!(split_padding_suffix("HelloWorld:5", false), Ok(("HelloWorld", 5)));
+    }
+
+    #[test]
+    fn test_split_padding_suffix_errors() {
+        assert_eq!(split_padding_suffix("HelloWorld", false), Err(Error::MissingSuffix));
+        assert_eq!(split_padding_suffix("Hello:x", false), Err(Error::InvalidPadding));
+        assert_eq!(split_padding_suffix("Hello:5", false), Err(Error::InvalidPadding));
+        assert_eq!(split_padding_suffix("HelloWorld:5", true), Err(Error::StrictPadding { padding: 5 }));
+        assert_eq!(split_padding_suffix("Hello#x", false), Err(Error::InvalidLengthSuffix));
+        assert_eq!(split_padding_suffix("Hello#5", false), Err(Error::Truncated { expected: 5, actual: 4 }));
+        assert_eq!(
+            split_padding_suffix("HelloWorld#4", false).unwrap_err().to_string(),
+            "Length suffix (4) does not match Z85 data (8 bytes)"
+        );
+    }
+
+    #[test]
+    fn test_split_padding_suffix_after() {
+        assert_eq!(split_padding_suffix_after("World#9", 8, false), Ok(("World", 3)));
+        assert_eq!(split_padding_suffix_after("World:3", 8, false), Ok(("World", 3)));
+        assert_eq!(split_padding_suffix_after("World#9", 0, false), Err(Error::Truncated { expected: 9, actual: 4 }));
+        assert_eq!(u64::MAX.to_string().len() + 1, MAX_SUFFIX_LEN);
+    }
+
+    #[test]
+    fn test_push_padding_suffix() {
+        let mut z85_data = String::from("HelloWorld");
+        push_padding_suffix(&mut z85_data, 3, PaddingFormat::Length);
+        assert_eq!(z85_data, "HelloWorld#5");
+        let mut z85_data = String::from("HelloWorld");
+        push_padding_suffix(&mut z85_data, 3, PaddingFormat::Count);
+        assert_eq!(z85_data, "HelloWorld:3");
+    }
+}